@@ -16,6 +16,10 @@
  * - endTime: Timestamp when execution completed (null while executing)
  * - oomKilled: Docker resource-exhaustion signal when available
  * - options: Execution options (isolation mode, etc.)
+ * - schemaVersion: Record layout version, shared with the Rust store
+ *
+ * Fields this implementation does not model (tags, labels, notes, lineage,
+ * provenance, owner) are kept when a record is read and written back.
  */
 
 const fs = require('fs');
@@ -52,6 +56,8 @@ const LINKS_DB_FILE = 'executions.links';
 const LOCK_FILE = 'executions.lock';
 const LOCK_TIMEOUT_MS = 30000; // 30 second timeout for lock acquisition
 const LOCK_STALE_MS = 60000; // Consider lock stale after 60 seconds
+// Record layout version written by this release (see execution_schema.rs)
+const SCHEMA_VERSION = 2;
 
 /**
  * Execution status enumeration
@@ -80,6 +86,8 @@ class ExecutionRecord {
     this.shell = options.shell || process.env.SHELL || '/bin/sh';
     this.platform = options.platform || process.platform;
     this.options = options.options || {};
+    // Original object the record was read from, to keep unmodeled fields
+    this.extra = {};
   }
 
   /**
@@ -97,6 +105,7 @@ class ExecutionRecord {
    */
   toObject() {
     const obj = {
+      ...this.extra,
       uuid: this.uuid,
       pid: this.pid,
       status: this.status,
@@ -114,15 +123,19 @@ class ExecutionRecord {
       shell: this.shell,
       platform: this.platform,
       options: this.options,
+      // Records from a newer release keep their version
+      schemaVersion: Math.max(this.extra.schemaVersion || 0, SCHEMA_VERSION),
     });
     return obj;
   }
 
   /**
-   * Create from plain object
+   * Create from plain object, keeping fields not modeled here
    */
   static fromObject(obj) {
-    return new ExecutionRecord(obj);
+    const record = new ExecutionRecord(obj);
+    record.extra = { ...obj };
+    return record;
   }
 }
 
@@ -234,6 +234,33 @@ describe('ExecutionStore', () => {
     expect(retrieved.endTime).toBeTruthy();
   });
 
+  it('should keep fields written by the Rust store when updating', () => {
+    // A record annotated by the Rust implementation
+    const record = ExecutionRecord.fromObject({
+      uuid: '6f1c7a52-1d1e-4c38-9a7e-2f0f7b6c1a01',
+      command: 'npm test',
+      status: 'executing',
+      tags: ['release'],
+      labels: { team: 'web' },
+      parentUuid: 'c3e5a7b9-1b2d-4f40-9c6e-8d0f2a4b6c03',
+      schemaVersion: 2,
+    });
+    store.save(record);
+
+    const retrieved = store.get(record.uuid);
+    retrieved.complete(0);
+    store.save(retrieved);
+
+    const obj = store.get(record.uuid).toObject();
+    expect(obj.status).toBe(ExecutionStatus.EXECUTED);
+    expect(obj.tags).toEqual(['release']);
+    expect(obj.labels).toEqual({ team: 'web' });
+    expect(obj.parentUuid).toBe('c3e5a7b9-1b2d-4f40-9c6e-8d0f2a4b6c03');
+    expect(obj.schemaVersion).toBe(2);
+    const fresh = new ExecutionRecord({ command: 'ls' }).toObject();
+    expect(fresh.schemaVersion).toBe(2);
+  });
+
   it('should get all records', () => {
     const record1 = new ExecutionRecord({ command: 'echo 1' });
     const record2 = new ExecutionRecord({ command: 'echo 2' });
@@ -7,37 +7,43 @@
 //! - Automatic failure reporting (GitHub issues)
 
 use std::env;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 
 use start_command::{
-    append_log_file,
+    append_log_file, apply_launch_annotations,
     args_parser::{
-        generate_session_name, generate_uuid, get_effective_mode, has_isolation, parse_args,
+        generate_session_name, generate_uuid, get_effective_mode, has_isolation, has_store_command,
+        output_mode, parse_args, record_filter, WrapperOptions,
     },
-    build_isolation_options_map, clear_current_execution, create_finish_block, create_log_footer,
-    create_log_header, create_log_path_for_execution, create_start_block,
-    docker_runtime_status_lines,
+    build_isolation_options_map, build_next_level_command,
+    ci_output::ci_enabled,
+    clear_current_execution, create_log_footer, create_log_header, create_log_path_for_execution,
+    create_start_block, docker_runtime_status_lines, emit_event, emit_started,
     execution_control::{control_execution, ControlAction},
     execution_store::{
         CleanupOptions, ExecutionRecord, ExecutionRecordOptions, ExecutionStore,
-        ExecutionStoreOptions,
+        ExecutionStoreOptions, SHARED_STORE_ENV,
     },
     failure_handler::{handle_failure, Config as FailureConfig},
-    get_timestamp,
-    isolation::{run_as_isolated_user, run_isolated, IsolationOptions},
-    output_blocks::{FinishBlockOptions, StartBlockOptions},
-    set_current_execution, setup_signal_handlers,
-    status_formatter::{list_executions, query_status},
+    follow_execution_log, get_timestamp,
+    isolation::{run_as_isolated_user, run_isolated, tee_child_output, IsolationOptions},
+    isolation_stack_status_lines, link_to_parent_execution, list_execution_trees,
+    list_executions_with_layout,
+    log_follower::FollowOptions,
+    log_viewer::{view_execution_log, LogViewOptions},
+    output_blocks::StartBlockOptions,
+    output_theme::{report_finish, FinishReport, OutputStyle, SILENT_TAIL_LINES},
+    query_status_tree, query_status_with_layout, record_provenance, run_dashboard,
+    run_store_command, set_current_execution, setup_signal_handlers,
+    status_formatter::{format_cleanup_report, list_executions_filtered, query_status},
     substitution::{process_command, ProcessOptions},
     upload_execution_log,
     usage::print_usage,
-    user_manager::{
-        create_isolated_user, delete_user, get_current_user_groups, has_sudo_access,
-        CreateIsolatedUserOptions, DeleteUserOptions,
-    },
-    write_log_file, LogHeaderParams,
+    user_manager::{delete_user, get_current_user, prepare_isolated_user, DeleteUserOptions},
+    version_info::print_version,
+    write_log_file, EventStream, LogHeaderParams, PROVENANCE_ENV,
 };
 
 /// Configuration from environment variables
@@ -56,8 +62,14 @@ struct Config {
     use_command_stream: bool,
     /// Disable execution tracking
     disable_tracking: bool,
+    /// Capture git/toolchain provenance
+    capture_provenance: bool,
     /// Custom app folder for execution tracking
     app_folder: Option<String>,
+    /// Root of a system-wide store shared by all users
+    shared_store: Option<String>,
+    /// Read every user's executions from the shared store (--all-users)
+    all_users: bool,
 }
 
 impl Config {
@@ -74,7 +86,10 @@ impl Config {
             substitutions_path: env::var("START_SUBSTITUTIONS_PATH").ok(),
             use_command_stream: env_bool("START_USE_COMMAND_STREAM"),
             disable_tracking: env_bool("START_DISABLE_TRACKING"),
+            capture_provenance: env_bool(PROVENANCE_ENV),
             app_folder: env::var("START_APP_FOLDER").ok().or(default_app_folder),
+            shared_store: env::var(SHARED_STORE_ENV).ok().filter(|v| !v.is_empty()),
+            all_users: false,
         }
     }
 
@@ -84,13 +99,18 @@ impl Config {
             return None;
         }
 
-        let options = ExecutionStoreOptions {
-            verbose: self.verbose,
-            app_folder: self.app_folder.as_ref().map(PathBuf::from),
-            ..ExecutionStoreOptions::default()
+        let store = match self.shared_store {
+            Some(ref root) => {
+                ExecutionStore::shared(Path::new(root), &get_current_user(), self.verbose)
+            }
+            None => ExecutionStore::with_options(ExecutionStoreOptions {
+                verbose: self.verbose,
+                app_folder: self.app_folder.as_ref().map(PathBuf::from),
+                ..ExecutionStoreOptions::default()
+            }),
         };
 
-        Some(ExecutionStore::with_options(options))
+        Some(store.with_all_users(self.all_users))
     }
 }
 
@@ -102,7 +122,7 @@ fn main() {
     // Set up signal handlers for graceful cleanup on interruption
     setup_signal_handlers();
 
-    let config = Config::from_env();
+    let mut config = Config::from_env();
     let args: Vec<String> = env::args().skip(1).collect();
 
     // Handle --version flag
@@ -138,13 +158,22 @@ fn main() {
     let wrapper_options = parsed.wrapper_options;
     let parsed_command = parsed.command.clone();
 
+    if wrapper_options.all_users && config.shared_store.is_none() {
+        eprintln!(
+            "Error: --all-users requires a shared store (set {})",
+            SHARED_STORE_ENV
+        );
+        process::exit(1);
+    }
+    config.all_users = wrapper_options.all_users;
+
     if let Some(ref uuid) = wrapper_options.status {
-        handle_status_query(&config, uuid, wrapper_options.output_format.as_deref());
+        handle_status_query(&config, &wrapper_options, uuid);
         process::exit(0);
     }
 
     if wrapper_options.list {
-        handle_list_query(&config, wrapper_options.output_format.as_deref());
+        handle_list_query(&config, &wrapper_options);
         process::exit(0);
     }
 
@@ -152,6 +181,28 @@ fn main() {
         process::exit(handle_upload_log_query(&config, identifier));
     }
 
+    if let Some(ref identifier) = wrapper_options.follow {
+        process::exit(handle_follow_query(&config, &wrapper_options, identifier));
+    }
+
+    if let Some(ref identifier) = wrapper_options.log {
+        let store = config.create_execution_store();
+        let log_view = LogViewOptions {
+            tail: wrapper_options.tail,
+            ..wrapper_options.log_view.clone()
+        };
+        let result = view_execution_log(store.as_ref(), identifier, &log_view);
+        print_query_result(result.is_ok(), None, result.err());
+        process::exit(0);
+    }
+
+    if wrapper_options.dashboard {
+        let store = config.create_execution_store();
+        let result = run_dashboard(store.as_ref(), &record_filter(&wrapper_options));
+        print_query_result(result.is_ok(), None, result.err());
+        process::exit(0);
+    }
+
     if let Some(ref identifier) = wrapper_options.stop {
         handle_control_query(&config, identifier, ControlAction::Stop);
         process::exit(0);
@@ -167,6 +218,13 @@ fn main() {
         process::exit(0);
     }
 
+    if has_store_command(&wrapper_options) {
+        let store = config.create_execution_store();
+        let result = run_store_command(&wrapper_options, store.as_ref());
+        print_query_result(result.success, result.output, result.error);
+        process::exit(0);
+    }
+
     // Check if no command was provided
     if parsed_command.is_empty() {
         eprintln!("Error: No command provided");
@@ -206,6 +264,26 @@ fn main() {
         .clone()
         .unwrap_or_else(generate_uuid);
 
+    // Theme, color and width of the start/finish blocks
+    let mode = output_mode(&wrapper_options);
+    let ci = ci_enabled(wrapper_options.ci);
+    OutputStyle::detect(wrapper_options.theme.as_deref(), mode, ci).install();
+
+    // NDJSON event stream for IDEs and harnesses (--events)
+    if let Some(ref spec) = wrapper_options.events {
+        match EventStream::open(spec, &session_id) {
+            Ok(stream) => stream.install(),
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(ref sub) = substitution_result {
+        let fields = serde_json::json!({"original": parsed_command, "command": sub.command});
+        emit_event("substitution-applied", fields);
+    }
+
     // Main execution
     if has_isolation(&wrapper_options) || wrapper_options.user {
         run_with_isolation(
@@ -218,6 +296,7 @@ fn main() {
     } else {
         run_direct(
             &config,
+            &wrapper_options,
             &command,
             &parsed_command,
             substitution_result.as_ref(),
@@ -226,121 +305,47 @@ fn main() {
     }
 }
 
-/// Print version information
-fn print_version(verbose: bool) {
-    let version = env!("CARGO_PKG_VERSION");
-    println!("start-command version: {} (Rust)", version);
-    println!();
-
-    println!("OS: {}", std::env::consts::OS);
-    println!("Architecture: {}", std::env::consts::ARCH);
-    println!();
-
-    // Check for installed isolation tools
-    println!("Isolation tools:");
-
-    if verbose {
-        println!("[verbose] Checking isolation tools...");
-    }
-
-    // Check screen
-    if let Some(version) = get_tool_version("screen", "-v", verbose) {
-        println!("  screen: {}", version);
-    } else {
-        println!("  screen: not installed");
-    }
-
-    // Check tmux
-    if let Some(version) = get_tool_version("tmux", "-V", verbose) {
-        println!("  tmux: {}", version);
-    } else {
-        println!("  tmux: not installed");
-    }
-
-    // Check docker
-    if let Some(version) = get_tool_version("docker", "--version", verbose) {
-        println!("  docker: {}", version);
+/// Print the output of a query, or its error and exit with code 1 on failure
+fn print_query_result(success: bool, output: Option<String>, error: Option<String>) {
+    if success {
+        if let Some(output) = output {
+            // A pipeline reader (`| head`) may stop early; that is not an error
+            let _ = writeln!(std::io::stdout(), "{}", output);
+        }
     } else {
-        println!("  docker: not installed");
-    }
-}
-
-/// Get version of an installed tool
-fn get_tool_version(tool_name: &str, version_flag: &str, verbose: bool) -> Option<String> {
-    let which_cmd = if cfg!(windows) { "where" } else { "which" };
-
-    // Check if tool exists
-    let exists = Command::new(which_cmd)
-        .arg(tool_name)
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false);
-
-    if !exists {
-        if verbose {
-            println!("[verbose] {}: not found in PATH", tool_name);
+        if let Some(error) = error {
+            eprintln!("Error: {}", error);
         }
-        return None;
-    }
-
-    // Get version
-    let output = Command::new(tool_name).arg(version_flag).output().ok()?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let combined = format!("{}{}", stdout, stderr).trim().to_string();
-
-    if verbose {
-        println!(
-            "[verbose] {} {}: exit={}, output=\"{}\"",
-            tool_name,
-            version_flag,
-            output.status.code().unwrap_or(-1),
-            &combined[..100.min(combined.len())]
-        );
-    }
-
-    if combined.is_empty() {
-        return None;
+        process::exit(1);
     }
-
-    combined.lines().next().map(String::from)
 }
 
 /// Handle status query
-fn handle_status_query(config: &Config, uuid: &str, output_format: Option<&str>) {
+fn handle_status_query(config: &Config, options: &WrapperOptions, uuid: &str) {
     let store = config.create_execution_store();
-    let result = query_status(store.as_ref(), uuid, output_format);
-
-    if result.success {
-        if let Some(output) = result.output {
-            println!("{}", output);
-        }
+    let output_format = options.output_format.as_deref();
+    let result = if let Some(ref layout) = options.layout {
+        query_status_with_layout(store.as_ref(), uuid, layout)
+    } else if options.tree {
+        query_status_tree(store.as_ref(), uuid, output_format)
     } else {
-        if let Some(error) = result.error {
-            eprintln!("Error: {}", error);
-        }
-        process::exit(1);
-    }
+        query_status(store.as_ref(), uuid, output_format)
+    };
+    print_query_result(result.success, result.output, result.error);
 }
 
 /// Handle list query
-fn handle_list_query(config: &Config, output_format: Option<&str>) {
+fn handle_list_query(config: &Config, options: &WrapperOptions) {
     let store = config.create_execution_store();
-    let result = list_executions(store.as_ref(), output_format);
-
-    if result.success {
-        if let Some(output) = result.output {
-            println!("{}", output);
-        }
+    let (filter, output_format) = (record_filter(options), options.output_format.as_deref());
+    let result = if let Some(ref layout) = options.layout {
+        list_executions_with_layout(store.as_ref(), &filter, layout)
+    } else if options.tree {
+        list_execution_trees(store.as_ref(), &filter, output_format)
     } else {
-        if let Some(error) = result.error {
-            eprintln!("Error: {}", error);
-        }
-        process::exit(1);
-    }
+        list_executions_filtered(store.as_ref(), &filter, output_format)
+    };
+    print_query_result(result.success, result.output, result.error);
 }
 
 /// Handle upload-log query
@@ -355,23 +360,30 @@ fn handle_upload_log_query(config: &Config, identifier: &str) -> i32 {
     }
 }
 
-/// Handle detached execution control query
-fn handle_control_query(config: &Config, identifier: &str, action: ControlAction) {
+/// Handle --follow: stream the log and exit with the execution's exit code
+fn handle_follow_query(config: &Config, options: &WrapperOptions, identifier: &str) -> i32 {
     let store = config.create_execution_store();
-    let result = control_execution(store.as_ref(), identifier, action);
-
-    if result.success {
-        if let Some(output) = result.output {
-            println!("{}", output);
-        }
-    } else {
-        if let Some(error) = result.error {
+    let follow_options = FollowOptions { tail: options.tail };
+    match follow_execution_log(store.as_ref(), identifier, &follow_options) {
+        Ok(code) => code,
+        Err(error) => {
             eprintln!("Error: {}", error);
+            1
         }
-        process::exit(1);
     }
 }
 
+/// Handle detached execution control query
+fn handle_control_query(config: &Config, identifier: &str, action: ControlAction) {
+    // Look across users so that someone else's execution is refused
+    // rather than reported as missing
+    let store = config
+        .create_execution_store()
+        .map(|store| store.with_all_users(true));
+    let result = control_execution(store.as_ref(), identifier, action);
+    print_query_result(result.success, result.output, result.error);
+}
+
 /// Handle --cleanup flag
 /// Cleans up stale "executing" records (processes that crashed or were killed)
 fn handle_cleanup(config: &Config, dry_run: bool) {
@@ -388,57 +400,21 @@ fn handle_cleanup(config: &Config, dry_run: bool) {
         ..Default::default()
     });
 
-    // Print any errors
     for error in &result.errors {
         eprintln!("Error: {}", error);
     }
-
-    if result.records.is_empty() {
-        println!("No stale records found.");
-        return;
-    }
-
-    if dry_run {
-        println!(
-            "Found {} stale record(s) that would be cleaned up:\n",
-            result.records.len()
-        );
-    } else {
-        println!("Cleaned up {} stale record(s):\n", result.cleaned);
-    }
-
-    for record in &result.records {
-        // Parse start time for display
-        let start_time_display = chrono::DateTime::parse_from_rfc3339(&record.start_time)
-            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
-            .unwrap_or_else(|_| record.start_time.clone());
-
-        println!("  UUID: {}", record.uuid);
-        println!("  Command: {}", record.command);
-        println!("  Started: {}", start_time_display);
-        println!(
-            "  PID: {}",
-            record
-                .pid
-                .map(|p| p.to_string())
-                .unwrap_or("N/A".to_string())
-        );
-        println!();
-    }
-
-    if dry_run {
-        println!("Run with --cleanup to actually clean up these records.");
-    }
+    println!("{}", format_cleanup_report(&result, dry_run));
 }
 
 /// Run command with isolation
 fn run_with_isolation(
     config: &Config,
-    wrapper_options: &start_command::WrapperOptions,
+    wrapper_options: &WrapperOptions,
     command: &str,
     _use_command_stream: bool,
     session_id: &str,
 ) {
+    let style = OutputStyle::current();
     let environment = wrapper_options.isolated.as_deref();
     let mode = get_effective_mode(wrapper_options);
     let start_time = get_timestamp();
@@ -455,64 +431,24 @@ fn run_with_isolation(
     let mut created_user: Option<String> = None;
 
     if wrapper_options.user {
-        // Check for sudo access
-        if !has_sudo_access() {
-            eprintln!("Error: --isolated-user requires sudo access without password.");
-            eprintln!("Configure NOPASSWD in sudoers or run with appropriate permissions.");
-            process::exit(1);
-        }
-
-        // Get current user groups
-        let current_groups = get_current_user_groups();
-        let important_groups: Vec<&str> = ["sudo", "docker", "wheel", "admin"]
-            .iter()
-            .copied()
-            .filter(|g| current_groups.iter().any(|cg| cg == *g))
-            .collect();
-
-        extra_lines.push("[User Isolation] Creating new user...".to_string());
-        if !important_groups.is_empty() {
-            extra_lines.push(format!(
-                "[User Isolation] Inheriting groups: {}",
-                important_groups.join(", ")
-            ));
-        }
-
-        // Create the isolated user
-        let user_result = create_isolated_user(
-            wrapper_options.user_name.as_deref(),
-            &CreateIsolatedUserOptions::default(),
-        );
-
-        if !user_result.success {
-            eprintln!(
-                "Error: Failed to create isolated user: {}",
-                user_result.message
-            );
-            process::exit(1);
-        }
-
-        let username = user_result.username.unwrap();
-        extra_lines.push(format!("[User Isolation] Created user: {}", username));
-        if let Some(groups) = &user_result.groups {
-            if !groups.is_empty() {
-                extra_lines.push(format!(
-                    "[User Isolation] User groups: {}",
-                    groups.join(", ")
-                ));
+        let user_name = wrapper_options.user_name.as_deref();
+        match prepare_isolated_user(user_name, wrapper_options.keep_user) {
+            Ok((username, lines)) => {
+                extra_lines.extend(lines);
+                created_user = Some(username);
+            }
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                process::exit(1);
             }
         }
-        if wrapper_options.keep_user {
-            extra_lines.push("[User Isolation] User will be kept after completion".to_string());
-        }
-
-        created_user = Some(username);
     }
 
     // Add isolation info to extra lines (session name for reconnecting, see issue #67)
     if let Some(env) = environment {
         extra_lines.push(format!("[Isolation] Environment: {}, Mode: {}", env, mode));
         extra_lines.push(format!("[Isolation] Session: {}", session_name));
+        extra_lines.extend(isolation_stack_status_lines(wrapper_options));
     }
     if let Some(ref image) = effective_image {
         extra_lines.push(format!("[Isolation] Image: {}", image));
@@ -531,28 +467,34 @@ fn run_with_isolation(
     }
 
     // Print start block with session ID and isolation info
-    // For Docker isolation, defer the command display since virtual commands may be shown first
-    let is_docker_isolation = environment == Some("docker");
+    // For container isolation, defer the command display since virtual commands may be shown first
+    let is_docker_isolation = matches!(environment, Some("docker" | "podman"));
     let extra_lines_refs: Vec<&str> = extra_lines.iter().map(|s| s.as_str()).collect();
-    println!(
-        "{}",
-        create_start_block(&StartBlockOptions {
-            session_id,
-            timestamp: &start_time,
-            command,
-            extra_lines: if extra_lines.is_empty() {
-                None
-            } else {
-                Some(extra_lines_refs)
-            },
-            style: None,
-            width: None,
-            defer_command: is_docker_isolation,
-        })
-    );
-    // Only print empty line when not deferring command (docker isolation handles its own spacing)
-    if !is_docker_isolation {
-        println!();
+    if style.mode.shows_start() {
+        println!(
+            "{}",
+            style.start_section(
+                command,
+                &create_start_block(&StartBlockOptions {
+                    session_id,
+                    timestamp: &start_time,
+                    command,
+                    extra_lines: if extra_lines.is_empty() {
+                        None
+                    } else {
+                        Some(extra_lines_refs)
+                    },
+                    style: Some(style.theme.as_str()),
+                    width: style.width,
+                    color: style.color,
+                    defer_command: is_docker_isolation,
+                })
+            )
+        );
+        // Only print empty line when not deferring command (docker isolation handles its own spacing)
+        if !is_docker_isolation {
+            println!();
+        }
     }
 
     // Create log header
@@ -584,6 +526,11 @@ fn run_with_isolation(
         options: Some(opts_map),
         ..Default::default()
     });
+    apply_launch_annotations(&mut execution_record, wrapper_options);
+    link_to_parent_execution(&mut execution_record);
+    let tool_versions = (execution_store.is_some() && config.capture_provenance)
+        .then(|| record_provenance(&mut execution_record))
+        .flatten();
     if let Some(ref store) = execution_store {
         match store.save(&execution_record) {
             Err(e) if config.verbose => {
@@ -610,6 +557,7 @@ fn run_with_isolation(
         1,
     );
     write_log_file(&log_file_path, &log_content);
+    emit_started(command, environment, mode, &log_file_path);
 
     let result = if let Some(env) = environment {
         // Run in isolation backend
@@ -631,10 +579,14 @@ fn run_with_isolation(
             shell: wrapper_options.shell.clone(),
             log_path: Some(log_file_path.clone()),
         };
-        run_isolated(env, command, &options)
+        run_isolated(
+            env,
+            &build_next_level_command(wrapper_options, command),
+            &options,
+        )
     } else if let Some(ref user) = created_user {
         // Run directly as the created user
-        run_as_isolated_user(command, user)
+        run_as_isolated_user(command, user, Some(&log_file_path))
     } else {
         // This shouldn't happen
         start_command::IsolationResult {
@@ -668,6 +620,9 @@ fn run_with_isolation(
         if mode != "detached" {
             execution_record.complete(exit_code);
         }
+        if let Some(versions) = tool_versions {
+            versions.attach(&mut execution_record);
+        }
         if let Err(e) = store.save(&execution_record) {
             if config.verbose {
                 eprintln!("[ExecutionStore] Warning: Failed to update record: {}", e);
@@ -678,17 +633,25 @@ fn run_with_isolation(
 
     // Cleanup: delete the created user if we created one (unless --keep-user)
     // This output goes to stdout but NOT inside the boxes - it's operational info
+    // (--silent and --summary-only only keep a failed deletion)
+    let notes = style.mode.shows_output();
     if let Some(ref user) = created_user {
         if !wrapper_options.keep_user {
-            println!("[User Isolation] Cleaning up user: {}", user);
+            if notes {
+                println!("[User Isolation] Cleaning up user: {}", user);
+            }
             let delete_result = delete_user(user, &DeleteUserOptions { remove_home: true });
-            if delete_result.success {
-                println!("[User Isolation] User deleted successfully");
-            } else {
+            let fields = serde_json::json!({"kind": "user", "target": user, "success": delete_result.success});
+            emit_event("cleanup", fields);
+            if !delete_result.success {
                 println!("[User Isolation] Warning: {}", delete_result.message);
+            } else if notes {
+                println!("[User Isolation] User deleted successfully");
             }
-            println!();
-        } else {
+            if notes {
+                println!();
+            }
+        } else if notes {
             println!(
                 "[User Isolation] Keeping user: {} (use 'sudo userdel -r {}' to delete)",
                 user, user
@@ -698,24 +661,21 @@ fn run_with_isolation(
     }
 
     // Print finish block with result message inside
-    // Add empty line before finish block for visual separation
-    println!();
-    let duration_ms = start_instant.elapsed().as_secs_f64() * 1000.0;
-    // Convert extra_lines to &str references for the finish block
     let extra_lines_refs: Vec<&str> = extra_lines.iter().map(|s| s.as_str()).collect();
-    println!(
-        "{}",
-        create_finish_block(&FinishBlockOptions {
+    report_finish(
+        &style,
+        &FinishReport {
+            command,
             session_id,
             timestamp: &end_time,
             exit_code,
-            log_path: &log_file_path.to_string_lossy(),
-            duration_ms: Some(duration_ms),
+            duration_ms: start_instant.elapsed().as_secs_f64() * 1000.0,
+            log_path: &log_file_path,
             result_message: Some(&result.message),
             extra_lines: Some(extra_lines_refs),
-            style: None,
-            width: None,
-        })
+            detached: mode == "detached" && result.success,
+            tail: wrapper_options.tail.unwrap_or(SILENT_TAIL_LINES),
+        },
     );
 
     process::exit(exit_code);
@@ -724,11 +684,13 @@ fn run_with_isolation(
 /// Run command directly (without isolation)
 fn run_direct(
     config: &Config,
+    wrapper_options: &WrapperOptions,
     command: &str,
     parsed_command: &str,
     substitution_result: Option<&start_command::SubstitutionResult>,
     session_id: &str,
 ) {
+    let style = OutputStyle::current();
     let start_time = get_timestamp();
     let start_instant = std::time::Instant::now();
 
@@ -744,19 +706,25 @@ fn run_direct(
     };
 
     // Print start block with session ID (no extra lines for direct execution)
-    println!(
-        "{}",
-        create_start_block(&StartBlockOptions {
-            session_id,
-            timestamp: &start_time,
-            command: &display_command,
-            extra_lines: None,
-            style: None,
-            width: None,
-            defer_command: false,
-        })
-    );
-    println!();
+    if style.mode.shows_start() {
+        println!(
+            "{}",
+            style.start_section(
+                &display_command,
+                &create_start_block(&StartBlockOptions {
+                    session_id,
+                    timestamp: &start_time,
+                    command: &display_command,
+                    extra_lines: None,
+                    style: Some(style.theme.as_str()),
+                    width: style.width,
+                    color: style.color,
+                    defer_command: false,
+                })
+            )
+        );
+        println!();
+    }
     let command_name = command.split_whitespace().next().unwrap_or(command);
 
     // Determine shell
@@ -785,6 +753,11 @@ fn run_direct(
         pid: Some(process::id()),
         ..Default::default()
     });
+    apply_launch_annotations(&mut execution_record, wrapper_options);
+    link_to_parent_execution(&mut execution_record);
+    let tool_versions = (execution_store.is_some() && config.capture_provenance)
+        .then(|| record_provenance(&mut execution_record))
+        .flatten();
 
     // Save initial execution record and set up signal cleanup
     if let Some(ref store) = execution_store {
@@ -827,6 +800,7 @@ fn run_direct(
     ));
     log_content.push_str(&format!("{}\n\n", "=".repeat(50)));
     write_log_file(&log_file_path, &log_content);
+    emit_started(command, None, "attached", &log_file_path);
 
     // Execute the command with piped stdout/stderr so we can capture and display output
     // Using spawn() instead of output() to stream data in real-time (Issue #57)
@@ -849,65 +823,30 @@ fn run_direct(
 
             write_log_file(&log_file_path, &log_content);
 
-            let duration_ms = start_instant.elapsed().as_secs_f64() * 1000.0;
-            println!();
-            println!(
-                "{}",
-                create_finish_block(&FinishBlockOptions {
+            report_finish(
+                &style,
+                &FinishReport {
+                    command: &display_command,
                     session_id,
                     timestamp: &end_time,
                     exit_code: 1,
-                    log_path: &log_file_path.to_string_lossy(),
-                    duration_ms: Some(duration_ms),
+                    duration_ms: start_instant.elapsed().as_secs_f64() * 1000.0,
+                    log_path: &log_file_path,
                     result_message: None,
                     extra_lines: None,
-                    style: None,
-                    width: None,
-                })
+                    detached: false,
+                    tail: wrapper_options.tail.unwrap_or(SILENT_TAIL_LINES),
+                },
             );
 
             process::exit(1);
         }
     };
 
-    // Read stdout and stderr, displaying and capturing in real-time
-    // Use threads to read both streams concurrently to avoid deadlocks
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
-
-    let stdout_log_path = log_file_path.clone();
-    let stdout_handle = std::thread::spawn(move || {
-        let mut output = String::new();
-        if let Some(stdout) = stdout {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines().map_while(Result::ok) {
-                println!("{}", line);
-                append_log_file(&stdout_log_path, &format!("{}\n", line));
-                output.push_str(&line);
-                output.push('\n');
-            }
-        }
-        output
-    });
-
-    let stderr_log_path = log_file_path.clone();
-    let stderr_handle = std::thread::spawn(move || {
-        let mut output = String::new();
-        if let Some(stderr) = stderr {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines().map_while(Result::ok) {
-                eprintln!("{}", line);
-                append_log_file(&stderr_log_path, &format!("{}\n", line));
-                output.push_str(&line);
-                output.push('\n');
-            }
-        }
-        output
-    });
-
-    // Wait for output threads to complete
-    let stdout_output = stdout_handle.join().unwrap_or_default();
-    let stderr_output = stderr_handle.join().unwrap_or_default();
+    // Read stdout and stderr, displaying (unless --silent or --summary-only)
+    // and capturing in real-time
+    let (stdout_output, stderr_output) =
+        tee_child_output(&mut child, &log_file_path, style.mode.shows_output());
 
     // Wait for child process to exit
     let exit_code = match child.wait() {
@@ -947,26 +886,28 @@ fn run_direct(
     );
 
     // Print finish block (no result_message for direct execution)
-    let duration_ms = start_instant.elapsed().as_secs_f64() * 1000.0;
-    println!();
-    println!(
-        "{}",
-        create_finish_block(&FinishBlockOptions {
+    report_finish(
+        &style,
+        &FinishReport {
+            command: &display_command,
             session_id,
             timestamp: &end_time,
             exit_code,
-            log_path: &log_file_path.to_string_lossy(),
-            duration_ms: Some(duration_ms),
+            duration_ms: start_instant.elapsed().as_secs_f64() * 1000.0,
+            log_path: &log_file_path,
             result_message: None,
             extra_lines: None,
-            style: None,
-            width: None,
-        })
+            detached: false,
+            tail: wrapper_options.tail.unwrap_or(SILENT_TAIL_LINES),
+        },
     );
 
     // Update execution record with completion status
     if let Some(ref store) = execution_store {
         execution_record.complete(exit_code);
+        if let Some(versions) = tool_versions {
+            versions.attach(&mut execution_record);
+        }
         if let Err(e) = store.save(&execution_record) {
             if config.verbose {
                 eprintln!(
@@ -5,16 +5,17 @@
 //! 2. $ [wrapper-options] command [command-options]
 //!
 //! Wrapper Options:
-//! --isolated, --isolation, -i <backend> Run in isolated environment (screen, tmux, docker, ssh)
+//! --isolated, --isolation, -i <backend> Run in isolated environment (screen, tmux, docker, podman, ssh)
+//!                                  A sequence like "screen ssh docker" stacks nested levels
 //! --attached, -a                   Run in attached mode (foreground)
 //! --detached, -d                   Run in detached mode (background)
-//! --session, -s <name>             Session name for isolation
-//! --image <image>                  Docker image (optional, defaults to OS-matched image)
-//! --volume, -v <host:container[:mode]> Docker bind mount/volume (repeatable, docker only)
-//! --mount <mount-spec>             Docker --mount spec (repeatable, docker only)
-//! --env, -e <KEY=VALUE>            Environment variable for docker container (repeatable, docker only)
-//! --privileged                     Run docker container in privileged mode (docker only)
-//! --endpoint <endpoint>            SSH endpoint (required for ssh isolation, e.g., user@host)
+//! --session, -s <name>             Session name for isolation (a sequence names each stacked level)
+//! --image <image>                  Docker image (optional, defaults to OS-matched image; per-level sequence)
+//! --volume, -v <host:container[:mode]> Docker bind mount/volume (repeatable, docker/podman only)
+//! --mount <mount-spec>             Docker --mount spec (repeatable, docker/podman only)
+//! --env, -e <KEY=VALUE>            Environment variable for docker container (repeatable, docker/podman only)
+//! --privileged                     Run docker container in privileged mode (docker/podman only)
+//! --endpoint <endpoint>            SSH endpoint (required for ssh isolation, e.g., user@host; per-level sequence)
 //! --isolated-user, -u [username]   Create isolated user with same permissions
 //! --keep-user                      Keep isolated user after command completes
 //! --keep-alive, -k                 Keep isolation environment alive after command exits
@@ -28,19 +29,86 @@
 //! --upload-log <uuid-or-session>   Upload the stored log for a tracked execution
 //! --stop <uuid-or-session-name>    Ask a detached execution to stop gracefully
 //! --terminate <uuid-or-session-name> Terminate a detached execution immediately
-
+//! --follow <uuid-or-session>       Stream an execution's log until it finishes, exiting with its code
+//! --tail <n>                       Start --follow with only the last n lines of the log
+//! --dashboard                      Interactive terminal dashboard of executions (honors --list filters)
+//! --log <uuid-or-session>          Print the stored log of an execution
+//! --ansi <mode>                    ANSI colors in --log output: auto, keep, strip (default: auto)
+//! --output-only                    Show --log without the start-command header and footer
+//! --stderr-only                    Show only stderr in --log (when recorded separately)
+//! --lines <start:end>              Show only a line range of --log
+//! --pager                          Page --log output through $PAGER
+//! --stats                          Show execution statistics grouped by command, directory and backend
+//! --group-by <group>               Limit --stats to one grouping (command, prefix, directory, backend)
+//! --export <path>                  Export execution records (jsonl, csv, lino; .tar bundles logs)
+//! --export-format <format>         Export record format (default: inferred from the path)
+//! --with-logs                      Bundle exported records with their logs into a tar archive
+//! --import <path>                  Merge exported records into the store (de-duplicated by UUID)
+//! --since, --until <time>          Filter records by start time (RFC 3339, YYYY-MM-DD, 7d, 24h)
+//! --command-contains <text>        Filter records by command text
+//! --backend <name>                 Filter records by isolation backend (direct for none)
+//! --outcome <outcome>              Filter records by outcome (succeeded, failed, executing)
+//! --limit <n>                      Keep only the newest N matching records
+//! --tag <tag>                      Tag the execution (repeatable; filters --list, --stats, --export, --grep)
+//! --label <key=value>              Label the execution (repeatable; filters --list, --stats, --export, --grep)
+//! --annotate <uuid-or-session>     Add a note, tags or labels to a tracked execution
+//! --note <text>                    Note text for --annotate
+//! --tree                           Show --status/--list as parent/child execution trees
+//! --schema                         Print the JSON Schema for execution records
+//! --repair                         Reconcile lino/links stores, recover finished runs, quarantine bad entries
+//! --repair-dry-run                 Show what --repair would change without writing
+//! --grep <pattern>                 Search stored logs (regex; honors --list filters)
+//! --ignore-case                    Match the --grep pattern case-insensitively
+//! --context <n>                    Show n lines of context around --grep matches
+//! --all-users                      Include every user's executions from the shared store
+//! --sync <remote>                  Push and pull execution records through a git remote
+//! --sync-logs                      Also sync compressed logs of finished executions with --sync
+//! --diff <id-a> <id-b>             Compare two executions and diff their normalized logs
+//! --columns <fields>               Show --status/--list as a table of comma-separated fields
+//! --format <template>              Show --status/--list as lines like '{{uuid}} {{exitCode}}'
+//! --report html                    Render filtered executions as a self-contained HTML report
+//! --output <file>                  Write the --report to a file instead of stdout
+//! --junit <file>                   Write filtered executions as JUnit XML test cases
+//! --parent <id>                    Limit --junit to the executions nested under this one
+//! --theme <theme>                  Output theme: timeline, ascii, compact, boxed
+//! --quiet, -q                      Print only the command output, no start/finish blocks
+//! --silent                         Print nothing unless the command fails (then its last lines)
+//! --summary-only                   Print only the finish block
+//! --events <path|fd:N>             Write NDJSON events about the run to a file or descriptor
+//! --ci                             GitHub Actions log groups, annotations and step summary
+
+use std::collections::BTreeMap;
 use std::env;
 
 use crate::isolation::get_default_docker_image;
+use crate::log_viewer::LogViewOptions;
+use crate::record_filter::RecordFilter;
+use crate::record_template::RecordLayout;
+
+#[path = "args_parser_output.rs"]
+mod output_options;
+#[path = "args_parser_query.rs"]
+mod query_options;
+#[path = "args_parser_stack.rs"]
+mod stack_options;
+pub use stack_options::MAX_ISOLATION_DEPTH;
+#[path = "args_parser_store.rs"]
+mod store_options;
+pub use self::output_options::output_mode;
+pub use self::store_options::{has_store_command, record_filter};
 
 /// Valid isolation backends
-pub const VALID_BACKENDS: [&str; 4] = ["screen", "tmux", "docker", "ssh"];
+pub const VALID_BACKENDS: [&str; 5] = ["screen", "tmux", "docker", "podman", "ssh"];
+
+/// Container backends, which take --image and the docker runtime options
+pub const CONTAINER_BACKENDS: [&str; 2] = ["docker", "podman"];
 
 /// Valid shell options for --shell
 pub const VALID_SHELLS: [&str; 4] = ["auto", "bash", "zsh", "sh"];
 
 /// Valid output formats for query output
-pub const VALID_OUTPUT_FORMATS: [&str; 3] = ["links-notation", "json", "text"];
+pub const VALID_OUTPUT_FORMATS: [&str; 6] =
+    ["links-notation", "json", "text", "yaml", "csv", "ndjson"];
 
 /// UUID v4 regex pattern for validation
 const UUID_REGEX: &str = r"^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$";
@@ -60,8 +128,16 @@ pub fn generate_uuid() -> String {
 /// Wrapper options parsed from command line
 #[derive(Debug, Clone)]
 pub struct WrapperOptions {
-    /// Isolation backend: screen, tmux, docker, ssh
+    /// Isolation backend: screen, tmux, docker, podman, ssh (the outermost level when stacked)
     pub isolated: Option<String>,
+    /// Every isolation level, outermost first (one entry without stacking)
+    pub isolated_stack: Vec<String>,
+    /// Container image of each stacked level (None for non-container levels)
+    pub image_stack: Vec<Option<String>>,
+    /// SSH endpoint of each stacked level (None for non-ssh levels)
+    pub endpoint_stack: Vec<Option<String>>,
+    /// Session name of each stacked level (the outermost may be generated later)
+    pub session_stack: Vec<Option<String>>,
     /// Run in attached mode
     pub attached: bool,
     /// Run in detached mode
@@ -108,22 +184,102 @@ pub struct WrapperOptions {
     pub list: bool,
     /// UUID/session name whose stored log should be uploaded
     pub upload_log: Option<String>,
-    /// Output format for status/list (links-notation, json, text)
+    /// Output format for status/list (links-notation, json, text, yaml, csv, ndjson)
     pub output_format: Option<String>,
     /// UUID/session name to stop gracefully
     pub stop: Option<String>,
     /// UUID/session name to terminate immediately
     pub terminate: Option<String>,
+    /// UUID/session name whose log should be streamed until it finishes
+    pub follow: Option<String>,
+    /// Lines of existing log to show before following (--tail)
+    pub tail: Option<usize>,
+    /// Show the interactive terminal dashboard of executions
+    pub dashboard: bool,
+    /// UUID/session name whose stored log should be printed
+    pub log: Option<String>,
+    /// How to print the log with --log
+    pub log_view: LogViewOptions,
     /// Clean up stale "executing" records
     pub cleanup: bool,
     /// Show what would be cleaned without actually cleaning
     pub cleanup_dry_run: bool,
+    /// Show execution statistics
+    pub stats: bool,
+    /// Single grouping for --stats (command, prefix, directory, backend)
+    pub group_by: Option<String>,
+    /// Path to export execution records to
+    pub export: Option<String>,
+    /// Export record format (jsonl, csv, lino)
+    pub export_format: Option<String>,
+    /// Bundle exported records with their logs into a tar archive
+    pub with_logs: bool,
+    /// Path of an export to merge into the store
+    pub import: Option<String>,
+    /// Record filters for --list, --stats, --export and --grep
+    pub filter: RecordFilter,
+    /// Tags to attach at launch or with --annotate (filters in list modes)
+    pub tags: Vec<String>,
+    /// Labels to attach at launch or with --annotate (filters in list modes)
+    pub labels: BTreeMap<String, String>,
+    /// UUID/session name of an execution to annotate
+    pub annotate: Option<String>,
+    /// Note text to add with --annotate
+    pub note: Option<String>,
+    /// Show --status/--list as parent/child execution trees
+    pub tree: bool,
+    /// Print the JSON Schema for execution records
+    pub schema: bool,
+    /// Reconcile the lino and links stores and quarantine undecodable entries
+    pub repair: bool,
+    /// Report what --repair would change without writing
+    pub repair_dry_run: bool,
+    /// Regex to search for in the logs of stored executions
+    pub grep: Option<String>,
+    /// Match the --grep pattern case-insensitively
+    pub grep_ignore_case: bool,
+    /// Lines of context around each --grep match
+    pub grep_context: Option<usize>,
+    /// Read the executions of every user of the shared store
+    pub all_users: bool,
+    /// Git remote (URL or repository path) to sync execution records with
+    pub sync: Option<String>,
+    /// Also sync compressed logs of finished executions with --sync
+    pub sync_logs: bool,
+    /// Two executions (UUIDs or session names) to compare
+    pub diff: Option<(String, String)>,
+    /// Table columns (--columns) or line template (--format) for --status/--list
+    pub layout: Option<RecordLayout>,
+    /// Render filtered executions as a report in this format (html)
+    pub report: Option<String>,
+    /// File the --report is written to (stdout when not set)
+    pub output: Option<String>,
+    /// Write filtered executions to this file as a JUnit XML report
+    pub junit: Option<String>,
+    /// Limit --junit to executions nested under this UUID or session name
+    pub parent: Option<String>,
+    /// Theme of the start/finish blocks (timeline, ascii, compact, boxed)
+    pub theme: Option<String>,
+    /// Print only the command output, without the start/finish blocks
+    pub quiet: bool,
+    /// Print nothing unless the command fails, then its last lines and log path
+    pub silent: bool,
+    /// Print only the finish block
+    pub summary_only: bool,
+    /// Where to write the NDJSON event stream (a path or `fd:N`)
+    pub events: Option<String>,
+    /// Print for CI even when CI/GITHUB_ACTIONS are not set
+    pub ci: bool,
 }
 
 impl Default for WrapperOptions {
     fn default() -> Self {
         WrapperOptions {
             isolated: None,
+            isolated_stack: Vec::new(),
+            image_stack: Vec::new(),
+            endpoint_stack: Vec::new(),
+            session_stack: Vec::new(),
             attached: false,
             detached: false,
             session: None,
@@ -150,8 +306,46 @@ impl Default for WrapperOptions {
             output_format: None,
             stop: None,
             terminate: None,
+            follow: None,
+            tail: None,
+            dashboard: false,
+            log: None,
+            log_view: LogViewOptions::default(),
             cleanup: false,
             cleanup_dry_run: false,
+            stats: false,
+            group_by: None,
+            export: None,
+            export_format: None,
+            with_logs: false,
+            import: None,
+            filter: RecordFilter::default(),
+            tags: Vec::new(),
+            labels: BTreeMap::new(),
+            annotate: None,
+            note: None,
+            tree: false,
+            schema: false,
+            repair: false,
+            repair_dry_run: false,
+            grep: None,
+            grep_ignore_case: false,
+            grep_context: None,
+            all_users: false,
+            sync: None,
+            sync_logs: false,
+            diff: None,
+            layout: None,
+            report: None,
+            output: None,
+            junit: None,
+            parent: None,
+            theme: None,
+            quiet: false,
+            silent: false,
+            summary_only: false,
+            events: None,
+            ci: false,
         }
     }
 }
@@ -250,7 +444,7 @@ fn parse_option(
             return Ok(2);
         } else {
             return Err(format!(
-                "Option {} requires a backend argument (screen, tmux, docker, ssh)",
+                "Option {} requires a backend argument (screen, tmux, docker, podman, ssh)",
                 arg
             ));
         }
@@ -483,131 +677,20 @@ fn parse_option(
         return Ok(1);
     }
 
-    // --status <uuid-or-session-name>
-    if arg == "--status" {
-        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
-            options.status = Some(args[index + 1].clone());
-            return Ok(2);
-        } else {
-            return Err(format!(
-                "Option {} requires a UUID or session name argument",
-                arg
-            ));
-        }
-    }
-
-    // --status=<value>
-    if let Some(value) = arg.strip_prefix("--status=") {
-        if value.is_empty() {
-            return Err("Option --status requires a UUID or session name argument".to_string());
-        }
-        options.status = Some(value.to_string());
-        return Ok(1);
-    }
-
-    // --upload-log <uuid-or-session-name>
-    if arg == "--upload-log" {
-        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
-            options.upload_log = Some(args[index + 1].clone());
-            return Ok(2);
-        } else {
-            return Err(format!(
-                "Option {} requires a UUID or session name argument",
-                arg
-            ));
-        }
-    }
-
-    // --upload-log=<value>
-    if let Some(value) = arg.strip_prefix("--upload-log=") {
-        if value.is_empty() {
-            return Err("Option --upload-log requires a UUID or session name argument".to_string());
-        }
-        options.upload_log = Some(value.to_string());
-        return Ok(1);
-    }
-
-    // --stop <uuid-or-session-name>
-    if arg == "--stop" {
-        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
-            options.stop = Some(args[index + 1].clone());
-            return Ok(2);
-        } else {
-            return Err(format!(
-                "Option {} requires a UUID or session name argument",
-                arg
-            ));
-        }
+    // Launch output (--theme, --quiet, --silent, --summary-only, --events, --ci)
+    let consumed = output_options::parse_output_option(args, index, options)?;
+    if consumed > 0 {
+        return Ok(consumed);
     }
 
-    // --stop=<value>
-    if let Some(value) = arg.strip_prefix("--stop=") {
-        if value.is_empty() {
-            return Err("Option --stop requires a UUID or session name argument".to_string());
-        }
-        options.stop = Some(value.to_string());
-        return Ok(1);
-    }
-
-    // --terminate <uuid-or-session-name>
-    if arg == "--terminate" {
-        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
-            options.terminate = Some(args[index + 1].clone());
-            return Ok(2);
-        } else {
-            return Err(format!(
-                "Option {} requires a UUID or session name argument",
-                arg
-            ));
-        }
+    // Query and control modes (--status, --list, --stop, --follow, --log, ...)
+    let consumed = query_options::parse_query_option(args, index, options)?;
+    if consumed > 0 {
+        return Ok(consumed);
     }
 
-    // --terminate=<value>
-    if let Some(value) = arg.strip_prefix("--terminate=") {
-        if value.is_empty() {
-            return Err("Option --terminate requires a UUID or session name argument".to_string());
-        }
-        options.terminate = Some(value.to_string());
-        return Ok(1);
-    }
-
-    // --list
-    if arg == "--list" {
-        options.list = true;
-        return Ok(1);
-    }
-
-    // --output-format <format>
-    if arg == "--output-format" {
-        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
-            options.output_format = Some(args[index + 1].to_lowercase());
-            return Ok(2);
-        } else {
-            return Err(format!("Option {} requires a format argument", arg));
-        }
-    }
-
-    // --output-format=<value>
-    if arg.starts_with("--output-format=") {
-        options.output_format = Some(arg.split('=').nth(1).unwrap_or("").to_lowercase());
-        return Ok(1);
-    }
-
-    // --cleanup
-    if arg == "--cleanup" {
-        options.cleanup = true;
-        return Ok(1);
-    }
-
-    // --cleanup-dry-run
-    if arg == "--cleanup-dry-run" {
-        options.cleanup = true;
-        options.cleanup_dry_run = true;
-        return Ok(1);
-    }
-
-    // Not a recognized wrapper option
-    Ok(0)
+    // Store commands (--stats, --export, --import) and record filters
+    store_options::parse_store_option(args, index, options)
 }
 
 /// Validate parsed options and apply defaults
@@ -620,6 +703,9 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
         );
     }
 
+    // Split a stacked --isolated sequence and distribute per-level options
+    stack_options::resolve_isolation_stack(options)?;
+
     // Validate isolation backend
     if let Some(ref backend) = options.isolated {
         if !VALID_BACKENDS.contains(&backend.as_str()) {
@@ -630,8 +716,8 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
             ));
         }
 
-        // Docker uses --image or defaults to OS-matched image
-        if backend == "docker" && options.image.is_none() {
+        // Containers use --image or default to an OS-matched image
+        if CONTAINER_BACKENDS.contains(&backend.as_str()) && options.image.is_none() {
             options.image = Some(get_default_docker_image());
         }
 
@@ -649,24 +735,26 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
         return Err("--session option is only valid with --isolated".to_string());
     }
 
-    // Image is only valid with docker
-    if options.image.is_some() && options.isolated.as_deref() != Some("docker") {
-        return Err("--image option is only valid with --isolated docker".to_string());
+    // Image is only valid with a container backend
+    let is_container = |backend: &String| CONTAINER_BACKENDS.contains(&backend.as_str());
+    if options.image.is_some() && !options.isolated.as_ref().is_some_and(is_container) {
+        return Err("--image option is only valid with --isolated docker or podman".to_string());
     }
 
-    // Docker runtime options (--volume, --mount, --env, --privileged) are only valid with docker
-    let is_docker = options.isolated.as_deref() == Some("docker");
-    if !options.volumes.is_empty() && !is_docker {
-        return Err("--volume option is only valid with --isolated docker".to_string());
-    }
-    if !options.mounts.is_empty() && !is_docker {
-        return Err("--mount option is only valid with --isolated docker".to_string());
-    }
-    if !options.env.is_empty() && !is_docker {
-        return Err("--env option is only valid with --isolated docker".to_string());
-    }
-    if options.privileged && !is_docker {
-        return Err("--privileged option is only valid with --isolated docker".to_string());
+    // Docker runtime options (--volume, --mount, --env, --privileged) are only valid with containers
+    let is_docker = options.isolated_stack.iter().any(is_container);
+    for (flag, given) in [
+        ("--volume", !options.volumes.is_empty()),
+        ("--mount", !options.mounts.is_empty()),
+        ("--env", !options.env.is_empty()),
+        ("--privileged", options.privileged),
+    ] {
+        if given && !is_docker {
+            return Err(format!(
+                "{} option is only valid with --isolated docker or podman",
+                flag
+            ));
+        }
     }
 
     // Endpoint is only valid with ssh
@@ -695,7 +783,7 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
     for (flag, enabled) in cleanup_flags {
         if enabled && !is_docker {
             return Err(format!(
-                "{} option is only valid with --isolated docker",
+                "{} option is only valid with --isolated docker or podman",
                 flag
             ));
         }
@@ -718,13 +806,19 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
 
     // User isolation validation
     if options.user {
-        // User isolation is not supported with Docker
+        // User isolation is not supported with containers
         if options.isolated.as_deref() == Some("docker") {
             return Err(
                 "--isolated-user is not supported with Docker isolation. Docker uses its own user namespace for isolation."
                     .to_string(),
             );
         }
+        if options.isolated.as_deref() == Some("podman") {
+            return Err(
+                "--isolated-user is not supported with Podman isolation. Podman uses its own user namespace for isolation."
+                    .to_string(),
+            );
+        }
         // Validate custom username if provided
         if let Some(ref username) = options.user_name {
             let username_regex = regex::Regex::new(r"^[a-zA-Z0-9_-]+$").unwrap();
@@ -759,30 +853,9 @@ pub fn validate_options(options: &mut WrapperOptions) -> Result<(), String> {
         }
     }
 
-    // Query/control modes are mutually exclusive
-    let query_modes = [
-        options.status.is_some(),
-        options.list,
-        options.upload_log.is_some(),
-        options.stop.is_some(),
-        options.terminate.is_some(),
-        options.cleanup,
-    ]
-    .into_iter()
-    .filter(|enabled| *enabled)
-    .count();
-
-    if query_modes > 1 {
-        return Err(
-            "Cannot combine --status, --list, --upload-log, --stop, --terminate, or --cleanup in the same invocation"
-                .to_string(),
-        );
-    }
-
-    // Output format is only valid with read-only query modes
-    if options.output_format.is_some() && options.status.is_none() && !options.list {
-        return Err("--output-format option is only valid with --status or --list".to_string());
-    }
+    output_options::validate_output_options(options)?;
+    let query_modes = query_options::validate_query_options(options)?;
+    store_options::validate_store_options(options, query_modes)?;
 
     // Validate shell option
     if !VALID_SHELLS.contains(&options.shell.as_str()) {
@@ -639,7 +639,7 @@ fn test_query_and_control_modes_are_mutually_exclusive() {
         .collect();
     let error = parse_args(&args).unwrap_err();
     assert!(error.contains(
-        "Cannot combine --status, --list, --upload-log, --stop, --terminate, or --cleanup"
+        "Cannot combine --status, --list, --upload-log, --stop, --terminate, --follow, --log, or --cleanup"
     ));
 }
 
@@ -651,7 +651,7 @@ fn test_upload_log_and_control_modes_are_mutually_exclusive() {
         .collect();
     let error = parse_args(&args).unwrap_err();
     assert!(error.contains(
-        "Cannot combine --status, --list, --upload-log, --stop, --terminate, or --cleanup"
+        "Cannot combine --status, --list, --upload-log, --stop, --terminate, --follow, --log, or --cleanup"
     ));
 }
 
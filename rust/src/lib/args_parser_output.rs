@@ -0,0 +1,104 @@
+//! Launch output options (--theme, --quiet, --silent, --summary-only, --events,
+//! --ci)
+//! extracted from args_parser.rs
+
+use super::store_options::take_value;
+use super::WrapperOptions;
+use crate::event_stream::EventTarget;
+use crate::output_theme::{OutputMode, VALID_THEMES};
+
+/// Output mode selected by --quiet, --silent or --summary-only
+pub fn output_mode(options: &WrapperOptions) -> OutputMode {
+    if options.silent {
+        OutputMode::Silent
+    } else if options.summary_only {
+        OutputMode::SummaryOnly
+    } else if options.quiet {
+        OutputMode::Quiet
+    } else {
+        OutputMode::Normal
+    }
+}
+
+/// Parse an option controlling how a launched command's output is shown.
+/// Returns number of arguments consumed (0 if not recognized)
+pub(super) fn parse_output_option(
+    args: &[String],
+    index: usize,
+    options: &mut WrapperOptions,
+) -> Result<usize, String> {
+    let arg = args[index].as_str();
+
+    // --quiet or -q
+    if arg == "--quiet" || arg == "-q" {
+        options.quiet = true;
+        return Ok(1);
+    }
+
+    // --silent
+    if arg == "--silent" {
+        options.silent = true;
+        return Ok(1);
+    }
+
+    // --summary-only
+    if arg == "--summary-only" {
+        options.summary_only = true;
+        return Ok(1);
+    }
+
+    // --ci
+    if arg == "--ci" {
+        options.ci = true;
+        return Ok(1);
+    }
+
+    let theme_expected = "a theme argument (timeline, ascii, compact, boxed)";
+    if let Some((value, used)) = take_value(args, index, "--theme", theme_expected)? {
+        options.theme = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--events", "a path or fd:N")? {
+        options.events = Some(value.to_string());
+        return Ok(used);
+    }
+
+    Ok(0)
+}
+
+/// Validate launch output options
+pub(super) fn validate_output_options(options: &WrapperOptions) -> Result<(), String> {
+    if let Some(ref theme) = options.theme {
+        if !VALID_THEMES.contains(&theme.as_str()) {
+            return Err(format!(
+                "Invalid theme: \"{}\". Valid options are: {}",
+                theme,
+                VALID_THEMES.join(", ")
+            ));
+        }
+    }
+
+    if let Some(ref events) = options.events {
+        EventTarget::parse(events)?;
+    }
+
+    let modes = [options.quiet, options.silent, options.summary_only];
+    if modes.iter().filter(|enabled| **enabled).count() > 1 {
+        return Err("--quiet, --silent and --summary-only cannot be combined".to_string());
+    }
+
+    // Attached isolated runs are captured into the log like direct ones, but
+    // a detached command or an attached tmux session writes to its own
+    // terminal, so its output cannot be held back
+    if (options.silent || options.summary_only)
+        && (options.detached || options.isolated.as_deref() == Some("tmux"))
+    {
+        return Err(
+            "--silent and --summary-only are not valid with --detached or --isolated tmux"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,266 @@
+//! Query and control mode options (--status, --list, --stop, --follow, --log,
+//! ...) extracted from args_parser.rs
+
+use super::store_options::take_value;
+use super::WrapperOptions;
+use crate::log_viewer::{parse_line_range, AnsiMode, LogViewOptions};
+use crate::record_template::{parse_columns, parse_template, RecordLayout};
+
+/// Parse a query, control or log viewing option at `index`.
+/// Returns number of arguments consumed (0 if not recognized)
+pub(super) fn parse_query_option(
+    args: &[String],
+    index: usize,
+    options: &mut WrapperOptions,
+) -> Result<usize, String> {
+    let arg = &args[index];
+
+    // --status <uuid-or-session-name>
+    if arg == "--status" {
+        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
+            options.status = Some(args[index + 1].clone());
+            return Ok(2);
+        } else {
+            return Err(format!(
+                "Option {} requires a UUID or session name argument",
+                arg
+            ));
+        }
+    }
+
+    // --status=<value>
+    if let Some(value) = arg.strip_prefix("--status=") {
+        if value.is_empty() {
+            return Err("Option --status requires a UUID or session name argument".to_string());
+        }
+        options.status = Some(value.to_string());
+        return Ok(1);
+    }
+
+    // --upload-log <uuid-or-session-name>
+    if arg == "--upload-log" {
+        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
+            options.upload_log = Some(args[index + 1].clone());
+            return Ok(2);
+        } else {
+            return Err(format!(
+                "Option {} requires a UUID or session name argument",
+                arg
+            ));
+        }
+    }
+
+    // --upload-log=<value>
+    if let Some(value) = arg.strip_prefix("--upload-log=") {
+        if value.is_empty() {
+            return Err("Option --upload-log requires a UUID or session name argument".to_string());
+        }
+        options.upload_log = Some(value.to_string());
+        return Ok(1);
+    }
+
+    // --stop <uuid-or-session-name>
+    if arg == "--stop" {
+        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
+            options.stop = Some(args[index + 1].clone());
+            return Ok(2);
+        } else {
+            return Err(format!(
+                "Option {} requires a UUID or session name argument",
+                arg
+            ));
+        }
+    }
+
+    // --stop=<value>
+    if let Some(value) = arg.strip_prefix("--stop=") {
+        if value.is_empty() {
+            return Err("Option --stop requires a UUID or session name argument".to_string());
+        }
+        options.stop = Some(value.to_string());
+        return Ok(1);
+    }
+
+    // --terminate <uuid-or-session-name>
+    if arg == "--terminate" {
+        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
+            options.terminate = Some(args[index + 1].clone());
+            return Ok(2);
+        } else {
+            return Err(format!(
+                "Option {} requires a UUID or session name argument",
+                arg
+            ));
+        }
+    }
+
+    // --terminate=<value>
+    if let Some(value) = arg.strip_prefix("--terminate=") {
+        if value.is_empty() {
+            return Err("Option --terminate requires a UUID or session name argument".to_string());
+        }
+        options.terminate = Some(value.to_string());
+        return Ok(1);
+    }
+
+    // --list
+    if arg == "--list" {
+        options.list = true;
+        return Ok(1);
+    }
+
+    // --output-format <format>
+    if arg == "--output-format" {
+        if index + 1 < args.len() && !args[index + 1].starts_with('-') {
+            options.output_format = Some(args[index + 1].to_lowercase());
+            return Ok(2);
+        } else {
+            return Err(format!("Option {} requires a format argument", arg));
+        }
+    }
+
+    // --output-format=<value>
+    if arg.starts_with("--output-format=") {
+        options.output_format = Some(arg.split('=').nth(1).unwrap_or("").to_lowercase());
+        return Ok(1);
+    }
+
+    // --cleanup
+    if arg == "--cleanup" {
+        options.cleanup = true;
+        return Ok(1);
+    }
+
+    // --cleanup-dry-run
+    if arg == "--cleanup-dry-run" {
+        options.cleanup = true;
+        options.cleanup_dry_run = true;
+        return Ok(1);
+    }
+
+    // --dashboard
+    if arg == "--dashboard" {
+        options.dashboard = true;
+        return Ok(1);
+    }
+
+    // --output-only
+    if arg == "--output-only" {
+        options.log_view.output_only = true;
+        return Ok(1);
+    }
+
+    // --stderr-only
+    if arg == "--stderr-only" {
+        options.log_view.stderr_only = true;
+        return Ok(1);
+    }
+
+    // --pager
+    if arg == "--pager" {
+        options.log_view.pager = true;
+        return Ok(1);
+    }
+
+    let identifier_expected = "a UUID or session name argument";
+    if let Some((value, used)) = take_value(args, index, "--follow", identifier_expected)? {
+        options.follow = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--log", identifier_expected)? {
+        options.log = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--tail", "a number argument")? {
+        let tail = value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid tail: \"{}\". Expected a number", value))?;
+        options.tail = Some(tail);
+        return Ok(used);
+    }
+
+    let ansi_expected = "a mode argument (auto, keep, strip)";
+    if let Some((value, used)) = take_value(args, index, "--ansi", ansi_expected)? {
+        options.log_view.ansi = AnsiMode::parse(&value.to_lowercase())?;
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--lines", "a line range argument")? {
+        options.log_view.lines = Some(parse_line_range(value)?);
+        return Ok(used);
+    }
+
+    let layout = if let Some((value, used)) = take_value(args, index, "--columns", "a field list")?
+    {
+        (RecordLayout::Columns(parse_columns(value)?), used)
+    } else if let Some((value, used)) = take_value(args, index, "--format", "a template argument")?
+    {
+        (RecordLayout::Template(parse_template(value)?), used)
+    } else {
+        return Ok(0);
+    };
+    if options.layout.is_some() {
+        return Err("--columns and --format cannot be combined".to_string());
+    }
+    options.layout = Some(layout.0);
+    Ok(layout.1)
+}
+
+/// Validate query, control and log viewing options.
+/// Returns the number of selected --status/--list/control modes.
+pub(super) fn validate_query_options(options: &WrapperOptions) -> Result<usize, String> {
+    // Query/control modes are mutually exclusive
+    let query_modes = [
+        options.status.is_some(),
+        options.list,
+        options.upload_log.is_some(),
+        options.stop.is_some(),
+        options.terminate.is_some(),
+        options.follow.is_some(),
+        options.log.is_some(),
+        options.cleanup,
+    ]
+    .into_iter()
+    .filter(|enabled| *enabled)
+    .count();
+
+    if query_modes > 1 {
+        return Err(
+            "Cannot combine --status, --list, --upload-log, --stop, --terminate, --follow, --log, or --cleanup in the same invocation"
+                .to_string(),
+        );
+    }
+
+    if options.tail.is_some()
+        && options.follow.is_none()
+        && options.log.is_none()
+        && !options.silent
+    {
+        return Err("--tail option is only valid with --follow, --log, or --silent".to_string());
+    }
+
+    if options.log_view != LogViewOptions::default() && options.log.is_none() {
+        return Err(
+            "--ansi, --output-only, --stderr-only, --lines, and --pager options are only valid with --log"
+                .to_string(),
+        );
+    }
+
+    if options.layout.is_some() {
+        if options.status.is_none() && !options.list {
+            return Err(
+                "--columns and --format options are only valid with --status or --list".to_string(),
+            );
+        }
+        if options.output_format.is_some() || options.tree {
+            return Err(
+                "--columns and --format cannot be combined with --output-format or --tree"
+                    .to_string(),
+            );
+        }
+    }
+
+    Ok(query_modes)
+}
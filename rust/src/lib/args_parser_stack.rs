@@ -0,0 +1,142 @@
+//! Isolation stacking options (`--isolated "screen ssh docker"`)
+//!
+//! A space-separated `--isolated` value stacks backends: each level runs the
+//! next one inside itself. `--image`, `--endpoint` and `--session` then take
+//! one value per level, with `_` as a placeholder for levels that do not need
+//! the option. A single `--image`/`--endpoint` value applies to every docker
+//! or ssh level; a single `--session` names the outermost level only.
+//! Mirrors the stacking validation in js/src/lib/args-parser.js.
+
+use super::{generate_session_name, WrapperOptions, CONTAINER_BACKENDS, VALID_BACKENDS};
+use crate::isolation::get_default_docker_image;
+use crate::sequence_parser::{distribute_option, is_sequence, parse_sequence};
+
+/// Maximum number of stacked isolation levels
+pub const MAX_ISOLATION_DEPTH: usize = 7;
+
+/// Resolve the isolation stack from `--isolated` and distribute `--image`,
+/// `--endpoint` and `--session` across its levels. The outermost level's
+/// values are kept in `isolated`, `image`, `endpoint` and `session`, so a
+/// single backend is validated exactly as before.
+pub(super) fn resolve_isolation_stack(options: &mut WrapperOptions) -> Result<(), String> {
+    let Some(raw) = options.isolated.clone() else {
+        return Ok(());
+    };
+    if !is_sequence(raw.trim()) {
+        if options.isolated_stack.is_empty() {
+            options.isolated_stack = vec![raw];
+        }
+        return Ok(());
+    }
+
+    let levels = parse_sequence(&raw);
+    if levels.iter().any(Option::is_none) {
+        return Err(format!(
+            "Invalid isolation stack: \"{}\". Every level needs a backend; placeholders (_) are only valid in --image, --endpoint and --session",
+            raw
+        ));
+    }
+    let stack: Vec<String> = levels.into_iter().flatten().collect();
+    if stack.len() > MAX_ISOLATION_DEPTH {
+        return Err(format!(
+            "Isolation stack too deep: {} levels (max: {})",
+            stack.len(),
+            MAX_ISOLATION_DEPTH
+        ));
+    }
+    for backend in &stack {
+        if !VALID_BACKENDS.contains(&backend.as_str()) {
+            return Err(format!(
+                "Invalid isolation backend: \"{}\". Valid options are: {}",
+                backend,
+                VALID_BACKENDS.join(", ")
+            ));
+        }
+    }
+
+    let mut images = level_values(
+        &stack,
+        options.image.as_deref(),
+        "--image",
+        &CONTAINER_BACKENDS,
+    )?;
+    let endpoints = level_values(&stack, options.endpoint.as_deref(), "--endpoint", &["ssh"])?;
+    for (level, backend) in stack.iter().enumerate() {
+        if CONTAINER_BACKENDS.contains(&backend.as_str()) && images[level].is_none() {
+            images[level] = Some(get_default_docker_image());
+        }
+        if backend == "ssh" && endpoints[level].is_none() {
+            return Err(format!(
+                "SSH isolation at level {} requires --endpoint option. Use a sequence like --endpoint \"_ user@host _\" to specify endpoints for specific levels.",
+                level + 1
+            ));
+        }
+    }
+
+    let mut sessions = match options.session.as_deref() {
+        Some(session) if is_sequence(session) => {
+            distribute_option(session, stack.len(), "--session")?
+        }
+        session => {
+            let mut sessions = vec![None; stack.len()];
+            sessions[0] = session.map(str::to_string);
+            sessions
+        }
+    };
+    // Nested levels are named up front so the record can list every session
+    for (level, session) in sessions.iter_mut().enumerate().skip(1) {
+        if session.is_none() {
+            *session = Some(generate_session_name(Some(&stack[level])));
+        }
+    }
+
+    options.isolated = Some(stack[0].clone());
+    options.image = images[0].clone();
+    options.endpoint = endpoints[0].clone();
+    options.session = sessions[0].clone();
+    options.isolated_stack = stack;
+    options.image_stack = images;
+    options.endpoint_stack = endpoints;
+    options.session_stack = sessions;
+    Ok(())
+}
+
+/// Distribute an option over the stack, keeping it only at levels running
+/// one of `backends`.
+/// A single value silently skips other levels; an explicit sequence must use
+/// `_` for them.
+fn level_values(
+    stack: &[String],
+    value: Option<&str>,
+    flag: &str,
+    backends: &[&str],
+) -> Result<Vec<Option<String>>, String> {
+    let Some(value) = value else {
+        return Ok(vec![None; stack.len()]);
+    };
+    let takes_value = |level: &String| backends.contains(&level.as_str());
+    let backend = backends.join(" or ");
+    if !stack.iter().any(takes_value) {
+        return Err(format!(
+            "{} option is only valid when the isolation stack includes {}",
+            flag, backend
+        ));
+    }
+    let explicit = is_sequence(value);
+    let mut values = distribute_option(value, stack.len(), flag)?;
+    for (level, slot) in values.iter_mut().enumerate() {
+        if slot.is_some() && !takes_value(&stack[level]) {
+            if explicit {
+                return Err(format!(
+                    "{} has a value for level {} ({}), which is not {}. Use _ as a placeholder for that level.",
+                    flag,
+                    level + 1,
+                    stack[level],
+                    backend
+                ));
+            }
+            *slot = None;
+        }
+    }
+    Ok(values)
+}
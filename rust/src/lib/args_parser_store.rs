@@ -0,0 +1,441 @@
+//! Store command, record filter and annotation options extracted from args_parser.rs
+
+use super::WrapperOptions;
+use crate::execution_annotations::{parse_label, parse_tag};
+use crate::execution_export::VALID_EXPORT_FORMATS;
+use crate::execution_grep::build_grep_regex;
+use crate::execution_stats::VALID_STATS_GROUPS;
+use crate::html_report::VALID_REPORT_FORMATS;
+use crate::record_filter::{parse_time_bound, RecordFilter, VALID_OUTCOMES};
+
+/// Store commands paired with whether each one is selected
+fn store_commands(options: &WrapperOptions) -> [(&'static str, bool); 11] {
+    [
+        ("--stats", options.stats),
+        ("--export", options.export.is_some()),
+        ("--import", options.import.is_some()),
+        ("--annotate", options.annotate.is_some()),
+        ("--schema", options.schema),
+        ("--repair", options.repair),
+        ("--grep", options.grep.is_some()),
+        ("--sync", options.sync.is_some()),
+        ("--diff", options.diff.is_some()),
+        ("--report", options.report.is_some()),
+        ("--junit", options.junit.is_some()),
+    ]
+}
+
+/// Check if a store command (--stats, --export, --import, --annotate, --schema, --repair, --grep,
+/// --sync, --diff, --report, --junit) is selected
+pub fn has_store_command(options: &WrapperOptions) -> bool {
+    store_commands(options).iter().any(|(_, enabled)| *enabled)
+}
+
+/// Record filter for --list, --stats, --export, --report and --junit, including the
+/// --tag/--label options (which tag the execution when launching a command)
+pub fn record_filter(options: &WrapperOptions) -> RecordFilter {
+    let mut filter = options.filter.clone();
+    filter.tags.extend(options.tags.iter().cloned());
+    filter.labels.extend(options.labels.clone());
+    filter
+}
+
+/// Value of `<flag> <value>` or `<flag>=<value>` at `index`, together with the
+/// number of arguments consumed. Returns `Ok(None)` when `args[index]` is not `flag`.
+pub(super) fn take_value<'a>(
+    args: &'a [String],
+    index: usize,
+    flag: &str,
+    expected: &str,
+) -> Result<Option<(&'a str, usize)>, String> {
+    let arg = &args[index];
+    if arg == flag {
+        return match args.get(index + 1) {
+            Some(value) if !value.starts_with('-') => Ok(Some((value, 2))),
+            _ => Err(format!("Option {} requires {}", flag, expected)),
+        };
+    }
+    match arg
+        .strip_prefix(flag)
+        .and_then(|rest| rest.strip_prefix('='))
+    {
+        Some("") => Err(format!("Option {} requires {}", flag, expected)),
+        Some(value) => Ok(Some((value, 1))),
+        None => Ok(None),
+    }
+}
+
+/// Parse a store command or record filter option at `index`.
+/// Returns number of arguments consumed (0 if not recognized)
+pub(super) fn parse_store_option(
+    args: &[String],
+    index: usize,
+    options: &mut WrapperOptions,
+) -> Result<usize, String> {
+    let arg = args[index].as_str();
+
+    // --stats
+    if arg == "--stats" {
+        options.stats = true;
+        return Ok(1);
+    }
+
+    // --tree
+    if arg == "--tree" {
+        options.tree = true;
+        return Ok(1);
+    }
+
+    // --schema
+    if arg == "--schema" {
+        options.schema = true;
+        return Ok(1);
+    }
+
+    // --repair
+    if arg == "--repair" {
+        options.repair = true;
+        return Ok(1);
+    }
+
+    // --repair-dry-run
+    if arg == "--repair-dry-run" {
+        options.repair = true;
+        options.repair_dry_run = true;
+        return Ok(1);
+    }
+
+    // --all-users
+    if arg == "--all-users" {
+        options.all_users = true;
+        return Ok(1);
+    }
+
+    // --ignore-case
+    if arg == "--ignore-case" {
+        options.grep_ignore_case = true;
+        return Ok(1);
+    }
+
+    // --sync-logs
+    if arg == "--sync-logs" {
+        options.sync_logs = true;
+        return Ok(1);
+    }
+
+    // --with-logs
+    if arg == "--with-logs" {
+        options.with_logs = true;
+        return Ok(1);
+    }
+
+    let group_expected = "a group argument (command, prefix, directory, backend)";
+    if let Some((value, used)) = take_value(args, index, "--group-by", group_expected)? {
+        options.group_by = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--export", "a file path argument")? {
+        options.export = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--report", "a format argument (html)")? {
+        options.report = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--output", "a file path argument")? {
+        options.output = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--junit", "a file path argument")? {
+        options.junit = Some(value.to_string());
+        return Ok(used);
+    }
+
+    let parent_expected = "an execution UUID or session name";
+    if let Some((value, used)) = take_value(args, index, "--parent", parent_expected)? {
+        options.parent = Some(value.to_string());
+        return Ok(used);
+    }
+
+    let format_expected = "a format argument (jsonl, csv, lino)";
+    if let Some((value, used)) = take_value(args, index, "--export-format", format_expected)? {
+        options.export_format = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--import", "a file path argument")? {
+        options.import = Some(value.to_string());
+        return Ok(used);
+    }
+
+    for flag in ["--since", "--until"] {
+        if let Some((value, used)) = take_value(args, index, flag, "a time argument")? {
+            let bound = Some(parse_time_bound(value)?);
+            if flag == "--since" {
+                options.filter.since = bound;
+            } else {
+                options.filter.until = bound;
+            }
+            return Ok(used);
+        }
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--command-contains", "a text argument")? {
+        options.filter.command_contains = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--backend", "a backend argument")? {
+        options.filter.backend = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    let outcome_expected = "an outcome argument (succeeded, failed, executing)";
+    if let Some((value, used)) = take_value(args, index, "--outcome", outcome_expected)? {
+        options.filter.outcome = Some(value.to_lowercase());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--tag", "a tag argument")? {
+        let tag = parse_tag(value)?;
+        if !options.tags.contains(&tag) {
+            options.tags.push(tag);
+        }
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--label", "a key=value argument")? {
+        let (key, label_value) = parse_label(value)?;
+        options.labels.insert(key, label_value);
+        return Ok(used);
+    }
+
+    let annotate_expected = "a UUID or session name argument";
+    if let Some((value, used)) = take_value(args, index, "--annotate", annotate_expected)? {
+        options.annotate = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--note", "a text argument")? {
+        options.note = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--grep", "a pattern argument")? {
+        options.grep = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--sync", "a git remote argument")? {
+        options.sync = Some(value.to_string());
+        return Ok(used);
+    }
+
+    if args[index] == "--diff" {
+        let expected = || "Option --diff requires two UUID or session name arguments".to_string();
+        let mut ids = args[index + 1..]
+            .iter()
+            .take(2)
+            .filter(|value| !value.starts_with('-'));
+        let (Some(a), Some(b)) = (ids.next(), ids.next()) else {
+            return Err(expected());
+        };
+        options.diff = Some((a.clone(), b.clone()));
+        return Ok(3);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--context", "a number argument")? {
+        let context = value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid context: \"{}\". Expected a number", value))?;
+        options.grep_context = Some(context);
+        return Ok(used);
+    }
+
+    if let Some((value, used)) = take_value(args, index, "--limit", "a number argument")? {
+        let limit = value
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid limit: \"{}\". Expected a number", value))?;
+        options.filter.limit = Some(limit);
+        return Ok(used);
+    }
+
+    // Not a recognized wrapper option
+    Ok(0)
+}
+
+/// Validate store command and record filter options.
+/// `query_modes` is the number of selected --status/--list/control modes.
+pub(super) fn validate_store_options(
+    options: &WrapperOptions,
+    query_modes: usize,
+) -> Result<(), String> {
+    // Store commands cannot be combined with any other query or control mode
+    let commands = store_commands(options);
+    let selected = commands.iter().filter(|(_, enabled)| *enabled).count();
+    if let Some((flag, _)) = commands.iter().find(|(_, enabled)| *enabled) {
+        if selected + query_modes > 1 {
+            return Err(format!(
+                "{} cannot be combined with other query or control options",
+                flag
+            ));
+        }
+    }
+
+    if options.dashboard && (selected + query_modes > 0) {
+        return Err(
+            "--dashboard cannot be combined with other query or control options".to_string(),
+        );
+    }
+
+    // Output format is only valid with modes that print records or reports
+    if options.output_format.is_some()
+        && options.status.is_none()
+        && !options.list
+        && !options.stats
+        && options.annotate.is_none()
+        && !options.repair
+        && options.grep.is_none()
+        && options.sync.is_none()
+        && options.diff.is_none()
+    {
+        return Err(
+            "--output-format option is only valid with --status, --list, --stats, --annotate, --repair, --grep, --sync, or --diff"
+                .to_string(),
+        );
+    }
+
+    if let Some(ref pattern) = options.grep {
+        build_grep_regex(pattern, options.grep_ignore_case)?;
+    } else if options.grep_ignore_case || options.grep_context.is_some() {
+        return Err("--ignore-case and --context options are only valid with --grep".to_string());
+    }
+
+    if options.sync_logs && options.sync.is_none() {
+        return Err("--sync-logs option is only valid with --sync".to_string());
+    }
+
+    if options.annotate.is_some()
+        && options.note.is_none()
+        && options.tags.is_empty()
+        && options.labels.is_empty()
+    {
+        return Err("--annotate requires --note, --tag, or --label".to_string());
+    }
+
+    if options.tree && options.status.is_none() && !options.list {
+        return Err("--tree option is only valid with --status or --list".to_string());
+    }
+
+    if options.all_users
+        && options.status.is_none()
+        && !options.list
+        && !options.stats
+        && options.export.is_none()
+        && options.grep.is_none()
+        && !options.dashboard
+        && options.report.is_none()
+        && options.junit.is_none()
+    {
+        return Err(
+            "--all-users option is only valid with --status, --list, --stats, --export, --grep, --dashboard, --report, or --junit"
+                .to_string(),
+        );
+    }
+
+    if options.note.is_some() && options.annotate.is_none() {
+        return Err("--note option is only valid with --annotate".to_string());
+    }
+
+    // Tags and labels mark a launch or an annotation, or filter list-like modes
+    let launching = query_modes == 0 && !has_store_command(options);
+    let lists = options.list
+        || options.stats
+        || options.export.is_some()
+        || options.grep.is_some()
+        || options.dashboard
+        || options.report.is_some()
+        || options.junit.is_some();
+    if (!options.tags.is_empty() || !options.labels.is_empty())
+        && !launching
+        && !lists
+        && options.annotate.is_none()
+    {
+        return Err(
+            "--tag and --label are only valid when running a command, with --annotate, or as --list, --stats, --export, --grep, --dashboard, --report, or --junit filters"
+                .to_string(),
+        );
+    }
+
+    if let Some(ref group) = options.group_by {
+        if !options.stats {
+            return Err("--group-by option is only valid with --stats".to_string());
+        }
+        if !VALID_STATS_GROUPS.contains(&group.as_str()) {
+            return Err(format!(
+                "Invalid stats grouping: \"{}\". Valid options are: {}",
+                group,
+                VALID_STATS_GROUPS.join(", ")
+            ));
+        }
+    }
+
+    if let Some(ref format) = options.export_format {
+        if options.export.is_none() {
+            return Err("--export-format option is only valid with --export".to_string());
+        }
+        if !VALID_EXPORT_FORMATS.contains(&format.as_str()) {
+            return Err(format!(
+                "Invalid export format: \"{}\". Valid options are: {}",
+                format,
+                VALID_EXPORT_FORMATS.join(", ")
+            ));
+        }
+    }
+
+    if let Some(ref format) = options.report {
+        if !VALID_REPORT_FORMATS.contains(&format.as_str()) {
+            return Err(format!(
+                "Invalid report format: \"{}\". Valid options are: {}",
+                format,
+                VALID_REPORT_FORMATS.join(", ")
+            ));
+        }
+    }
+
+    if options.output.is_some() && options.report.is_none() {
+        return Err("--output option is only valid with --report".to_string());
+    }
+
+    if options.parent.is_some() && options.junit.is_none() {
+        return Err("--parent option is only valid with --junit".to_string());
+    }
+
+    if options.with_logs && options.export.is_none() {
+        return Err("--with-logs option is only valid with --export".to_string());
+    }
+
+    // Record filters narrow the history read by list-like modes
+    if !options.filter.is_empty() && !lists {
+        return Err(
+            "Record filters (--since, --until, --command-contains, --backend, --outcome, --limit) are only valid with --list, --stats, --export, --grep, --dashboard, --report, or --junit"
+                .to_string(),
+        );
+    }
+
+    if let Some(ref outcome) = options.filter.outcome {
+        if !VALID_OUTCOMES.contains(&outcome.as_str()) {
+            return Err(format!(
+                "Invalid outcome: \"{}\". Valid options are: {}",
+                outcome,
+                VALID_OUTCOMES.join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+//! CI mode (--ci): GitHub Actions log groups, annotations and step summary
+//!
+//! Under CI (`--ci`, or `CI`/`GITHUB_ACTIONS` set to a true value unless
+//! `START_DISABLE_CI=1`) a run is printed for the Actions log viewer:
+//! - the start and finish blocks become collapsed `::group::` sections with a
+//!   one-line title, leaving the command output itself visible
+//! - a failure emits an `::error` annotation with the command and exit code
+//! - the command, result, duration, execution UUID and log path are appended
+//!   to `$GITHUB_STEP_SUMMARY` as a markdown table
+//!
+//! Durations are always printed as seconds with millisecond precision so they
+//! compare and grep well across runs.
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// Environment variable that disables CI mode detection
+pub const DISABLE_CI_ENV: &str = "START_DISABLE_CI";
+
+/// Environment variable naming the GitHub Actions step summary file
+pub const STEP_SUMMARY_ENV: &str = "GITHUB_STEP_SUMMARY";
+
+/// Whether CI mode is on given `--ci` and the `CI`, `GITHUB_ACTIONS` and
+/// `START_DISABLE_CI` values
+pub fn ci_from_env(
+    flag: bool,
+    ci: Option<&str>,
+    github_actions: Option<&str>,
+    disabled: Option<&str>,
+) -> bool {
+    let truthy = |value: Option<&str>| {
+        value.is_some_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true"))
+    };
+    flag || (!truthy(disabled) && (truthy(ci) || truthy(github_actions)))
+}
+
+/// Whether CI mode is on for this process (see [`ci_from_env`])
+pub fn ci_enabled(flag: bool) -> bool {
+    ci_from_env(
+        flag,
+        env::var("CI").ok().as_deref(),
+        env::var("GITHUB_ACTIONS").ok().as_deref(),
+        env::var(DISABLE_CI_ENV).ok().as_deref(),
+    )
+}
+
+/// A duration as seconds with millisecond precision (`12.345s`)
+pub fn ci_duration(duration_ms: f64) -> String {
+    format!("{:.3}s", duration_ms.max(0.0) / 1000.0)
+}
+
+/// Wrap a block in a collapsed log group titled `title`
+pub fn ci_group(title: &str, block: &str) -> String {
+    format!(
+        "::group::{}\n{}\n::endgroup::",
+        escape_data(title),
+        block.trim_end()
+    )
+}
+
+/// Escape the message of a workflow command
+pub fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property (like `title`) of a workflow command
+pub fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// A finished run as reported to CI
+#[derive(Debug, Clone)]
+pub struct CiRun<'a> {
+    pub command: &'a str,
+    pub session_id: &'a str,
+    pub exit_code: i32,
+    pub duration_ms: f64,
+    pub log_path: &'a Path,
+    /// The command keeps running in a detached session
+    pub detached: bool,
+}
+
+impl CiRun<'_> {
+    fn result(&self) -> String {
+        if self.detached {
+            "executing (detached)".to_string()
+        } else if self.exit_code == 0 {
+            "✓ success".to_string()
+        } else {
+            format!("✗ exit code {}", self.exit_code)
+        }
+    }
+}
+
+/// The `::error` annotation for a failed run
+pub fn error_annotation(run: &CiRun) -> String {
+    format!(
+        "::error title={}::{}",
+        escape_property(&format!("Command failed with exit code {}", run.exit_code)),
+        escape_data(&format!(
+            "{} exited with code {} after {}\nExecution: {}\nLog: {}",
+            run.command,
+            run.exit_code,
+            ci_duration(run.duration_ms),
+            run.session_id,
+            run.log_path.display()
+        ))
+    )
+}
+
+/// `value` as a code span in a markdown table cell: fenced with one more
+/// backtick than its longest run of backticks, with `|` escaped
+fn code_cell(value: &str) -> String {
+    let value = value.replace('|', "\\|").replace('\n', " ");
+    let longest_run = value.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run + 1);
+    // A span starting or ending with a backtick needs a space to separate it
+    // from the fence; one space on each side is stripped when rendered
+    if value.starts_with('`') || value.ends_with('`') {
+        format!("{} {} {}", fence, value, fence)
+    } else {
+        format!("{}{}{}", fence, value, fence)
+    }
+}
+
+/// The markdown table written to the step summary. The header is only
+/// included for the first run written to a summary file.
+pub fn step_summary(run: &CiRun, with_header: bool) -> String {
+    let mut summary = String::new();
+    if with_header {
+        summary.push_str("| Command | Result | Duration | Execution | Log |\n");
+        summary.push_str("| --- | --- | --- | --- | --- |\n");
+    }
+    summary.push_str(&format!(
+        "| {} | {} | {} | `{}` | {} |\n",
+        code_cell(run.command),
+        run.result(),
+        ci_duration(run.duration_ms),
+        run.session_id,
+        code_cell(&run.log_path.to_string_lossy())
+    ));
+    summary
+}
+
+/// Append the run to the step summary file, starting a new table unless the
+/// file already ends with a row of one
+pub fn append_step_summary(path: &Path, run: &CiRun) -> std::io::Result<()> {
+    let existing = std::fs::read_to_string(path).unwrap_or_default();
+    let continues_table = existing
+        .trim_end_matches('\n')
+        .lines()
+        .last()
+        .is_some_and(|line| line.starts_with("| `"));
+    let mut summary = step_summary(run, !continues_table);
+    if !continues_table && !existing.is_empty() && !existing.ends_with("\n\n") {
+        summary.insert(0, '\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(summary.as_bytes())
+}
+
+/// Print the `::error` annotation for a failed run and add the run to
+/// `$GITHUB_STEP_SUMMARY` when it is set
+pub fn report_ci_run(run: &CiRun) {
+    if run.exit_code != 0 && !run.detached {
+        println!("{}", error_annotation(run));
+    }
+    if let Some(path) = env::var_os(STEP_SUMMARY_ENV).filter(|path| !path.is_empty()) {
+        if let Err(e) = append_step_summary(Path::new(&path), run) {
+            eprintln!("Warning: Could not write {}: {}", STEP_SUMMARY_ENV, e);
+        }
+    }
+}
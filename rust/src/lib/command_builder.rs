@@ -0,0 +1,127 @@
+//! Command Builder for Isolation Stacking
+//!
+//! Builds the command to execute at each isolation level, including the
+//! nested `start` invocation for the remaining levels.
+//!
+//! Mirrors the JavaScript implementation in js/src/lib/command-builder.js.
+//! The nested invocation does not forward `--session-id`: a level running on
+//! this host shares the execution store, where reusing the UUID would
+//! overwrite the outer execution's record. Each level records its own
+//! execution, found through the session names in the outer `sessionStack`.
+
+use crate::args_parser::{WrapperOptions, CONTAINER_BACKENDS};
+use crate::isolation::isolation_log::shell_quote;
+use crate::sequence_parser::format_sequence;
+
+/// Program that runs the next level inside `backend`. Terminal multiplexers
+/// run on this host, so they reuse this binary; container and ssh levels rely on
+/// `start` being installed in the container or on the remote host.
+pub fn level_program(backend: &str) -> String {
+    match backend {
+        "screen" | "tmux" => std::env::current_exe()
+            .map(|path| shell_quote(&path.to_string_lossy()))
+            .unwrap_or_else(|_| "start".to_string()),
+        _ => "start".to_string(),
+    }
+}
+
+/// Format the values of the remaining levels, or None when all are placeholders
+fn remaining_sequence(stack: &[Option<String>]) -> Option<String> {
+    let remaining = stack.get(1..)?;
+    if remaining.iter().all(Option::is_none) {
+        return None;
+    }
+    Some(format_sequence(remaining))
+}
+
+/// Build the command for the outermost isolation level. Without stacking this
+/// is the command itself; otherwise it is a `start` invocation that runs the
+/// remaining levels with their per-level options and the original command.
+///
+/// # Examples
+///
+/// ```
+/// use start_command::{build_next_level_command, parse_args};
+/// let args: Vec<String> = ["--isolated", "docker ssh", "--endpoint", "_ user@host", "--", "ls"]
+///     .iter()
+///     .map(|s| s.to_string())
+///     .collect();
+/// let options = parse_args(&args).unwrap().wrapper_options;
+/// let command = build_next_level_command(&options, "ls");
+/// assert!(command.starts_with("start --isolated 'ssh' --endpoint 'user@host'"));
+/// assert!(command.ends_with("-- 'ls'"));
+/// ```
+pub fn build_next_level_command(options: &WrapperOptions, command: &str) -> String {
+    if options.isolated_stack.len() <= 1 {
+        return command.to_string();
+    }
+
+    let remaining_stack = &options.isolated_stack[1..];
+    let mut parts = vec![
+        level_program(&options.isolated_stack[0]),
+        "--isolated".to_string(),
+        shell_quote(&remaining_stack.join(" ")),
+    ];
+
+    for (flag, stack) in [
+        ("--image", &options.image_stack),
+        ("--endpoint", &options.endpoint_stack),
+        ("--session", &options.session_stack),
+    ] {
+        if let Some(values) = remaining_sequence(stack) {
+            parts.push(flag.to_string());
+            parts.push(shell_quote(&values));
+        }
+    }
+
+    // Docker runtime options are flat (not per-level); forward them only when
+    // a remaining level is a container so the nested invocation accepts them
+    if remaining_stack
+        .iter()
+        .any(|level| CONTAINER_BACKENDS.contains(&level.as_str()))
+    {
+        for (flag, values) in [
+            ("--volume", &options.volumes),
+            ("--mount", &options.mounts),
+            ("--env", &options.env),
+        ] {
+            for value in values {
+                parts.push(flag.to_string());
+                parts.push(shell_quote(value));
+            }
+        }
+        for (flag, enabled) in [
+            ("--privileged", options.privileged),
+            (
+                "--auto-remove-docker-container",
+                options.auto_remove_docker_container,
+            ),
+            (
+                "--always-cleanup-container",
+                options.always_cleanup_container,
+            ),
+            ("--keep-container", options.keep_container),
+            ("--keep-container-on-fail", options.keep_container_on_fail),
+        ] {
+            if enabled {
+                parts.push(flag.to_string());
+            }
+        }
+    }
+
+    // Pass through global flags
+    if options.detached {
+        parts.push("--detached".to_string());
+    }
+    if options.keep_alive {
+        parts.push("--keep-alive".to_string());
+    }
+    if options.shell != "auto" {
+        parts.push("--shell".to_string());
+        parts.push(options.shell.clone());
+    }
+
+    parts.push("--".to_string());
+    parts.push(shell_quote(command));
+    parts.join(" ")
+}
@@ -0,0 +1,416 @@
+//! Interactive terminal dashboard of tracked executions (`--dashboard`)
+//!
+//! Lists executions newest first and refreshes every second: executing
+//! detached sessions are probed with `enrich_detached_status`, and durations
+//! of executing records tick using `attach_current_time`. The log tail of the
+//! selected execution is previewed below the list, and single keys stop,
+//! terminate, upload, replay or attach to the selected execution without
+//! leaving the dashboard.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use crate::execution_control::{control_execution, ControlAction};
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::log_follower::{log_source, tail_lines, LogSource};
+use crate::log_uploader::upload_execution_log;
+use crate::log_viewer::strip_ansi;
+use crate::output_blocks::format_duration;
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::{attach_current_time, enrich_detached_status};
+
+#[path = "dashboard_terminal.rs"]
+mod terminal;
+
+/// How often the list of executions is reloaded
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long to wait for a key before redrawing
+const KEY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How much of the end of a log is read for its preview
+const PREVIEW_BYTES: u64 = 64 * 1024;
+
+/// Key help shown at the bottom of the dashboard
+pub const DASHBOARD_KEYS: &str =
+    "up/down select  s stop  t terminate  u upload log  r replay  a attach  q quit";
+
+/// Action bound to a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DashboardKey {
+    Up,
+    Down,
+    Stop,
+    Terminate,
+    Upload,
+    Replay,
+    Attach,
+    Quit,
+}
+
+/// Action for the bytes read for one key press
+pub fn parse_key(input: &[u8]) -> Option<DashboardKey> {
+    match input {
+        b"\x1b[A" | b"\x1bOA" | b"k" => Some(DashboardKey::Up),
+        b"\x1b[B" | b"\x1bOB" | b"j" => Some(DashboardKey::Down),
+        b"s" => Some(DashboardKey::Stop),
+        b"t" => Some(DashboardKey::Terminate),
+        b"u" => Some(DashboardKey::Upload),
+        b"r" => Some(DashboardKey::Replay),
+        b"a" | b"\r" | b"\n" => Some(DashboardKey::Attach),
+        b"q" | b"\x1b" | b"\x03" => Some(DashboardKey::Quit),
+        _ => None,
+    }
+}
+
+/// Short status of a record: executing, succeeded or failed (exit code)
+pub fn status_label(record: &ExecutionRecord) -> String {
+    match (record.status, record.exit_code) {
+        (ExecutionStatus::Executing, _) => "executing".to_string(),
+        _ if record.oom_killed == Some(true) => "oom-killed".to_string(),
+        (_, Some(0)) => "succeeded".to_string(),
+        (_, Some(code)) => format!("failed ({})", code),
+        (_, None) => "finished".to_string(),
+    }
+}
+
+/// Time taken so far by an executing record, or in total by a finished one
+pub fn elapsed_label(record: &ExecutionRecord) -> String {
+    let parse = |time: &str| chrono::DateTime::parse_from_rfc3339(time).ok();
+    let end = record
+        .end_time
+        .clone()
+        .or_else(|| attach_current_time(record));
+    match (parse(&record.start_time), end.as_deref().and_then(parse)) {
+        (Some(start), Some(end)) => format_duration((end - start).num_milliseconds().max(0) as f64),
+        _ => "-".to_string(),
+    }
+}
+
+/// Isolation backend of a record ("direct" for none)
+fn backend_label(record: &ExecutionRecord) -> &str {
+    record
+        .options
+        .get("isolated")
+        .and_then(|value| value.as_str())
+        .unwrap_or("direct")
+}
+
+/// Session name of a record, or the start of its UUID
+fn session_label(record: &ExecutionRecord) -> String {
+    record
+        .options
+        .get("sessionName")
+        .and_then(|value| value.as_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| record.uuid.chars().take(8).collect())
+}
+
+/// `text` cut to `width` characters
+fn fit(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Command reattaching the terminal to a running detached session
+pub fn attach_command(record: &ExecutionRecord) -> Option<(String, Vec<String>)> {
+    let option = |key: &str| record.options.get(key).and_then(|value| value.as_str());
+    if record.status != ExecutionStatus::Executing || option("isolationMode") != Some("detached") {
+        return None;
+    }
+    let session = option("sessionName")?.to_string();
+    let (command, args) = match option("isolated")? {
+        "screen" => ("screen", vec!["-r".to_string(), session]),
+        "tmux" => (
+            "tmux",
+            vec!["attach".to_string(), "-t".to_string(), session],
+        ),
+        engine @ ("docker" | "podman") => (engine, vec!["attach".to_string(), session]),
+        _ => return None,
+    };
+    Some((command.to_string(), args))
+}
+
+/// Arguments for `start` that run the command of `record` again in the
+/// foreground, in the same isolation environment
+pub fn replay_args(record: &ExecutionRecord) -> Vec<String> {
+    let mut args = Vec::new();
+    for (key, flag) in [
+        ("isolated", "--isolated"),
+        ("image", "--image"),
+        ("endpoint", "--endpoint"),
+    ] {
+        if let Some(value) = record.options.get(key).and_then(|value| value.as_str()) {
+            args.push(flag.to_string());
+            args.push(value.to_string());
+        }
+    }
+    args.push("--".to_string());
+    args.push(record.command.clone());
+    args
+}
+
+/// Last `lines` lines of the log of `record` as plain text cut to `width`
+/// characters. Only the end of the log is read, since the preview is
+/// redrawn several times a second.
+pub fn log_preview(record: &ExecutionRecord, lines: usize, width: usize) -> Vec<String> {
+    match log_source(record) {
+        Ok(LogSource::Local(path)) => match read_log_end(&path, PREVIEW_BYTES) {
+            Ok(text) => tail_lines(&strip_ansi(&text), lines)
+                .lines()
+                .map(|line| preview_line(line, width))
+                .collect(),
+            Err(_) => vec![format!("(log not found: {})", path.display())],
+        },
+        Ok(LogSource::Remote { endpoint, .. }) => {
+            vec![format!("(log is on {}: press a to follow it)", endpoint)]
+        }
+        Err(e) => vec![format!("({})", e)],
+    }
+}
+
+/// The last `bytes` bytes of the file at `path`, starting at a line
+fn read_log_end(path: &Path, bytes: u64) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let start = file.metadata()?.len().saturating_sub(bytes);
+    file.seek(SeekFrom::Start(start))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    if start > 0 {
+        // Drop the line the read started in the middle of
+        let line_start = content
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .map_or(0, |i| i + 1);
+        content.drain(..line_start);
+    }
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// A log line as the terminal would show it: only the text after the last
+/// carriage return, without control characters, cut to `width`
+fn preview_line(line: &str, width: usize) -> String {
+    let shown = line.rsplit('\r').next().unwrap_or_default();
+    let plain: String = shown
+        .chars()
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .filter(|c| !c.is_control())
+        .collect();
+    fit(&plain, width)
+}
+
+/// State of the dashboard: the listed records and the selection
+#[derive(Debug, Default)]
+pub struct Dashboard {
+    records: Vec<ExecutionRecord>,
+    selected: usize,
+    /// Result of the last action, shown above the key help
+    pub message: Option<String>,
+    /// Terminate was pressed once and waits for confirmation
+    confirm_terminate: bool,
+}
+
+impl Dashboard {
+    /// Replace the listed records, keeping the selected execution selected
+    pub fn set_records(&mut self, records: Vec<ExecutionRecord>) {
+        let selected_uuid = self.selected().map(|record| record.uuid.clone());
+        self.records = records;
+        self.selected = selected_uuid
+            .and_then(|uuid| self.records.iter().position(|r| r.uuid == uuid))
+            .unwrap_or(self.selected.min(self.records.len().saturating_sub(1)));
+    }
+
+    pub fn records(&self) -> &[ExecutionRecord] {
+        &self.records
+    }
+
+    pub fn selected(&self) -> Option<&ExecutionRecord> {
+        self.records.get(self.selected)
+    }
+
+    /// Move the selection up (negative) or down, staying within the list
+    pub fn move_selection(&mut self, delta: isize) {
+        self.confirm_terminate = false;
+        let last = self.records.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Render a `width` × `height` frame given the selected record's log tail
+    pub fn render(&self, log_tail: &[String], width: usize, height: usize) -> Vec<String> {
+        let executing = self
+            .records
+            .iter()
+            .filter(|r| r.status == ExecutionStatus::Executing)
+            .count();
+        let mut lines = vec![
+            format!(
+                "start --dashboard  {} executions, {} executing",
+                self.records.len(),
+                executing
+            ),
+            format!(
+                "  {:<12} {:>9}  {:<8} {:<20} COMMAND",
+                "STATUS", "DURATION", "BACKEND", "SESSION"
+            ),
+        ];
+
+        // The list takes up to half of the screen and scrolls with the selection
+        let list_height = (height.saturating_sub(6) / 2).max(1);
+        let first = self.selected.saturating_sub(list_height - 1);
+        for (index, record) in self
+            .records
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(list_height)
+        {
+            lines.push(format!(
+                "{} {:<12} {:>9}  {:<8} {:<20} {}",
+                if index == self.selected { ">" } else { " " },
+                status_label(record),
+                elapsed_label(record),
+                fit(backend_label(record), 8),
+                fit(&session_label(record), 20),
+                record.command
+            ));
+        }
+        if self.records.is_empty() {
+            lines.push("  No executions found.".to_string());
+        }
+
+        lines.push("-".repeat(width));
+        if let Some(record) = self.selected() {
+            lines.push(format!("Log: {}", record.log_path));
+            let preview_height = height.saturating_sub(lines.len() + 2);
+            let skip = log_tail.len().saturating_sub(preview_height);
+            lines.extend(log_tail.iter().skip(skip).cloned());
+        }
+        while lines.len() + 2 < height {
+            lines.push(String::new());
+        }
+        lines.push(self.message.clone().unwrap_or_default());
+        lines.push(DASHBOARD_KEYS.to_string());
+        lines.iter().map(|line| fit(line, width)).collect()
+    }
+}
+
+/// Records to list, newest first, with live status for executing ones
+fn load_records(store: &ExecutionStore, filter: &RecordFilter) -> Vec<ExecutionRecord> {
+    filter
+        .apply(store.get_all())
+        .into_iter()
+        .map(|record| match record.status {
+            ExecutionStatus::Executing => enrich_detached_status(&record),
+            ExecutionStatus::Executed => record,
+        })
+        .collect()
+}
+
+/// Run `start` itself with `args` in `directory`, returning a summary
+fn run_start(args: &[String], directory: &str) -> String {
+    let program = std::env::current_exe().unwrap_or_else(|_| "start".into());
+    let mut command = Command::new(program);
+    command.args(args);
+    if Path::new(directory).is_dir() {
+        command.current_dir(directory);
+    }
+    match command.status() {
+        Ok(status) => format!("Exited with code {}", status.code().unwrap_or(-1)),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+/// Handle a key press on the selected record
+fn handle_key(
+    key: DashboardKey,
+    dashboard: &mut Dashboard,
+    store: &ExecutionStore,
+    terminal: &mut terminal::Terminal,
+) {
+    let confirming = std::mem::take(&mut dashboard.confirm_terminate);
+    let Some(record) = dashboard.selected().cloned() else {
+        return;
+    };
+    let label = session_label(&record);
+    dashboard.message = match key {
+        DashboardKey::Up | DashboardKey::Down | DashboardKey::Quit => return,
+        DashboardKey::Terminate if !confirming => {
+            dashboard.confirm_terminate = true;
+            Some(format!("Press t again to terminate {}", label))
+        }
+        DashboardKey::Stop | DashboardKey::Terminate => {
+            let action = if key == DashboardKey::Stop {
+                ControlAction::Stop
+            } else {
+                ControlAction::Terminate
+            };
+            let result = control_execution(Some(store), &record.uuid, action);
+            Some(match result.error {
+                Some(error) => format!("Error: {}", error),
+                None => format!("Sent {} to {}", action.as_str(), label),
+            })
+        }
+        DashboardKey::Upload => Some(terminal.suspend(true, || {
+            match upload_execution_log(Some(store), &record.uuid) {
+                Ok(0) => format!("Uploaded the log of {}", label),
+                Ok(code) => format!("Upload exited with code {}", code),
+                Err(error) => format!("Error: {}", error),
+            }
+        })),
+        DashboardKey::Replay => Some(terminal.suspend(true, || {
+            run_start(&replay_args(&record), &record.working_directory)
+        })),
+        DashboardKey::Attach => Some(terminal.suspend(false, || {
+            match attach_command(&record) {
+                Some((command, args)) => match Command::new(&command).args(&args).status() {
+                    Ok(_) => format!("Detached from {}", label),
+                    Err(e) => format!("Error: failed to run {}: {}", command, e),
+                },
+                // Nothing to attach to: follow the log instead
+                None => run_start(
+                    &["--follow".to_string(), record.uuid.clone()],
+                    &record.working_directory,
+                ),
+            }
+        })),
+    };
+}
+
+/// Run the dashboard until the user quits
+pub fn run_dashboard(store: Option<&ExecutionStore>, filter: &RecordFilter) -> Result<(), String> {
+    let store = store.ok_or_else(|| "Execution tracking is disabled.".to_string())?;
+    let mut terminal = terminal::Terminal::enter()?;
+    let mut dashboard = Dashboard::default();
+    let mut last_refresh: Option<Instant> = None;
+
+    loop {
+        if last_refresh.is_none_or(|at| at.elapsed() >= REFRESH_INTERVAL) {
+            dashboard.set_records(load_records(store, filter));
+            last_refresh = Some(Instant::now());
+        }
+        let (width, height) = terminal.size();
+        let log_tail = dashboard
+            .selected()
+            .map(|record| log_preview(record, height, width))
+            .unwrap_or_default();
+        terminal.draw(&dashboard.render(&log_tail, width, height))?;
+
+        let Some(key) = terminal
+            .read_key(KEY_TIMEOUT)
+            .and_then(|input| parse_key(&input))
+        else {
+            continue;
+        };
+        match key {
+            DashboardKey::Quit => return Ok(()),
+            DashboardKey::Up => dashboard.move_selection(-1),
+            DashboardKey::Down => dashboard.move_selection(1),
+            _ => {
+                handle_key(key, &mut dashboard, store, &mut terminal);
+                last_refresh = None;
+            }
+        }
+    }
+}
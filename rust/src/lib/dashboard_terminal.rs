@@ -0,0 +1,158 @@
+//! Raw-mode terminal for the dashboard
+//!
+//! Puts the controlling terminal into non-canonical mode on the alternate
+//! screen and restores it when dropped, or temporarily while another program
+//! (an attached session, an upload prompt, a replay) uses the terminal.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+#[cfg(unix)]
+pub(super) struct Terminal {
+    original: libc::termios,
+}
+
+#[cfg(unix)]
+impl Terminal {
+    /// Enter raw mode on the alternate screen
+    pub fn enter() -> Result<Self, String> {
+        let interactive = unsafe {
+            libc::isatty(libc::STDIN_FILENO) != 0 && libc::isatty(libc::STDOUT_FILENO) != 0
+        };
+        if !interactive {
+            return Err("--dashboard requires an interactive terminal".to_string());
+        }
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return Err(format!(
+                "Failed to read terminal settings: {}",
+                io::Error::last_os_error()
+            ));
+        }
+        let terminal = Terminal { original };
+        terminal.activate();
+        Ok(terminal)
+    }
+
+    fn activate(&self) {
+        let mut raw = self.original;
+        // Read keys one by one without echo; Ctrl-C arrives as a key
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) };
+        // Alternate screen, hidden cursor
+        print_raw("\x1b[?1049h\x1b[?25l");
+    }
+
+    fn deactivate(&self) {
+        print_raw("\x1b[?25h\x1b[?1049l");
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original) };
+    }
+
+    /// Terminal width and height, 80×24 when unknown
+    pub fn size(&self) -> (usize, usize) {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let known = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } == 0;
+        if known && size.ws_col > 0 && size.ws_row > 0 {
+            (size.ws_col as usize, size.ws_row as usize)
+        } else {
+            (80, 24)
+        }
+    }
+
+    /// Bytes of the next key press, or `None` if none arrives within `timeout`
+    pub fn read_key(&self, timeout: Duration) -> Option<Vec<u8>> {
+        let mut poll = libc::pollfd {
+            fd: libc::STDIN_FILENO,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut poll, 1, timeout.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+        // An escape sequence (arrow key) arrives in a single read
+        let mut buffer = [0u8; 16];
+        let read = unsafe {
+            libc::read(
+                libc::STDIN_FILENO,
+                buffer.as_mut_ptr() as *mut libc::c_void,
+                buffer.len(),
+            )
+        };
+        (read > 0).then(|| buffer[..read as usize].to_vec())
+    }
+
+    /// Give the terminal back to the user while `f` runs. With
+    /// `wait_for_key`, the output of `f` stays visible until a key is pressed.
+    pub fn suspend<T>(&mut self, wait_for_key: bool, f: impl FnOnce() -> T) -> T {
+        self.deactivate();
+        let result = f();
+        if wait_for_key {
+            print_raw("\nPress any key to return to the dashboard...");
+            let mut raw = self.original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) };
+            while self.read_key(Duration::from_secs(3600)).is_none() {}
+        }
+        self.activate();
+        result
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        self.deactivate();
+    }
+}
+
+#[cfg(not(unix))]
+pub(super) struct Terminal;
+
+#[cfg(not(unix))]
+impl Terminal {
+    pub fn enter() -> Result<Self, String> {
+        Err("--dashboard is only supported on Unix terminals".to_string())
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (80, 24)
+    }
+
+    pub fn read_key(&self, _timeout: Duration) -> Option<Vec<u8>> {
+        None
+    }
+
+    pub fn suspend<T>(&mut self, _wait_for_key: bool, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+}
+
+impl Terminal {
+    /// Redraw the screen with `lines`
+    pub fn draw(&mut self, lines: &[String]) -> Result<(), String> {
+        let mut frame = String::from("\x1b[H");
+        for (index, line) in lines.iter().enumerate() {
+            if index > 0 {
+                frame.push_str("\r\n");
+            }
+            frame.push_str(line);
+            frame.push_str("\x1b[K");
+        }
+        frame.push_str("\x1b[J");
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(frame.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("Failed to draw the dashboard: {}", e))
+    }
+}
+
+fn print_raw(text: &str) {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(text.as_bytes());
+    let _ = stdout.flush();
+}
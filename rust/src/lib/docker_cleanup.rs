@@ -9,6 +9,7 @@ use crate::isolation::isolation_log::{
     append_log_file, create_shell_log_footer_snippet, shell_quote,
 };
 use crate::isolation::IsolationOptions;
+use crate::output_theme::OutputStyle;
 
 /// Build the extra `docker run` arguments contributed by runtime options
 /// (--privileged, --env/-e, --volume/-v, --mount). Returned references borrow
@@ -41,8 +42,61 @@ pub(crate) enum DockerContainerCleanupPolicy {
     KeepOnFail,
 }
 
-pub(crate) fn docker_command() -> std::ffi::OsString {
-    std::env::var_os("START_DOCKER_BIN").unwrap_or_else(|| std::ffi::OsString::from("docker"))
+/// Container engine behind a container backend. Podman takes the same
+/// `run`/`inspect`/`rm` arguments as docker and runs rootless without a daemon.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ContainerEngine {
+    Docker,
+    Podman,
+}
+
+impl ContainerEngine {
+    /// The engine of a container backend (`docker` or `podman`)
+    pub(crate) fn from_backend(backend: &str) -> Option<Self> {
+        match backend {
+            "docker" => Some(ContainerEngine::Docker),
+            "podman" => Some(ContainerEngine::Podman),
+            _ => None,
+        }
+    }
+
+    /// Backend name, also the default executable
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+
+    /// Name used in messages
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "Docker",
+            ContainerEngine::Podman => "Podman",
+        }
+    }
+
+    /// Executable to run, overridable with START_DOCKER_BIN / START_PODMAN_BIN
+    pub(crate) fn command(self) -> std::ffi::OsString {
+        let variable = match self {
+            ContainerEngine::Docker => "START_DOCKER_BIN",
+            ContainerEngine::Podman => "START_PODMAN_BIN",
+        };
+        std::env::var_os(variable).unwrap_or_else(|| std::ffi::OsString::from(self.name()))
+    }
+
+    /// The executable as a word for shell scripts
+    fn shell_command(self) -> String {
+        let command = self.command().to_string_lossy().to_string();
+        if command
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./".contains(c))
+        {
+            command
+        } else {
+            shell_quote(&command)
+        }
+    }
 }
 
 pub(crate) fn get_docker_container_cleanup_policy(
@@ -76,14 +130,19 @@ pub(crate) fn should_cleanup_docker_container(
     }
 }
 
-pub(crate) fn docker_container_cleanup_instructions(container_name: &str) -> String {
+pub(crate) fn docker_container_cleanup_instructions(
+    engine: ContainerEngine,
+    container_name: &str,
+) -> String {
+    let cli = engine.name();
     format!(
-        "Container kept for investigation: {}\nInspect: docker exec -it {} sh (if running) or docker start -ai {}\nRemove when done: docker rm -f {}",
-        container_name, container_name, container_name, container_name
+        "Container kept for investigation: {}\nInspect: {} exec -it {} sh (if running) or {} start -ai {}\nRemove when done: {} rm -f {}",
+        container_name, cli, container_name, cli, container_name, cli, container_name
     )
 }
 
 pub(crate) fn append_docker_container_cleanup_policy_message(
+    engine: ContainerEngine,
     message: &mut String,
     container_name: &str,
     policy: DockerContainerCleanupPolicy,
@@ -94,33 +153,43 @@ pub(crate) fn append_docker_container_cleanup_policy_message(
         }
         DockerContainerCleanupPolicy::Default => {
             message.push_str("\nContainer will be removed after successful completion.");
-            message.push_str(
-                "\nContainer will be kept if the command fails or Docker reports OOMKilled.",
-            );
             message.push_str(&format!(
-                "\nRemove when done: docker rm -f {}",
+                "\nContainer will be kept if the command fails or {} reports OOMKilled.",
+                engine.label()
+            ));
+            message.push_str(&format!(
+                "\nRemove when done: {} rm -f {}",
+                engine.name(),
                 container_name
             ));
         }
         DockerContainerCleanupPolicy::Keep => {
             message.push('\n');
-            message.push_str(&docker_container_cleanup_instructions(container_name));
+            message.push_str(&docker_container_cleanup_instructions(
+                engine,
+                container_name,
+            ));
         }
         DockerContainerCleanupPolicy::KeepOnFail => {
             message.push_str("\nContainer will be removed after successful completion.");
-            message.push_str(
-                "\nContainer will be kept if the command fails or Docker reports OOMKilled.",
-            );
             message.push_str(&format!(
-                "\nRemove when done: docker rm -f {}",
+                "\nContainer will be kept if the command fails or {} reports OOMKilled.",
+                engine.label()
+            ));
+            message.push_str(&format!(
+                "\nRemove when done: {} rm -f {}",
+                engine.name(),
                 container_name
             ));
         }
     }
 }
 
-pub(crate) fn read_docker_container_oom_killed(container_name: &str) -> Option<bool> {
-    let output = Command::new(docker_command())
+pub(crate) fn read_docker_container_oom_killed(
+    engine: ContainerEngine,
+    container_name: &str,
+) -> Option<bool> {
+    let output = Command::new(engine.command())
         .args(["inspect", "-f", "{{.State.OOMKilled}}", container_name])
         .output()
         .ok()?;
@@ -134,8 +203,12 @@ pub(crate) fn read_docker_container_oom_killed(container_name: &str) -> Option<b
     }
 }
 
-pub(crate) fn remove_docker_container(container_name: &str, log_path: Option<&PathBuf>) -> bool {
-    let output = Command::new(docker_command())
+pub(crate) fn remove_docker_container(
+    engine: ContainerEngine,
+    container_name: &str,
+    log_path: Option<&PathBuf>,
+) -> bool {
+    let output = Command::new(engine.command())
         .args(["rm", "-f", container_name])
         .output();
     match output {
@@ -159,10 +232,15 @@ pub(crate) fn remove_docker_container(container_name: &str, log_path: Option<&Pa
     }
 }
 
-fn build_docker_kept_log_snippet(container_name: &str, quoted_log_path: &str) -> String {
+fn build_docker_kept_log_snippet(
+    engine: ContainerEngine,
+    container_name: &str,
+    quoted_log_path: &str,
+) -> String {
     let quoted_name = shell_quote(container_name);
+    let cli = engine.name();
     format!(
-        "printf '\\nContainer kept for investigation: %s\\nReason: exitCode=%s oomKilled=%s\\nInspect: docker exec -it %s sh (if running) or docker start -ai %s\\nRemove when done: docker rm -f %s\\n' {} \"$__start_command_exit\" \"$__start_command_oom\" {} {} {} >> {}",
+        "printf '\\nContainer kept for investigation: %s\\nReason: exitCode=%s oomKilled=%s\\nInspect: {cli} exec -it %s sh (if running) or {cli} start -ai %s\\nRemove when done: {cli} rm -f %s\\n' {} \"$__start_command_exit\" \"$__start_command_oom\" {} {} {} >> {}",
         quoted_name, quoted_name, quoted_name, quoted_name, quoted_log_path
     )
 }
@@ -172,44 +250,48 @@ fn successful_non_oom_condition() -> &'static str {
 }
 
 fn build_detached_docker_completion_script(
+    engine: ContainerEngine,
     container_name: &str,
     policy: DockerContainerCleanupPolicy,
     log_path: Option<&PathBuf>,
 ) -> String {
     let quoted_name = shell_quote(container_name);
+    let cli = engine.shell_command();
     let mut parts = Vec::new();
 
     if let Some(path) = log_path {
         let log_path_string = path.to_string_lossy().to_string();
         let quoted_log_path = shell_quote(&log_path_string);
         parts.push(format!(
-            "docker logs -f {} >> {} 2>&1",
-            quoted_name, quoted_log_path
+            "{} logs -f {} >> {} 2>&1",
+            cli, quoted_name, quoted_log_path
         ));
         parts.push(format!(
-            "__start_command_state=$(docker inspect -f '{{{{.State.ExitCode}}}} {{{{.State.OOMKilled}}}}' {} 2>/dev/null || printf '%s' '-1 false')",
-            quoted_name
+            "__start_command_state=$({} inspect -f '{{{{.State.ExitCode}}}} {{{{.State.OOMKilled}}}}' {} 2>/dev/null || printf '%s' '-1 false')",
+            cli, quoted_name
         ));
         parts.push("__start_command_exit=${__start_command_state%% *}".to_string());
         parts.push("__start_command_oom=${__start_command_state##* }".to_string());
         match policy {
             DockerContainerCleanupPolicy::Always => parts.push(format!(
-                "docker rm -f {} >> {} 2>&1 || true",
-                quoted_name, quoted_log_path
+                "{} rm -f {} >> {} 2>&1 || true",
+                cli, quoted_name, quoted_log_path
             )),
             DockerContainerCleanupPolicy::Default => parts.push(format!(
-                "if {}; then docker rm -f {} >> {} 2>&1 || true; else {}; fi",
+                "if {}; then {} rm -f {} >> {} 2>&1 || true; else {}; fi",
                 successful_non_oom_condition(),
+                cli,
                 quoted_name,
                 quoted_log_path,
-                build_docker_kept_log_snippet(container_name, &quoted_log_path)
+                build_docker_kept_log_snippet(engine, container_name, &quoted_log_path)
             )),
             DockerContainerCleanupPolicy::KeepOnFail => parts.push(format!(
-                "if {}; then docker rm -f {} >> {} 2>&1 || true; else {}; fi",
+                "if {}; then {} rm -f {} >> {} 2>&1 || true; else {}; fi",
                 successful_non_oom_condition(),
+                cli,
                 quoted_name,
                 quoted_log_path,
-                build_docker_kept_log_snippet(container_name, &quoted_log_path)
+                build_docker_kept_log_snippet(engine, container_name, &quoted_log_path)
             )),
             DockerContainerCleanupPolicy::Keep => {}
         }
@@ -219,26 +301,28 @@ fn build_detached_docker_completion_script(
             quoted_log_path
         ));
     } else {
-        parts.push(format!("docker wait {} >/dev/null 2>&1", quoted_name));
+        parts.push(format!("{} wait {} >/dev/null 2>&1", cli, quoted_name));
         parts.push(format!(
-            "__start_command_state=$(docker inspect -f '{{{{.State.ExitCode}}}} {{{{.State.OOMKilled}}}}' {} 2>/dev/null || printf '%s' '-1 false')",
-            quoted_name
+            "__start_command_state=$({} inspect -f '{{{{.State.ExitCode}}}} {{{{.State.OOMKilled}}}}' {} 2>/dev/null || printf '%s' '-1 false')",
+            cli, quoted_name
         ));
         parts.push("__start_command_exit=${__start_command_state%% *}".to_string());
         parts.push("__start_command_oom=${__start_command_state##* }".to_string());
         match policy {
             DockerContainerCleanupPolicy::Always => parts.push(format!(
-                "docker rm -f {} >/dev/null 2>&1 || true",
-                quoted_name
+                "{} rm -f {} >/dev/null 2>&1 || true",
+                cli, quoted_name
             )),
             DockerContainerCleanupPolicy::Default => parts.push(format!(
-                "if {}; then docker rm -f {} >/dev/null 2>&1 || true; fi",
+                "if {}; then {} rm -f {} >/dev/null 2>&1 || true; fi",
                 successful_non_oom_condition(),
+                cli,
                 quoted_name
             )),
             DockerContainerCleanupPolicy::KeepOnFail => parts.push(format!(
-                "if {}; then docker rm -f {} >/dev/null 2>&1 || true; fi",
+                "if {}; then {} rm -f {} >/dev/null 2>&1 || true; fi",
                 successful_non_oom_condition(),
+                cli,
                 quoted_name
             )),
             DockerContainerCleanupPolicy::Keep => {}
@@ -249,11 +333,12 @@ fn build_detached_docker_completion_script(
 }
 
 pub(crate) fn start_detached_docker_completion_watcher(
+    engine: ContainerEngine,
     container_name: &str,
     policy: DockerContainerCleanupPolicy,
     log_path: Option<&PathBuf>,
 ) {
-    let script = build_detached_docker_completion_script(container_name, policy, log_path);
+    let script = build_detached_docker_completion_script(engine, container_name, policy, log_path);
     let _ = Command::new("sh")
         .args(["-c", &script])
         .stdout(Stdio::null())
@@ -281,11 +366,12 @@ impl AttachedDockerChild {
 }
 
 pub(crate) fn spawn_attached_docker(
+    engine: ContainerEngine,
     args: &[&str],
     log_path: Option<&PathBuf>,
 ) -> std::io::Result<AttachedDockerChild> {
     if log_path.is_none() {
-        let child = Command::new(docker_command())
+        let child = Command::new(engine.command())
             .args(args)
             .stdin(Stdio::inherit())
             .stdout(Stdio::inherit())
@@ -298,7 +384,7 @@ pub(crate) fn spawn_attached_docker(
         });
     }
 
-    let mut child = Command::new(docker_command())
+    let mut child = Command::new(engine.command())
         .args(args)
         .stdin(Stdio::inherit())
         .stdout(Stdio::piped())
@@ -310,6 +396,8 @@ pub(crate) fn spawn_attached_docker(
         .append(true)
         .open(log_path.unwrap())?;
     let shared_log = Arc::new(Mutex::new(file));
+    // --silent and --summary-only keep the output in the log only
+    let echo = OutputStyle::current().mode.shows_output();
 
     let mut stdout_thread = None;
     let mut stderr_thread = None;
@@ -324,8 +412,10 @@ pub(crate) fn spawn_attached_docker(
                     break;
                 }
                 let chunk = &buffer[..size];
-                let _ = terminal.write_all(chunk);
-                let _ = terminal.flush();
+                if echo {
+                    let _ = terminal.write_all(chunk);
+                    let _ = terminal.flush();
+                }
                 if let Ok(mut file) = log.lock() {
                     let _ = file.write_all(chunk);
                     let _ = file.flush();
@@ -344,8 +434,10 @@ pub(crate) fn spawn_attached_docker(
                     break;
                 }
                 let chunk = &buffer[..size];
-                let _ = terminal.write_all(chunk);
-                let _ = terminal.flush();
+                if echo {
+                    let _ = terminal.write_all(chunk);
+                    let _ = terminal.flush();
+                }
                 if let Ok(mut file) = log.lock() {
                     let _ = file.write_all(chunk);
                     let _ = file.flush();
@@ -403,6 +495,7 @@ mod tests {
     fn detached_watcher_inspects_oom_killed_before_default_cleanup() {
         let log_path = PathBuf::from("/tmp/issue144.log");
         let script = build_detached_docker_completion_script(
+            ContainerEngine::Docker,
             "issue144-container",
             DockerContainerCleanupPolicy::Default,
             Some(&log_path),
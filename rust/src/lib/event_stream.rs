@@ -0,0 +1,189 @@
+//! Machine-readable event stream for a launched command (--events)
+//!
+//! While a command runs, one JSON object per line is written to a file or to
+//! an inherited file descriptor (`fd:N`), so IDEs and harnesses can track an
+//! execution without parsing the timeline blocks. The human output is not
+//! changed. Every event carries `event`, `timestamp` (RFC 3339, UTC) and
+//! `session` (the execution UUID) next to its own fields:
+//! - `started` → `command`, `isolated`, `mode`, `logPath`, `pid`
+//! - `substitution-applied` → `original`, `command`
+//! - `isolation-session-created` → `isolated`, `name`, `mode`
+//! - `image-pulled` → `image`, `success`, `durationMs`
+//! - `output` → `stream` (`stdout`/`stderr`), `line`
+//! - `finished` → `exitCode`, `durationMs`, `logPath`, `detached`
+//! - `cleanup` → `kind` (`user`/`container`), `target`, `success`
+//! - `issue-created` → `url`
+//!
+//! `output` events are emitted for direct runs, and for attached
+//! `--isolated ssh` and `--isolated-user` runs launched with `--silent` or
+//! `--summary-only`, whose output is captured instead of shown. Other
+//! isolated runs write to the terminal themselves and emit none.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde_json::{json, Map, Value};
+
+/// Event types written to the stream
+pub const EVENT_TYPES: [&str; 8] = [
+    "started",
+    "substitution-applied",
+    "isolation-session-created",
+    "image-pulled",
+    "output",
+    "finished",
+    "cleanup",
+    "issue-created",
+];
+
+/// Where --events writes to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventTarget {
+    /// A file, created if missing and appended to
+    Path(PathBuf),
+    /// A file descriptor inherited from the caller (`fd:N`)
+    Fd(i32),
+}
+
+impl EventTarget {
+    /// Parse a `<path>` or `fd:N` target
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if spec.is_empty() {
+            return Err("--events requires a path or fd:N".to_string());
+        }
+        match spec.strip_prefix("fd:") {
+            Some(fd) => fd
+                .parse::<i32>()
+                .ok()
+                .filter(|fd| *fd >= 0)
+                .map(EventTarget::Fd)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid --events target: \"{}\". Expected a path or fd:N",
+                        spec
+                    )
+                }),
+            None => Ok(EventTarget::Path(PathBuf::from(spec))),
+        }
+    }
+}
+
+/// An open event stream for one execution
+#[derive(Debug)]
+pub struct EventStream {
+    file: Mutex<File>,
+    session: String,
+}
+
+static INSTALLED_STREAM: OnceLock<EventStream> = OnceLock::new();
+
+impl EventStream {
+    /// Open the stream for `spec` (a path or `fd:N`)
+    pub fn open(spec: &str, session: &str) -> Result<Self, String> {
+        let file = match EventTarget::parse(spec)? {
+            EventTarget::Path(path) => OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| format!("Could not open --events file {}: {}", path.display(), e))?,
+            EventTarget::Fd(fd) => open_fd(fd)?,
+        };
+        Ok(EventStream {
+            file: Mutex::new(file),
+            session: session.to_string(),
+        })
+    }
+
+    /// Make this the stream [`emit_event`] writes to for the rest of the process
+    pub fn install(self) {
+        let _ = INSTALLED_STREAM.set(self);
+    }
+
+    /// Write one event; failures to write are ignored so a closed reader
+    /// never breaks the run
+    pub fn emit(&self, event: &str, fields: Value) {
+        let line = event_line(event, &timestamp(), &self.session, fields);
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(format!("{}\n", line).as_bytes());
+        }
+    }
+}
+
+/// Write an event to the installed stream, if --events was given
+pub fn emit_event(event: &str, fields: Value) {
+    if let Some(stream) = INSTALLED_STREAM.get() {
+        stream.emit(event, fields);
+    }
+}
+
+/// Report the launch of a command (`isolated` is `None` for direct runs)
+pub fn emit_started(command: &str, isolated: Option<&str>, mode: &str, log_path: &Path) {
+    emit_event(
+        "started",
+        json!({
+            "command": command,
+            "isolated": isolated,
+            "mode": mode,
+            "logPath": log_path.to_string_lossy(),
+            "pid": std::process::id(),
+        }),
+    );
+}
+
+/// Report the end of a run; a `detached` run is still executing
+pub fn emit_finished(exit_code: i32, duration_ms: f64, log_path: &Path, detached: bool) {
+    emit_event(
+        "finished",
+        json!({
+            "exitCode": exit_code,
+            "durationMs": duration_ms.round() as u64,
+            "logPath": log_path.to_string_lossy(),
+            "detached": detached,
+        }),
+    );
+}
+
+/// The JSON line for an event: the fields of `fields` (when it is an
+/// object) plus `event`, `timestamp` and `session`
+pub fn event_line(event: &str, timestamp: &str, session: &str, fields: Value) -> String {
+    let mut object = match fields {
+        Value::Object(fields) => fields,
+        _ => Map::new(),
+    };
+    object.insert("event".to_string(), Value::from(event));
+    object.insert("timestamp".to_string(), Value::from(timestamp));
+    object.insert("session".to_string(), Value::from(session));
+    Value::Object(object).to_string()
+}
+
+fn timestamp() -> String {
+    chrono::Utc::now()
+        .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+        .to_string()
+}
+
+#[cfg(unix)]
+fn open_fd(fd: i32) -> Result<File, String> {
+    use std::os::unix::io::FromRawFd;
+    // Duplicate the descriptor so an invalid one is reported up front, and
+    // close the copy on exec so the launched command does not inherit it
+    let duplicate = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+    if duplicate < 0 {
+        return Err(format!(
+            "Could not open --events fd:{}: {}",
+            fd,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(unsafe { File::from_raw_fd(duplicate) })
+}
+
+#[cfg(not(unix))]
+fn open_fd(fd: i32) -> Result<File, String> {
+    Err(format!(
+        "Could not open --events fd:{}: file descriptors are only supported on Unix",
+        fd
+    ))
+}
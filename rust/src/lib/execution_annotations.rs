@@ -0,0 +1,108 @@
+//! Tags, labels and notes on execution records
+//!
+//! Tags (`--tag release-1.4`) and labels (`--label env=staging`) are attached
+//! when a command is launched, and `--annotate <uuid> --note "..."` adds notes
+//! (and further tags or labels) after the fact. `--status` shows them and the
+//! same `--tag`/`--label` options filter `--list`, `--stats` and `--export`.
+
+use crate::args_parser::WrapperOptions;
+use crate::execution_store::{ExecutionNote, ExecutionRecord, ExecutionStore};
+use crate::status_formatter::{format_record, StatusQueryResult};
+use chrono::Utc;
+use std::collections::BTreeMap;
+
+/// Parse a `key=value` label. Keys must be non-empty and contain no whitespace.
+pub fn parse_label(value: &str) -> Result<(String, String), String> {
+    let invalid = || format!("Invalid label: \"{}\". Expected key=value", value);
+    let (key, label_value) = value.split_once('=').ok_or_else(invalid)?;
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+    Ok((key.to_string(), label_value.trim().to_string()))
+}
+
+/// Validate a tag: non-empty and without whitespace
+pub fn parse_tag(value: &str) -> Result<String, String> {
+    let tag = value.trim();
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        return Err(format!(
+            "Invalid tag: \"{}\". Tags must be non-empty and contain no whitespace",
+            value
+        ));
+    }
+    Ok(tag.to_string())
+}
+
+/// Add tags (without duplicates) and labels (overwriting same keys) to a record
+pub fn add_tags_and_labels(
+    record: &mut ExecutionRecord,
+    tags: &[String],
+    labels: &BTreeMap<String, String>,
+) {
+    for tag in tags {
+        if !record.tags.contains(tag) {
+            record.tags.push(tag.clone());
+        }
+    }
+    record
+        .labels
+        .extend(labels.iter().map(|(k, v)| (k.clone(), v.clone())));
+}
+
+/// Attach the `--tag`/`--label` options of a launch to its execution record
+pub fn apply_launch_annotations(record: &mut ExecutionRecord, options: &WrapperOptions) {
+    add_tags_and_labels(record, &options.tags, &options.labels);
+}
+
+/// Add a note and/or tags and labels to a stored execution
+pub fn annotate_execution(
+    store: &ExecutionStore,
+    identifier: &str,
+    note: Option<&str>,
+    tags: &[String],
+    labels: &BTreeMap<String, String>,
+) -> Result<ExecutionRecord, String> {
+    store
+        .update(identifier, |record| {
+            add_tags_and_labels(record, tags, labels);
+            if let Some(text) = note {
+                record.notes.push(ExecutionNote {
+                    time: Utc::now().to_rfc3339(),
+                    text: text.to_string(),
+                });
+            }
+        })?
+        .ok_or_else(|| {
+            format!(
+                "No execution found with UUID or session name: {}",
+                identifier
+            )
+        })
+}
+
+/// Handle --annotate and return the updated record in the requested format
+pub fn query_annotate(
+    store: Option<&ExecutionStore>,
+    options: &WrapperOptions,
+) -> StatusQueryResult {
+    let result = store
+        .ok_or_else(|| "Execution tracking is disabled.".to_string())
+        .and_then(|store| {
+            annotate_execution(
+                store,
+                options.annotate.as_deref().unwrap_or_default(),
+                options.note.as_deref(),
+                &options.tags,
+                &options.labels,
+            )
+        })
+        .and_then(|record| {
+            format_record(
+                &record,
+                options.output_format.as_deref().unwrap_or("links-notation"),
+            )
+        });
+
+    result.into()
+}
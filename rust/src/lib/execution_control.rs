@@ -4,7 +4,7 @@
 //! controls so callers can stop or terminate a running session by UUID or
 //! session name.
 
-use crate::docker_cleanup::docker_command;
+use crate::docker_cleanup::ContainerEngine;
 use crate::execution_store::{ExecutionRecord, ExecutionStore};
 use crate::output_blocks::{escape_for_links_notation, format_value_for_links_notation};
 use serde_json::{json, Map, Value};
@@ -224,7 +224,7 @@ pub fn collect_process_ids_with_runner<R: CommandRunner>(
             }
             insert_if_present(&mut process_ids, "commandPids", Some(json!(command_pids)));
         }
-        "docker" => {
+        "docker" | "podman" => {
             insert_if_present(
                 &mut process_ids,
                 "containerId",
@@ -236,8 +236,9 @@ pub fn collect_process_ids_with_runner<R: CommandRunner>(
                 "{{.Id}} {{.State.Pid}}".to_string(),
                 session_name.to_string(),
             ];
-            let docker = docker_command().to_string_lossy().to_string();
-            let result = runner.run(&docker, &inspect_args);
+            let engine = ContainerEngine::from_backend(isolated).unwrap_or(ContainerEngine::Docker);
+            let program = engine.command().to_string_lossy().to_string();
+            let result = runner.run(&program, &inspect_args);
             if result.success && !result.stdout.trim().is_empty() {
                 let mut parts = result.stdout.split_whitespace();
                 if let Some(container_id) = parts.next() {
@@ -315,13 +316,13 @@ pub fn get_control_command(
             method: "CTRL_C".to_string(),
             message: format!("Sent CTRL+C to detached tmux session: {}", session_name),
         },
-        (ControlAction::Stop, "docker") => ControlCommand {
-            command: "docker".to_string(),
+        (ControlAction::Stop, engine @ ("docker" | "podman")) => ControlCommand {
+            command: engine.to_string(),
             args: vec!["stop".to_string(), session_name.to_string()],
-            method: "DOCKER_STOP".to_string(),
+            method: format!("{}_STOP", engine.to_uppercase()),
             message: format!(
-                "Requested graceful stop for detached docker container: {}",
-                session_name
+                "Requested graceful stop for detached {} container: {}",
+                engine, session_name
             ),
         },
         (ControlAction::Terminate, "screen") => ControlCommand {
@@ -345,11 +346,11 @@ pub fn get_control_command(
             method: "KILL_SESSION".to_string(),
             message: format!("Terminated detached tmux session: {}", session_name),
         },
-        (ControlAction::Terminate, "docker") => ControlCommand {
-            command: "docker".to_string(),
+        (ControlAction::Terminate, engine @ ("docker" | "podman")) => ControlCommand {
+            command: engine.to_string(),
             args: vec!["kill".to_string(), session_name.to_string()],
             method: "SIGKILL".to_string(),
-            message: format!("Terminated detached docker container: {}", session_name),
+            message: format!("Terminated detached {} container: {}", engine, session_name),
         },
         (ControlAction::Stop, other) => {
             return Err(format!(
@@ -497,6 +498,14 @@ pub fn control_execution_with_runner<R: CommandRunner>(
         };
     };
 
+    if let Err(error) = store.check_ownership(&record) {
+        return ExecutionControlResult {
+            success: false,
+            output: None,
+            error: Some(error),
+        };
+    }
+
     let control = match get_control_command(&record, action) {
         Ok(command) => command,
         Err(error) => {
@@ -0,0 +1,589 @@
+//! Comparing two tracked executions (`--diff`)
+//!
+//! Answers "why did this pass yesterday and fail today": lists the recorded
+//! fields that differ between two executions (command, working directory,
+//! options, status, exit code, duration and, when recorded, git and
+//! toolchain provenance) and a unified diff of their command output. Before
+//! diffing, values that change on every run (timestamps, durations, PIDs,
+//! temporary paths and UUIDs) are replaced with placeholders, so only real
+//! differences in the output remain.
+
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+
+use crate::execution_stats::record_duration_ms;
+use crate::execution_store::{ExecutionRecord, ExecutionStore};
+use crate::log_viewer::{read_stored_log, split_log, strip_ansi};
+use crate::output_blocks::{escape_for_links_notation, format_duration};
+use crate::status_formatter::{enrich_detached_status, StatusQueryResult};
+
+/// Lines of unchanged output shown around each change
+pub const DIFF_CONTEXT: usize = 3;
+
+/// Above about this many changed lines the logs are reported as entirely
+/// replaced instead of searching further for a minimal diff
+const MAX_EDIT_DISTANCE: usize = 2_000;
+
+/// A recorded field whose value differs between the two executions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    /// Value in the first execution, `None` when not recorded
+    pub a: Option<String>,
+    /// Value in the second execution, `None` when not recorded
+    pub b: Option<String>,
+}
+
+/// Comparison of two executions
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionDiff {
+    pub a: String,
+    pub b: String,
+    pub changes: Vec<FieldChange>,
+    /// Whether the normalized command output of both logs is the same
+    pub logs_identical: bool,
+    /// Unified diff of the normalized command output, empty when identical
+    pub log_diff: String,
+    /// Logs that could not be read, as `<uuid>: <reason>`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_logs: Vec<String>,
+}
+
+fn normalization_rules() -> &'static [(Regex, &'static str)] {
+    static RULES: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        let rule = |pattern: &str, replacement| {
+            (
+                Regex::new(pattern).expect("valid normalization regex"),
+                replacement,
+            )
+        };
+        // Order matters: temporary paths often contain UUIDs and timestamps
+        vec![
+            rule(
+                r#"(?:/private)?/(?:tmp|var/tmp|var/folders)/[^\s'"`:,;)\]]*"#,
+                "<tmp>",
+            ),
+            rule(
+                r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?",
+                "<timestamp>",
+            ),
+            rule(r"\b\d{2}:\d{2}:\d{2}(?:[.,]\d+)?\b", "<time>"),
+            rule(
+                r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b",
+                "<uuid>",
+            ),
+            rule(r"(?i)\b(pid)([\s:=#]+)\d+\b", "${1}${2}<pid>"),
+            rule(
+                r"\b\d+(?:\.\d+)?\s?(?:ns|µs|us|ms|s|secs?|seconds?|mins?|minutes?|h|hours?)\b",
+                "<duration>",
+            ),
+        ]
+    })
+}
+
+/// `line` with ANSI sequences removed and volatile values (temporary paths,
+/// timestamps, UUIDs, PIDs and durations) replaced with placeholders
+pub fn normalize_log_line(line: &str) -> String {
+    let mut normalized = strip_ansi(line).trim_end().to_string();
+    for (regex, replacement) in normalization_rules() {
+        if regex.is_match(&normalized) {
+            normalized = regex.replace_all(&normalized, *replacement).to_string();
+        }
+    }
+    normalized
+}
+
+/// One step of a line diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Shortest edit script turning `a` into `b` (Myers' algorithm in its
+/// linear-space form: each range is split at its middle snake and both halves
+/// are diffed recursively, so memory stays O(n + m))
+pub fn diff_lines<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let mut search = MiddleSnake::new(a.len() + b.len());
+    let mut ops = Vec::with_capacity(a.len().max(b.len()));
+    diff_range(a, b, &mut search, &mut ops);
+    ops
+}
+
+fn diff_range<'a>(
+    a: &[&'a str],
+    b: &[&'a str],
+    search: &mut MiddleSnake,
+    ops: &mut Vec<DiffOp<'a>>,
+) {
+    // Common prefix and suffix need no search
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let middle_a = &a[prefix..a.len() - suffix];
+    let middle_b = &b[prefix..b.len() - suffix];
+
+    ops.extend(a[..prefix].iter().map(|line| DiffOp::Equal(line)));
+    let split = if middle_a.is_empty() || middle_b.is_empty() {
+        None
+    } else {
+        search.find(middle_a, middle_b)
+    };
+    match split {
+        // Both halves are strictly smaller, so the recursion ends
+        Some((x, y)) if (x, y) != (0, 0) && (x, y) != (middle_a.len(), middle_b.len()) => {
+            diff_range(&middle_a[..x], &middle_b[..y], search, ops);
+            diff_range(&middle_a[x..], &middle_b[y..], search, ops);
+        }
+        _ => {
+            ops.extend(middle_a.iter().map(|line| DiffOp::Delete(line)));
+            ops.extend(middle_b.iter().map(|line| DiffOp::Insert(line)));
+        }
+    }
+    ops.extend(a[a.len() - suffix..].iter().map(|line| DiffOp::Equal(line)));
+}
+
+/// Furthest-reaching x per diagonal of the forward and backward searches,
+/// shared by every range of one diff
+struct MiddleSnake {
+    forward: Vec<isize>,
+    backward: Vec<isize>,
+    offset: isize,
+}
+
+impl MiddleSnake {
+    fn new(size: usize) -> Self {
+        let rounds = (size + 1).min(MAX_EDIT_DISTANCE) / 2;
+        MiddleSnake {
+            forward: vec![0; 2 * rounds + 3],
+            backward: vec![0; 2 * rounds + 3],
+            offset: rounds as isize + 1,
+        }
+    }
+
+    /// Start of the middle snake of an optimal path through `a` and `b`, or
+    /// None when they differ in more than MAX_EDIT_DISTANCE lines
+    fn find(&mut self, a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+        let (n, m) = (a.len() as isize, b.len() as isize);
+        let delta = n - m;
+        let odd = delta % 2 != 0;
+        let offset = self.offset;
+        let rounds = (n + m + 1).min(MAX_EDIT_DISTANCE as isize) / 2;
+        self.forward[offset as usize + 1] = 0;
+        self.backward[offset as usize + 1] = 0;
+
+        for d in 0..=rounds {
+            for k in (-d..=d).step_by(2) {
+                let index = (k + offset) as usize;
+                let v = &mut self.forward;
+                let start_x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                    v[index + 1]
+                } else {
+                    v[index - 1] + 1
+                };
+                let (mut x, mut y) = (start_x, start_x - k);
+                while x < n && y < m && a[x as usize] == b[y as usize] {
+                    x += 1;
+                    y += 1;
+                }
+                v[index] = x;
+                let reverse = (delta - k + offset) as usize;
+                if odd && (k - delta).abs() < d && x + self.backward[reverse] >= n {
+                    return Some((start_x as usize, (start_x - k) as usize));
+                }
+            }
+            // Backward search: x and y count lines from the end
+            for k in (-d..=d).step_by(2) {
+                let index = (k + offset) as usize;
+                let v = &mut self.backward;
+                let start_x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                    v[index + 1]
+                } else {
+                    v[index - 1] + 1
+                };
+                let (mut x, mut y) = (start_x, start_x - k);
+                while x < n && y < m && a[(n - x - 1) as usize] == b[(m - y - 1) as usize] {
+                    x += 1;
+                    y += 1;
+                }
+                v[index] = x;
+                let forward = (delta - k + offset) as usize;
+                if !odd && (k - delta).abs() <= d && x + self.forward[forward] >= n {
+                    return Some(((n - x) as usize, (m - y) as usize));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Unified diff (`---`/`+++` header and `@@` hunks) of two texts, or an empty
+/// string when they are the same
+pub fn unified_diff(a: &str, b: &str, label_a: &str, label_b: &str, context: usize) -> String {
+    let lines_a: Vec<&str> = a.lines().collect();
+    let lines_b: Vec<&str> = b.lines().collect();
+    let ops = diff_lines(&lines_a, &lines_b);
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Equal(_)))
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Line number (0-based) in each text before every op
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut line_a, mut line_b) = (0, 0);
+    for op in &ops {
+        positions.push((line_a, line_b));
+        match op {
+            DiffOp::Equal(_) => {
+                line_a += 1;
+                line_b += 1;
+            }
+            DiffOp::Delete(_) => line_a += 1,
+            DiffOp::Insert(_) => line_b += 1,
+        }
+    }
+
+    let mut out = vec![format!("--- {}", label_a), format!("+++ {}", label_b)];
+    let mut next = 0;
+    while next < changes.len() {
+        let start = changes[next].saturating_sub(context);
+        let mut end = changes[next] + context;
+        next += 1;
+        // Changes closer than twice the context share a hunk
+        while next < changes.len() && changes[next] <= end + context {
+            end = changes[next] + context;
+            next += 1;
+        }
+        let end = end.min(ops.len() - 1);
+        let hunk = &ops[start..=end];
+        let count_a = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Insert(_)))
+            .count();
+        let count_b = hunk
+            .iter()
+            .filter(|op| !matches!(op, DiffOp::Delete(_)))
+            .count();
+        let (first_a, first_b) = positions[start];
+        // An empty range starts at the line before it
+        let range = |first: usize, count: usize| {
+            let first = if count == 0 { first } else { first + 1 };
+            format!("{},{}", first, count)
+        };
+        out.push(format!(
+            "@@ -{} +{} @@",
+            range(first_a, count_a),
+            range(first_b, count_b)
+        ));
+        for op in hunk {
+            out.push(match op {
+                DiffOp::Equal(line) => format!(" {}", line),
+                DiffOp::Delete(line) => format!("-{}", line),
+                DiffOp::Insert(line) => format!("+{}", line),
+            });
+        }
+    }
+    out.join("\n")
+}
+
+/// Command output of a stored log with volatile values normalized
+pub fn normalized_log_output(content: &str) -> String {
+    split_log(content)
+        .output
+        .lines()
+        .map(normalize_log_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Recorded fields that differ between `a` and `b`
+pub fn compare_records(a: &ExecutionRecord, b: &ExecutionRecord) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, a: Option<String>, b: Option<String>| {
+        if a != b {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                a,
+                b,
+            });
+        }
+    };
+    let text = |value: &str| (!value.is_empty()).then(|| value.to_string());
+
+    compare("command", text(&a.command), text(&b.command));
+    compare(
+        "workingDirectory",
+        text(&a.working_directory),
+        text(&b.working_directory),
+    );
+    compare("shell", text(&a.shell), text(&b.shell));
+    compare("platform", text(&a.platform), text(&b.platform));
+    compare(
+        "status",
+        Some(a.status.to_string()),
+        Some(b.status.to_string()),
+    );
+    compare(
+        "exitCode",
+        a.exit_code.map(|code| code.to_string()),
+        b.exit_code.map(|code| code.to_string()),
+    );
+    // Durations are only worth reporting when they differ noticeably
+    let duration_a = record_duration_ms(a);
+    let duration_b = record_duration_ms(b);
+    let similar = match (duration_a, duration_b) {
+        (Some(x), Some(y)) => format_duration(x) == format_duration(y),
+        (None, None) => true,
+        _ => false,
+    };
+    if !similar {
+        compare(
+            "duration",
+            duration_a.map(format_duration),
+            duration_b.map(format_duration),
+        );
+    }
+
+    let mut option_keys: Vec<&String> = a.options.keys().chain(b.options.keys()).collect();
+    option_keys.sort();
+    option_keys.dedup();
+    for key in option_keys {
+        let value = |record: &ExecutionRecord| {
+            record.options.get(key).map(|value| match value.as_str() {
+                Some(text) => text.to_string(),
+                None => value.to_string(),
+            })
+        };
+        compare(&format!("options.{}", key), value(a), value(b));
+    }
+
+    let git_a = a.provenance.as_ref().and_then(|p| p.git.as_ref());
+    let git_b = b.provenance.as_ref().and_then(|p| p.git.as_ref());
+    compare(
+        "git.branch",
+        git_a.and_then(|git| git.branch.clone()),
+        git_b.and_then(|git| git.branch.clone()),
+    );
+    compare(
+        "git.commit",
+        git_a.and_then(|git| git.commit.clone()),
+        git_b.and_then(|git| git.commit.clone()),
+    );
+    compare(
+        "git.dirty",
+        git_a.map(|git| git.dirty.to_string()),
+        git_b.map(|git| git.dirty.to_string()),
+    );
+    compare(
+        "executable",
+        a.provenance.as_ref().and_then(|p| p.executable.clone()),
+        b.provenance.as_ref().and_then(|p| p.executable.clone()),
+    );
+    let tools = |record: &ExecutionRecord| {
+        record
+            .provenance
+            .as_ref()
+            .map(|p| p.tools.clone())
+            .unwrap_or_default()
+    };
+    let (tools_a, tools_b) = (tools(a), tools(b));
+    let mut tool_names: Vec<&String> = tools_a.keys().chain(tools_b.keys()).collect();
+    tool_names.sort();
+    tool_names.dedup();
+    for name in tool_names {
+        compare(
+            &format!("tools.{}", name),
+            tools_a.get(name).cloned(),
+            tools_b.get(name).cloned(),
+        );
+    }
+    changes
+}
+
+/// Compare two records and their stored logs
+pub fn diff_executions(a: &ExecutionRecord, b: &ExecutionRecord) -> ExecutionDiff {
+    let mut missing_logs = Vec::new();
+    let mut output = |record: &ExecutionRecord| {
+        let content = if record.log_path.is_empty() {
+            Err("Execution record does not have a log path.".to_string())
+        } else {
+            read_stored_log(Path::new(&record.log_path))
+        };
+        match content {
+            Ok(content) => normalized_log_output(&content),
+            Err(e) => {
+                missing_logs.push(format!("{}: {}", record.uuid, e));
+                String::new()
+            }
+        }
+    };
+    let (output_a, output_b) = (output(a), output(b));
+    let log_diff = unified_diff(
+        &output_a,
+        &output_b,
+        &format!("a/{}", a.uuid),
+        &format!("b/{}", b.uuid),
+        DIFF_CONTEXT,
+    );
+    ExecutionDiff {
+        a: a.uuid.clone(),
+        b: b.uuid.clone(),
+        changes: compare_records(a, b),
+        logs_identical: log_diff.is_empty(),
+        log_diff,
+        missing_logs,
+    }
+}
+
+fn display(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("(none)")
+}
+
+pub fn format_diff_as_links_notation(diff: &ExecutionDiff) -> String {
+    let mut lines = vec![
+        "diff".to_string(),
+        format!("  a {}", diff.a),
+        format!("  b {}", diff.b),
+    ];
+    if diff.changes.is_empty() {
+        lines.push("  changes ()".to_string());
+    } else {
+        lines.push("  changes".to_string());
+        for change in &diff.changes {
+            lines.push(format!("    {}", escape_for_links_notation(&change.field)));
+            for (side, value) in [("a", &change.a), ("b", &change.b)] {
+                if let Some(value) = value {
+                    lines.push(format!(
+                        "      {} {}",
+                        side,
+                        escape_for_links_notation(value)
+                    ));
+                }
+            }
+        }
+    }
+    lines.push(format!("  logsIdentical {}", diff.logs_identical));
+    if !diff.log_diff.is_empty() {
+        lines.push(format!(
+            "  logDiff {}",
+            escape_for_links_notation(&diff.log_diff)
+        ));
+    }
+    for missing in &diff.missing_logs {
+        lines.push(format!(
+            "  missingLog {}",
+            escape_for_links_notation(missing)
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn format_diff_as_text(diff: &ExecutionDiff) -> String {
+    let mut lines = vec![
+        "Execution Diff".to_string(),
+        "=".repeat(50),
+        format!("A: {}", diff.a),
+        format!("B: {}", diff.b),
+        String::new(),
+    ];
+    if diff.changes.is_empty() {
+        lines.push("No differences in the recorded fields.".to_string());
+    } else {
+        lines.push("Recorded Fields".to_string());
+        lines.push("-".repeat(50));
+        let width = diff
+            .changes
+            .iter()
+            .map(|change| change.field.len())
+            .max()
+            .unwrap_or(0);
+        for change in &diff.changes {
+            lines.push(format!(
+                "{:<width$}  {} -> {}",
+                change.field,
+                display(&change.a),
+                display(&change.b),
+                width = width
+            ));
+        }
+    }
+    lines.push(String::new());
+    for missing in &diff.missing_logs {
+        lines.push(format!("Log unavailable: {}", missing));
+    }
+    if diff.logs_identical {
+        lines.push("Logs are identical after normalization.".to_string());
+    } else {
+        lines.push("Log Diff (normalized)".to_string());
+        lines.push("-".repeat(50));
+        lines.push(diff.log_diff.clone());
+    }
+    lines.join("\n")
+}
+
+pub fn format_diff_report(diff: &ExecutionDiff, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_diff_as_links_notation(diff)),
+        "json" => serde_json::to_string_pretty(diff)
+            .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(format_diff_as_text(diff)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+/// Handle a diff query for two executions by UUID or session name
+pub fn query_diff(
+    store: Option<&ExecutionStore>,
+    a: &str,
+    b: &str,
+    output_format: Option<&str>,
+) -> StatusQueryResult {
+    let failure = |error: String| StatusQueryResult {
+        success: false,
+        output: None,
+        error: Some(error),
+    };
+    let Some(store) = store else {
+        return failure("Execution tracking is disabled.".to_string());
+    };
+    let lookup = |identifier: &str| {
+        store
+            .get(identifier)
+            .map(|record| enrich_detached_status(&record))
+            .ok_or_else(|| {
+                format!(
+                    "No execution found with UUID or session name: {}",
+                    identifier
+                )
+            })
+    };
+    let (record_a, record_b) = match (lookup(a), lookup(b)) {
+        (Ok(record_a), Ok(record_b)) => (record_a, record_b),
+        (Err(e), _) | (_, Err(e)) => return failure(e),
+    };
+
+    let diff = diff_executions(&record_a, &record_b);
+    match format_diff_report(&diff, output_format.unwrap_or("links-notation")) {
+        Ok(output) => StatusQueryResult {
+            success: true,
+            output: Some(output),
+            error: None,
+        },
+        Err(e) => failure(e),
+    }
+}
@@ -0,0 +1,532 @@
+//! Export, import and merge of execution history
+//!
+//! Exports a (filtered) set of execution records as JSON Lines, CSV or a
+//! single `.lino` document, optionally bundled with the referenced logs into
+//! a tar archive. Imports any of those back into a store, de-duplicating by
+//! UUID and keeping the copy with the latest `end_time` on conflict.
+
+use crate::execution_store::{ExecutionRecord, ExecutionStore};
+use crate::lino_value_json::{json_to_lino_value, lino_value_to_json};
+use crate::output_blocks::escape_for_links_notation;
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::StatusQueryResult;
+use lino_objects_codec::{decode, encode, LinoValue};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Valid formats for --export-format
+pub const VALID_EXPORT_FORMATS: [&str; 3] = ["jsonl", "csv", "lino"];
+
+/// Stable CSV header used for exported records
+pub const CSV_COLUMNS: [&str; 21] = [
+    "uuid",
+    "status",
+    "exitCode",
+    "oomKilled",
+    "pid",
+    "command",
+    "workingDirectory",
+    "shell",
+    "platform",
+    "startTime",
+    "endTime",
+    "logPath",
+    "options",
+    "tags",
+    "labels",
+    "notes",
+    "parentUuid",
+    "rootUuid",
+    "provenance",
+    "schemaVersion",
+    "owner",
+];
+
+/// Directory inside a tar bundle holding the exported logs
+const BUNDLE_LOG_DIR: &str = "logs";
+
+/// Folder (inside the app folder) receiving logs extracted from imported bundles
+pub(crate) const IMPORTED_LOG_DIR: &str = "imported-logs";
+
+/// Split records from an untrusted source (an import file or a sync remote)
+/// into records with a real UUID and the UUIDs that were rejected. The UUID
+/// names the record's log file and store entry, so a value like
+/// `../../escaped` must never reach the filesystem.
+pub(crate) fn split_invalid_uuids(
+    records: Vec<ExecutionRecord>,
+) -> (Vec<ExecutionRecord>, Vec<String>) {
+    let (valid, invalid): (Vec<_>, Vec<_>) = records
+        .into_iter()
+        .partition(|record| uuid::Uuid::parse_str(&record.uuid).is_ok());
+    (
+        valid,
+        invalid.into_iter().map(|record| record.uuid).collect(),
+    )
+}
+
+/// Options for exporting execution records
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Record format: jsonl, csv, lino (inferred from the path when None)
+    pub format: Option<String>,
+    /// Bundle the records and their logs into a tar archive
+    pub with_logs: bool,
+}
+
+/// Summary of an export
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: String,
+    pub records: usize,
+    pub logs: usize,
+}
+
+/// Summary of an import
+#[derive(Debug, Clone)]
+pub struct ImportSummary {
+    pub path: String,
+    pub added: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub logs: usize,
+    /// UUIDs of entries skipped because they are not valid UUIDs
+    pub skipped: Vec<String>,
+}
+
+fn is_tar_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "tar")
+}
+
+/// Infer the record format from a file name (`.csv`, `.lino`, otherwise jsonl)
+pub fn infer_export_format(path: &Path) -> &'static str {
+    let name = path.to_string_lossy().to_lowercase();
+    let name = name.strip_suffix(".tar").unwrap_or(&name);
+    if name.ends_with(".csv") {
+        "csv"
+    } else if name.ends_with(".lino") {
+        "lino"
+    } else {
+        "jsonl"
+    }
+}
+
+/// Serialize records as JSON Lines (one record per line)
+pub fn records_to_jsonl(records: &[ExecutionRecord]) -> String {
+    records
+        .iter()
+        .map(|record| record.to_json().to_string() + "\n")
+        .collect()
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(crate) fn csv_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => escape_csv(s),
+        Some(Value::Object(map)) if map.is_empty() => String::new(),
+        Some(Value::Array(values)) if values.is_empty() => String::new(),
+        Some(other) => escape_csv(&other.to_string()),
+    }
+}
+
+/// Serialize records as CSV with the stable [`CSV_COLUMNS`] header.
+/// The `options`, `tags`, `labels`, `notes` and `provenance` columns hold JSON.
+pub fn records_to_csv(records: &[ExecutionRecord]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+    for record in records {
+        let json = record.to_json();
+        let row: Vec<String> = CSV_COLUMNS
+            .iter()
+            .map(|column| csv_cell(json.get(*column)))
+            .collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Serialize records as a single Links Notation document (same shape as
+/// `executions.lino`)
+pub fn records_to_lino(records: &[ExecutionRecord]) -> String {
+    let data: Vec<LinoValue> = records
+        .iter()
+        .map(|record| json_to_lino_value(&record.to_json()))
+        .collect();
+    encode(&LinoValue::Array(data))
+}
+
+/// Serialize records in one of [`VALID_EXPORT_FORMATS`]
+pub fn serialize_records(records: &[ExecutionRecord], format: &str) -> Result<String, String> {
+    match format {
+        "jsonl" => Ok(records_to_jsonl(records)),
+        "csv" => Ok(records_to_csv(records)),
+        "lino" => Ok(records_to_lino(records)),
+        _ => Err(format!(
+            "Invalid export format: \"{}\". Valid options are: {}",
+            format,
+            VALID_EXPORT_FORMATS.join(", ")
+        )),
+    }
+}
+
+/// Split CSV content into rows of fields (RFC 4180 quoting)
+fn parse_csv_rows(content: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn csv_value(column: &str, raw: &str) -> Value {
+    match column {
+        "options" | "labels" if raw.is_empty() => Value::Object(Map::new()),
+        "tags" | "notes" if raw.is_empty() => Value::Array(Vec::new()),
+        "exitCode" | "pid" | "oomKilled" | "endTime" | "parentUuid" | "rootUuid" | "provenance"
+        | "owner"
+            if raw.is_empty() =>
+        {
+            Value::Null
+        }
+        "exitCode" | "pid" | "oomKilled" | "options" | "tags" | "labels" | "notes"
+        | "provenance" | "schemaVersion" => {
+            serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+        }
+        // Required string fields stay empty strings rather than null
+        _ => Value::String(raw.to_string()),
+    }
+}
+
+fn parse_csv_records(content: &str) -> Result<Vec<ExecutionRecord>, String> {
+    let mut rows = parse_csv_rows(content).into_iter();
+    let header = rows.next().unwrap_or_default();
+    rows.filter(|row| row.iter().any(|field| !field.is_empty()))
+        .enumerate()
+        .map(|(index, row)| {
+            let mut map = Map::new();
+            for (column, raw) in header.iter().zip(row.iter()) {
+                map.insert(column.clone(), csv_value(column, raw));
+            }
+            ExecutionRecord::from_json(&Value::Object(map))
+                .ok_or_else(|| format!("Invalid record on CSV row {}", index + 2))
+        })
+        .collect()
+}
+
+/// Parse exported records, detecting the format from the content:
+/// JSON array, JSON Lines, CSV (with the `uuid` header) or Links Notation.
+pub fn parse_records(content: &str) -> Result<Vec<ExecutionRecord>, String> {
+    let trimmed = content.trim_start();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    if trimmed.starts_with('[') {
+        let values: Vec<Value> =
+            serde_json::from_str(trimmed).map_err(|e| format!("Invalid JSON: {}", e))?;
+        return values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                ExecutionRecord::from_json(value)
+                    .ok_or_else(|| format!("Invalid record at index {}", index))
+            })
+            .collect();
+    }
+
+    if trimmed.starts_with('{') {
+        return trimmed
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str::<Value>(line)
+                    .ok()
+                    .and_then(|value| ExecutionRecord::from_json(&value))
+                    .ok_or_else(|| format!("Invalid record on line {}", index + 1))
+            })
+            .collect();
+    }
+
+    if trimmed.starts_with("uuid,") {
+        return parse_csv_records(trimmed);
+    }
+
+    match decode(content).map_err(|e| format!("Invalid Links Notation: {}", e))? {
+        LinoValue::Array(values) => values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                ExecutionRecord::from_json(&lino_value_to_json(value))
+                    .ok_or_else(|| format!("Invalid record at index {}", index))
+            })
+            .collect(),
+        _ => Err("Links Notation document does not contain a record list".to_string()),
+    }
+}
+
+fn append_tar_entry<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .map_err(|e| format!("Failed to write {} to archive: {}", name, e))
+}
+
+/// Export records to `path`. With `with_logs` (or a `.tar` path) the records
+/// and every readable referenced log are written into a tar archive as
+/// `executions.<format>` and `logs/<uuid>.log`.
+pub fn export_records(
+    records: &[ExecutionRecord],
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<ExportSummary, String> {
+    let format = options
+        .format
+        .clone()
+        .unwrap_or_else(|| infer_export_format(path).to_string());
+    let content = serialize_records(records, &format)?;
+    let bundle = options.with_logs || is_tar_path(path);
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut logs = 0;
+    if bundle {
+        let file = fs::File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut builder = tar::Builder::new(file);
+        append_tar_entry(
+            &mut builder,
+            &format!("executions.{}", format),
+            content.as_bytes(),
+        )?;
+        for record in records {
+            if record.log_path.is_empty() {
+                continue;
+            }
+            if let Ok(log) = fs::read(&record.log_path) {
+                let name = format!("{}/{}.log", BUNDLE_LOG_DIR, record.uuid);
+                append_tar_entry(&mut builder, &name, &log)?;
+                logs += 1;
+            }
+        }
+        builder
+            .finish()
+            .map_err(|e| format!("Failed to finish archive: {}", e))?;
+    } else {
+        fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    Ok(ExportSummary {
+        path: path.to_string_lossy().to_string(),
+        format: if bundle {
+            format!("{}+tar", format)
+        } else {
+            format
+        },
+        records: records.len(),
+        logs,
+    })
+}
+
+/// Whether the file at `path` is a tar archive (by extension or ustar magic)
+fn is_tar_file(path: &Path) -> bool {
+    if is_tar_path(path) {
+        return true;
+    }
+    let mut header = [0u8; 512];
+    fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+        && &header[257..262] == b"ustar"
+}
+
+/// Log contents bundled in an export archive, keyed by execution UUID
+type BundledLogs = HashMap<String, Vec<u8>>;
+
+/// Read records and bundled logs (keyed by UUID) from a tar archive
+fn read_bundle(path: &Path) -> Result<(Vec<ExecutionRecord>, BundledLogs), String> {
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut archive = tar::Archive::new(file);
+    let mut records = None;
+    let mut logs = HashMap::new();
+
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let name = entry
+            .path()
+            .map_err(|e| format!("Invalid archive entry name: {}", e))?
+            .to_string_lossy()
+            .to_string();
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+
+        if let Some(log_name) = name.strip_prefix(&format!("{}/", BUNDLE_LOG_DIR)) {
+            if let Some(uuid) = log_name.strip_suffix(".log") {
+                logs.insert(uuid.to_string(), data);
+            }
+        } else if name.starts_with("executions.") {
+            records = Some(parse_records(&String::from_utf8_lossy(&data))?);
+        }
+    }
+
+    let records = records.ok_or_else(|| "Archive does not contain executions".to_string())?;
+    Ok((records, logs))
+}
+
+/// Import records from `path` (any exported format or tar bundle) into the
+/// store. Bundled logs of added or updated records are extracted into
+/// `<app-folder>/imported-logs/<uuid>.log` and the records point at them.
+pub fn import_records(store: &ExecutionStore, path: &Path) -> Result<ImportSummary, String> {
+    let (records, logs) = if is_tar_file(path) {
+        read_bundle(path)?
+    } else {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        (parse_records(&content)?, HashMap::new())
+    };
+    let (mut records, skipped) = split_invalid_uuids(records);
+
+    let log_dir = store.app_folder().join(IMPORTED_LOG_DIR);
+    let log_path_for = |uuid: &str| -> PathBuf { log_dir.join(format!("{}.log", uuid)) };
+    for record in records.iter_mut() {
+        if logs.contains_key(&record.uuid) {
+            record.log_path = log_path_for(&record.uuid).to_string_lossy().to_string();
+        }
+    }
+
+    let merged = store.merge(&records)?;
+
+    let mut extracted = 0;
+    for uuid in merged.added.iter().chain(merged.updated.iter()) {
+        if let Some(data) = logs.get(uuid) {
+            fs::create_dir_all(&log_dir)
+                .map_err(|e| format!("Failed to create {}: {}", log_dir.display(), e))?;
+            fs::write(log_path_for(uuid), data)
+                .map_err(|e| format!("Failed to write log for {}: {}", uuid, e))?;
+            extracted += 1;
+        }
+    }
+
+    Ok(ImportSummary {
+        path: path.to_string_lossy().to_string(),
+        added: merged.added.len(),
+        updated: merged.updated.len(),
+        unchanged: merged.unchanged.len(),
+        logs: extracted,
+        skipped,
+    })
+}
+
+/// Format an export summary as Links Notation
+pub fn format_export_summary(summary: &ExportSummary) -> String {
+    [
+        "executionExport".to_string(),
+        format!("  path {}", escape_for_links_notation(&summary.path)),
+        format!("  format {}", summary.format),
+        format!("  records {}", summary.records),
+        format!("  logs {}", summary.logs),
+    ]
+    .join("\n")
+}
+
+/// Format an import summary as Links Notation
+pub fn format_import_summary(summary: &ImportSummary) -> String {
+    let mut lines = vec![
+        "executionImport".to_string(),
+        format!("  path {}", escape_for_links_notation(&summary.path)),
+        format!("  added {}", summary.added),
+        format!("  updated {}", summary.updated),
+        format!("  unchanged {}", summary.unchanged),
+        format!("  logs {}", summary.logs),
+    ];
+    if !summary.skipped.is_empty() {
+        lines.push("  skipped".to_string());
+        lines.extend(
+            summary
+                .skipped
+                .iter()
+                .map(|uuid| format!("    {}", escape_for_links_notation(uuid))),
+        );
+    }
+    lines.join("\n")
+}
+
+/// Handle --export and return the result
+pub fn query_export(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    path: &Path,
+    options: &ExportOptions,
+) -> StatusQueryResult {
+    StatusQueryResult::from(
+        store
+            .ok_or_else(|| "Execution tracking is disabled.".to_string())
+            .and_then(|store| {
+                let records = filter.apply(store.get_all());
+                export_records(&records, path, options)
+            })
+            .map(|summary| format_export_summary(&summary)),
+    )
+}
+
+/// Handle --import and return the result
+pub fn query_import(store: Option<&ExecutionStore>, path: &Path) -> StatusQueryResult {
+    StatusQueryResult::from(
+        store
+            .ok_or_else(|| "Execution tracking is disabled.".to_string())
+            .and_then(|store| import_records(store, path))
+            .map(|summary| format_import_summary(&summary)),
+    )
+}
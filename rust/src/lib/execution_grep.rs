@@ -0,0 +1,247 @@
+//! Full-text search across stored execution logs (`--grep`)
+//!
+//! Searches the log file of every record that passes the `--list` record
+//! filters and groups matching lines by execution, so a match keeps its UUID,
+//! command and start time. Records whose log file is gone are counted rather
+//! than failing the search.
+
+use crate::execution_store::{ExecutionRecord, ExecutionStore};
+use crate::output_blocks::escape_for_links_notation;
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::StatusQueryResult;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::fs;
+
+/// Search settings for --grep
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrepOptions {
+    /// Regular expression to search for
+    pub pattern: String,
+    /// Match without regard to case (--ignore-case)
+    pub ignore_case: bool,
+    /// Lines of context around each match (--context)
+    pub context: usize,
+}
+
+/// A matching or context line of a log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepLine {
+    /// 1-based line number in the log
+    pub line_number: usize,
+    pub text: String,
+    /// False for context lines
+    pub is_match: bool,
+}
+
+/// Matches found in the log of one execution
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionMatches {
+    pub uuid: String,
+    pub command: String,
+    pub start_time: String,
+    pub log_path: String,
+    /// Number of matching lines
+    pub matches: usize,
+    pub lines: Vec<GrepLine>,
+}
+
+/// Result of searching the logs of a set of records
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepReport {
+    pub pattern: String,
+    /// Number of logs searched
+    pub searched: usize,
+    /// Total number of matching lines
+    pub matches: usize,
+    /// UUIDs of records whose log file could not be read
+    pub missing_logs: Vec<String>,
+    pub executions: Vec<ExecutionMatches>,
+}
+
+/// Compile the --grep pattern
+pub fn build_grep_regex(pattern: &str, ignore_case: bool) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| format!("Invalid --grep pattern \"{}\": {}", pattern, e))
+}
+
+/// Matching lines of `content` with `context` lines around each match.
+/// Overlapping context is merged, so every line appears at most once.
+pub fn grep_text(content: &str, regex: &Regex, context: usize) -> Vec<GrepLine> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut result: Vec<GrepLine> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if !regex.is_match(line) {
+            continue;
+        }
+        let first = index.saturating_sub(context);
+        let last = (index + context).min(lines.len() - 1);
+        for (offset, text) in lines[first..=last].iter().enumerate() {
+            let line_number = first + offset + 1;
+            let is_match = first + offset == index;
+            match result.last_mut() {
+                Some(previous) if previous.line_number == line_number => {
+                    previous.is_match |= is_match;
+                }
+                Some(previous) if previous.line_number > line_number => {}
+                _ => result.push(GrepLine {
+                    line_number,
+                    text: text.to_string(),
+                    is_match,
+                }),
+            }
+        }
+    }
+    result
+}
+
+/// Search the logs of `records` (in the given order)
+pub fn grep_records(
+    records: &[ExecutionRecord],
+    options: &GrepOptions,
+) -> Result<GrepReport, String> {
+    let regex = build_grep_regex(&options.pattern, options.ignore_case)?;
+    let mut report = GrepReport {
+        pattern: options.pattern.clone(),
+        ..Default::default()
+    };
+
+    for record in records {
+        // Logs may hold arbitrary bytes; search them as lossy UTF-8
+        let Ok(bytes) = fs::read(&record.log_path) else {
+            report.missing_logs.push(record.uuid.clone());
+            continue;
+        };
+        report.searched += 1;
+        let lines = grep_text(&String::from_utf8_lossy(&bytes), &regex, options.context);
+        let matches = lines.iter().filter(|line| line.is_match).count();
+        if matches == 0 {
+            continue;
+        }
+        report.matches += matches;
+        report.executions.push(ExecutionMatches {
+            uuid: record.uuid.clone(),
+            command: record.command.clone(),
+            start_time: record.start_time.clone(),
+            log_path: record.log_path.clone(),
+            matches,
+            lines,
+        });
+    }
+    Ok(report)
+}
+
+/// Format a grep report as links-notation, json or text
+pub fn format_grep_report(report: &GrepReport, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_grep_as_links_notation(report)),
+        "json" => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(format_grep_as_text(report)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+fn format_grep_as_links_notation(report: &GrepReport) -> String {
+    let mut lines = vec![
+        "grep".to_string(),
+        format!("  pattern {}", escape_for_links_notation(&report.pattern)),
+        format!("  searched {}", report.searched),
+        format!("  matches {}", report.matches),
+    ];
+    if !report.missing_logs.is_empty() {
+        lines.push("  missingLogs".to_string());
+        lines.extend(
+            report
+                .missing_logs
+                .iter()
+                .map(|uuid| format!("    {}", uuid)),
+        );
+    }
+    for execution in &report.executions {
+        lines.push(format!("  {}", execution.uuid));
+        lines.push(format!(
+            "    command {}",
+            escape_for_links_notation(&execution.command)
+        ));
+        lines.push(format!(
+            "    startTime {}",
+            escape_for_links_notation(&execution.start_time)
+        ));
+        lines.push(format!(
+            "    logPath {}",
+            escape_for_links_notation(&execution.log_path)
+        ));
+        lines.push(format!("    matches {}", execution.matches));
+        lines.push("    lines".to_string());
+        for line in &execution.lines {
+            lines.push(format!(
+                "      {} {} {}",
+                if line.is_match { "match" } else { "context" },
+                line.line_number,
+                escape_for_links_notation(&line.text)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// grep-like text: `12:` marks a matching line, `13-` a context line and
+/// `--` separates non-adjacent groups of lines
+fn format_grep_as_text(report: &GrepReport) -> String {
+    let mut lines = Vec::new();
+    for execution in &report.executions {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(format!(
+            "{}  {}  {}",
+            execution.uuid, execution.start_time, execution.command
+        ));
+        let mut previous: Option<usize> = None;
+        for line in &execution.lines {
+            if previous.is_some_and(|number| number + 1 != line.line_number) {
+                lines.push("  --".to_string());
+            }
+            let marker = if line.is_match { ':' } else { '-' };
+            lines.push(format!("  {}{} {}", line.line_number, marker, line.text));
+            previous = Some(line.line_number);
+        }
+    }
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(format!(
+        "{} matching lines in {} of {} logs searched",
+        report.matches,
+        report.executions.len(),
+        report.searched
+    ));
+    if !report.missing_logs.is_empty() {
+        lines.push(format!(
+            "{} logs could not be read",
+            report.missing_logs.len()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Handle --grep: search the logs of the records passing `filter`, newest first
+pub fn query_grep(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    options: &GrepOptions,
+    output_format: Option<&str>,
+) -> StatusQueryResult {
+    let result = store
+        .ok_or_else(|| "Execution tracking is disabled.".to_string())
+        .and_then(|store| grep_records(&filter.apply(store.get_all()), options))
+        .and_then(|report| format_grep_report(&report, output_format.unwrap_or("links-notation")));
+
+    result.into()
+}
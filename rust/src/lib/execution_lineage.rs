@@ -0,0 +1,459 @@
+//! Parent/child linkage for nested executions
+//!
+//! The wrapper exports `START_EXECUTION_ID` and `START_ROOT_EXECUTION_ID` to
+//! the command it runs, so a nested `$` invocation (from a script, makefile or
+//! agent) records `parentUuid`/`rootUuid`. `--status --tree` and
+//! `--list --tree` rebuild the hierarchy with aggregate durations and point
+//! at the failing leaf.
+
+use crate::execution_stats::record_duration_ms;
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::output_blocks::{escape_for_links_notation, format_duration};
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::StatusQueryResult;
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::env;
+
+/// Environment variable holding the UUID of the enclosing execution
+pub const EXECUTION_ID_ENV: &str = "START_EXECUTION_ID";
+/// Environment variable holding the UUID of the top-level execution
+pub const ROOT_EXECUTION_ID_ENV: &str = "START_ROOT_EXECUTION_ID";
+
+/// Set `parentUuid`/`rootUuid` from the IDs exported by an enclosing wrapper.
+/// The root falls back to the parent when only the parent ID is known.
+pub fn link_to_parent(record: &mut ExecutionRecord, parent: Option<&str>, root: Option<&str>) {
+    let Some(parent) = parent.filter(|id| !id.is_empty() && *id != record.uuid) else {
+        return;
+    };
+    let root = root.filter(|id| !id.is_empty()).unwrap_or(parent);
+    record.parent_uuid = Some(parent.to_string());
+    record.root_uuid = Some(root.to_string());
+}
+
+/// Link a launching record to the execution that spawned it (if any) and
+/// export its own IDs so nested invocations link to it in turn
+pub fn link_to_parent_execution(record: &mut ExecutionRecord) {
+    let parent = env::var(EXECUTION_ID_ENV).ok();
+    let root = env::var(ROOT_EXECUTION_ID_ENV).ok();
+    link_to_parent(record, parent.as_deref(), root.as_deref());
+
+    let root = record.root_uuid.as_deref().unwrap_or(&record.uuid);
+    env::set_var(ROOT_EXECUTION_ID_ENV, root);
+    env::set_var(EXECUTION_ID_ENV, &record.uuid);
+}
+
+/// Outcome of a record: succeeded, failed or executing
+pub fn record_outcome(record: &ExecutionRecord) -> &'static str {
+    match (record.status, record.exit_code) {
+        (ExecutionStatus::Executing, _) => "executing",
+        (ExecutionStatus::Executed, Some(0)) => "succeeded",
+        (ExecutionStatus::Executed, _) => "failed",
+    }
+}
+
+/// One execution in a tree, with its nested executions as children
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTreeNode {
+    pub uuid: String,
+    pub command: String,
+    pub outcome: String,
+    pub exit_code: Option<i32>,
+    pub start_time: String,
+    /// Duration of this execution alone
+    pub duration_ms: Option<f64>,
+    /// From this execution's start to the last end in its subtree
+    /// (absent while anything in the subtree is still executing)
+    pub total_duration_ms: Option<f64>,
+    pub children: Vec<ExecutionTreeNode>,
+}
+
+/// A root execution with all of its descendants
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionTree {
+    /// Number of executions in the tree
+    pub count: usize,
+    /// First failed execution (depth-first) without failed descendants
+    pub failing_leaf: Option<String>,
+    pub root: ExecutionTreeNode,
+}
+
+impl ExecutionTreeNode {
+    fn count(&self) -> usize {
+        1 + self.children.iter().map(Self::count).sum::<usize>()
+    }
+
+    fn failing_leaf(&self) -> Option<&ExecutionTreeNode> {
+        self.children
+            .iter()
+            .find_map(Self::failing_leaf)
+            .or_else(|| (self.outcome == "failed").then_some(self))
+    }
+
+    fn find(&self, uuid: &str) -> Option<&ExecutionTreeNode> {
+        if self.uuid == uuid {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(uuid))
+    }
+}
+
+impl ExecutionTree {
+    fn from_root(root: ExecutionTreeNode) -> Self {
+        ExecutionTree {
+            count: root.count(),
+            failing_leaf: root.failing_leaf().map(|node| node.uuid.clone()),
+            root,
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok()
+}
+
+/// End of a built subtree: its start plus its total duration
+fn subtree_end(node: &ExecutionTreeNode) -> Option<DateTime<FixedOffset>> {
+    let total = Duration::milliseconds(node.total_duration_ms? as i64);
+    Some(parse_time(&node.start_time)? + total)
+}
+
+fn build_node(
+    uuid: &str,
+    records: &HashMap<&str, &ExecutionRecord>,
+    children: &HashMap<&str, Vec<&str>>,
+    visited: &mut HashSet<String>,
+) -> ExecutionTreeNode {
+    let record = records[uuid];
+    visited.insert(uuid.to_string());
+    let child_nodes = children
+        .get(uuid)
+        .into_iter()
+        .flatten()
+        .filter(|child| !visited.contains(**child))
+        .copied()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .map(|child| build_node(child, records, children, visited))
+        .collect::<Vec<_>>();
+    // Latest end in the subtree; unknown while anything is still executing
+    let end = record
+        .end_time
+        .as_deref()
+        .and_then(parse_time)
+        .and_then(|own| {
+            child_nodes.iter().try_fold(own, |latest, child| {
+                subtree_end(child).map(|end| end.max(latest))
+            })
+        });
+    let total_duration_ms = parse_time(&record.start_time)
+        .zip(end)
+        .map(|(start, end)| (end - start).num_milliseconds().max(0) as f64);
+
+    ExecutionTreeNode {
+        uuid: record.uuid.clone(),
+        command: record.command.clone(),
+        outcome: record_outcome(record).to_string(),
+        exit_code: record.exit_code,
+        start_time: record.start_time.clone(),
+        duration_ms: record_duration_ms(record),
+        total_duration_ms,
+        children: child_nodes,
+    }
+}
+
+/// Parent → children index (oldest child first) over records whose parent
+/// is present in `records`
+fn child_index<'a>(
+    records: &HashMap<&'a str, &'a ExecutionRecord>,
+) -> HashMap<&'a str, Vec<&'a str>> {
+    let mut children: HashMap<&str, Vec<&ExecutionRecord>> = HashMap::new();
+    for record in records.values() {
+        if let Some(parent) = record.parent_uuid.as_deref() {
+            if let Some((&parent, _)) = records.get_key_value(parent) {
+                children.entry(parent).or_default().push(record);
+            }
+        }
+    }
+    children
+        .into_iter()
+        .map(|(parent, mut kids)| {
+            kids.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+            (parent, kids.into_iter().map(|r| r.uuid.as_str()).collect())
+        })
+        .collect()
+}
+
+/// Oldest record of the parent cycle that the chain above `uuid` runs into.
+/// Every parent on the chain must be among `records`.
+fn oldest_in_cycle<'a>(uuid: &'a str, records: &HashMap<&'a str, &'a ExecutionRecord>) -> &'a str {
+    let mut chain = Vec::new();
+    let mut current = uuid;
+    while !chain.contains(&current) {
+        chain.push(current);
+        match records[current].parent_uuid.as_deref() {
+            Some(parent) if records.contains_key(parent) => current = parent,
+            _ => return current,
+        }
+    }
+    let start = chain.iter().position(|&uuid| uuid == current).unwrap_or(0);
+    chain[start..]
+        .iter()
+        .copied()
+        .min_by(|a, b| records[a].start_time.cmp(&records[b].start_time))
+        .unwrap_or(current)
+}
+
+/// Build execution trees from records. Records whose parent is not among
+/// `records` become roots; a parent chain that loops back on itself (a
+/// corrupted store) is broken at its oldest record, which becomes a root
+/// too. Roots are ordered newest first.
+pub fn build_execution_trees(records: &[ExecutionRecord]) -> Vec<ExecutionTree> {
+    let by_uuid: HashMap<&str, &ExecutionRecord> =
+        records.iter().map(|r| (r.uuid.as_str(), r)).collect();
+    let children = child_index(&by_uuid);
+
+    let mut roots: Vec<&ExecutionRecord> = records
+        .iter()
+        .filter(|r| {
+            r.parent_uuid
+                .as_deref()
+                .is_none_or(|parent| !by_uuid.contains_key(parent))
+        })
+        .collect();
+    roots.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+
+    let mut visited = HashSet::new();
+    let mut trees: Vec<ExecutionTree> = roots
+        .into_iter()
+        .map(|root| {
+            ExecutionTree::from_root(build_node(&root.uuid, &by_uuid, &children, &mut visited))
+        })
+        .collect();
+    // Records not reached from a root hang below a parent cycle
+    for record in records {
+        if !visited.contains(&record.uuid) {
+            let root = oldest_in_cycle(&record.uuid, &by_uuid);
+            trees.push(ExecutionTree::from_root(build_node(
+                root,
+                &by_uuid,
+                &children,
+                &mut visited,
+            )));
+        }
+    }
+    trees.sort_by(|a, b| b.root.start_time.cmp(&a.root.start_time));
+    trees
+}
+
+/// Build the full tree containing the record `uuid`, starting from its
+/// top-most ancestor among `records`
+pub fn execution_tree_for(records: &[ExecutionRecord], uuid: &str) -> Option<ExecutionTree> {
+    let by_uuid: HashMap<&str, &ExecutionRecord> =
+        records.iter().map(|r| (r.uuid.as_str(), r)).collect();
+    let mut top = *by_uuid.get(uuid)?;
+    let mut seen = HashSet::from([top.uuid.as_str()]);
+    while let Some(parent) = top.parent_uuid.as_deref().and_then(|p| by_uuid.get(p)) {
+        if !seen.insert(parent.uuid.as_str()) {
+            top = by_uuid[oldest_in_cycle(&parent.uuid, &by_uuid)];
+            break;
+        }
+        top = parent;
+    }
+
+    let children = child_index(&by_uuid);
+    let mut visited = HashSet::new();
+    Some(ExecutionTree::from_root(build_node(
+        &top.uuid,
+        &by_uuid,
+        &children,
+        &mut visited,
+    )))
+}
+
+fn append_node_links(lines: &mut Vec<String>, node: &ExecutionTreeNode, indent: usize) {
+    let pad = " ".repeat(indent);
+    lines.push(format!("{}{}", pad, node.uuid));
+    lines.push(format!(
+        "{}  command {}",
+        pad,
+        escape_for_links_notation(&node.command)
+    ));
+    lines.push(format!("{}  outcome {}", pad, node.outcome));
+    if let Some(code) = node.exit_code {
+        lines.push(format!("{}  exitCode {}", pad, code));
+    }
+    lines.push(format!(
+        "{}  startTime {}",
+        pad,
+        escape_for_links_notation(&node.start_time)
+    ));
+    if let Some(ms) = node.duration_ms {
+        lines.push(format!("{}  durationMs {}", pad, ms));
+    }
+    if let Some(ms) = node.total_duration_ms {
+        lines.push(format!("{}  totalDurationMs {}", pad, ms));
+    }
+    if !node.children.is_empty() {
+        lines.push(format!("{}  children", pad));
+        for child in &node.children {
+            append_node_links(lines, child, indent + 4);
+        }
+    }
+}
+
+/// Format an execution tree as Links Notation
+pub fn format_tree_as_links_notation(tree: &ExecutionTree) -> String {
+    let mut lines = vec![
+        "executionTree".to_string(),
+        format!("  root {}", tree.root.uuid),
+        format!("  count {}", tree.count),
+    ];
+    if let Some(ms) = tree.root.total_duration_ms {
+        lines.push(format!("  totalDurationMs {}", ms));
+    }
+    if let Some(ref leaf) = tree.failing_leaf {
+        lines.push(format!("  failingLeaf {}", leaf));
+    }
+    lines.push("  nodes".to_string());
+    append_node_links(&mut lines, &tree.root, 4);
+    lines.join("\n")
+}
+
+fn node_text(node: &ExecutionTreeNode) -> String {
+    let mut text = format!("{} [{}", node.command, node.outcome);
+    if let (Some(code), "failed") = (node.exit_code, node.outcome.as_str()) {
+        text.push_str(&format!(", exit {}", code));
+    }
+    text.push(']');
+    if let Some(ms) = node.duration_ms {
+        text.push_str(&format!(" {}", format_duration(ms)));
+    }
+    if !node.children.is_empty() {
+        if let Some(ms) = node.total_duration_ms {
+            text.push_str(&format!(" (total {})", format_duration(ms)));
+        }
+    }
+    text.push_str(&format!(" {}", node.uuid));
+    text
+}
+
+fn append_node_text(lines: &mut Vec<String>, node: &ExecutionTreeNode, prefix: &str) {
+    for (index, child) in node.children.iter().enumerate() {
+        let last = index + 1 == node.children.len();
+        let (branch, next) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!("{}{}{}", prefix, branch, node_text(child)));
+        append_node_text(lines, child, &format!("{}{}", prefix, next));
+    }
+}
+
+/// Format an execution tree as human-readable text
+pub fn format_tree_as_text(tree: &ExecutionTree) -> String {
+    let mut lines = vec![
+        "Execution Tree".to_string(),
+        "=".repeat(50),
+        node_text(&tree.root),
+    ];
+    append_node_text(&mut lines, &tree.root, "");
+    lines.push(format!("Executions: {}", tree.count));
+    if let Some(leaf) = tree
+        .failing_leaf
+        .as_deref()
+        .and_then(|uuid| tree.root.find(uuid))
+    {
+        lines.push(format!("Failing leaf: {} ({})", leaf.command, leaf.uuid));
+    }
+    lines.join("\n")
+}
+
+/// Format a single execution tree in the given output format
+pub fn format_tree(tree: &ExecutionTree, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_tree_as_links_notation(tree)),
+        "json" => serde_json::to_string_pretty(tree)
+            .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(format_tree_as_text(tree)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+/// Format several execution trees in the given output format
+pub fn format_trees(trees: &[ExecutionTree], format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => {
+            let mut lines = vec![
+                "executionTrees".to_string(),
+                format!("  count {}", trees.len()),
+            ];
+            if trees.is_empty() {
+                lines.push("  trees ()".to_string());
+            } else {
+                lines.push("  trees".to_string());
+                for tree in trees {
+                    for line in format_tree_as_links_notation(tree).lines() {
+                        lines.push(format!("    {}", line));
+                    }
+                }
+            }
+            Ok(lines.join("\n"))
+        }
+        "json" => serde_json::to_string_pretty(&json!({
+            "count": trees.len(),
+            "trees": trees,
+        }))
+        .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(trees
+            .iter()
+            .map(format_tree_as_text)
+            .collect::<Vec<_>>()
+            .join("\n\n")),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+/// Handle --status <id> --tree and return the result
+pub fn query_status_tree(
+    store: Option<&ExecutionStore>,
+    identifier: &str,
+    output_format: Option<&str>,
+) -> StatusQueryResult {
+    StatusQueryResult::from(
+        store
+            .ok_or_else(|| "Execution tracking is disabled.".to_string())
+            .and_then(|store| {
+                let record = store.get(identifier).ok_or_else(|| {
+                    format!(
+                        "No execution found with UUID or session name: {}",
+                        identifier
+                    )
+                })?;
+                let tree = execution_tree_for(&store.get_all(), &record.uuid)
+                    .ok_or_else(|| format!("No execution found with UUID: {}", record.uuid))?;
+                format_tree(&tree, output_format.unwrap_or("links-notation"))
+            }),
+    )
+}
+
+/// Handle --list --tree and return the result
+pub fn list_execution_trees(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    output_format: Option<&str>,
+) -> StatusQueryResult {
+    StatusQueryResult::from(
+        store
+            .ok_or_else(|| "Execution tracking is disabled.".to_string())
+            .and_then(|store| {
+                let trees = build_execution_trees(&filter.apply(store.get_all()));
+                format_trees(&trees, output_format.unwrap_or("links-notation"))
+            }),
+    )
+}
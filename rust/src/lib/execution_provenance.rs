@@ -0,0 +1,244 @@
+//! Git and toolchain provenance for execution records
+//!
+//! With START_PROVENANCE=1 each launch records which repository state and
+//! toolchain produced the result: the git repository root, branch, commit
+//! and whether tracked files were modified, the resolved path of the
+//! command's executable, and the versions of well-known tools (node, bun,
+//! cargo, python) the command uses. Only tools named in the command are
+//! probed, and their versions are detected on a background thread while the
+//! command runs, so capture never waits on a `--version` before launch.
+
+use crate::execution_store::ExecutionRecord;
+use crate::version_info::get_tool_version;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Environment variable that turns provenance capture on
+pub const PROVENANCE_ENV: &str = "START_PROVENANCE";
+
+/// How long completing a record waits for tool versions still being detected
+const TOOL_VERSIONS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Environments where the command runs outside this machine's filesystem,
+/// so host executable paths and tool versions would be misleading
+const REMOTE_ENVIRONMENTS: [&str; 3] = ["docker", "podman", "ssh"];
+
+/// Well-known tools: recorded name, command words that imply it, and the
+/// binaries tried (in order) for its version
+const KNOWN_TOOLS: [(&str, &[&str], &[&str]); 4] = [
+    ("node", &["node", "npm", "npx", "pnpm", "yarn"], &["node"]),
+    ("bun", &["bun", "bunx"], &["bun"]),
+    ("cargo", &["cargo", "rustc", "rustup"], &["cargo"]),
+    (
+        "python",
+        &["python", "python3", "pip", "pip3", "pytest", "uv"],
+        &["python3", "python"],
+    ),
+];
+
+/// Where an execution came from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionProvenance {
+    /// Git state of the working directory, if it is inside a repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitProvenance>,
+    /// Resolved path of the command's executable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executable: Option<String>,
+    /// Versions of well-known tools used by the command, keyed by tool name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tools: BTreeMap<String, String>,
+}
+
+/// Git repository state at launch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitProvenance {
+    pub root: String,
+    /// Current branch, `None` when HEAD is detached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit SHA, `None` before the first commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Whether tracked files had uncommitted changes
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+impl ExecutionProvenance {
+    pub fn is_empty(&self) -> bool {
+        self.git.is_none() && self.executable.is_none() && self.tools.is_empty()
+    }
+}
+
+/// Versions of the tools a command uses, detected on a background thread
+#[derive(Debug)]
+pub struct ToolVersions(Receiver<BTreeMap<String, String>>);
+
+impl ToolVersions {
+    /// Start detecting the versions of the tools `command` uses
+    pub fn detect(command: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let command = command.to_string();
+        thread::spawn(move || {
+            let _ = sender.send(tool_versions(&command));
+        });
+        ToolVersions(receiver)
+    }
+
+    /// Add the detected versions to the provenance of `record`, waiting a
+    /// short while for detection that has not finished yet
+    pub fn attach(self, record: &mut ExecutionRecord) {
+        let Ok(tools) = self.0.recv_timeout(TOOL_VERSIONS_TIMEOUT) else {
+            return;
+        };
+        if !tools.is_empty() {
+            record.provenance.get_or_insert_with(Default::default).tools = tools;
+        }
+    }
+}
+
+/// Capture provenance for a record about to be launched and store it on the
+/// record. Tool versions are detected in the background; the returned
+/// [`ToolVersions`] adds them when the record is completed.
+pub fn record_provenance(record: &mut ExecutionRecord) -> Option<ToolVersions> {
+    let remote = record
+        .options
+        .get("isolated")
+        .and_then(|v| v.as_str())
+        .is_some_and(|env| REMOTE_ENVIRONMENTS.contains(&env));
+    let provenance = capture_provenance(&record.command, &record.working_directory, !remote);
+    record.provenance = (!provenance.is_empty()).then_some(provenance);
+    (!remote && !tools_used_by(&record.command).is_empty())
+        .then(|| ToolVersions::detect(&record.command))
+}
+
+/// Capture the git state and, when `local_tools` is set, the executable of
+/// `command` run from `working_directory`. Tool versions are left to
+/// [`tool_versions`], which spawns each tool.
+pub fn capture_provenance(
+    command: &str,
+    working_directory: &str,
+    local_tools: bool,
+) -> ExecutionProvenance {
+    let dir = Path::new(working_directory);
+    let mut provenance = ExecutionProvenance {
+        git: capture_git(dir),
+        ..Default::default()
+    };
+    if local_tools {
+        provenance.executable = command
+            .split_whitespace()
+            .next()
+            .and_then(|program| resolve_executable(program, dir))
+            .map(|path| path.to_string_lossy().to_string());
+    }
+    provenance
+}
+
+/// Versions of the well-known tools `command` uses, keyed by tool name
+pub fn tool_versions(command: &str) -> BTreeMap<String, String> {
+    tools_used_by(command)
+        .into_iter()
+        .filter_map(|tool| Some((tool.to_string(), tool_version(tool)?)))
+        .collect()
+}
+
+/// Git root, branch, commit and dirty state of `dir`, or `None` outside a repository
+pub fn capture_git(dir: &Path) -> Option<GitProvenance> {
+    let root = git_output(dir, &["rev-parse", "--show-toplevel"])?;
+    let status = git_output(
+        dir,
+        &[
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--untracked-files=no",
+        ],
+    )?;
+    let mut git = parse_git_status(&status);
+    git.root = root.trim().to_string();
+    Some(git)
+}
+
+/// Parse `git status --porcelain=v2 --branch` output (root is left empty)
+pub fn parse_git_status(output: &str) -> GitProvenance {
+    let mut git = GitProvenance::default();
+    for line in output.lines() {
+        if let Some(oid) = line.strip_prefix("# branch.oid ") {
+            git.commit = (oid != "(initial)").then(|| oid.to_string());
+        } else if let Some(head) = line.strip_prefix("# branch.head ") {
+            git.branch = (head != "(detached)").then(|| head.to_string());
+        } else if !line.starts_with('#') && !line.trim().is_empty() {
+            git.dirty = true;
+        }
+    }
+    git
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Resolve a program name the way the shell would: paths containing a slash
+/// are taken relative to `dir`, bare names are looked up on PATH
+pub fn resolve_executable(program: &str, dir: &Path) -> Option<PathBuf> {
+    if program.contains(std::path::MAIN_SEPARATOR) || program.contains('/') {
+        let path = dir.join(program);
+        return is_executable(&path).then(|| path.canonicalize().unwrap_or(path));
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|entry| dir.join(entry).join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Names of the well-known tools referenced by any word of `command`
+pub fn tools_used_by(command: &str) -> Vec<&'static str> {
+    let words: Vec<&str> = command
+        .split_whitespace()
+        .map(|word| word.rsplit('/').next().unwrap_or(word))
+        .collect();
+    KNOWN_TOOLS
+        .iter()
+        .filter(|(_, aliases, _)| words.iter().any(|word| aliases.contains(word)))
+        .map(|(name, _, _)| *name)
+        .collect()
+}
+
+fn tool_version(tool: &str) -> Option<String> {
+    let (_, _, binaries) = KNOWN_TOOLS.iter().find(|(name, _, _)| *name == tool)?;
+    binaries
+        .iter()
+        .find_map(|binary| get_tool_version(binary, "--version", false))
+}
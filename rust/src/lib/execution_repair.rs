@@ -0,0 +1,327 @@
+//! Consistency repair for the execution store (`--repair`)
+//!
+//! Brings `executions.lino` and `executions.links` back in line: records that
+//! exist on one side only are copied to the other, and when both copies differ
+//! the more complete one wins (a finished copy beats one still executing, then
+//! the later `end_time`). Executions still marked as executing whose log ends
+//! with the footer (a separator, `Finished:` and `Exit Code:`) are recovered
+//! as finished. Undecodable lino
+//! entries are quarantined by the store. `--repair-dry-run` only reports.
+
+use crate::args_parser::WrapperOptions;
+use crate::execution_store::{ends_later, ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::log_follower::LogFooterScanner;
+use crate::output_blocks::escape_for_links_notation;
+use crate::status_formatter::StatusQueryResult;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Record fields stored as strings, kept as strings when read back from links
+const STRING_FIELDS: [&str; 12] = [
+    "uuid",
+    "status",
+    "command",
+    "logPath",
+    "startTime",
+    "endTime",
+    "workingDirectory",
+    "shell",
+    "platform",
+    "parentUuid",
+    "rootUuid",
+    "owner",
+];
+
+/// What a repair changed (or would change, for a dry run)
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    pub dry_run: bool,
+    /// Oldest schema version migrated
+    pub migrated_from: Option<u32>,
+    /// Number of undecodable lino entries moved to the quarantine file
+    pub quarantined: usize,
+    pub quarantine_path: Option<String>,
+    /// Records only present in the links database, copied to lino
+    pub added_from_links: Vec<String>,
+    /// Records whose links copy was more complete than the lino copy
+    pub updated_from_links: Vec<String>,
+    /// Records (re)written to the links database
+    pub written_to_links: Vec<String>,
+    /// Executions marked finished from their log footer
+    pub recovered: Vec<RecoveredExecution>,
+    pub warnings: Vec<String>,
+}
+
+/// An execution recovered from its log footer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredExecution {
+    pub uuid: String,
+    pub exit_code: i32,
+}
+
+impl RepairReport {
+    /// Whether the repair changed (or would change) anything
+    pub fn has_changes(&self) -> bool {
+        self.migrated_from.is_some()
+            || self.quarantined > 0
+            || !self.added_from_links.is_empty()
+            || !self.updated_from_links.is_empty()
+            || !self.written_to_links.is_empty()
+            || !self.recovered.is_empty()
+    }
+}
+
+/// Lino records after reconciliation with the links database
+#[derive(Debug, Default)]
+pub struct Reconciliation {
+    pub records: Vec<ExecutionRecord>,
+    /// UUIDs copied from links into lino
+    pub added: Vec<String>,
+    /// UUIDs whose lino copy was replaced by the links copy
+    pub updated: Vec<String>,
+    /// UUIDs that must be (re)written to links
+    pub to_links: Vec<String>,
+}
+
+/// Reconcile lino records with the records read back from links
+pub fn reconcile_stores(lino: Vec<ExecutionRecord>, links: &[ExecutionRecord]) -> Reconciliation {
+    let mut plan = Reconciliation {
+        records: lino,
+        ..Default::default()
+    };
+    let links_by_uuid: BTreeMap<&str, &ExecutionRecord> =
+        links.iter().map(|r| (r.uuid.as_str(), r)).collect();
+
+    for record in plan.records.iter_mut() {
+        match links_by_uuid.get(record.uuid.as_str()) {
+            None => plan.to_links.push(record.uuid.clone()),
+            Some(linked) if more_complete(linked, record) => {
+                let mut updated = (*linked).clone();
+                updated.keep_annotations_from(record);
+                *record = updated;
+                plan.updated.push(record.uuid.clone());
+            }
+            Some(linked) if linked.to_json() != record.to_json() => {
+                plan.to_links.push(record.uuid.clone());
+            }
+            Some(_) => {}
+        }
+    }
+
+    for linked in links {
+        if !plan.records.iter().any(|r| r.uuid == linked.uuid) {
+            plan.records.push(linked.clone());
+            plan.added.push(linked.uuid.clone());
+        }
+    }
+    plan
+}
+
+/// Whether `candidate` is a more complete copy of the same execution:
+/// finished where the other is still executing, or finished later
+fn more_complete(candidate: &ExecutionRecord, other: &ExecutionRecord) -> bool {
+    let finished = |r: &ExecutionRecord| r.status == ExecutionStatus::Executed;
+    (finished(candidate) && !finished(other)) || ends_later(candidate, other)
+}
+
+/// Mark executing records whose log ends with an `Exit Code:` footer as
+/// finished, taking the end time from the `Finished:` footer line
+pub fn recover_from_log_footers(records: &mut [ExecutionRecord]) -> Vec<RecoveredExecution> {
+    let mut recovered = Vec::new();
+    for record in records
+        .iter_mut()
+        .filter(|r| r.status == ExecutionStatus::Executing && !r.log_path.is_empty())
+    {
+        let Some((exit_code, finished)) = read_log_footer(&record.log_path) else {
+            continue;
+        };
+        record.status = ExecutionStatus::Executed;
+        record.exit_code = Some(exit_code);
+        record.end_time = Some(
+            finished
+                .or_else(|| log_modified_time(&record.log_path))
+                .unwrap_or_else(|| Utc::now().to_rfc3339()),
+        );
+        recovered.push(RecoveredExecution {
+            uuid: record.uuid.clone(),
+            exit_code,
+        });
+    }
+    recovered
+}
+
+/// Exit code and RFC 3339 finish time from the footer that ends a log file.
+/// An `Exit Code:` line printed by the command itself is not a footer.
+fn read_log_footer(log_path: &str) -> Option<(i32, Option<String>)> {
+    let content = fs::read_to_string(log_path).ok()?;
+    let mut scanner = LogFooterScanner::default();
+    let mut footer = None;
+    for line in content.lines() {
+        if let Some(exit_code) = scanner.feed(&format!("{}\n", line)) {
+            footer = Some((exit_code, scanner.finished().map(str::to_string)));
+        } else if !line.trim().is_empty() {
+            // Output after a footer means the footer did not end the log
+            footer = None;
+        }
+    }
+    let (exit_code, finished) = footer?;
+    let finished = finished
+        .and_then(|time| NaiveDateTime::parse_from_str(&time, "%Y-%m-%d %H:%M:%S%.f").ok())
+        .map(|time| time.and_utc().to_rfc3339());
+    Some((exit_code, finished))
+}
+
+fn log_modified_time(log_path: &str) -> Option<String> {
+    let modified = fs::metadata(log_path).ok()?.modified().ok()?;
+    Some(DateTime::<Utc>::from(modified).to_rfc3339())
+}
+
+/// Rebuild records from links in the notation the store writes them in:
+/// `(<uuid>.<key>: <key> "<value>")`, with `"` escaped as `\"`
+pub fn parse_links_records(output: &str) -> Vec<ExecutionRecord> {
+    let re = regex::Regex::new(
+        r#"\(([0-9A-Fa-f-]{36})\.([A-Za-z]+):\s+[A-Za-z]+\s+"((?:[^"\\]|\\.)*)"\)"#,
+    )
+    .unwrap();
+    let mut objects: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    let mut order: Vec<String> = Vec::new();
+    for caps in re.captures_iter(output) {
+        let uuid = caps[1].to_lowercase();
+        let raw = caps[3].replace("\\\"", "\"");
+        let value = if raw == "null" {
+            Value::Null
+        } else if STRING_FIELDS.contains(&&caps[2]) {
+            Value::String(raw)
+        } else {
+            serde_json::from_str(&raw).unwrap_or(Value::String(raw))
+        };
+        if !objects.contains_key(&uuid) {
+            order.push(uuid.clone());
+        }
+        objects
+            .entry(uuid)
+            .or_default()
+            .insert(caps[2].to_string(), value);
+    }
+    order
+        .iter()
+        .filter_map(|uuid| objects.remove(uuid))
+        .filter_map(|map| ExecutionRecord::from_json(&Value::Object(map)))
+        .collect()
+}
+
+/// Format a repair report as links-notation, json or text
+pub fn format_repair_report(report: &RepairReport, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_report_as_links_notation(report)),
+        "json" => serde_json::to_string_pretty(report).map_err(|e| e.to_string()),
+        "text" => Ok(format_report_as_text(report)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+fn format_report_as_links_notation(report: &RepairReport) -> String {
+    let mut lines = vec![
+        "repair".to_string(),
+        format!("  dryRun {}", report.dry_run),
+        format!("  changed {}", report.has_changes()),
+    ];
+    if let Some(version) = report.migrated_from {
+        lines.push(format!("  migratedFrom {}", version));
+    }
+    lines.push(format!("  quarantined {}", report.quarantined));
+    if let Some(ref path) = report.quarantine_path {
+        lines.push(format!(
+            "  quarantinePath {}",
+            escape_for_links_notation(path)
+        ));
+    }
+    for (key, uuids) in [
+        ("addedFromLinks", &report.added_from_links),
+        ("updatedFromLinks", &report.updated_from_links),
+        ("writtenToLinks", &report.written_to_links),
+    ] {
+        if !uuids.is_empty() {
+            lines.push(format!("  {}", key));
+            lines.extend(uuids.iter().map(|uuid| format!("    {}", uuid)));
+        }
+    }
+    if !report.recovered.is_empty() {
+        lines.push("  recovered".to_string());
+        for recovered in &report.recovered {
+            lines.push(format!("    {} {}", recovered.uuid, recovered.exit_code));
+        }
+    }
+    if !report.warnings.is_empty() {
+        lines.push("  warnings".to_string());
+        for warning in &report.warnings {
+            lines.push(format!("    {}", escape_for_links_notation(warning)));
+        }
+    }
+    lines.join("\n")
+}
+
+fn format_report_as_text(report: &RepairReport) -> String {
+    let title = if report.dry_run {
+        "Store Repair (dry run)"
+    } else {
+        "Store Repair"
+    };
+    let mut lines = vec![title.to_string(), "=".repeat(50)];
+    if !report.has_changes() {
+        lines.push("Store is consistent; nothing to repair.".to_string());
+    }
+    if let Some(version) = report.migrated_from {
+        lines.push(format!("Migrated from:     schema version {}", version));
+    }
+    if report.quarantined > 0 {
+        lines.push(format!(
+            "Quarantined:       {} entries -> {}",
+            report.quarantined,
+            report.quarantine_path.as_deref().unwrap_or_default()
+        ));
+    }
+    for (label, uuids) in [
+        ("Added from links:", &report.added_from_links),
+        ("Updated from links:", &report.updated_from_links),
+        ("Written to links:", &report.written_to_links),
+    ] {
+        if !uuids.is_empty() {
+            lines.push(label.to_string());
+            lines.extend(uuids.iter().map(|uuid| format!("  {}", uuid)));
+        }
+    }
+    if !report.recovered.is_empty() {
+        lines.push("Recovered from log footers:".to_string());
+        for recovered in &report.recovered {
+            lines.push(format!(
+                "  {}: exit code {}",
+                recovered.uuid, recovered.exit_code
+            ));
+        }
+    }
+    for warning in &report.warnings {
+        lines.push(format!("Warning: {}", warning));
+    }
+    lines.join("\n")
+}
+
+/// Handle --repair and --repair-dry-run
+pub fn query_repair(store: Option<&ExecutionStore>, options: &WrapperOptions) -> StatusQueryResult {
+    let result = store
+        .ok_or_else(|| "Execution tracking is disabled.".to_string())
+        .and_then(|store| store.repair(options.repair_dry_run))
+        .and_then(|report| {
+            format_repair_report(
+                &report,
+                options.output_format.as_deref().unwrap_or("links-notation"),
+            )
+        });
+
+    result.into()
+}
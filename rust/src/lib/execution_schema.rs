@@ -0,0 +1,95 @@
+//! Execution store schema versioning
+//!
+//! Every record carries the `schemaVersion` it was written with; records
+//! without one predate versioning and count as version 1. Records are brought
+//! up to [`CURRENT_SCHEMA_VERSION`] by the registered [`MIGRATIONS`] whenever
+//! they are decoded, so history written by older releases of either the JS or
+//! the Rust implementation keeps loading. Reads migrate in memory; the next
+//! write stores the migrated records after copying the old file to
+//! `executions.lino.v<N>.bak` (kept once per version).
+//!
+//! `--schema` prints the published JSON Schema for the current record layout.
+
+use crate::status_formatter::StatusQueryResult;
+use serde_json::{Map, Value};
+
+/// Schema version written by this release
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Version assumed for records without a `schemaVersion` field
+pub const LEGACY_SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema describing an execution record at [`CURRENT_SCHEMA_VERSION`]
+pub const EXECUTION_RECORD_SCHEMA: &str = include_str!("../../schema/execution-record.schema.json");
+
+/// A migration upgrades a record object by exactly one schema version
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// Registered migrations, keyed by the version they upgrade from
+pub const MIGRATIONS: [(u32, Migration); 1] = [(1, migrate_v1_to_v2)];
+
+/// Schema version a record value was written with
+pub fn record_schema_version(value: &Value) -> u32 {
+    value
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .unwrap_or(LEGACY_SCHEMA_VERSION)
+}
+
+/// Bring a record value up to the current schema version.
+/// Returns the version the record was written with. Records from newer
+/// versions are left untouched.
+pub fn migrate_record(value: &mut Value) -> u32 {
+    let from = record_schema_version(value);
+    let Value::Object(map) = value else {
+        return from;
+    };
+
+    let mut version = from;
+    for (source, migration) in MIGRATIONS {
+        if version == source {
+            migration(map);
+            version = source + 1;
+        }
+    }
+    if version != from || !map.contains_key("schemaVersion") {
+        map.insert("schemaVersion".to_string(), Value::from(version));
+    }
+    from
+}
+
+/// Version 1 records were written by releases that did not always include
+/// every field. Fill in what is missing so they decode instead of being
+/// silently dropped.
+fn migrate_v1_to_v2(record: &mut Map<String, Value>) {
+    for key in [
+        "command",
+        "logPath",
+        "startTime",
+        "workingDirectory",
+        "shell",
+        "platform",
+    ] {
+        if record.get(key).is_none_or(Value::is_null) {
+            record.insert(key.to_string(), Value::String(String::new()));
+        }
+    }
+    if !record.get("options").is_some_and(Value::is_object) {
+        record.insert("options".to_string(), Value::Object(Map::new()));
+    }
+    if record.get("status").is_none_or(Value::is_null) {
+        let finished = record.get("exitCode").is_some_and(Value::is_number);
+        let status = if finished { "executed" } else { "executing" };
+        record.insert("status".to_string(), Value::from(status));
+    }
+}
+
+/// Handle --schema: print the JSON Schema for execution records
+pub fn query_schema() -> StatusQueryResult {
+    StatusQueryResult {
+        success: true,
+        output: Some(EXECUTION_RECORD_SCHEMA.trim_end().to_string()),
+        error: None,
+    }
+}
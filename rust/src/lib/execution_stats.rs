@@ -0,0 +1,473 @@
+//! Execution statistics and analytics for stored execution records
+//!
+//! Turns the execution history into an actionable report:
+//! - Run counts and success rate per group
+//! - p50/p95/max durations of completed runs
+//! - Flakiness (how often consecutive runs of a group flip between pass and fail)
+//! - Daily trends over the most recent days with activity
+//!
+//! Records can be grouped by full command, normalized command prefix,
+//! working directory, and isolation backend.
+
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::output_blocks::{escape_for_links_notation, format_duration};
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::{enrich_detached_status, StatusQueryResult};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Valid groupings for --group-by
+pub const VALID_STATS_GROUPS: [&str; 4] = ["command", "prefix", "directory", "backend"];
+
+/// Groupings included in the report when --group-by is not given
+const DEFAULT_STATS_GROUPS: [&str; 3] = ["command", "directory", "backend"];
+
+/// Number of words kept when normalizing a command to its prefix
+const COMMAND_PREFIX_WORDS: usize = 2;
+
+/// Number of most recent days with activity included in trends
+const STATS_TREND_DAYS: usize = 14;
+
+/// Aggregated statistics for one group of executions
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupStats {
+    pub key: String,
+    pub runs: usize,
+    pub executing: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub success_rate: Option<f64>,
+    pub p50_duration_ms: Option<f64>,
+    pub p95_duration_ms: Option<f64>,
+    pub max_duration_ms: Option<f64>,
+    pub flakiness: f64,
+    pub last_run: Option<String>,
+}
+
+/// Aggregated statistics for all executions started on one day (UTC)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendBucket {
+    pub date: String,
+    pub runs: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub success_rate: Option<f64>,
+    pub p50_duration_ms: Option<f64>,
+}
+
+/// Statistics grouped by a single dimension
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsGrouping {
+    pub group_by: String,
+    pub groups: Vec<GroupStats>,
+}
+
+/// Full statistics report over a set of execution records
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsReport {
+    pub total: usize,
+    pub executing: usize,
+    pub executed: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub success_rate: Option<f64>,
+    pub p50_duration_ms: Option<f64>,
+    pub p95_duration_ms: Option<f64>,
+    pub max_duration_ms: Option<f64>,
+    pub groupings: Vec<StatsGrouping>,
+    pub trends: Vec<TrendBucket>,
+}
+
+/// Normalize a command to its leading words, skipping `KEY=value` assignments
+/// and collapsing whitespace, so `FOO=1 npm  test -- --watch` groups as `npm test`.
+pub fn normalize_command_prefix(command: &str, words: usize) -> String {
+    command
+        .split_whitespace()
+        .skip_while(|word| {
+            word.split_once('=').is_some_and(|(name, _)| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+        })
+        .take(words)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Duration of a completed execution in milliseconds, if both timestamps parse
+pub fn record_duration_ms(record: &ExecutionRecord) -> Option<f64> {
+    let start = chrono::DateTime::parse_from_rfc3339(&record.start_time).ok()?;
+    let end = chrono::DateTime::parse_from_rfc3339(record.end_time.as_deref()?).ok()?;
+    let ms = (end - start).num_milliseconds();
+    (ms >= 0).then_some(ms as f64)
+}
+
+/// Nearest-rank percentile of an ascending-sorted slice (`p` in 0..=100)
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Fraction of consecutive completed runs whose outcome flipped between
+/// pass and fail (0.0 = stable, 1.0 = alternates on every run).
+pub fn flakiness(outcomes: &[bool]) -> f64 {
+    if outcomes.len() < 2 {
+        return 0.0;
+    }
+    let flips = outcomes.windows(2).filter(|w| w[0] != w[1]).count();
+    flips as f64 / (outcomes.len() - 1) as f64
+}
+
+fn is_successful(record: &ExecutionRecord) -> bool {
+    record.status == ExecutionStatus::Executed && record.exit_code == Some(0)
+}
+
+fn is_failed(record: &ExecutionRecord) -> bool {
+    record.status == ExecutionStatus::Executed && record.exit_code.is_some_and(|c| c != 0)
+}
+
+fn success_rate(successful: usize, failed: usize) -> Option<f64> {
+    let completed = successful + failed;
+    (completed > 0).then(|| successful as f64 / completed as f64)
+}
+
+fn sorted_durations(records: &[&ExecutionRecord]) -> Vec<f64> {
+    let mut durations: Vec<f64> = records
+        .iter()
+        .filter(|r| r.status == ExecutionStatus::Executed)
+        .filter_map(|r| record_duration_ms(r))
+        .collect();
+    durations.sort_by(|a, b| a.total_cmp(b));
+    durations
+}
+
+/// Key a record belongs to for the given grouping
+fn group_key(record: &ExecutionRecord, group_by: &str) -> String {
+    match group_by {
+        "prefix" => normalize_command_prefix(&record.command, COMMAND_PREFIX_WORDS),
+        "directory" => record.working_directory.clone(),
+        "backend" => record
+            .options
+            .get("isolated")
+            .and_then(|v| v.as_str())
+            .unwrap_or("direct")
+            .to_string(),
+        _ => record
+            .command
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn compute_group(key: String, records: &mut [&ExecutionRecord]) -> GroupStats {
+    records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+    let successful = records.iter().filter(|r| is_successful(r)).count();
+    let failed = records.iter().filter(|r| is_failed(r)).count();
+    let outcomes: Vec<bool> = records
+        .iter()
+        .filter(|r| r.status == ExecutionStatus::Executed && r.exit_code.is_some())
+        .map(|r| r.exit_code == Some(0))
+        .collect();
+    let durations = sorted_durations(records);
+
+    GroupStats {
+        key,
+        runs: records.len(),
+        executing: records
+            .iter()
+            .filter(|r| r.status == ExecutionStatus::Executing)
+            .count(),
+        successful,
+        failed,
+        success_rate: success_rate(successful, failed),
+        p50_duration_ms: percentile(&durations, 50.0),
+        p95_duration_ms: percentile(&durations, 95.0),
+        max_duration_ms: durations.last().copied(),
+        flakiness: flakiness(&outcomes),
+        last_run: records.last().map(|r| r.start_time.clone()),
+    }
+}
+
+fn compute_grouping(records: &[ExecutionRecord], group_by: &str) -> StatsGrouping {
+    let mut buckets: BTreeMap<String, Vec<&ExecutionRecord>> = BTreeMap::new();
+    for record in records {
+        buckets
+            .entry(group_key(record, group_by))
+            .or_default()
+            .push(record);
+    }
+
+    let mut groups: Vec<GroupStats> = buckets
+        .into_iter()
+        .map(|(key, mut members)| compute_group(key, &mut members))
+        .collect();
+    // Most active groups first; ties broken by key for stable output
+    groups.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.key.cmp(&b.key)));
+
+    StatsGrouping {
+        group_by: group_by.to_string(),
+        groups,
+    }
+}
+
+fn compute_trends(records: &[ExecutionRecord]) -> Vec<TrendBucket> {
+    let mut days: BTreeMap<String, Vec<&ExecutionRecord>> = BTreeMap::new();
+    for record in records {
+        let Ok(start) = chrono::DateTime::parse_from_rfc3339(&record.start_time) else {
+            continue;
+        };
+        let date = start
+            .with_timezone(&chrono::Utc)
+            .format("%Y-%m-%d")
+            .to_string();
+        days.entry(date).or_default().push(record);
+    }
+
+    let skip = days.len().saturating_sub(STATS_TREND_DAYS);
+    days.into_iter()
+        .skip(skip)
+        .map(|(date, members)| {
+            let successful = members.iter().filter(|r| is_successful(r)).count();
+            let failed = members.iter().filter(|r| is_failed(r)).count();
+            TrendBucket {
+                date,
+                runs: members.len(),
+                successful,
+                failed,
+                success_rate: success_rate(successful, failed),
+                p50_duration_ms: percentile(&sorted_durations(&members), 50.0),
+            }
+        })
+        .collect()
+}
+
+/// Compute a statistics report. `group_by` selects a single grouping from
+/// [`VALID_STATS_GROUPS`]; when `None`, the report is grouped by command,
+/// directory, and backend.
+pub fn compute_stats(records: &[ExecutionRecord], group_by: Option<&str>) -> StatsReport {
+    let all: Vec<&ExecutionRecord> = records.iter().collect();
+    let successful = records.iter().filter(|r| is_successful(r)).count();
+    let failed = records.iter().filter(|r| is_failed(r)).count();
+    let durations = sorted_durations(&all);
+    let groups: Vec<&str> = match group_by {
+        Some(group) => vec![group],
+        None => DEFAULT_STATS_GROUPS.to_vec(),
+    };
+
+    StatsReport {
+        total: records.len(),
+        executing: records
+            .iter()
+            .filter(|r| r.status == ExecutionStatus::Executing)
+            .count(),
+        executed: records
+            .iter()
+            .filter(|r| r.status == ExecutionStatus::Executed)
+            .count(),
+        successful,
+        failed,
+        success_rate: success_rate(successful, failed),
+        p50_duration_ms: percentile(&durations, 50.0),
+        p95_duration_ms: percentile(&durations, 95.0),
+        max_duration_ms: durations.last().copied(),
+        groupings: groups
+            .into_iter()
+            .map(|group| compute_grouping(records, group))
+            .collect(),
+        trends: compute_trends(records),
+    }
+}
+
+/// Section name used for a grouping in links-notation and text output
+fn grouping_title(group_by: &str) -> &'static str {
+    match group_by {
+        "prefix" => "byCommandPrefix",
+        "directory" => "byDirectory",
+        "backend" => "byBackend",
+        _ => "byCommand",
+    }
+}
+
+fn format_rate(rate: Option<f64>) -> String {
+    rate.map(|r| format!("{:.1}%", r * 100.0))
+        .unwrap_or_else(|| "N/A".to_string())
+}
+
+fn format_ms(ms: Option<f64>) -> String {
+    ms.map(format_duration).unwrap_or_else(|| "N/A".to_string())
+}
+
+fn push_links_optional(lines: &mut Vec<String>, indent: usize, key: &str, value: Option<f64>) {
+    if let Some(value) = value {
+        lines.push(format!("{}{} {}", " ".repeat(indent), key, round3(value)));
+    }
+}
+
+fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+/// Format a statistics report as Links Notation (indented style)
+pub fn format_stats_as_links_notation(report: &StatsReport) -> String {
+    let mut lines = vec![
+        "stats".to_string(),
+        format!("  total {}", report.total),
+        format!("  executing {}", report.executing),
+        format!("  executed {}", report.executed),
+        format!("  successful {}", report.successful),
+        format!("  failed {}", report.failed),
+    ];
+    push_links_optional(&mut lines, 2, "successRate", report.success_rate);
+    push_links_optional(&mut lines, 2, "p50DurationMs", report.p50_duration_ms);
+    push_links_optional(&mut lines, 2, "p95DurationMs", report.p95_duration_ms);
+    push_links_optional(&mut lines, 2, "maxDurationMs", report.max_duration_ms);
+
+    for grouping in &report.groupings {
+        if grouping.groups.is_empty() {
+            lines.push(format!("  {} ()", grouping_title(&grouping.group_by)));
+            continue;
+        }
+        lines.push(format!("  {}", grouping_title(&grouping.group_by)));
+        for group in &grouping.groups {
+            lines.push(format!("    {}", escape_for_links_notation(&group.key)));
+            lines.push(format!("      runs {}", group.runs));
+            lines.push(format!("      executing {}", group.executing));
+            lines.push(format!("      successful {}", group.successful));
+            lines.push(format!("      failed {}", group.failed));
+            push_links_optional(&mut lines, 6, "successRate", group.success_rate);
+            push_links_optional(&mut lines, 6, "p50DurationMs", group.p50_duration_ms);
+            push_links_optional(&mut lines, 6, "p95DurationMs", group.p95_duration_ms);
+            push_links_optional(&mut lines, 6, "maxDurationMs", group.max_duration_ms);
+            lines.push(format!("      flakiness {}", round3(group.flakiness)));
+            if let Some(ref last_run) = group.last_run {
+                lines.push(format!(
+                    "      lastRun {}",
+                    escape_for_links_notation(last_run)
+                ));
+            }
+        }
+    }
+
+    if report.trends.is_empty() {
+        lines.push("  trends ()".to_string());
+    } else {
+        lines.push("  trends".to_string());
+        for bucket in &report.trends {
+            lines.push(format!("    {}", bucket.date));
+            lines.push(format!("      runs {}", bucket.runs));
+            lines.push(format!("      successful {}", bucket.successful));
+            lines.push(format!("      failed {}", bucket.failed));
+            push_links_optional(&mut lines, 6, "successRate", bucket.success_rate);
+            push_links_optional(&mut lines, 6, "p50DurationMs", bucket.p50_duration_ms);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Format a statistics report as human-readable text
+pub fn format_stats_as_text(report: &StatsReport) -> String {
+    let mut lines = vec![
+        "Execution Statistics".to_string(),
+        "=".repeat(50),
+        format!("Total:             {}", report.total),
+        format!("Executing:         {}", report.executing),
+        format!("Executed:          {}", report.executed),
+        format!("Successful:        {}", report.successful),
+        format!("Failed:            {}", report.failed),
+        format!("Success Rate:      {}", format_rate(report.success_rate)),
+        format!(
+            "Duration:          p50 {}, p95 {}, max {}",
+            format_ms(report.p50_duration_ms),
+            format_ms(report.p95_duration_ms),
+            format_ms(report.max_duration_ms)
+        ),
+    ];
+
+    for grouping in &report.groupings {
+        lines.push(String::new());
+        lines.push(match grouping.group_by.as_str() {
+            "prefix" => "By Command Prefix".to_string(),
+            "directory" => "By Directory".to_string(),
+            "backend" => "By Backend".to_string(),
+            _ => "By Command".to_string(),
+        });
+        lines.push("-".repeat(50));
+        for group in &grouping.groups {
+            lines.push(group.key.clone());
+            lines.push(format!(
+                "  Runs: {}, Success Rate: {}, Failed: {}, Executing: {}",
+                group.runs,
+                format_rate(group.success_rate),
+                group.failed,
+                group.executing
+            ));
+            lines.push(format!(
+                "  Duration: p50 {}, p95 {}, max {}",
+                format_ms(group.p50_duration_ms),
+                format_ms(group.p95_duration_ms),
+                format_ms(group.max_duration_ms)
+            ));
+            if group.flakiness > 0.0 {
+                lines.push(format!("  Flakiness: {:.1}%", group.flakiness * 100.0));
+            }
+        }
+    }
+
+    if !report.trends.is_empty() {
+        lines.push(String::new());
+        lines.push("Trends".to_string());
+        lines.push("-".repeat(50));
+        for bucket in &report.trends {
+            lines.push(format!(
+                "{}  Runs: {}, Success Rate: {}, p50: {}",
+                bucket.date,
+                bucket.runs,
+                format_rate(bucket.success_rate),
+                format_ms(bucket.p50_duration_ms)
+            ));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Format a statistics report based on format type
+pub fn format_stats_report(report: &StatsReport, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_stats_as_links_notation(report)),
+        "json" => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(format_stats_as_text(report)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+/// Handle statistics query and return the result
+pub fn query_stats(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    group_by: Option<&str>,
+    output_format: Option<&str>,
+) -> StatusQueryResult {
+    let Some(store) = store else {
+        return StatusQueryResult {
+            success: false,
+            output: None,
+            error: Some("Execution tracking is disabled.".to_string()),
+        };
+    };
+
+    let records: Vec<ExecutionRecord> =
+        filter.apply(store.get_all().iter().map(enrich_detached_status).collect());
+    let report = compute_stats(&records, group_by);
+
+    format_stats_report(&report, output_format.unwrap_or("links-notation")).into()
+}
@@ -1,19 +1,17 @@
 //! Execution Store - Dual storage (.lino text + .links binary) for command execution records
 
-use crate::lino_value_json::{json_to_lino_value, lino_value_to_json};
-use crate::local_hostname;
+use crate::execution_provenance::ExecutionProvenance;
+use crate::execution_schema::{migrate_record, CURRENT_SCHEMA_VERSION, LEGACY_SCHEMA_VERSION};
+use crate::lino_value_json::json_to_lino_value;
 use chrono::Utc;
-use lino_objects_codec::{decode, encode, LinoValue};
+use lino_objects_codec::{encode, LinoValue};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::thread;
-use std::time::Duration;
 use uuid::Uuid;
 
 /// Default application folder name
@@ -26,8 +24,6 @@ const LINKS_DB_FILE: &str = "executions.links";
 const LOCK_FILE: &str = "executions.lock";
 /// Lock timeout in milliseconds
 const LOCK_TIMEOUT_MS: u64 = 30000;
-/// Consider lock stale after this many milliseconds
-const LOCK_STALE_MS: u64 = 60000;
 
 /// Execution status
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -52,6 +48,18 @@ impl std::fmt::Display for ExecutionStatus {
     }
 }
 
+#[path = "execution_store_lock.rs"]
+mod lock;
+pub use self::lock::LockManager;
+
+#[path = "execution_store_load.rs"]
+mod load;
+pub use self::load::decode_lino_entries;
+
+#[path = "execution_store_shared.rs"]
+mod shared;
+pub use self::shared::{shared_store_users, SHARED_STORE_ENV};
+
 /// Command Execution Record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -71,6 +79,46 @@ pub struct ExecutionRecord {
     pub platform: String,
     #[serde(default)]
     pub options: HashMap<String, Value>,
+    /// Free-form tags such as "release-1.4" or "bisect-good"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Key/value labels such as "env=staging"
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    /// Notes added after the fact with --annotate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<ExecutionNote>,
+    /// Execution that launched this one (from START_EXECUTION_ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_uuid: Option<String>,
+    /// Top-level execution of the nesting chain (from START_ROOT_EXECUTION_ID)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_uuid: Option<String>,
+    /// Git and toolchain state captured at launch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<ExecutionProvenance>,
+    /// User who recorded the execution in a shared store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Store schema version the record was written with
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
+    /// Fields this version does not know about (e.g. written by a newer
+    /// release), kept so that rewriting the store does not drop them
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+
+fn legacy_schema_version() -> u32 {
+    LEGACY_SCHEMA_VERSION
+}
+
+/// A note attached to an execution record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionNote {
+    /// When the note was added (RFC 3339)
+    pub time: String,
+    pub text: String,
 }
 
 impl ExecutionRecord {
@@ -93,6 +141,15 @@ impl ExecutionRecord {
             shell: env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string()),
             platform: std::env::consts::OS.to_string(),
             options: HashMap::new(),
+            tags: Vec::new(),
+            labels: BTreeMap::new(),
+            notes: Vec::new(),
+            parent_uuid: None,
+            root_uuid: None,
+            provenance: None,
+            owner: None,
+            schema_version: CURRENT_SCHEMA_VERSION,
+            extra: BTreeMap::new(),
         }
     }
 
@@ -145,14 +202,40 @@ impl ExecutionRecord {
         self.end_time = Some(Utc::now().to_rfc3339());
     }
 
+    /// Carry over tags, labels and notes that another process added to the
+    /// stored copy of this record. Tags and notes are only ever appended;
+    /// `--annotate` changes labels on the stored copy, so its values win.
+    pub fn keep_annotations_from(&mut self, stored: &ExecutionRecord) {
+        for tag in &stored.tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+            }
+        }
+        self.labels.extend(
+            stored
+                .labels
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        let mut notes = stored.notes.clone();
+        for note in self.notes.drain(..) {
+            if !notes.contains(&note) {
+                notes.push(note);
+            }
+        }
+        self.notes = notes;
+    }
+
     /// Convert to JSON Value
     pub fn to_json(&self) -> Value {
         serde_json::to_value(self).unwrap_or(Value::Null)
     }
 
-    /// Create from JSON Value
+    /// Create from JSON Value, migrating records written with older schema versions
     pub fn from_json(value: &Value) -> Option<Self> {
-        serde_json::from_value(value.clone()).ok()
+        let mut value = value.clone();
+        migrate_record(&mut value);
+        serde_json::from_value(value).ok()
     }
 }
 
@@ -174,122 +257,6 @@ pub struct ExecutionRecordOptions {
     pub options: Option<HashMap<String, Value>>,
 }
 
-/// File-based lock manager
-pub struct LockManager {
-    lock_file_path: PathBuf,
-    lock_acquired: bool,
-}
-
-impl LockManager {
-    /// Create a new lock manager
-    pub fn new(lock_file_path: PathBuf) -> Self {
-        LockManager {
-            lock_file_path,
-            lock_acquired: false,
-        }
-    }
-
-    /// Acquire an exclusive lock
-    pub fn acquire(&mut self, timeout_ms: u64) -> bool {
-        let start = std::time::Instant::now();
-        let timeout = Duration::from_millis(timeout_ms);
-
-        while start.elapsed() < timeout {
-            // Check if existing lock is stale
-            if self.lock_file_path.exists() {
-                if let Some(lock_data) = self.read_lock_file() {
-                    if self.is_lock_stale(&lock_data) {
-                        let _ = fs::remove_file(&self.lock_file_path);
-                    }
-                }
-            }
-
-            // Try to create lock file exclusively
-            match OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(&self.lock_file_path)
-            {
-                Ok(mut file) => {
-                    let lock_data = json!({
-                        "pid": std::process::id(),
-                        "timestamp": std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .map(|d| d.as_millis())
-                            .unwrap_or(0),
-                        "hostname": local_hostname::get()
-                            .map(|h| h.to_string_lossy().to_string())
-                            .unwrap_or_default()
-                    });
-                    let _ = file.write_all(lock_data.to_string().as_bytes());
-                    self.lock_acquired = true;
-                    return true;
-                }
-                Err(_) => {
-                    // Lock file exists, wait and retry
-                    thread::sleep(Duration::from_millis(100));
-                    continue;
-                }
-            }
-        }
-
-        false
-    }
-
-    /// Release the lock
-    pub fn release(&mut self) {
-        if self.lock_acquired {
-            let _ = fs::remove_file(&self.lock_file_path);
-            self.lock_acquired = false;
-        }
-    }
-
-    /// Read lock file data
-    fn read_lock_file(&self) -> Option<Value> {
-        let content = fs::read_to_string(&self.lock_file_path).ok()?;
-        serde_json::from_str(&content).ok()
-    }
-
-    /// Check if lock is stale
-    fn is_lock_stale(&self, lock_data: &Value) -> bool {
-        let timestamp = lock_data.get("timestamp").and_then(|t| t.as_u64());
-
-        // Check if lock is too old
-        if let Some(ts) = timestamp {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .map(|d| d.as_millis() as u64)
-                .unwrap_or(0);
-            if now - ts > LOCK_STALE_MS {
-                return true;
-            }
-        } else {
-            return true;
-        }
-
-        // Check if the process that holds the lock is still running (Unix only)
-        #[cfg(unix)]
-        {
-            let pid = lock_data.get("pid").and_then(|p| p.as_u64());
-            if let Some(p) = pid {
-                // Check if process exists using kill(pid, 0)
-                let result = unsafe { libc::kill(p as i32, 0) };
-                if result != 0 {
-                    return true; // Process doesn't exist
-                }
-            }
-        }
-
-        false
-    }
-}
-
-impl Drop for LockManager {
-    fn drop(&mut self) {
-        self.release();
-    }
-}
-
 /// Check if clink is installed
 pub fn is_clink_installed() -> bool {
     Command::new("clink")
@@ -322,6 +289,10 @@ pub struct ExecutionStore {
     lock_file_path: PathBuf,
     use_links: bool,
     verbose: bool,
+    /// Location inside a shared multi-user store
+    shared: Option<shared::SharedLocation>,
+    /// Read the records of every user of a shared store
+    all_users: bool,
 }
 
 /// Options for creating an ExecutionStore
@@ -356,6 +327,8 @@ impl ExecutionStore {
             lock_file_path,
             use_links,
             verbose: options.verbose,
+            shared: None,
+            all_users: false,
         }
     }
 
@@ -366,50 +339,43 @@ impl ExecutionStore {
         }
     }
 
-    /// Read all execution records from lino file
-    pub fn read_lino_records(&self) -> Vec<ExecutionRecord> {
-        if !self.lino_db_path.exists() {
-            return Vec::new();
+    /// Take the exclusive store lock for a write
+    fn write_lock(&self) -> Result<LockManager, String> {
+        let mut lock = LockManager::new(self.lock_file_path.clone()).with_verbose(self.verbose);
+        if !lock.acquire(LOCK_TIMEOUT_MS) {
+            return Err("Failed to acquire lock for database write".to_string());
         }
+        Ok(lock)
+    }
 
-        match fs::read_to_string(&self.lino_db_path) {
-            Ok(content) => {
-                if content.trim().is_empty() {
-                    return Vec::new();
-                }
-
-                match decode(&content) {
-                    Ok(data) => {
-                        if let LinoValue::Array(arr) = data {
-                            arr.iter()
-                                .map(lino_value_to_json)
-                                .filter_map(|value| ExecutionRecord::from_json(&value))
-                                .collect()
-                        } else {
-                            Vec::new()
-                        }
-                    }
-                    Err(e) => {
-                        self.log(&format!("Error decoding lino records: {}", e));
-                        Vec::new()
-                    }
-                }
-            }
-            Err(e) => {
-                self.log(&format!("Error reading lino records: {}", e));
-                Vec::new()
-            }
+    /// Take a shared store lock for a read. Readers that time out still read:
+    /// writes replace the file atomically, so they never see a partial write.
+    fn read_lock(&self) -> Option<LockManager> {
+        let mut lock = LockManager::new(self.lock_file_path.clone()).with_verbose(self.verbose);
+        if lock.acquire_shared(LOCK_TIMEOUT_MS) {
+            Some(lock)
+        } else {
+            self.log("Reading without a shared lock");
+            None
         }
     }
 
-    /// Write execution records to lino file
+    /// Write execution records to lino file. The content goes to a temporary
+    /// file first and is renamed into place, so readers never see a partial write.
     fn write_lino_records(&self, records: &[ExecutionRecord]) -> std::io::Result<()> {
         let data: Vec<LinoValue> = records
             .iter()
             .map(|record| json_to_lino_value(&record.to_json()))
             .collect();
         let content = encode(&LinoValue::Array(data));
-        fs::write(&self.lino_db_path, content)?;
+        let temp_path =
+            self.app_folder
+                .join(format!("{}.{}.tmp", LINO_DB_FILE, std::process::id()));
+        fs::write(&temp_path, content)?;
+        if let Err(e) = fs::rename(&temp_path, &self.lino_db_path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
         self.log(&format!("Wrote {} records to lino file", records.len()));
         Ok(())
     }
@@ -501,21 +467,20 @@ impl ExecutionStore {
 
     /// Save an execution record (creates or updates)
     pub fn save(&self, record: &ExecutionRecord) -> Result<(), String> {
-        let mut lock = LockManager::new(self.lock_file_path.clone());
-
-        if !lock.acquire(LOCK_TIMEOUT_MS) {
-            return Err("Failed to acquire lock for database write".to_string());
-        }
+        let record = &self.with_owner(record);
+        let _lock = self.write_lock()?;
 
         // Read existing records
-        let mut records = self.read_lino_records();
+        let mut records = self.read_records_for_write()?;
 
         // Find existing record index
         let existing_index = records.iter().position(|r| r.uuid == record.uuid);
 
         if let Some(idx) = existing_index {
-            // Update existing record
-            records[idx] = record.clone();
+            // Update existing record, keeping annotations added by other processes
+            let mut updated = record.clone();
+            updated.keep_annotations_from(&records[idx]);
+            records[idx] = updated;
         } else {
             // Add new record
             records.push(record.clone());
@@ -533,27 +498,84 @@ impl ExecutionStore {
         Ok(())
     }
 
+    /// Merge records into the store, de-duplicated by UUID. An existing record
+    /// is replaced only when the incoming copy has a later `end_time`; a
+    /// finished copy always wins over one that is still executing.
+    pub fn merge(&self, incoming: &[ExecutionRecord]) -> Result<MergeResult, String> {
+        let _lock = self.write_lock()?;
+
+        let mut records = self.read_records_for_write()?;
+        let mut result = MergeResult::default();
+        let mut changed: Vec<ExecutionRecord> = Vec::new();
+
+        for record in incoming {
+            match records.iter().position(|r| r.uuid == record.uuid) {
+                Some(idx) if ends_later(record, &records[idx]) => {
+                    let mut updated = record.clone();
+                    updated.keep_annotations_from(&records[idx]);
+                    records[idx] = updated.clone();
+                    result.updated.push(record.uuid.clone());
+                    changed.push(updated);
+                }
+                Some(_) => result.unchanged.push(record.uuid.clone()),
+                None => {
+                    records.push(record.clone());
+                    result.added.push(record.uuid.clone());
+                    changed.push(record.clone());
+                }
+            }
+        }
+
+        if !changed.is_empty() {
+            self.write_lino_records(&records)
+                .map_err(|e| e.to_string())?;
+            if self.use_links {
+                for record in &changed {
+                    self.write_links_record(record);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Update a record by UUID or session name while holding the store lock.
+    /// Returns the updated record, or `None` when no record matches.
+    pub fn update<F>(&self, identifier: &str, apply: F) -> Result<Option<ExecutionRecord>, String>
+    where
+        F: FnOnce(&mut ExecutionRecord),
+    {
+        let _lock = self.write_lock()?;
+
+        let mut records = self.read_records_for_write()?;
+        let Some(idx) = find_record_index(&records, identifier) else {
+            return Ok(None);
+        };
+        apply(&mut records[idx]);
+        let updated = records[idx].clone();
+
+        self.write_lino_records(&records)
+            .map_err(|e| e.to_string())?;
+        if self.use_links {
+            self.write_links_record(&updated);
+        }
+
+        Ok(Some(updated))
+    }
+
     /// Get an execution record by UUID or session name (falls back to options.sessionName)
     pub fn get(&self, identifier: &str) -> Option<ExecutionRecord> {
-        let records = self.read_lino_records();
-        records
-            .iter()
-            .find(|r| r.uuid == identifier)
-            .cloned()
-            .or_else(|| {
-                records.into_iter().find(|r| {
-                    r.options.get("sessionName").and_then(|v| v.as_str()) == Some(identifier)
-                })
-            })
+        let mut records = self.read_visible_records();
+        find_record_index(&records, identifier).map(|idx| records.swap_remove(idx))
     }
 
     /// Get all execution records
     pub fn get_all(&self) -> Vec<ExecutionRecord> {
-        self.read_lino_records()
+        self.read_visible_records()
     }
     /// Get records filtered by status
     pub fn get_by_status(&self, status: ExecutionStatus) -> Vec<ExecutionRecord> {
-        self.read_lino_records()
+        self.read_visible_records()
             .into_iter()
             .filter(|r| r.status == status)
             .collect()
@@ -564,7 +586,7 @@ impl ExecutionStore {
     }
     /// Get recently executed commands (sorted by start_time descending)
     pub fn get_recent(&self, limit: usize) -> Vec<ExecutionRecord> {
-        let mut records = self.read_lino_records();
+        let mut records = self.read_visible_records();
         records.sort_by(|a, b| b.start_time.cmp(&a.start_time));
         records.truncate(limit);
         records
@@ -623,13 +645,21 @@ impl ExecutionStore {
         result.records = stale_records.clone();
 
         if !dry_run && !stale_records.is_empty() {
-            let mut lock = LockManager::new(self.lock_file_path.clone());
-            if !lock.acquire(LOCK_TIMEOUT_MS) {
-                result.errors.push("Failed to acquire lock".to_string());
-                return result;
-            }
+            let _lock = match self.write_lock() {
+                Ok(lock) => lock,
+                Err(e) => {
+                    result.errors.push(e);
+                    return result;
+                }
+            };
 
-            let mut current = self.read_lino_records();
+            let mut current = match self.read_records_for_write() {
+                Ok(records) => records,
+                Err(e) => {
+                    result.errors.push(format!("Cleanup error: {}", e));
+                    return result;
+                }
+            };
             for stale in &stale_records {
                 if let Some(i) = current.iter().position(|r| r.uuid == stale.uuid) {
                     current[i].status = ExecutionStatus::Executed;
@@ -653,13 +683,9 @@ impl ExecutionStore {
 
     /// Delete an execution record
     pub fn delete(&self, uuid: &str) -> Result<bool, String> {
-        let mut lock = LockManager::new(self.lock_file_path.clone());
+        let _lock = self.write_lock()?;
 
-        if !lock.acquire(LOCK_TIMEOUT_MS) {
-            return Err("Failed to acquire lock for database write".to_string());
-        }
-
-        let records = self.read_lino_records();
+        let records = self.read_records_for_write()?;
         let filtered: Vec<_> = records.iter().filter(|r| r.uuid != uuid).cloned().collect();
 
         if filtered.len() == records.len() {
@@ -679,11 +705,7 @@ impl ExecutionStore {
 
     /// Delete all records
     pub fn clear(&self) -> Result<(), String> {
-        let mut lock = LockManager::new(self.lock_file_path.clone());
-
-        if !lock.acquire(LOCK_TIMEOUT_MS) {
-            return Err("Failed to acquire lock for database write".to_string());
-        }
+        let _lock = self.write_lock()?;
 
         self.write_lino_records(&[]).map_err(|e| e.to_string())?;
 
@@ -804,6 +826,41 @@ impl Default for ExecutionStore {
     }
 }
 
+/// Index of the record with this UUID, or else with this session name
+fn find_record_index(records: &[ExecutionRecord], identifier: &str) -> Option<usize> {
+    records
+        .iter()
+        .position(|r| r.uuid == identifier)
+        .or_else(|| {
+            records.iter().position(|r| {
+                r.options.get("sessionName").and_then(|v| v.as_str()) == Some(identifier)
+            })
+        })
+}
+
+/// Whether `incoming` finished later than `existing` (by `end_time`)
+pub(crate) fn ends_later(incoming: &ExecutionRecord, existing: &ExecutionRecord) -> bool {
+    let parse = |record: &ExecutionRecord| {
+        record
+            .end_time
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+    };
+    match (parse(incoming), parse(existing)) {
+        (Some(incoming_end), Some(existing_end)) => incoming_end > existing_end,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// Result of merging records into the store: UUIDs per outcome
+#[derive(Debug, Default)]
+pub struct MergeResult {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+}
+
 /// Result of consistency verification
 #[derive(Debug)]
 pub struct ConsistencyResult {
@@ -842,146 +899,5 @@ pub struct ExecutionStats {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_store() -> (ExecutionStore, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
-        let store = ExecutionStore::with_options(ExecutionStoreOptions {
-            app_folder: Some(temp_dir.path().to_path_buf()),
-            use_links: Some(false), // Disable links for unit tests
-            verbose: false,
-        });
-        (store, temp_dir)
-    }
-
-    #[test]
-    fn test_execution_record_new() {
-        let record = ExecutionRecord::new("echo hello");
-        assert!(!record.uuid.is_empty());
-        assert_eq!(record.command, "echo hello");
-        assert_eq!(record.status, ExecutionStatus::Executing);
-        assert!(record.exit_code.is_none());
-        assert!(record.end_time.is_none());
-    }
-
-    #[test]
-    fn test_execution_record_complete() {
-        let mut record = ExecutionRecord::new("echo hello");
-        assert_eq!(record.status, ExecutionStatus::Executing);
-        assert!(record.exit_code.is_none());
-
-        record.complete(0);
-
-        assert_eq!(record.status, ExecutionStatus::Executed);
-        assert_eq!(record.exit_code, Some(0));
-        assert!(record.end_time.is_some());
-    }
-
-    #[test]
-    fn test_execution_record_json_roundtrip() {
-        let mut record = ExecutionRecord::new("echo hello");
-        record.pid = Some(12345);
-        record.log_path = "/tmp/test.log".to_string();
-
-        let json = record.to_json();
-        let restored = ExecutionRecord::from_json(&json).unwrap();
-
-        assert_eq!(restored.uuid, record.uuid);
-        assert_eq!(restored.command, "echo hello");
-        assert_eq!(restored.pid, Some(12345));
-    }
-
-    #[test]
-    fn test_store_save_and_get() {
-        let (store, _temp) = create_test_store();
-        let mut record = ExecutionRecord::new("echo hello");
-        record.pid = Some(12345);
-        store.save(&record).unwrap();
-        let retrieved = store.get(&record.uuid).unwrap();
-        assert_eq!(
-            (retrieved.uuid, retrieved.command.as_str(), retrieved.pid),
-            (record.uuid, "echo hello", Some(12345))
-        );
-    }
-
-    #[test]
-    fn test_store_update() {
-        let (store, _temp) = create_test_store();
-        let mut record = ExecutionRecord::new("echo hello");
-        store.save(&record).unwrap();
-        record.complete(0);
-        store.save(&record).unwrap();
-        let r = store.get(&record.uuid).unwrap();
-        assert_eq!(
-            (r.status, r.exit_code),
-            (ExecutionStatus::Executed, Some(0))
-        );
-    }
-
-    #[test]
-    fn test_store_get_all() {
-        let (store, _temp) = create_test_store();
-        for i in 1..=3 {
-            store
-                .save(&ExecutionRecord::new(&format!("e{}", i)))
-                .unwrap();
-        }
-        assert_eq!(store.get_all().len(), 3);
-    }
-
-    #[test]
-    fn test_store_get_by_status() {
-        let (store, _temp) = create_test_store();
-        store.save(&ExecutionRecord::new("1")).unwrap();
-        store.save(&ExecutionRecord::new("2")).unwrap();
-        let mut done = ExecutionRecord::new("3");
-        done.complete(0);
-        store.save(&done).unwrap();
-        assert_eq!(
-            (
-                store.get_executing().len(),
-                store.get_by_status(ExecutionStatus::Executed).len()
-            ),
-            (2, 1)
-        );
-    }
-
-    #[test]
-    fn test_store_delete() {
-        let (store, _temp) = create_test_store();
-        let record = ExecutionRecord::new("echo hello");
-        store.save(&record).unwrap();
-        assert!(store.get(&record.uuid).is_some() && store.delete(&record.uuid).unwrap());
-        assert!(store.get(&record.uuid).is_none());
-    }
-
-    #[test]
-    fn test_store_clear() {
-        let (store, _temp) = create_test_store();
-        store.save(&ExecutionRecord::new("1")).unwrap();
-        store.save(&ExecutionRecord::new("2")).unwrap();
-        assert_eq!(store.get_all().len(), 2);
-        store.clear().unwrap();
-        assert_eq!(store.get_all().len(), 0);
-    }
-
-    #[test]
-    fn test_store_get_stats() {
-        let (store, _temp) = create_test_store();
-        store.save(&ExecutionRecord::new("1")).unwrap();
-        let mut ok = ExecutionRecord::new("2");
-        ok.complete(0);
-        store.save(&ok).unwrap();
-        let mut fail = ExecutionRecord::new("3");
-        fail.complete(1);
-        store.save(&fail).unwrap();
-        let s = store.get_stats();
-        assert_eq!(
-            (s.total, s.executing, s.executed, s.successful, s.failed),
-            (3, 1, 2, 1, 1)
-        );
-    }
-    // Note: Additional tests in tests/cleanup.rs
-}
+#[path = "execution_store_cases.rs"]
+mod tests;
@@ -0,0 +1,291 @@
+//! Loading the lino file: schema migrations, quarantine and repair
+//!
+//! Entries are decoded one at a time so a single corrupt entry does not hide
+//! the rest of the history. Entries that cannot be decoded (or are not valid
+//! records) are appended to `executions.lino.quarantine` before the store
+//! rewrites the file, instead of being dropped.
+
+use super::{ExecutionRecord, ExecutionStore, LINO_DB_FILE};
+use crate::execution_repair::{
+    reconcile_stores, recover_from_log_footers, RecoveredExecution, RepairReport,
+};
+use crate::execution_schema::{record_schema_version, CURRENT_SCHEMA_VERSION};
+use crate::lino_value_json::{json_to_lino_value, lino_value_to_json};
+use lino_objects_codec::{decode, encode, LinoValue};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Quarantine file name for undecodable entries
+const QUARANTINE_FILE: &str = "executions.lino.quarantine";
+
+/// Records decoded from the lino file
+#[derive(Debug, Default)]
+pub(super) struct LoadedRecords {
+    pub records: Vec<ExecutionRecord>,
+    /// Oldest schema version found when any record needed migrating
+    pub migrated_from: Option<u32>,
+    /// Raw text of entries that could not be decoded into records
+    pub rejected: Vec<String>,
+}
+
+/// Decode the entries of a lino array one by one. Returns the decoded values
+/// and the raw text of entries that failed to decode.
+pub fn decode_lino_entries(content: &str) -> (Vec<Value>, Vec<String>) {
+    let content = content.trim();
+    if content.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    if let Ok(LinoValue::Array(arr)) = decode(content) {
+        return (arr.iter().map(lino_value_to_json).collect(), Vec::new());
+    }
+
+    let Some(body) = content.strip_prefix("(array") else {
+        return (Vec::new(), vec![content.to_string()]);
+    };
+    let (mut values, mut rejected) = (Vec::new(), Vec::new());
+    for entry in split_top_level_groups(body) {
+        match decode(&format!("(array {})", entry)) {
+            Ok(LinoValue::Array(arr)) if arr.len() == 1 => {
+                values.push(lino_value_to_json(&arr[0]));
+            }
+            _ => rejected.push(entry.to_string()),
+        }
+    }
+    (values, rejected)
+}
+
+/// Split the body of `(array ...)` into its top-level parenthesized groups.
+/// Anything unbalanced at the end (e.g. a truncated write) is returned as a
+/// final group so it can be quarantined. Encoded strings are base64, so
+/// parentheses only ever appear as structure.
+fn split_top_level_groups(body: &str) -> Vec<&str> {
+    let mut groups = Vec::new();
+    let (mut depth, mut start) = (0usize, None);
+    for (index, ch) in body.char_indices() {
+        match ch {
+            '(' => {
+                if depth == 0 {
+                    start = Some(index);
+                }
+                depth += 1;
+            }
+            ')' if depth == 0 => {} // closing paren of the array itself
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    groups.extend(start.take().map(|s| &body[s..=index]));
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        groups.push(body[s..].trim_end());
+    }
+    groups
+}
+
+impl ExecutionStore {
+    /// Read all execution records from lino file under a shared lock.
+    /// Records written with an older schema version are migrated in memory
+    /// only; the next write (or [`migrate`](Self::migrate)) persists them.
+    pub fn read_lino_records(&self) -> Vec<ExecutionRecord> {
+        let _lock = self.read_lock();
+        self.load_lino_records().records
+    }
+
+    /// Decode the lino file, migrating older records in memory
+    pub(super) fn load_lino_records(&self) -> LoadedRecords {
+        if !self.lino_db_path.exists() {
+            return LoadedRecords::default();
+        }
+
+        let content = match fs::read_to_string(&self.lino_db_path) {
+            Ok(content) => content,
+            Err(e) => {
+                self.log(&format!("Error reading lino records: {}", e));
+                return LoadedRecords::default();
+            }
+        };
+
+        let (values, mut rejected) = decode_lino_entries(&content);
+        if !rejected.is_empty() {
+            self.log(&format!(
+                "Could not decode {} lino entries; they will be quarantined",
+                rejected.len()
+            ));
+        }
+        let migrated_from = values
+            .iter()
+            .map(record_schema_version)
+            .filter(|version| *version < CURRENT_SCHEMA_VERSION)
+            .min();
+        let mut records = Vec::with_capacity(values.len());
+        for value in &values {
+            match ExecutionRecord::from_json(value) {
+                Some(record) => records.push(record),
+                None => rejected.push(encode(&LinoValue::Array(vec![json_to_lino_value(value)]))),
+            }
+        }
+        LoadedRecords {
+            records,
+            migrated_from,
+            rejected,
+        }
+    }
+
+    /// Read records for a write made while holding the lock. The write
+    /// persists any migration and drops rejected entries, so the old file is
+    /// backed up and rejected entries are quarantined first.
+    pub(super) fn read_records_for_write(&self) -> Result<Vec<ExecutionRecord>, String> {
+        let loaded = self.load_lino_records();
+        self.quarantine_entries(&loaded.rejected)?;
+        if let Some(version) = loaded.migrated_from {
+            self.backup_before_migration(version)?;
+        }
+        Ok(loaded.records)
+    }
+
+    /// Path of the backup of the lino file labelled `label`
+    fn lino_backup_path(&self, label: &str) -> PathBuf {
+        self.app_folder
+            .join(format!("{}.{}.bak", LINO_DB_FILE, label))
+    }
+
+    /// Back up the lino file before migrating records from schema `version`.
+    /// There is one backup per version: it holds the store as it was before
+    /// the first migration, and records older releases write later do not
+    /// replace it.
+    fn backup_before_migration(&self, version: u32) -> Result<(), String> {
+        let label = format!("v{}", version);
+        if !self.lino_backup_path(&label).exists() {
+            self.backup_lino_file(&label)?;
+        }
+        Ok(())
+    }
+
+    /// Copy the lino file to `executions.lino.<label>.bak` before rewriting it
+    fn backup_lino_file(&self, label: &str) -> Result<PathBuf, String> {
+        let backup = self.lino_backup_path(label);
+        fs::copy(&self.lino_db_path, &backup)
+            .map_err(|e| format!("Failed to back up {}: {}", self.lino_db_path.display(), e))?;
+        self.log(&format!("Backed up lino file to {}", backup.display()));
+        Ok(backup)
+    }
+
+    /// Path of the file collecting undecodable lino entries
+    pub fn quarantine_path(&self) -> PathBuf {
+        self.app_folder.join(QUARANTINE_FILE)
+    }
+
+    /// Append undecodable entries to the quarantine file, one per line
+    fn quarantine_entries(&self, entries: &[String]) -> Result<(), String> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let path = self.quarantine_path();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        for entry in entries {
+            writeln!(file, "{}", entry.replace('\n', " "))
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+        self.log(&format!("Quarantined {} lino entries", entries.len()));
+        Ok(())
+    }
+
+    /// Write records with an older schema version back in the current one,
+    /// backing up the old file first. Returns the oldest version migrated,
+    /// or `None` when every record was already current.
+    pub fn migrate(&self) -> Result<Option<u32>, String> {
+        let _lock = self.write_lock()?;
+
+        let loaded = self.load_lino_records();
+        let Some(version) = loaded.migrated_from else {
+            return Ok(None);
+        };
+        self.quarantine_entries(&loaded.rejected)?;
+        self.backup_before_migration(version)?;
+        self.write_lino_records(&loaded.records)
+            .map_err(|e| e.to_string())?;
+        Ok(Some(version))
+    }
+
+    /// Reconcile the lino and links stores, recover executions whose log
+    /// footer shows they finished, and quarantine undecodable entries.
+    /// With `dry_run` nothing is written; the report says what would change.
+    pub fn repair(&self, dry_run: bool) -> Result<RepairReport, String> {
+        let _lock = self.write_lock()?;
+
+        let loaded = self.load_lino_records();
+        let mut report = RepairReport {
+            dry_run,
+            migrated_from: loaded.migrated_from,
+            quarantined: loaded.rejected.len(),
+            ..Default::default()
+        };
+
+        let links = if !self.use_links {
+            report
+                .warnings
+                .push("clink not installed - links database not available".to_string());
+            None
+        } else {
+            match self.read_links_records() {
+                Ok(records) => Some(records),
+                Err(e) => {
+                    report
+                        .warnings
+                        .push(format!("Failed to query links database: {}", e));
+                    None
+                }
+            }
+        };
+
+        let mut plan = reconcile_stores(loaded.records, links.as_deref().unwrap_or_default());
+        report.recovered = recover_from_log_footers(&mut plan.records);
+        report.added_from_links = plan.added;
+        report.updated_from_links = plan.updated;
+        if links.is_some() {
+            for RecoveredExecution { uuid, .. } in &report.recovered {
+                if !plan.to_links.contains(uuid) {
+                    plan.to_links.push(uuid.clone());
+                }
+            }
+            report.written_to_links = plan.to_links;
+        }
+        if report.quarantined > 0 {
+            report.quarantine_path = Some(self.quarantine_path().to_string_lossy().to_string());
+        }
+
+        if dry_run || !report.has_changes() {
+            return Ok(report);
+        }
+
+        self.quarantine_entries(&loaded.rejected)?;
+        if self.lino_db_path.exists() {
+            self.backup_lino_file("repair")?;
+        }
+        self.write_lino_records(&plan.records)
+            .map_err(|e| e.to_string())?;
+        for record in &plan.records {
+            if report.written_to_links.contains(&record.uuid) && !self.write_links_record(record) {
+                report
+                    .warnings
+                    .push(format!("Failed to write {} to links database", record.uuid));
+            }
+        }
+        Ok(report)
+    }
+
+    /// Read records back from the links database
+    fn read_links_records(&self) -> Result<Vec<ExecutionRecord>, String> {
+        let output = self.exec_clink("((($id: $type $value)) (($id: $type $value)))")?;
+        Ok(crate::execution_repair::parse_links_records(&output))
+    }
+}
@@ -0,0 +1,287 @@
+//! Cross-process locks guarding the execution store
+//!
+//! Writers take an exclusive and readers a shared OS advisory lock (`flock`
+//! on Unix, `LockFileEx` on Windows) on `executions.flock`. The OS releases
+//! the lock when its holder exits, so a crash leaves nothing stale behind and
+//! a slow writer is never mistaken for a dead one.
+//!
+//! Writers additionally hold the legacy `executions.lock` file, which the JS
+//! implementation polls for, so both implementations keep excluding each other.
+
+use crate::local_hostname;
+use serde_json::{json, Value};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Consider a legacy lock file stale after this many milliseconds
+const LOCK_STALE_MS: u64 = 60000;
+
+/// How often a waiting process retries the advisory lock
+const LOCK_POLL_MS: u64 = 10;
+
+/// Shared locks admit other readers; exclusive locks admit nobody else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    Shared,
+    Exclusive,
+}
+
+impl std::fmt::Display for LockMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockMode::Shared => write!(f, "shared"),
+            LockMode::Exclusive => write!(f, "exclusive"),
+        }
+    }
+}
+
+/// Lock manager for the execution store
+pub struct LockManager {
+    lock_file_path: PathBuf,
+    lock_acquired: bool,
+    advisory: Option<File>,
+    verbose: bool,
+}
+
+impl LockManager {
+    /// Create a new lock manager
+    pub fn new(lock_file_path: PathBuf) -> Self {
+        LockManager {
+            lock_file_path,
+            lock_acquired: false,
+            advisory: None,
+            verbose: false,
+        }
+    }
+
+    /// Log lock waits and timeouts
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Path of the file carrying the OS advisory lock
+    pub fn advisory_lock_path(&self) -> PathBuf {
+        self.lock_file_path.with_extension("flock")
+    }
+
+    /// Acquire an exclusive lock (for writers)
+    pub fn acquire(&mut self, timeout_ms: u64) -> bool {
+        self.acquire_mode(LockMode::Exclusive, timeout_ms)
+    }
+
+    /// Acquire a shared lock (for readers)
+    pub fn acquire_shared(&mut self, timeout_ms: u64) -> bool {
+        self.acquire_mode(LockMode::Shared, timeout_ms)
+    }
+
+    fn acquire_mode(&mut self, mode: LockMode, timeout_ms: u64) -> bool {
+        let start = Instant::now();
+        let timeout = Duration::from_millis(timeout_ms);
+        let path = self.advisory_lock_path();
+        // Readers of a store they cannot write to (another user's folder in
+        // a shared store) lock a read-only handle instead
+        let file = match OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .or_else(|e| match mode {
+                LockMode::Shared => File::open(&path),
+                LockMode::Exclusive => Err(e),
+            }) {
+            Ok(file) => file,
+            Err(e) => {
+                self.log(&format!("Cannot open {}: {}", path.display(), e));
+                return false;
+            }
+        };
+
+        let mut waited = false;
+        loop {
+            let attempt = match mode {
+                LockMode::Shared => file.try_lock_shared(),
+                LockMode::Exclusive => file.try_lock(),
+            };
+            match attempt {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {
+                    if !waited {
+                        waited = true;
+                        self.log(&format!(
+                            "Waiting for {} lock on {}{}",
+                            mode,
+                            path.display(),
+                            self.describe_last_writer()
+                        ));
+                    }
+                    if start.elapsed() >= timeout {
+                        self.log(&format!(
+                            "Timed out after {} ms waiting for {} lock",
+                            timeout_ms, mode
+                        ));
+                        return false;
+                    }
+                    thread::sleep(Duration::from_millis(LOCK_POLL_MS));
+                }
+                Err(TryLockError::Error(e)) => {
+                    self.log(&format!("Cannot lock {}: {}", path.display(), e));
+                    return false;
+                }
+            }
+        }
+
+        if mode == LockMode::Exclusive {
+            record_holder(&file);
+            let remaining = timeout.saturating_sub(start.elapsed());
+            if !self.acquire_legacy(remaining) {
+                self.log("Timed out waiting for the legacy lock file");
+                return false;
+            }
+        }
+        if waited {
+            self.log(&format!(
+                "Acquired {} lock after {} ms",
+                mode,
+                start.elapsed().as_millis()
+            ));
+        }
+        self.advisory = Some(file);
+        true
+    }
+
+    /// Hold the legacy lock file that the JS implementation polls for
+    fn acquire_legacy(&mut self, timeout: Duration) -> bool {
+        let start = Instant::now();
+
+        loop {
+            // Check if existing lock is stale
+            if self.lock_file_path.exists() {
+                if let Some(lock_data) = self.read_lock_file() {
+                    if self.is_lock_stale(&lock_data) {
+                        let _ = fs::remove_file(&self.lock_file_path);
+                    }
+                }
+            }
+
+            // Try to create lock file exclusively
+            if let Ok(mut file) = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&self.lock_file_path)
+            {
+                let _ = file.write_all(holder_info().to_string().as_bytes());
+                self.lock_acquired = true;
+                return true;
+            }
+
+            if start.elapsed() >= timeout {
+                return false;
+            }
+            // Lock file exists, wait and retry
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Release the lock
+    pub fn release(&mut self) {
+        if self.lock_acquired {
+            let _ = fs::remove_file(&self.lock_file_path);
+            self.lock_acquired = false;
+        }
+        if let Some(file) = self.advisory.take() {
+            let _ = file.unlock();
+        }
+    }
+
+    /// Describe the last exclusive holder recorded in the advisory lock file
+    fn describe_last_writer(&self) -> String {
+        let Some(info) = fs::read_to_string(self.advisory_lock_path())
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        else {
+            return String::new();
+        };
+        format!(
+            " (last writer: pid {} on {})",
+            info.get("pid").and_then(Value::as_u64).unwrap_or_default(),
+            info.get("hostname").and_then(Value::as_str).unwrap_or("?")
+        )
+    }
+
+    fn log(&self, message: &str) {
+        if self.verbose {
+            println!("[LockManager] {}", message);
+        }
+    }
+
+    /// Read lock file data
+    fn read_lock_file(&self) -> Option<Value> {
+        let content = fs::read_to_string(&self.lock_file_path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Check if lock is stale
+    fn is_lock_stale(&self, lock_data: &Value) -> bool {
+        let timestamp = lock_data.get("timestamp").and_then(|t| t.as_u64());
+
+        // Check if lock is too old
+        if let Some(ts) = timestamp {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            if now.saturating_sub(ts) > LOCK_STALE_MS {
+                return true;
+            }
+        } else {
+            return true;
+        }
+
+        // Check if the process that holds the lock is still running (Unix only)
+        #[cfg(unix)]
+        {
+            let pid = lock_data.get("pid").and_then(|p| p.as_u64());
+            if let Some(p) = pid {
+                // Check if process exists using kill(pid, 0)
+                let result = unsafe { libc::kill(p as i32, 0) };
+                if result != 0 {
+                    return true; // Process doesn't exist
+                }
+            }
+        }
+
+        false
+    }
+}
+
+impl Drop for LockManager {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Identity of the current lock holder
+fn holder_info() -> Value {
+    json!({
+        "pid": std::process::id(),
+        "timestamp": std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0),
+        "hostname": local_hostname::get()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Record the exclusive holder in the advisory lock file for diagnostics
+fn record_holder(mut file: &File) {
+    if file.set_len(0).is_ok() {
+        let _ = file.write_all(holder_info().to_string().as_bytes());
+    }
+}
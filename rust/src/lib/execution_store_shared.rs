@@ -0,0 +1,167 @@
+//! System-wide execution store shared by the users of one machine
+//!
+//! When `START_SHARED_STORE` points at a directory (e.g.
+//! `/var/lib/start-command`), every user records executions in their own
+//! folder under `<root>/users/<user>`. Only the owner can write there; the
+//! folder is group-readable, so members of the root folder's group can read
+//! each other's history with `--all-users`. The `users` folder gets the
+//! sticky bit so users cannot remove each other's folders. Records written
+//! to a shared store carry their `owner`, and `--stop`/`--terminate` refuse
+//! to control executions owned by someone else.
+
+use super::{ExecutionRecord, ExecutionStore, ExecutionStoreOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting the shared store root
+pub const SHARED_STORE_ENV: &str = "START_SHARED_STORE";
+
+/// Folder under the shared root holding one folder per user
+const SHARED_USERS_DIR: &str = "users";
+
+/// Where a store lives inside a shared store
+#[derive(Debug, Clone)]
+pub(super) struct SharedLocation {
+    root: PathBuf,
+    user: String,
+}
+
+impl ExecutionStore {
+    /// Open `user`'s store inside the shared store at `root`, creating the
+    /// user's folder (owner read/write, group read) if needed
+    pub fn shared(root: &Path, user: &str, verbose: bool) -> Self {
+        let users_dir = root.join(SHARED_USERS_DIR);
+        if !users_dir.exists() {
+            create_dir_with_mode(&users_dir, shared_users_dir_mode(root));
+        }
+        let app_folder = users_dir.join(user);
+        if !app_folder.exists() {
+            create_dir_with_mode(&app_folder, 0o750);
+        }
+
+        let mut store = Self::with_options(ExecutionStoreOptions {
+            app_folder: Some(app_folder),
+            verbose,
+            ..Default::default()
+        });
+        store.shared = Some(SharedLocation {
+            root: root.to_path_buf(),
+            user: user.to_string(),
+        });
+        store
+    }
+
+    /// Also read the records of every other user of the shared store
+    /// (a no-op for a personal store). Writes still go to the own folder.
+    pub fn with_all_users(mut self, all_users: bool) -> Self {
+        self.all_users = all_users;
+        self
+    }
+
+    /// Root of the shared store this store belongs to, if any
+    pub fn shared_root(&self) -> Option<&Path> {
+        self.shared.as_ref().map(|shared| shared.root.as_path())
+    }
+
+    /// User owning this store's folder in a shared store
+    pub fn owner(&self) -> Option<&str> {
+        self.shared.as_ref().map(|shared| shared.user.as_str())
+    }
+
+    /// Copy of `record` stamped with this store's owner, if not already set
+    pub(super) fn with_owner(&self, record: &ExecutionRecord) -> ExecutionRecord {
+        let mut record = record.clone();
+        if record.owner.is_none() {
+            record.owner = self.owner().map(str::to_string);
+        }
+        record
+    }
+
+    /// Records visible to reads: the own records, plus those of every other
+    /// user whose folder is readable when reading across all users
+    pub(super) fn read_visible_records(&self) -> Vec<ExecutionRecord> {
+        let mut records = self.read_lino_records();
+        let Some(shared) = self.shared.as_ref().filter(|_| self.all_users) else {
+            return records;
+        };
+        for user in shared_store_users(&shared.root) {
+            if user == shared.user {
+                continue;
+            }
+            let other = ExecutionStore::with_options(ExecutionStoreOptions {
+                app_folder: Some(shared.root.join(SHARED_USERS_DIR).join(&user)),
+                use_links: Some(false),
+                verbose: self.verbose,
+            });
+            // Other users' stores are read-only here: no migration, no quarantine
+            let loaded = {
+                let _lock = other.read_lock();
+                other.load_lino_records()
+            };
+            records.extend(loaded.records.into_iter().map(|mut record| {
+                record.owner.get_or_insert_with(|| user.clone());
+                record
+            }));
+        }
+        records
+    }
+
+    /// Check that the current user may control `record`: in a shared store
+    /// only the owner of an execution may stop or terminate it
+    pub fn check_ownership(&self, record: &ExecutionRecord) -> Result<(), String> {
+        match (self.owner(), record.owner.as_deref()) {
+            (Some(user), Some(owner)) if user != owner => Err(format!(
+                "Execution {} is owned by {}; only its owner can control it",
+                record.uuid, owner
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Users with a folder in the shared store at `root`, sorted by name
+pub fn shared_store_users(root: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(root.join(SHARED_USERS_DIR)) else {
+        return Vec::new();
+    };
+    let mut users: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    users.sort();
+    users
+}
+
+/// The `users` folder keeps the root's permissions plus the sticky bit
+#[cfg(unix)]
+fn shared_users_dir_mode(root: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    let root_mode = fs::metadata(root)
+        .map(|metadata| metadata.permissions().mode() & 0o7777)
+        .unwrap_or(0o775);
+    root_mode | 0o1000
+}
+
+#[cfg(not(unix))]
+fn shared_users_dir_mode(_root: &Path) -> u32 {
+    0
+}
+
+#[cfg(unix)]
+fn create_dir_with_mode(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    if fs::create_dir_all(path).is_ok() {
+        // Set explicitly: the mode given at creation is reduced by the umask.
+        // A setgid bit inherited from the parent keeps the group shared.
+        let inherited = fs::metadata(path)
+            .map(|metadata| metadata.permissions().mode() & 0o2000)
+            .unwrap_or(0);
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(mode | inherited));
+    }
+}
+
+#[cfg(not(unix))]
+fn create_dir_with_mode(path: &Path, _mode: u32) {
+    let _ = fs::create_dir_all(path);
+}
@@ -0,0 +1,439 @@
+//! Store synchronization through a git remote (`--sync <remote>`)
+//!
+//! Execution records are kept in a plain git repository, one JSON file per
+//! execution (`records/<uuid>.json`) on the `start-command-executions`
+//! branch, so any bare repository or file path works as the remote. Files
+//! are merged here rather than by git, which makes a sync conflict-free: the
+//! local checkout (under `<app-folder>/sync/`) is reset to the remote branch,
+//! remote records are merged into the store by UUID, local records that are
+//! new or more complete are written back, and the result is committed on top
+//! and pushed. A push rejected because another machine pushed first is
+//! retried after fetching again. With `--sync-logs`, logs of finished
+//! executions travel along gzip-compressed as `logs/<uuid>.log.gz`.
+
+use crate::args_parser::WrapperOptions;
+use crate::execution_export::{split_invalid_uuids, IMPORTED_LOG_DIR};
+use crate::execution_store::{ends_later, ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::local_hostname;
+use crate::output_blocks::escape_for_links_notation;
+use crate::status_formatter::StatusQueryResult;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Branch holding the synchronized history
+pub const SYNC_BRANCH: &str = "start-command-executions";
+
+/// Folder under the app folder holding one checkout per remote
+const SYNC_DIR: &str = "sync";
+
+/// Folder in the repository holding one JSON file per execution
+const RECORDS_DIR: &str = "records";
+
+/// Folder in the repository holding gzip-compressed logs
+const LOGS_DIR: &str = "logs";
+
+/// How often a rejected push is retried
+const SYNC_ATTEMPTS: usize = 3;
+
+/// Settings for a sync
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Also push and pull compressed logs
+    pub logs: bool,
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub remote: String,
+    pub branch: String,
+    /// Records added to or updated in the local store
+    pub pulled: Vec<String>,
+    /// Records added to or updated in the repository
+    pub pushed: Vec<String>,
+    pub logs_pulled: usize,
+    pub logs_pushed: usize,
+    /// Repository records skipped because their UUID is not a valid UUID
+    pub skipped: Vec<String>,
+    /// Commit pushed to the remote, if anything changed
+    pub commit: Option<String>,
+}
+
+/// Run git in `dir`, returning trimmed stdout
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| format!("Failed to run git (required for --sync): {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Local checkout folder name for a remote
+fn checkout_name(remote: &str) -> String {
+    let name: String = remote
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    name.trim_matches('-').to_string()
+}
+
+/// Remote as given, or made absolute when it is a local path
+fn resolve_remote(remote: &str) -> String {
+    fs::canonicalize(remote)
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|_| remote.to_string())
+}
+
+/// Create or update the local checkout of `remote`
+fn prepare_checkout(checkout: &Path, remote: &str) -> Result<(), String> {
+    if checkout.join(".git").exists() {
+        git(checkout, &["remote", "set-url", "origin", remote])?;
+        return Ok(());
+    }
+    fs::create_dir_all(checkout)
+        .map_err(|e| format!("Failed to create {}: {}", checkout.display(), e))?;
+    git(checkout, &["init", "-q"])?;
+    git(checkout, &["remote", "add", "origin", remote])?;
+    Ok(())
+}
+
+/// Fetch the remote and reset the checkout to its branch, or to an empty
+/// branch when the remote has no history yet
+fn reset_to_remote(checkout: &Path) -> Result<(), String> {
+    git(checkout, &["fetch", "-q", "origin"])?;
+    let remote_ref = format!("refs/remotes/origin/{}", SYNC_BRANCH);
+    if git(checkout, &["rev-parse", "-q", "--verify", &remote_ref]).is_ok() {
+        git(
+            checkout,
+            &["checkout", "-q", "-B", SYNC_BRANCH, &remote_ref],
+        )?;
+        git(checkout, &["reset", "-q", "--hard", &remote_ref])?;
+    } else {
+        let branch_ref = format!("refs/heads/{}", SYNC_BRANCH);
+        git(checkout, &["symbolic-ref", "HEAD", &branch_ref])?;
+        let _ = git(checkout, &["update-ref", "-d", &branch_ref]);
+        git(checkout, &["read-tree", "--empty"])?;
+    }
+    git(checkout, &["clean", "-q", "-f", "-d", "-x"])?;
+    Ok(())
+}
+
+/// Records stored in the checkout
+fn read_repo_records(checkout: &Path) -> Vec<ExecutionRecord> {
+    let Ok(entries) = fs::read_dir(checkout.join(RECORDS_DIR)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .filter_map(|value| ExecutionRecord::from_json(&value))
+        .collect()
+}
+
+/// Copy of `local` to write to the repository, or `None` when the
+/// repository copy is already as complete
+fn repo_copy(local: &ExecutionRecord, stored: Option<&ExecutionRecord>) -> Option<ExecutionRecord> {
+    let Some(stored) = stored else {
+        return Some(local.clone());
+    };
+    let (base, other) = if ends_later(local, stored) {
+        (local, stored)
+    } else {
+        (stored, local)
+    };
+    let mut merged = base.clone();
+    merged.keep_annotations_from(other);
+    (ends_later(local, stored) || gains_annotations(&merged, stored)).then_some(merged)
+}
+
+/// Whether `merged` holds annotations that `original` lacks
+fn gains_annotations(merged: &ExecutionRecord, original: &ExecutionRecord) -> bool {
+    merged.tags.len() > original.tags.len()
+        || merged.labels.len() > original.labels.len()
+        || merged.notes.len() > original.notes.len()
+}
+
+fn log_file(checkout: &Path, uuid: &str) -> PathBuf {
+    checkout.join(LOGS_DIR).join(format!("{}.log.gz", uuid))
+}
+
+fn compress_log(source: &Path, target: &Path) -> Result<(), String> {
+    let data =
+        fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&data)
+        .and_then(|_| encoder.finish())
+        .and_then(|compressed| {
+            fs::create_dir_all(target.parent().unwrap_or(Path::new(".")))?;
+            fs::write(target, compressed)
+        })
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))
+}
+
+fn decompress_log(source: &Path, target: &Path) -> Result<(), String> {
+    let compressed =
+        fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let mut data = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut data)
+        .and_then(|_| {
+            fs::create_dir_all(target.parent().unwrap_or(Path::new(".")))?;
+            fs::write(target, data)
+        })
+        .map_err(|e| format!("Failed to extract {}: {}", target.display(), e))
+}
+
+/// Pull remote records into the store and write back local records that
+/// are new or more complete
+fn sync_once(
+    store: &ExecutionStore,
+    checkout: &Path,
+    options: &SyncOptions,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let (mut incoming, skipped) = split_invalid_uuids(read_repo_records(checkout));
+    report.skipped = skipped;
+    let stored: HashMap<String, ExecutionRecord> = incoming
+        .iter()
+        .map(|record| (record.uuid.clone(), record.clone()))
+        .collect();
+
+    // Pull: remote logs replace log paths that do not exist on this machine
+    let log_dir = store.app_folder().join(IMPORTED_LOG_DIR);
+    let mut bundled = Vec::new();
+    for record in incoming.iter_mut() {
+        let compressed = log_file(checkout, &record.uuid);
+        if options.logs && compressed.exists() && !Path::new(&record.log_path).exists() {
+            let target = log_dir.join(format!("{}.log", record.uuid));
+            record.log_path = target.to_string_lossy().to_string();
+            bundled.push((record.uuid.clone(), compressed, target));
+        }
+    }
+    let merged = store.merge(&incoming)?;
+    report.logs_pulled = 0;
+    report.pulled = merged.added.into_iter().chain(merged.updated).collect();
+    // Records kept locally may still gain annotations made elsewhere
+    for uuid in merged.unchanged {
+        let Some(local) = store.get(&uuid) else {
+            continue;
+        };
+        let mut annotated = local.clone();
+        annotated.keep_annotations_from(&stored[&uuid]);
+        if gains_annotations(&annotated, &local) {
+            store.save(&annotated)?;
+            report.pulled.push(uuid);
+        }
+    }
+    for (uuid, compressed, target) in &bundled {
+        if report.pulled.contains(uuid) && !target.exists() {
+            decompress_log(compressed, target)?;
+            report.logs_pulled += 1;
+        }
+    }
+
+    // Push: only this store's own records, never records read across users
+    report.pushed.clear();
+    report.logs_pushed = 0;
+    let records_dir = checkout.join(RECORDS_DIR);
+    fs::create_dir_all(&records_dir)
+        .map_err(|e| format!("Failed to create {}: {}", records_dir.display(), e))?;
+    for record in store.clone().with_all_users(false).get_all() {
+        if let Some(copy) = repo_copy(&record, stored.get(&record.uuid)) {
+            let path = records_dir.join(format!("{}.json", record.uuid));
+            let json = serde_json::to_string_pretty(&copy.to_json())
+                .map_err(|e| format!("Failed to serialize {}: {}", record.uuid, e))?;
+            fs::write(&path, json + "\n")
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            report.pushed.push(record.uuid.clone());
+        }
+        let compressed = log_file(checkout, &record.uuid);
+        if options.logs
+            && record.status == ExecutionStatus::Executed
+            && !compressed.exists()
+            && Path::new(&record.log_path).is_file()
+        {
+            compress_log(Path::new(&record.log_path), &compressed)?;
+            report.logs_pushed += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Commit the checkout if anything changed. Returns the commit hash.
+fn commit_changes(checkout: &Path, report: &SyncReport) -> Result<Option<String>, String> {
+    git(checkout, &["add", "-A"])?;
+    if git(checkout, &["status", "--porcelain"])?.is_empty() {
+        return Ok(None);
+    }
+    let host = local_hostname::get()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "unknown host".to_string());
+    let message = format!("Sync {} executions from {}", report.pushed.len(), host);
+    // Fall back to a generic identity on machines without git configured
+    let mut args = Vec::new();
+    if git(checkout, &["config", "user.email"]).is_err() {
+        args.extend([
+            "-c",
+            "user.name=start-command",
+            "-c",
+            "user.email=start-command@localhost",
+        ]);
+    }
+    args.extend(["commit", "-q", "-m", &message]);
+    git(checkout, &args)?;
+    git(checkout, &["rev-parse", "HEAD"]).map(Some)
+}
+
+/// Synchronize the store with `remote` (a git URL or a repository path)
+pub fn sync_store(
+    store: &ExecutionStore,
+    remote: &str,
+    options: &SyncOptions,
+) -> Result<SyncReport, String> {
+    let remote = resolve_remote(remote);
+    let checkout = store
+        .app_folder()
+        .join(SYNC_DIR)
+        .join(checkout_name(&remote));
+    prepare_checkout(&checkout, &remote)?;
+
+    let mut report = SyncReport {
+        remote: remote.clone(),
+        branch: SYNC_BRANCH.to_string(),
+        ..Default::default()
+    };
+    let mut last_error = String::new();
+    for _ in 0..SYNC_ATTEMPTS {
+        reset_to_remote(&checkout)?;
+        sync_once(store, &checkout, options, &mut report)?;
+        let Some(commit) = commit_changes(&checkout, &report)? else {
+            return Ok(report);
+        };
+        match git(&checkout, &["push", "-q", "origin", SYNC_BRANCH]) {
+            Ok(_) => {
+                report.commit = Some(commit);
+                return Ok(report);
+            }
+            // Another machine pushed first: fetch its history and merge again
+            Err(e) => last_error = e,
+        }
+    }
+    Err(format!(
+        "Failed to push to {} after {} attempts: {}",
+        remote, SYNC_ATTEMPTS, last_error
+    ))
+}
+
+/// Format a sync report as links-notation, json or text
+pub fn format_sync_report(report: &SyncReport, format: &str) -> Result<String, String> {
+    match format {
+        "links-notation" => Ok(format_sync_as_links_notation(report)),
+        "json" => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize to JSON: {}", e)),
+        "text" => Ok(format_sync_as_text(report)),
+        _ => Err(format!("Unknown output format: {}", format)),
+    }
+}
+
+fn format_sync_as_links_notation(report: &SyncReport) -> String {
+    let mut lines = vec![
+        "sync".to_string(),
+        format!("  remote {}", escape_for_links_notation(&report.remote)),
+        format!("  branch {}", report.branch),
+    ];
+    for (key, uuids) in [("pulled", &report.pulled), ("pushed", &report.pushed)] {
+        if uuids.is_empty() {
+            lines.push(format!("  {} ()", key));
+        } else {
+            lines.push(format!("  {}", key));
+            lines.extend(uuids.iter().map(|uuid| format!("    {}", uuid)));
+        }
+    }
+    lines.push(format!("  logsPulled {}", report.logs_pulled));
+    lines.push(format!("  logsPushed {}", report.logs_pushed));
+    if !report.skipped.is_empty() {
+        lines.push("  skipped".to_string());
+        lines.extend(
+            report
+                .skipped
+                .iter()
+                .map(|uuid| format!("    {}", escape_for_links_notation(uuid))),
+        );
+    }
+    if let Some(ref commit) = report.commit {
+        lines.push(format!("  commit {}", commit));
+    }
+    lines.join("\n")
+}
+
+fn format_sync_as_text(report: &SyncReport) -> String {
+    let mut lines = vec![
+        "Store Sync".to_string(),
+        "=".repeat(50),
+        format!("Remote:            {} ({})", report.remote, report.branch),
+        format!("Pulled:            {} executions", report.pulled.len()),
+        format!("Pushed:            {} executions", report.pushed.len()),
+    ];
+    if report.logs_pulled > 0 || report.logs_pushed > 0 {
+        lines.push(format!(
+            "Logs:              {} pulled, {} pushed",
+            report.logs_pulled, report.logs_pushed
+        ));
+    }
+    if !report.skipped.is_empty() {
+        lines.push(format!(
+            "Skipped:           {} records with an invalid UUID",
+            report.skipped.len()
+        ));
+    }
+    lines.push(match report.commit {
+        Some(ref commit) => format!("Commit:            {}", commit),
+        None => "Remote already up to date.".to_string(),
+    });
+    lines.join("\n")
+}
+
+/// Handle --sync
+pub fn query_sync(store: Option<&ExecutionStore>, options: &WrapperOptions) -> StatusQueryResult {
+    let remote = options.sync.as_deref().unwrap_or_default();
+    let result = store
+        .ok_or_else(|| "Execution tracking is disabled.".to_string())
+        .and_then(|store| {
+            sync_store(
+                store,
+                remote,
+                &SyncOptions {
+                    logs: options.sync_logs,
+                },
+            )
+        })
+        .and_then(|report| {
+            format_sync_report(
+                &report,
+                options.output_format.as_deref().unwrap_or("links-notation"),
+            )
+        });
+
+    result.into()
+}
@@ -6,6 +6,7 @@ use std::env;
 use std::fs;
 use std::process::Command;
 
+use crate::event_stream::emit_event;
 use crate::isolation::get_timestamp;
 
 /// Configuration for the failure handler
@@ -91,6 +92,7 @@ pub fn handle_failure(
     // Create issue
     if let Some(issue_url) = create_issue(&repo_info, full_command, exit_code, log_url.as_deref()) {
         println!("Issue created: {}", issue_url);
+        emit_event("issue-created", serde_json::json!({ "url": issue_url }));
     }
 }
 
@@ -0,0 +1,557 @@
+//! Self-contained HTML report of executions (`--report html`)
+//!
+//! Renders a filtered set of executions into a single static HTML file that
+//! can be attached to pull requests and incident write-ups: an overview table
+//! sortable by any column, and a page per execution with the timeline
+//! header and footer (as printed by start-command), isolation metadata,
+//! process IDs and the colorized log. Styles and the sorting script are
+//! inlined, so the file opens anywhere without network access; per-execution
+//! pages are anchors and work without JavaScript.
+
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::execution_control::collect_process_ids;
+use crate::execution_stats::record_duration_ms;
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::log_viewer::{read_stored_log, split_log, strip_ansi};
+use crate::output_blocks::{
+    create_finish_block, create_start_block, format_duration, FinishBlockOptions, StartBlockOptions,
+};
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::{enrich_detached_status, StatusQueryResult};
+
+/// Valid formats for --report
+pub const VALID_REPORT_FORMATS: [&str; 1] = ["html"];
+
+/// Only the last lines of longer logs are embedded in the report
+pub const MAX_REPORT_LOG_LINES: usize = 5000;
+
+const STYLE: &str = r##"
+:root { color-scheme: light dark; --fg: #1f2328; --bg: #ffffff; --muted: #656d76; --line: #d0d7de; --ok: #1a7f37; --fail: #cf222e; --run: #9a6700; --code: #f6f8fa; }
+@media (prefers-color-scheme: dark) { :root { --fg: #e6edf3; --bg: #0d1117; --muted: #8d96a0; --line: #30363d; --ok: #3fb950; --fail: #f85149; --run: #d29922; --code: #161b22; } }
+body { font: 14px/1.5 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: var(--fg); background: var(--bg); margin: 2em auto; max-width: 1200px; padding: 0 1em; }
+h1, h2 { font-weight: 600; } .muted { color: var(--muted); }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid var(--line); padding: 4px 8px; text-align: left; vertical-align: top; }
+th { cursor: pointer; user-select: none; white-space: nowrap; }
+th[aria-sort="ascending"]::after { content: " \25B2"; } th[aria-sort="descending"]::after { content: " \25BC"; }
+td.command { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; word-break: break-all; }
+.ok { color: var(--ok); } .fail { color: var(--fail); } .run { color: var(--run); }
+pre { background: var(--code); border: 1px solid var(--line); border-radius: 6px; padding: 8px 12px; overflow-x: auto; font: 12px/1.45 ui-monospace, SFMono-Regular, Menlo, monospace; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 2px 16px; } dt { color: var(--muted); } dd { margin: 0; font-family: ui-monospace, monospace; }
+.execution { display: none; } .execution:target { display: block; }
+body:has(.execution:target) #overview { display: none; }
+"##;
+
+const SCRIPT: &str = r##"
+document.querySelectorAll("#executions th").forEach(function (th, column) {
+  th.addEventListener("click", function () {
+    var body = th.closest("table").tBodies[0];
+    var ascending = th.getAttribute("aria-sort") !== "ascending";
+    th.parentNode.querySelectorAll("th").forEach(function (other) { other.removeAttribute("aria-sort"); });
+    th.setAttribute("aria-sort", ascending ? "ascending" : "descending");
+    var key = function (row) {
+      var cell = row.cells[column];
+      var value = cell.getAttribute("data-sort");
+      return value === null ? cell.textContent.trim() : value;
+    };
+    var rows = Array.prototype.slice.call(body.rows);
+    rows.sort(function (a, b) {
+      var x = key(a), y = key(b), nx = parseFloat(x), ny = parseFloat(y);
+      var order = !isNaN(nx) && !isNaN(ny) ? nx - ny : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});
+"##;
+
+/// `text` with the HTML special characters escaped
+pub fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The 16 standard terminal colors (normal, then bright)
+const ANSI_COLORS: [&str; 16] = [
+    "#000000", "#cd3131", "#0dbc79", "#e5e510", "#2472c8", "#bc3fbc", "#11a8cd", "#e5e5e5",
+    "#666666", "#f14c4c", "#23d18b", "#f5f543", "#3b8eea", "#d670d6", "#29b8db", "#ffffff",
+];
+
+/// CSS color of a 256-color palette index
+fn palette_color(index: u8) -> String {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize].to_string(),
+        16..=231 => {
+            let level = |value: u8| {
+                if value == 0 {
+                    0
+                } else {
+                    55 + value as u16 * 40
+                }
+            };
+            let index = index - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(index / 36),
+                level((index / 6) % 6),
+                level(index % 6)
+            )
+        }
+        _ => {
+            let gray = 8 + (index - 232) as u16 * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
+    }
+}
+
+/// Text attributes set by SGR escape sequences
+#[derive(Debug, Clone, Default, PartialEq)]
+struct SgrState {
+    foreground: Option<String>,
+    background: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl SgrState {
+    /// Extended color (`38;5;n` or `38;2;r;g;b`) starting at `codes[0]`,
+    /// with the number of codes it used
+    fn extended_color(codes: &[u16]) -> (Option<String>, usize) {
+        match codes {
+            [5, index, ..] => (Some(palette_color((*index).min(255) as u8)), 2),
+            [2, r, g, b, ..] => (
+                Some(format!(
+                    "#{:02x}{:02x}{:02x}",
+                    (*r).min(255),
+                    (*g).min(255),
+                    (*b).min(255)
+                )),
+                4,
+            ),
+            _ => (None, codes.len()),
+        }
+    }
+
+    fn apply(&mut self, codes: &[u16]) {
+        let mut index = 0;
+        while index < codes.len() {
+            match codes[index] {
+                0 => *self = SgrState::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                code @ 30..=37 => self.foreground = Some(ANSI_COLORS[(code - 30) as usize].into()),
+                code @ 90..=97 => self.foreground = Some(ANSI_COLORS[(code - 82) as usize].into()),
+                code @ 40..=47 => self.background = Some(ANSI_COLORS[(code - 40) as usize].into()),
+                code @ 100..=107 => {
+                    self.background = Some(ANSI_COLORS[(code - 92) as usize].into())
+                }
+                39 => self.foreground = None,
+                49 => self.background = None,
+                code @ (38 | 48) => {
+                    let (color, used) = Self::extended_color(&codes[index + 1..]);
+                    if code == 38 {
+                        self.foreground = color;
+                    } else {
+                        self.background = color;
+                    }
+                    index += used;
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+    }
+
+    fn style(&self) -> String {
+        let mut style = Vec::new();
+        if let Some(ref color) = self.foreground {
+            style.push(format!("color:{}", color));
+        }
+        if let Some(ref color) = self.background {
+            style.push(format!("background:{}", color));
+        }
+        if self.bold {
+            style.push("font-weight:bold".to_string());
+        }
+        if self.dim {
+            style.push("opacity:.7".to_string());
+        }
+        if self.italic {
+            style.push("font-style:italic".to_string());
+        }
+        if self.underline {
+            style.push("text-decoration:underline".to_string());
+        }
+        style.join(";")
+    }
+}
+
+fn sgr_regex() -> &'static Regex {
+    static SGR: OnceLock<Regex> = OnceLock::new();
+    SGR.get_or_init(|| Regex::new(r"\x1b\[([0-9;]*)m").expect("valid SGR regex"))
+}
+
+/// Escaped HTML of terminal output, with SGR colors and text attributes
+/// turned into styled spans and other escape sequences dropped
+pub fn ansi_to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    let mut state = SgrState::default();
+    let mut open = false;
+    let mut last = 0;
+    let push_text = |html: &mut String, segment: &str| {
+        html.push_str(&html_escape(&strip_ansi(segment)));
+    };
+    for captures in sgr_regex().captures_iter(text) {
+        let sequence = captures.get(0).expect("whole match");
+        push_text(&mut html, &text[last..sequence.start()]);
+        last = sequence.end();
+
+        let codes: Vec<u16> = match &captures[1] {
+            "" => vec![0],
+            list => list
+                .split(';')
+                .map(|code| code.parse().unwrap_or(0))
+                .collect(),
+        };
+        let mut next = state.clone();
+        next.apply(&codes);
+        if next == state {
+            continue;
+        }
+        if open {
+            html.push_str("</span>");
+            open = false;
+        }
+        state = next;
+        let style = state.style();
+        if !style.is_empty() {
+            html.push_str(&format!("<span style=\"{}\">", style));
+            open = true;
+        }
+    }
+    push_text(&mut html, &text[last..]);
+    if open {
+        html.push_str("</span>");
+    }
+    html
+}
+
+fn option<'a>(record: &'a ExecutionRecord, key: &str) -> Option<&'a str> {
+    record.options.get(key).and_then(Value::as_str)
+}
+
+/// `[Isolation]` lines describing how the record was launched, in the form
+/// printed at launch (and understood by the timeline blocks)
+pub fn isolation_lines(record: &ExecutionRecord) -> Vec<String> {
+    let Some(environment) = option(record, "isolated") else {
+        return Vec::new();
+    };
+    let mut lines = vec![format!(
+        "[Isolation] Environment: {}, Mode: {}",
+        environment,
+        option(record, "isolationMode").unwrap_or("attached")
+    )];
+    let mut push = |label: &str, key: &str| {
+        if let Some(value) = option(record, key) {
+            lines.push(format!("[Isolation] {}: {}", label, value));
+        }
+    };
+    push("Session", "sessionName");
+    push("Image", "image");
+    push("Endpoint", "endpoint");
+    push("User", "user");
+    lines
+}
+
+/// Timeline header of a record, as printed when it started
+fn timeline_header(record: &ExecutionRecord) -> String {
+    let extra = isolation_lines(record);
+    create_start_block(&StartBlockOptions {
+        session_id: &record.uuid,
+        timestamp: &record.start_time,
+        command: &record.command,
+        extra_lines: (!extra.is_empty()).then(|| extra.iter().map(String::as_str).collect()),
+        style: None,
+        width: None,
+        color: false,
+        defer_command: false,
+    })
+}
+
+/// Timeline footer of a finished record, as printed when it finished
+fn timeline_footer(record: &ExecutionRecord) -> Option<String> {
+    if record.status != ExecutionStatus::Executed {
+        return None;
+    }
+    let extra = isolation_lines(record);
+    Some(create_finish_block(&FinishBlockOptions {
+        session_id: &record.uuid,
+        timestamp: record.end_time.as_deref().unwrap_or(""),
+        exit_code: record.exit_code.unwrap_or(-1),
+        log_path: &record.log_path,
+        duration_ms: record_duration_ms(record),
+        result_message: None,
+        extra_lines: (!extra.is_empty()).then(|| extra.iter().map(String::as_str).collect()),
+        style: None,
+        width: None,
+        color: false,
+    }))
+}
+
+/// Status cell class and label
+fn outcome(record: &ExecutionRecord) -> (&'static str, String) {
+    match (record.status, record.exit_code) {
+        (ExecutionStatus::Executing, _) => ("run", "executing".to_string()),
+        (_, Some(0)) => ("ok", "✓ succeeded".to_string()),
+        (_, Some(code)) => ("fail", format!("✗ failed ({})", code)),
+        (_, None) => ("fail", "✗ failed".to_string()),
+    }
+}
+
+/// Command output of a record's log as HTML, keeping only the last
+/// [`MAX_REPORT_LOG_LINES`] lines
+fn log_html(record: &ExecutionRecord) -> String {
+    if record.log_path.is_empty() {
+        return "<p class=\"muted\">No log was recorded.</p>".to_string();
+    }
+    match read_stored_log(Path::new(&record.log_path)) {
+        Ok(content) => {
+            let output = split_log(&content).output;
+            let lines: Vec<&str> = output.lines().collect();
+            let skipped = lines.len().saturating_sub(MAX_REPORT_LOG_LINES);
+            let note = if skipped > 0 {
+                format!(
+                    "<p class=\"muted\">First {} lines omitted; see {}</p>",
+                    skipped,
+                    html_escape(&record.log_path)
+                )
+            } else {
+                String::new()
+            };
+            format!(
+                "{}<pre class=\"log\">{}</pre>",
+                note,
+                ansi_to_html(&lines[skipped..].join("\n"))
+            )
+        }
+        Err(e) => format!("<p class=\"muted\">{}</p>", html_escape(&e)),
+    }
+}
+
+fn definition_list(entries: &[(String, String)]) -> String {
+    let items: String = entries
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "<dt>{}</dt><dd>{}</dd>",
+                html_escape(key),
+                html_escape(value)
+            )
+        })
+        .collect();
+    format!("<dl>{}</dl>", items)
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Page of one execution
+fn execution_section(record: &ExecutionRecord, process_ids: Option<&Value>) -> String {
+    let (class, label) = outcome(record);
+    let mut html = format!(
+        "<section class=\"execution\" id=\"exec-{uuid}\">\n<p><a href=\"#\">&larr; All executions</a></p>\n<h2><code>{command}</code></h2>\n<p class=\"{class}\">{label}</p>\n",
+        uuid = html_escape(&record.uuid),
+        command = html_escape(&record.command),
+        class = class,
+        label = html_escape(&label),
+    );
+    html.push_str(&format!(
+        "<pre class=\"timeline\">{}</pre>\n",
+        html_escape(&timeline_header(record))
+    ));
+    html.push_str(&log_html(record));
+    html.push('\n');
+    if let Some(footer) = timeline_footer(record) {
+        html.push_str(&format!(
+            "<pre class=\"timeline\">{}</pre>\n",
+            html_escape(&footer)
+        ));
+    }
+
+    let mut details = vec![
+        ("uuid".to_string(), record.uuid.clone()),
+        ("directory".to_string(), record.working_directory.clone()),
+        ("shell".to_string(), record.shell.clone()),
+        ("platform".to_string(), record.platform.clone()),
+    ];
+    if let Some(ref owner) = record.owner {
+        details.push(("owner".to_string(), owner.clone()));
+    }
+    if !record.tags.is_empty() {
+        details.push(("tags".to_string(), record.tags.join(", ")));
+    }
+    for (key, value) in &record.labels {
+        details.push((format!("label {}", key), value.clone()));
+    }
+    html.push_str("<h3>Details</h3>\n");
+    html.push_str(&definition_list(&details));
+
+    let mut isolation: Vec<(String, String)> = record
+        .options
+        .iter()
+        .map(|(key, value)| (key.clone(), value_text(value)))
+        .collect();
+    if !isolation.is_empty() {
+        isolation.sort();
+        html.push_str("\n<h3>Isolation</h3>\n");
+        html.push_str(&definition_list(&isolation));
+    }
+    if let Some(Value::Object(ids)) = process_ids {
+        let ids: Vec<(String, String)> = ids
+            .iter()
+            .map(|(key, value)| (key.clone(), value_text(value)))
+            .collect();
+        html.push_str("\n<h3>Process IDs</h3>\n");
+        html.push_str(&definition_list(&ids));
+    }
+    for note in &record.notes {
+        html.push_str(&format!(
+            "\n<p class=\"muted\">Note ({}): {}</p>",
+            html_escape(&note.time),
+            html_escape(&note.text)
+        ));
+    }
+    html.push_str("\n</section>\n");
+    html
+}
+
+fn overview_row(record: &ExecutionRecord) -> String {
+    let (class, label) = outcome(record);
+    let duration = record_duration_ms(record);
+    format!(
+        "<tr><td class=\"{class}\">{label}</td><td data-sort=\"{start}\">{start}</td><td data-sort=\"{duration_ms}\">{duration}</td><td data-sort=\"{exit_sort}\">{exit}</td><td>{backend}</td><td class=\"command\"><a href=\"#exec-{uuid}\">{command}</a></td><td>{directory}</td></tr>",
+        class = class,
+        label = html_escape(&label),
+        start = html_escape(&record.start_time),
+        duration_ms = duration.unwrap_or(-1.0),
+        duration = duration.map(format_duration).unwrap_or_default(),
+        exit_sort = record.exit_code.map_or(-1, i64::from),
+        exit = record.exit_code.map(|code| code.to_string()).unwrap_or_default(),
+        backend = html_escape(option(record, "isolated").unwrap_or("direct")),
+        uuid = html_escape(&record.uuid),
+        command = html_escape(&record.command),
+        directory = html_escape(&record.working_directory),
+    )
+}
+
+/// Render records (with their process IDs) as a complete HTML document
+pub fn render_html_report(records: &[(ExecutionRecord, Option<Value>)], generated: &str) -> String {
+    let failed = records
+        .iter()
+        .filter(|(record, _)| outcome(record).0 == "fail")
+        .count();
+    let executing = records
+        .iter()
+        .filter(|(record, _)| record.status == ExecutionStatus::Executing)
+        .count();
+    let rows: String = records
+        .iter()
+        .map(|(record, _)| overview_row(record) + "\n")
+        .collect();
+    let sections: String = records
+        .iter()
+        .map(|(record, process_ids)| execution_section(record, process_ids.as_ref()))
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>Execution Report</title>\n<style>{style}</style>\n</head>\n<body>\n<div id=\"overview\">\n<h1>Execution Report</h1>\n<p class=\"muted\">Generated {generated} &middot; {total} executions &middot; {succeeded} succeeded &middot; {failed} failed &middot; {executing} executing</p>\n<table id=\"executions\">\n<thead><tr><th>Status</th><th>Started</th><th>Duration</th><th>Exit</th><th>Backend</th><th>Command</th><th>Directory</th></tr></thead>\n<tbody>\n{rows}</tbody>\n</table>\n</div>\n{sections}<script>{script}</script>\n</body>\n</html>\n",
+        style = STYLE,
+        generated = html_escape(generated),
+        total = records.len(),
+        succeeded = records.len() - failed - executing,
+        failed = failed,
+        executing = executing,
+        rows = rows,
+        sections = sections,
+        script = SCRIPT,
+    )
+}
+
+/// Handle --report: render the filtered executions and write them to
+/// `output` (or return the document when no output file is given)
+pub fn query_report(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    format: &str,
+    output: Option<&Path>,
+) -> StatusQueryResult {
+    let failure = |error: String| StatusQueryResult {
+        success: false,
+        output: None,
+        error: Some(error),
+    };
+    let Some(store) = store else {
+        return failure("Execution tracking is disabled.".to_string());
+    };
+    if !VALID_REPORT_FORMATS.contains(&format) {
+        return failure(format!("Unknown report format: {}", format));
+    }
+
+    let mut records: Vec<ExecutionRecord> =
+        filter.apply(store.get_all().iter().map(enrich_detached_status).collect());
+    records.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    let records: Vec<(ExecutionRecord, Option<Value>)> = records
+        .into_iter()
+        .map(|record| {
+            let process_ids = collect_process_ids(&record);
+            (record, process_ids)
+        })
+        .collect();
+    let html = render_html_report(&records, &chrono::Utc::now().to_rfc3339());
+
+    let Some(path) = output else {
+        return StatusQueryResult {
+            success: true,
+            output: Some(html),
+            error: None,
+        };
+    };
+    match fs::write(path, html) {
+        Ok(()) => StatusQueryResult {
+            success: true,
+            output: Some(format!(
+                "Wrote report of {} executions to {}",
+                records.len(),
+                path.display()
+            )),
+            error: None,
+        },
+        Err(e) => failure(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
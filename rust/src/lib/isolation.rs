@@ -4,19 +4,19 @@
 //! - screen: GNU Screen terminal multiplexer
 //! - tmux: tmux terminal multiplexer
 //! - docker: Docker containers
+//! - podman: Podman containers (rootless, same options as docker)
 //! - ssh: Remote SSH execution
 
 use std::env;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+
+use serde_json::json;
 
 use crate::args_parser::generate_session_name;
-use crate::docker_cleanup::{
-    append_docker_container_cleanup_policy_message, build_docker_runtime_args,
-    docker_container_cleanup_instructions, get_docker_container_cleanup_policy,
-    read_docker_container_oom_killed, remove_docker_container, should_cleanup_docker_container,
-    spawn_attached_docker, start_detached_docker_completion_watcher, DockerContainerCleanupPolicy,
-};
+use crate::docker_cleanup::ContainerEngine;
+use crate::event_stream::emit_event;
+use crate::output_theme::OutputStyle;
 
 /// Result of an isolation run
 #[derive(Debug, Default)]
@@ -209,14 +209,14 @@ pub fn detect_shell_in_environment(environment: &str, options: &IsolationOptions
     // In auto mode, try shells in order of preference
     let shells_to_try = ["bash", "zsh", "sh"];
 
-    if environment == "docker" {
+    if let Some(engine) = ContainerEngine::from_backend(environment) {
         let image = match &options.image {
             Some(i) => i.clone(),
             None => return "sh".to_string(),
         };
 
         for shell in &shells_to_try {
-            let result = Command::new("docker")
+            let result = Command::new(engine.command())
                 .args([
                     "run",
                     "--rm",
@@ -235,8 +235,8 @@ pub fn detect_shell_in_environment(environment: &str, options: &IsolationOptions
                     if !detected.is_empty() {
                         if is_debug() {
                             eprintln!(
-                                "[DEBUG] Detected shell in docker image {}: {}",
-                                image, detected
+                                "[DEBUG] Detected shell in {} image {}: {}",
+                                environment, image, detected
                             );
                         }
                         return detected;
@@ -247,8 +247,8 @@ pub fn detect_shell_in_environment(environment: &str, options: &IsolationOptions
 
         if is_debug() {
             eprintln!(
-                "[DEBUG] Could not detect shell in docker image {}, falling back to sh",
-                image
+                "[DEBUG] Could not detect shell in {} image {}, falling back to sh",
+                environment, image
             );
         }
         return "sh".to_string();
@@ -304,6 +304,34 @@ pub fn detect_shell_in_environment(environment: &str, options: &IsolationOptions
 pub mod isolation_screen;
 pub use self::isolation_screen::{get_screen_version, supports_logfile_option};
 
+/// Report a starting isolation session on the --events stream
+fn emit_session_created(environment: &str, session_name: &str, options: &IsolationOptions) {
+    emit_event(
+        "isolation-session-created",
+        json!({
+            "isolated": environment,
+            "name": session_name,
+            "mode": if options.detached { "detached" } else { "attached" },
+        }),
+    );
+}
+
+/// Run an attached command to completion. When the launch hides command
+/// output (--silent, --summary-only) the output goes only to `log_path`.
+fn run_attached(command: &mut Command, log_path: Option<&Path>) -> std::io::Result<ExitStatus> {
+    match log_path {
+        Some(log_path) if !OutputStyle::current().mode.shows_output() => {
+            let mut child = command
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .spawn()?;
+            tee_child_output(&mut child, log_path, false);
+            child.wait()
+        }
+        _ => command.status(),
+    }
+}
+
 /// Run command in GNU Screen
 pub fn run_in_screen(command: &str, options: &IsolationOptions) -> IsolationResult {
     if !is_command_available("screen") {
@@ -318,6 +346,7 @@ pub fn run_in_screen(command: &str, options: &IsolationOptions) -> IsolationResu
         .session
         .clone()
         .unwrap_or_else(|| generate_session_name(Some("screen")));
+    emit_session_created("screen", &session_name, options);
 
     if options.detached {
         isolation_screen::start_detached_screen_with_log_capture(
@@ -355,6 +384,7 @@ pub fn run_in_tmux(command: &str, options: &IsolationOptions) -> IsolationResult
 
     let (shell, _) = get_shell();
     let effective_command = wrap_command_with_user(command, options.user.as_deref());
+    emit_session_created("tmux", &session_name, options);
 
     if options.detached {
         let final_command = if options.log_path.is_some() {
@@ -480,6 +510,7 @@ pub fn run_in_ssh(command: &str, options: &IsolationOptions) -> IsolationResult
     // Use interactive mode (-i) for shells that support it (bash, zsh) so that startup
     // files like .bashrc are sourced, making tools like nvm available in commands.
     let shell_interactive_flag = get_shell_interactive_flag(&shell_to_use);
+    emit_session_created("ssh", &session_name, options);
 
     if options.detached {
         // Detached mode: run in background on remote server using nohup
@@ -489,12 +520,8 @@ pub fn run_in_ssh(command: &str, options: &IsolationOptions) -> IsolationResult
         } else {
             shell_to_use.clone()
         };
-        let remote_command = format!(
-            "mkdir -p /tmp/start-command/logs/isolation/ssh && nohup {} -c {} > /tmp/start-command/logs/isolation/ssh/{}.log 2>&1 &",
-            shell_invocation,
-            shell_escape(command),
-            session_name
-        );
+        let remote_command =
+            ssh_detached_command(&shell_invocation, &shell_to_use, command, &session_name);
         let ssh_args = vec![endpoint.as_str(), remote_command.as_str()];
 
         if is_debug() {
@@ -509,8 +536,12 @@ pub fn run_in_ssh(command: &str, options: &IsolationOptions) -> IsolationResult
                 success: true,
                 session_name: Some(session_name.clone()),
                 message: format!(
-                    "Command started in detached SSH session on {}\nSession: {}\nView logs: ssh {} \"tail -f /tmp/start-command/logs/isolation/ssh/{}.log\"",
-                    endpoint, session_name, endpoint, session_name
+                    "Command started in detached SSH session on {}\nSession: {}\nView logs: start --follow {} (or ssh {} \"tail -f {}\")",
+                    endpoint,
+                    session_name,
+                    session_name,
+                    endpoint,
+                    isolation_log::shell_quote(&ssh_remote_log_path(&session_name))
                 ),
                 ..Default::default()
             },
@@ -536,7 +567,10 @@ pub fn run_in_ssh(command: &str, options: &IsolationOptions) -> IsolationResult
             eprintln!("[DEBUG] shell: {}", shell_to_use);
         }
 
-        let status = Command::new("ssh").args(&ssh_cmd_args).status();
+        let status = run_attached(
+            Command::new("ssh").args(&ssh_cmd_args),
+            options.log_path.as_deref(),
+        );
 
         match status {
             Ok(s) => IsolationResult {
@@ -561,383 +595,13 @@ pub fn run_in_ssh(command: &str, options: &IsolationOptions) -> IsolationResult
     }
 }
 
-/// Check if a Docker image exists locally
-pub fn docker_image_exists(image: &str) -> bool {
-    Command::new("docker")
-        .args(["image", "inspect", image])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .map(|s| s.success())
-        .unwrap_or(false)
-}
-
-/// Pull a Docker image with output streaming
-///
-/// When `log_path` is provided, the image-preparation phase (the `docker pull`)
-/// is also recorded in the session log so the single log file is a gap-free
-/// record of everything that ran (issue #138): a `Preparing image …` marker with
-/// a timestamp is written before the pull, each line of pull output is teed into
-/// the log as it streams, and an `Image ready (<duration>)` marker is written
-/// afterwards. Without a `log_path` the behavior is unchanged.
-///
-/// Returns (success, output) tuple
-pub fn docker_pull_image(image: &str, log_path: Option<&PathBuf>) -> (bool, String) {
-    use crate::isolation::isolation_log::{append_log_file, get_timestamp};
-    use std::io::{BufRead, BufReader};
-    use std::time::Instant;
-
-    // Print the virtual command line followed by empty line for visual separation
-    println!(
-        "{}",
-        crate::output_blocks::create_virtual_command_block(&format!("docker pull {}", image))
-    );
-    println!();
-
-    // Record the start of the image-preparation phase in the session log so
-    // operators tailing the log see progress instead of a header-only file.
-    let prep_start = Instant::now();
-    if let Some(path) = log_path {
-        append_log_file(
-            path,
-            &format!(
-                "$ docker pull {}\nPreparing image {}… ({})\n",
-                image,
-                image,
-                get_timestamp()
-            ),
-        );
-    }
-
-    let mut child = match Command::new("docker")
-        .args(["pull", image])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            let error_msg = format!("Failed to run docker pull: {}", e);
-            eprintln!("{}", error_msg);
-            if let Some(path) = log_path {
-                append_log_file(
-                    path,
-                    &format!(
-                        "{}\nImage preparation failed ({:.1}s)\n",
-                        error_msg,
-                        prep_start.elapsed().as_secs_f64()
-                    ),
-                );
-            }
-            println!();
-            println!(
-                "{}",
-                crate::output_blocks::create_virtual_command_result(false)
-            );
-            return (false, error_msg);
-        }
-    };
-
-    let mut output = String::new();
-
-    // Read and display stdout, teeing each line into the session log.
-    if let Some(stdout) = child.stdout.take() {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines().map_while(Result::ok) {
-            println!("{}", line);
-            if let Some(path) = log_path {
-                append_log_file(path, &format!("{}\n", line));
-            }
-            output.push_str(&line);
-            output.push('\n');
-        }
-    }
-
-    // Read and display stderr, teeing each line into the session log.
-    if let Some(stderr) = child.stderr.take() {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines().map_while(Result::ok) {
-            eprintln!("{}", line);
-            if let Some(path) = log_path {
-                append_log_file(path, &format!("{}\n", line));
-            }
-            output.push_str(&line);
-            output.push('\n');
-        }
-    }
-
-    let success = child.wait().map(|s| s.success()).unwrap_or(false);
-
-    // Record the end of the image-preparation phase with elapsed duration so the
-    // prep time is visible even when full progress is unavailable (issue #138).
-    if let Some(path) = log_path {
-        let duration = prep_start.elapsed().as_secs_f64();
-        append_log_file(
-            path,
-            &if success {
-                format!("Image ready ({:.1}s)\n", duration)
-            } else {
-                format!("Image preparation failed ({:.1}s)\n", duration)
-            },
-        );
-    }
-
-    // Print empty line before result marker for visual separation (issue #73)
-    // This ensures output is visually separated from the result marker
-    println!();
-    println!(
-        "{}",
-        crate::output_blocks::create_virtual_command_result(success)
-    );
-    println!("{}", crate::output_blocks::create_timeline_separator());
-
-    (success, output)
-}
-
-/// Run command in Docker container
-pub fn run_in_docker(command: &str, options: &IsolationOptions) -> IsolationResult {
-    if !is_command_available("docker") {
-        return IsolationResult {
-            success: false,
-            message:
-                "docker is not installed. Install Docker from https://docs.docker.com/get-docker/"
-                    .to_string(),
-            ..Default::default()
-        };
-    }
-
-    let image = match &options.image {
-        Some(i) => i.clone(),
-        None => {
-            return IsolationResult {
-                success: false,
-                message: "Docker isolation requires --image option".to_string(),
-                ..Default::default()
-            };
-        }
-    };
-
-    // Check if image exists locally; if not, pull it as a virtual command.
-    // Pass log_path so the image-preparation phase (docker pull) is recorded in
-    // the session log, keeping it a gap-free record of the run (issue #138).
-    if !docker_image_exists(&image) {
-        let (pull_success, _pull_output) = docker_pull_image(&image, options.log_path.as_ref());
-        if !pull_success {
-            return IsolationResult {
-                success: false,
-                message: format!("Failed to pull Docker image: {}", image),
-                exit_code: Some(1),
-                ..Default::default()
-            };
-        }
-    }
-
-    let container_name = options
-        .session
-        .clone()
-        .unwrap_or_else(|| generate_session_name(Some("docker")));
-    let cleanup_policy = get_docker_container_cleanup_policy(options);
-
-    // Detect the shell to use in the container
-    let shell_to_use = detect_shell_in_environment("docker", options);
-    // Use interactive mode (-i) for shells that support it (bash, zsh) so that startup
-    // files like .bashrc are sourced, making tools like nvm available in commands.
-    let shell_interactive_flag = get_shell_interactive_flag(&shell_to_use);
-
-    // Print the user command (this appears after any virtual commands like docker pull)
-    println!("{}", crate::output_blocks::create_command_line(command));
-    println!();
-
-    if options.detached {
-        let effective_command = if options.keep_alive {
-            format!("{}; exec {}", command, shell_to_use)
-        } else {
-            command.to_string()
-        };
-
-        let mut args = vec!["run", "-d", "--name", &container_name];
-
-        if let Some(ref user) = options.user {
-            args.push("--user");
-            args.push(user);
-        }
-
-        args.extend(build_docker_runtime_args(options));
-
-        args.push(&image);
-        args.push(&shell_to_use);
-        if let Some(flag) = shell_interactive_flag {
-            args.push(flag);
-        }
-        args.extend(&["-c", &effective_command]);
-
-        if is_debug() {
-            eprintln!("[DEBUG] Running: docker {:?}", args);
-            eprintln!("[DEBUG] shell: {}", shell_to_use);
-        }
-
-        match Command::new("docker").args(&args).output() {
-            Ok(output) if output.status.success() => {
-                let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-                if let Some(log_path) = options.log_path.as_ref() {
-                    start_detached_docker_completion_watcher(
-                        &container_name,
-                        cleanup_policy,
-                        Some(log_path),
-                    );
-                } else {
-                    start_detached_docker_completion_watcher(&container_name, cleanup_policy, None);
-                }
-
-                let mut message = format!(
-                    "Command started in detached docker container: {}",
-                    container_name
-                );
-                message.push_str(&format!(
-                    "\nContainer ID: {}",
-                    &container_id[..12.min(container_id.len())]
-                ));
-                if options.keep_alive {
-                    message.push_str("\nContainer will stay alive after command completes.");
-                } else {
-                    message
-                        .push_str("\nContainer will exit automatically after command completes.");
-                }
-                append_docker_container_cleanup_policy_message(
-                    &mut message,
-                    &container_name,
-                    cleanup_policy,
-                );
-                message.push_str(&format!("\nAttach with: docker attach {}", container_name));
-                message.push_str(&format!("\nView logs: docker logs {}", container_name));
-                if let Some(log_path) = options.log_path.as_ref() {
-                    message.push_str(&format!("\nLive log: {}", log_path.display()));
-                }
-
-                IsolationResult {
-                    success: true,
-                    session_name: Some(container_name),
-                    container_id: Some(container_id),
-                    message,
-                    ..Default::default()
-                }
-            }
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                IsolationResult {
-                    success: false,
-                    session_name: Some(container_name),
-                    message: format!("Failed to start docker container: {}", stderr),
-                    ..Default::default()
-                }
-            }
-            Err(e) => IsolationResult {
-                success: false,
-                session_name: Some(container_name),
-                message: format!("Failed to run docker: {}", e),
-                ..Default::default()
-            },
-        }
-    } else {
-        // Attached mode
-        let mut args = vec!["run"];
-        args.push(if has_tty() { "-it" } else { "-i" });
-        args.extend(["--name", &container_name]);
-
-        if let Some(ref user) = options.user {
-            args.push("--user");
-            args.push(user);
-        }
-
-        args.extend(build_docker_runtime_args(options));
-
-        if is_debug() {
-            eprintln!("[DEBUG] shell: {}", shell_to_use);
-        }
-
-        args.push(&image);
-        args.push(&shell_to_use);
-        if let Some(flag) = shell_interactive_flag {
-            args.push(flag);
-        }
-        args.extend(&["-c", command]);
-
-        let child = spawn_attached_docker(&args, options.log_path.as_ref());
-
-        match child {
-            Ok(child) => match child.wait() {
-                Ok(s) => {
-                    let exit_code = s.code().unwrap_or(1);
-                    let mut message = format!(
-                        "Docker container \"{}\" exited with code {}",
-                        container_name, exit_code
-                    );
-                    let oom_killed =
-                        read_docker_container_oom_killed(&container_name).unwrap_or(false);
-                    if should_cleanup_docker_container(cleanup_policy, exit_code, oom_killed) {
-                        if remove_docker_container(&container_name, options.log_path.as_ref()) {
-                            message.push_str("\nContainer removed after completion.");
-                        } else {
-                            message
-                                .push_str("\nWarning: failed to remove container automatically.");
-                            message.push_str(&format!(
-                                "\nRemove when done: docker rm -f {}",
-                                container_name
-                            ));
-                        }
-                    } else if cleanup_policy == DockerContainerCleanupPolicy::Keep {
-                        message.push('\n');
-                        message.push_str(&docker_container_cleanup_instructions(&container_name));
-                    } else if matches!(
-                        cleanup_policy,
-                        DockerContainerCleanupPolicy::KeepOnFail
-                            | DockerContainerCleanupPolicy::Default
-                    ) {
-                        if oom_killed {
-                            message.push_str(
-                                "\nContainer kept because Docker reports it was OOM-killed.",
-                            );
-                        } else {
-                            message.push_str("\nContainer kept because the command failed.");
-                        }
-                        message.push_str(&format!(
-                            "\nRemove when done: docker rm -f {}",
-                            container_name
-                        ));
-                    }
-
-                    IsolationResult {
-                        success: s.success(),
-                        session_name: Some(container_name.clone()),
-                        message,
-                        exit_code: Some(exit_code),
-                        ..Default::default()
-                    }
-                }
-                Err(e) => IsolationResult {
-                    success: false,
-                    session_name: Some(container_name),
-                    message: format!("Failed to wait for docker: {}", e),
-                    ..Default::default()
-                },
-            },
-            Err(e) => IsolationResult {
-                success: false,
-                session_name: Some(container_name),
-                message: format!("Failed to start docker: {}", e),
-                ..Default::default()
-            },
-        }
-    }
-}
-
 /// Run command in the specified isolation backend
 pub fn run_isolated(backend: &str, command: &str, options: &IsolationOptions) -> IsolationResult {
     match backend {
         "screen" => run_in_screen(command, options),
         "tmux" => run_in_tmux(command, options),
         "docker" => run_in_docker(command, options),
+        "podman" => run_in_podman(command, options),
         "ssh" => run_in_ssh(command, options),
         _ => IsolationResult {
             success: false,
@@ -947,11 +611,17 @@ pub fn run_isolated(backend: &str, command: &str, options: &IsolationOptions) ->
     }
 }
 
-/// Run command as an isolated user (without isolation backend)
-pub fn run_as_isolated_user(command: &str, username: &str) -> IsolationResult {
-    let status = Command::new("sudo")
-        .args(["-n", "-u", username, "sh", "-c", command])
-        .status();
+/// Run command as an isolated user (without isolation backend); `log_path`
+/// receives the output when the launch hides it
+pub fn run_as_isolated_user(
+    command: &str,
+    username: &str,
+    log_path: Option<&Path>,
+) -> IsolationResult {
+    let status = run_attached(
+        Command::new("sudo").args(["-n", "-u", username, "sh", "-c", command]),
+        log_path,
+    );
 
     match status {
         Ok(s) => IsolationResult {
@@ -973,22 +643,29 @@ pub fn run_as_isolated_user(command: &str, username: &str) -> IsolationResult {
     }
 }
 
+#[path = "isolation_docker_pull.rs"]
+mod docker_pull;
+pub use self::docker_pull::docker_pull_image;
+
+#[path = "isolation_container.rs"]
+mod container;
+pub use self::container::{
+    docker_image_exists, podman_image_reference, run_in_docker, run_in_podman,
+};
+
 #[path = "isolation_log.rs"]
 pub mod isolation_log;
 pub use self::isolation_log::{
     append_log_file, create_log_footer, create_log_header, create_log_path,
     create_log_path_for_execution, generate_log_filename, get_default_docker_image, get_log_dir,
-    get_temp_dir, get_temp_root, get_timestamp, write_log_file, LogHeaderParams,
+    get_temp_dir, get_temp_root, get_timestamp, ssh_detached_command, ssh_remote_log_path,
+    stderr_log_path, tee_child_output, write_log_file, LogHeaderParams, SSH_REMOTE_LOG_DIR,
 };
 
 fn is_debug() -> bool {
     env::var("START_DEBUG").is_ok_and(|v| v == "1" || v == "true")
 }
 
-fn shell_escape(command: &str) -> String {
-    format!("'{}'", command.replace('\'', "'\\''"))
-}
-
 #[path = "atty.rs"]
 mod atty;
 
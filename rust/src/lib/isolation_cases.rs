@@ -1,4 +1,5 @@
 use super::*;
+use crate::docker_cleanup::build_docker_runtime_args;
 
 #[test]
 fn test_is_command_available() {
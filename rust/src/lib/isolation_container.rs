@@ -0,0 +1,362 @@
+//! Container isolation (docker and podman), extracted from isolation.rs
+//!
+//! Both engines share one runner: the same `run` arguments for `--image`,
+//! `--volume`, `--mount`, `--env` and `--privileged`, and the same
+//! keep-container and cleanup policies from `docker_cleanup`. Podman runs
+//! rootless without a daemon, so a podman level never needs root or a group
+//! membership; short image names are qualified for it the way docker
+//! resolves them, since podman would otherwise ask which registry to use.
+
+use std::process::{Command, Stdio};
+
+use serde_json::json;
+
+use super::docker_pull::container_pull_image;
+use super::{
+    detect_shell_in_environment, emit_session_created, generate_session_name,
+    get_shell_interactive_flag, has_tty, is_command_available, is_debug, IsolationOptions,
+    IsolationResult,
+};
+use crate::docker_cleanup::{
+    append_docker_container_cleanup_policy_message, build_docker_runtime_args,
+    docker_container_cleanup_instructions, get_docker_container_cleanup_policy,
+    read_docker_container_oom_killed, remove_docker_container, should_cleanup_docker_container,
+    spawn_attached_docker, start_detached_docker_completion_watcher, ContainerEngine,
+    DockerContainerCleanupPolicy,
+};
+use crate::event_stream::emit_event;
+
+/// Check if a Docker image exists locally
+pub fn docker_image_exists(image: &str) -> bool {
+    container_image_exists(ContainerEngine::Docker, image)
+}
+
+fn container_image_exists(engine: ContainerEngine, image: &str) -> bool {
+    Command::new(engine.command())
+        .args(["image", "inspect", image])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// The image reference to pull. Podman does not default to Docker Hub, so a
+/// short name like `ubuntu:22.04` becomes `docker.io/library/ubuntu:22.04`;
+/// references with a registry host, `localhost/` or an image ID are kept.
+pub fn podman_image_reference(image: &str) -> String {
+    let first = image.split('/').next().unwrap_or(image);
+    let has_registry =
+        image.contains('/') && (first.contains('.') || first.contains(':') || first == "localhost");
+    if has_registry || image.starts_with("sha256:") {
+        image.to_string()
+    } else if image.contains('/') {
+        format!("docker.io/{}", image)
+    } else {
+        format!("docker.io/library/{}", image)
+    }
+}
+
+/// Whether podman runs rootless for this user (None when it cannot tell)
+fn podman_rootless() -> Option<bool> {
+    let output = Command::new(ContainerEngine::Podman.command())
+        .args(["info", "--format", "{{.Host.Security.Rootless}}"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Run command in Docker container
+pub fn run_in_docker(command: &str, options: &IsolationOptions) -> IsolationResult {
+    run_in_container(ContainerEngine::Docker, command, options)
+}
+
+/// Run command in Podman container
+pub fn run_in_podman(command: &str, options: &IsolationOptions) -> IsolationResult {
+    run_in_container(ContainerEngine::Podman, command, options)
+}
+
+fn run_in_container(
+    engine: ContainerEngine,
+    command: &str,
+    options: &IsolationOptions,
+) -> IsolationResult {
+    let cli = engine.name();
+    if !is_command_available(&engine.command().to_string_lossy()) {
+        let install = match engine {
+            ContainerEngine::Docker => "Install Docker from https://docs.docker.com/get-docker/",
+            ContainerEngine::Podman => "Install Podman from https://podman.io/docs/installation",
+        };
+        return IsolationResult {
+            success: false,
+            message: format!("{} is not installed. {}", cli, install),
+            ..Default::default()
+        };
+    }
+
+    let mut image = match &options.image {
+        Some(i) => i.clone(),
+        None => {
+            return IsolationResult {
+                success: false,
+                message: format!("{} isolation requires --image option", engine.label()),
+                ..Default::default()
+            };
+        }
+    };
+
+    // Check if image exists locally; if not, pull it as a virtual command.
+    // Pass log_path so the image-preparation phase (docker pull) is recorded in
+    // the session log, keeping it a gap-free record of the run (issue #138).
+    if !container_image_exists(engine, &image) {
+        if engine == ContainerEngine::Podman {
+            image = podman_image_reference(&image);
+        }
+        if !container_image_exists(engine, &image) {
+            let (pull_success, _pull_output) =
+                container_pull_image(engine, &image, options.log_path.as_ref());
+            if !pull_success {
+                return IsolationResult {
+                    success: false,
+                    message: format!("Failed to pull {} image: {}", engine.label(), image),
+                    exit_code: Some(1),
+                    ..Default::default()
+                };
+            }
+        }
+    }
+    let options = &IsolationOptions {
+        image: Some(image.clone()),
+        ..options.clone()
+    };
+
+    let container_name = options
+        .session
+        .clone()
+        .unwrap_or_else(|| generate_session_name(Some(cli)));
+    let cleanup_policy = get_docker_container_cleanup_policy(options);
+    let rootless_note = match engine {
+        ContainerEngine::Podman => podman_rootless()
+            .map(|rootless| format!("\nRootless: {}", rootless))
+            .unwrap_or_default(),
+        ContainerEngine::Docker => String::new(),
+    };
+
+    // Detect the shell to use in the container
+    let shell_to_use = detect_shell_in_environment(cli, options);
+    // Use interactive mode (-i) for shells that support it (bash, zsh) so that startup
+    // files like .bashrc are sourced, making tools like nvm available in commands.
+    let shell_interactive_flag = get_shell_interactive_flag(&shell_to_use);
+
+    // Print the user command (this appears after any virtual commands like docker pull)
+    if crate::output_theme::OutputStyle::current()
+        .mode
+        .shows_start()
+    {
+        println!("{}\n", crate::output_blocks::create_command_line(command));
+    }
+    emit_session_created(cli, &container_name, options);
+
+    if options.detached {
+        let effective_command = if options.keep_alive {
+            format!("{}; exec {}", command, shell_to_use)
+        } else {
+            command.to_string()
+        };
+
+        let mut args = vec!["run", "-d", "--name", &container_name];
+
+        if let Some(ref user) = options.user {
+            args.push("--user");
+            args.push(user);
+        }
+
+        args.extend(build_docker_runtime_args(options));
+
+        args.push(&image);
+        args.push(&shell_to_use);
+        if let Some(flag) = shell_interactive_flag {
+            args.push(flag);
+        }
+        args.extend(&["-c", &effective_command]);
+
+        if is_debug() {
+            eprintln!("[DEBUG] Running: {} {:?}", cli, args);
+            eprintln!("[DEBUG] shell: {}", shell_to_use);
+        }
+
+        match Command::new(engine.command()).args(&args).output() {
+            Ok(output) if output.status.success() => {
+                let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+                start_detached_docker_completion_watcher(
+                    engine,
+                    &container_name,
+                    cleanup_policy,
+                    options.log_path.as_ref(),
+                );
+
+                let mut message = format!(
+                    "Command started in detached {} container: {}",
+                    cli, container_name
+                );
+                message.push_str(&format!(
+                    "\nContainer ID: {}",
+                    &container_id[..12.min(container_id.len())]
+                ));
+                message.push_str(&rootless_note);
+                if options.keep_alive {
+                    message.push_str("\nContainer will stay alive after command completes.");
+                } else {
+                    message
+                        .push_str("\nContainer will exit automatically after command completes.");
+                }
+                append_docker_container_cleanup_policy_message(
+                    engine,
+                    &mut message,
+                    &container_name,
+                    cleanup_policy,
+                );
+                message.push_str(&format!("\nAttach with: {} attach {}", cli, container_name));
+                message.push_str(&format!("\nView logs: {} logs {}", cli, container_name));
+                if let Some(log_path) = options.log_path.as_ref() {
+                    message.push_str(&format!("\nLive log: {}", log_path.display()));
+                }
+
+                IsolationResult {
+                    success: true,
+                    session_name: Some(container_name),
+                    container_id: Some(container_id),
+                    message,
+                    ..Default::default()
+                }
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                IsolationResult {
+                    success: false,
+                    session_name: Some(container_name),
+                    message: format!("Failed to start {} container: {}", cli, stderr),
+                    ..Default::default()
+                }
+            }
+            Err(e) => IsolationResult {
+                success: false,
+                session_name: Some(container_name),
+                message: format!("Failed to run {}: {}", cli, e),
+                ..Default::default()
+            },
+        }
+    } else {
+        // Attached mode
+        let mut args = vec!["run"];
+        args.push(if has_tty() { "-it" } else { "-i" });
+        args.extend(["--name", &container_name]);
+
+        if let Some(ref user) = options.user {
+            args.push("--user");
+            args.push(user);
+        }
+
+        args.extend(build_docker_runtime_args(options));
+
+        if is_debug() {
+            eprintln!("[DEBUG] shell: {}", shell_to_use);
+        }
+
+        args.push(&image);
+        args.push(&shell_to_use);
+        if let Some(flag) = shell_interactive_flag {
+            args.push(flag);
+        }
+        args.extend(&["-c", command]);
+
+        let child = spawn_attached_docker(engine, &args, options.log_path.as_ref());
+
+        match child {
+            Ok(child) => match child.wait() {
+                Ok(s) => {
+                    let exit_code = s.code().unwrap_or(1);
+                    let mut message = format!(
+                        "{} container \"{}\" exited with code {}",
+                        engine.label(),
+                        container_name,
+                        exit_code
+                    );
+                    message.push_str(&rootless_note);
+                    let oom_killed =
+                        read_docker_container_oom_killed(engine, &container_name).unwrap_or(false);
+                    if should_cleanup_docker_container(cleanup_policy, exit_code, oom_killed) {
+                        let removed = remove_docker_container(
+                            engine,
+                            &container_name,
+                            options.log_path.as_ref(),
+                        );
+                        emit_event(
+                            "cleanup",
+                            json!({"kind": "container", "target": container_name, "success": removed}),
+                        );
+                        if removed {
+                            message.push_str("\nContainer removed after completion.");
+                        } else {
+                            message
+                                .push_str("\nWarning: failed to remove container automatically.");
+                            message.push_str(&format!(
+                                "\nRemove when done: {} rm -f {}",
+                                cli, container_name
+                            ));
+                        }
+                    } else if cleanup_policy == DockerContainerCleanupPolicy::Keep {
+                        message.push('\n');
+                        message.push_str(&docker_container_cleanup_instructions(
+                            engine,
+                            &container_name,
+                        ));
+                    } else if matches!(
+                        cleanup_policy,
+                        DockerContainerCleanupPolicy::KeepOnFail
+                            | DockerContainerCleanupPolicy::Default
+                    ) {
+                        if oom_killed {
+                            message.push_str(&format!(
+                                "\nContainer kept because {} reports it was OOM-killed.",
+                                engine.label()
+                            ));
+                        } else {
+                            message.push_str("\nContainer kept because the command failed.");
+                        }
+                        message.push_str(&format!(
+                            "\nRemove when done: {} rm -f {}",
+                            cli, container_name
+                        ));
+                    }
+
+                    IsolationResult {
+                        success: s.success(),
+                        session_name: Some(container_name.clone()),
+                        message,
+                        exit_code: Some(exit_code),
+                        ..Default::default()
+                    }
+                }
+                Err(e) => IsolationResult {
+                    success: false,
+                    session_name: Some(container_name),
+                    message: format!("Failed to wait for {}: {}", cli, e),
+                    ..Default::default()
+                },
+            },
+            Err(e) => IsolationResult {
+                success: false,
+                session_name: Some(container_name),
+                message: format!("Failed to start {}: {}", cli, e),
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Container image pulls (docker, podman) shown as a virtual command,
+//! extracted from isolation.rs
+
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use super::isolation_log::{append_log_file, get_timestamp};
+use crate::docker_cleanup::ContainerEngine;
+use crate::event_stream::emit_event;
+use crate::output_blocks::{
+    create_timeline_separator, create_virtual_command_block, create_virtual_command_result,
+};
+use crate::output_theme::OutputStyle;
+
+/// Pull a Docker image with output streaming
+///
+/// When `log_path` is provided, the image-preparation phase (the `docker pull`)
+/// is also recorded in the session log so the single log file is a gap-free
+/// record of everything that ran (issue #138): a `Preparing image …` marker with
+/// a timestamp is written before the pull, each line of pull output is teed into
+/// the log as it streams, and an `Image ready (<duration>)` marker is written
+/// afterwards. Without a `log_path` the behavior is unchanged.
+///
+/// Returns (success, output) tuple
+pub fn docker_pull_image(image: &str, log_path: Option<&PathBuf>) -> (bool, String) {
+    container_pull_image(ContainerEngine::Docker, image, log_path)
+}
+
+/// Pull an image with `engine` (see [`docker_pull_image`])
+pub(crate) fn container_pull_image(
+    engine: ContainerEngine,
+    image: &str,
+    log_path: Option<&PathBuf>,
+) -> (bool, String) {
+    let cli = engine.name();
+    // Virtual commands are part of the timeline, hidden with the start block
+    let show = OutputStyle::current().mode.shows_start();
+
+    // Print the virtual command line followed by empty line for visual separation
+    if show {
+        println!(
+            "{}",
+            create_virtual_command_block(&format!("{} pull {}", cli, image))
+        );
+        println!();
+    }
+
+    // Record the start of the image-preparation phase in the session log so
+    // operators tailing the log see progress instead of a header-only file.
+    let prep_start = Instant::now();
+    if let Some(path) = log_path {
+        append_log_file(
+            path,
+            &format!(
+                "$ {} pull {}\nPreparing image {}… ({})\n",
+                cli,
+                image,
+                image,
+                get_timestamp()
+            ),
+        );
+    }
+
+    let mut child = match Command::new(engine.command())
+        .args(["pull", image])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            let error_msg = format!("Failed to run {} pull: {}", cli, e);
+            eprintln!("{}", error_msg);
+            if let Some(path) = log_path {
+                append_log_file(
+                    path,
+                    &format!(
+                        "{}\nImage preparation failed ({:.1}s)\n",
+                        error_msg,
+                        prep_start.elapsed().as_secs_f64()
+                    ),
+                );
+            }
+            if show {
+                println!();
+                println!("{}", create_virtual_command_result(false));
+            }
+            emit_image_pulled(image, false, prep_start);
+            return (false, error_msg);
+        }
+    };
+
+    let mut output = String::new();
+
+    // Read and display stdout, teeing each line into the session log.
+    if let Some(stdout) = child.stdout.take() {
+        let reader = BufReader::new(stdout);
+        for line in reader.lines().map_while(Result::ok) {
+            if show {
+                println!("{}", line);
+            }
+            if let Some(path) = log_path {
+                append_log_file(path, &format!("{}\n", line));
+            }
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+
+    // Read and display stderr, teeing each line into the session log.
+    if let Some(stderr) = child.stderr.take() {
+        let reader = BufReader::new(stderr);
+        for line in reader.lines().map_while(Result::ok) {
+            if show {
+                eprintln!("{}", line);
+            }
+            if let Some(path) = log_path {
+                append_log_file(path, &format!("{}\n", line));
+            }
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+
+    let success = child.wait().map(|s| s.success()).unwrap_or(false);
+    emit_image_pulled(image, success, prep_start);
+
+    // Record the end of the image-preparation phase with elapsed duration so the
+    // prep time is visible even when full progress is unavailable (issue #138).
+    if let Some(path) = log_path {
+        let duration = prep_start.elapsed().as_secs_f64();
+        append_log_file(
+            path,
+            &if success {
+                format!("Image ready ({:.1}s)\n", duration)
+            } else {
+                format!("Image preparation failed ({:.1}s)\n", duration)
+            },
+        );
+    }
+
+    if !show {
+        // A failed pull is still explained when the timeline is hidden
+        if !success {
+            eprint!("{}", output);
+        }
+        return (success, output);
+    }
+
+    // Print empty line before result marker for visual separation (issue #73)
+    // This ensures output is visually separated from the result marker
+    println!();
+    println!("{}", create_virtual_command_result(success));
+    println!("{}", create_timeline_separator());
+
+    (success, output)
+}
+
+/// Report the end of a pull on the --events stream
+fn emit_image_pulled(image: &str, success: bool, prep_start: Instant) {
+    emit_event(
+        "image-pulled",
+        serde_json::json!({
+            "image": image,
+            "success": success,
+            "durationMs": prep_start.elapsed().as_millis() as u64,
+        }),
+    );
+}
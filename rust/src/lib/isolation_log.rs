@@ -2,8 +2,13 @@
 
 use std::env;
 use std::fs::{self, File, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+
+use serde_json::json;
+
+use crate::event_stream::emit_event;
 
 /// Generate timestamp for logging
 pub fn get_timestamp() -> String {
@@ -166,6 +171,66 @@ pub fn create_log_path_for_execution(environment: &str, execution_id: &str) -> P
     }
 }
 
+/// Path of the separate stderr log recorded next to `log_path` (`<name>.stderr.log`)
+pub fn stderr_log_path(log_path: &Path) -> PathBuf {
+    let stem = log_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    log_path.with_file_name(format!("{}.stderr.log", stem))
+}
+
+/// Stream a child's stdout and stderr into `log_path` (stderr also into its
+/// stderr log) as they arrive, echoing each line to the terminal when `echo`
+/// is set and emitting an `output` event per line. Both streams are read on
+/// their own thread to avoid deadlocks.
+/// Returns the captured (stdout, stderr).
+pub fn tee_child_output(child: &mut Child, log_path: &Path, echo: bool) -> (String, String) {
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let stdout_log_path = log_path.to_path_buf();
+    let stdout_handle = std::thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stdout) = stdout {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if echo {
+                    println!("{}", line);
+                }
+                emit_event("output", json!({"stream": "stdout", "line": line}));
+                append_log_file(&stdout_log_path, &format!("{}\n", line));
+                output.push_str(&line);
+                output.push('\n');
+            }
+        }
+        output
+    });
+
+    let stderr_only_log_path = stderr_log_path(log_path);
+    let combined_log_path = log_path.to_path_buf();
+    let stderr_handle = std::thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                if echo {
+                    eprintln!("{}", line);
+                }
+                emit_event("output", json!({"stream": "stderr", "line": line}));
+                append_log_file(&combined_log_path, &format!("{}\n", line));
+                append_log_file(&stderr_only_log_path, &format!("{}\n", line));
+                output.push_str(&line);
+                output.push('\n');
+            }
+        }
+        output
+    });
+
+    (
+        stdout_handle.join().unwrap_or_default(),
+        stderr_handle.join().unwrap_or_default(),
+    )
+}
+
 pub fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\\''"))
 }
@@ -192,6 +257,31 @@ pub fn wrap_command_with_log_footer(command: &str, shell: &str, keep_alive: bool
     )
 }
 
+/// Folder on the remote host holding the logs of detached ssh executions
+pub const SSH_REMOTE_LOG_DIR: &str = "/tmp/start-command/logs/isolation/ssh";
+
+/// Log path on the remote host of a detached ssh execution
+pub fn ssh_remote_log_path(session_name: &str) -> String {
+    format!("{}/{}.log", SSH_REMOTE_LOG_DIR, session_name)
+}
+
+/// Remote command starting a detached ssh execution in the background. Its
+/// output goes to the remote log, which ends with the usual exit code footer.
+pub fn ssh_detached_command(
+    shell_invocation: &str,
+    shell: &str,
+    command: &str,
+    session_name: &str,
+) -> String {
+    format!(
+        "mkdir -p {} && nohup {} -c {} > {} 2>&1 &",
+        SSH_REMOTE_LOG_DIR,
+        shell_invocation,
+        shell_quote(&wrap_command_with_log_footer(command, shell, false)),
+        shell_quote(&ssh_remote_log_path(session_name))
+    )
+}
+
 /// Get the default Docker image based on the host operating system
 /// Returns an image that matches the current OS as closely as possible:
 /// - macOS: Uses alpine (since macOS cannot run in Docker)
@@ -3,13 +3,31 @@
 //! Builds the human-readable `[Isolation]` status lines and the execution
 //! record options map that describe how an isolated command was launched,
 //! including the configurable Docker runtime options (volumes, mounts,
-//! environment variables, privileged mode). Kept separate from `isolation`
-//! so the runtime backends and the metadata representation can evolve
-//! independently.
+//! environment variables, privileged mode) and the levels of a stacked
+//! isolation. Kept separate from `isolation` so the runtime backends and the
+//! metadata representation can evolve independently.
 
 use crate::args_parser::WrapperOptions;
+use crate::sequence_parser::{format_isolation_chain, IsolationChainOptions};
 use std::collections::HashMap;
 
+/// Build the `[Isolation] Chain` status line of a stacked isolation, such as
+/// `screen → ssh@user@host → docker:ubuntu`. Empty for a single backend.
+pub fn isolation_stack_status_lines(options: &WrapperOptions) -> Vec<String> {
+    if options.isolated_stack.len() <= 1 {
+        return Vec::new();
+    }
+    let stack: Vec<Option<String>> = options.isolated_stack.iter().cloned().map(Some).collect();
+    let chain = format_isolation_chain(
+        &stack,
+        &IsolationChainOptions {
+            endpoint_stack: options.endpoint_stack.clone(),
+            image_stack: options.image_stack.clone(),
+        },
+    );
+    vec![format!("[Isolation] Chain: {}", chain)]
+}
+
 /// Build the human-readable `[Isolation]` status lines for docker runtime
 /// options (volumes, mounts, env, privileged). Used for the start block and
 /// log header; empty collections contribute no lines.
@@ -70,8 +88,9 @@ pub fn docker_runtime_metadata(
 
 /// Build the execution-record options map describing how an isolated command
 /// was launched (environment, mode, session, image, docker runtime options,
-/// endpoint, user, keep-alive). Used to persist the execution record so it can
-/// be surfaced via `--status`/`--list`.
+/// endpoint, user, keep-alive). A stacked isolation also records each level's
+/// backend and session name (`isolationStack`, `sessionStack`). Used to
+/// persist the execution record so it can be surfaced via `--status`/`--list`.
 pub fn build_isolation_options_map(
     environment: Option<&str>,
     mode: &str,
@@ -104,6 +123,23 @@ pub fn build_isolation_options_map(
     if let Some(v) = created_user {
         opts_map.insert("user".to_string(), str_val(v));
     }
+    if options.isolated_stack.len() > 1 {
+        let sessions = std::iter::once(session_name).chain(
+            options
+                .session_stack
+                .iter()
+                .skip(1)
+                .map(|s| s.as_deref().unwrap_or("")),
+        );
+        opts_map.insert(
+            "isolationStack".to_string(),
+            serde_json::Value::Array(options.isolated_stack.iter().map(|b| str_val(b)).collect()),
+        );
+        opts_map.insert(
+            "sessionStack".to_string(),
+            serde_json::Value::Array(sessions.map(str_val).collect()),
+        );
+    }
     opts_map.insert(
         "keepAlive".to_string(),
         serde_json::Value::Bool(options.keep_alive),
@@ -8,6 +8,7 @@ use std::time::Duration;
 
 use super::{get_shell, is_debug, wrap_command_with_user, IsolationResult};
 use crate::isolation::isolation_log::{get_temp_dir, wrap_command_with_log_footer};
+use crate::output_theme::OutputStyle;
 
 /// Get the installed screen version
 pub fn get_screen_version() -> Option<(u32, u32, u32)> {
@@ -297,9 +298,9 @@ pub fn run_screen_with_log_capture(
             let output = read_log_with_retry();
             let exit_code = read_exit_code();
 
-            // Display output
+            // Display output (unless --silent or --summary-only)
             if let Some(ref out) = output {
-                if !out.trim().is_empty() {
+                if !out.trim().is_empty() && OutputStyle::current().mode.shows_output() {
                     print!("{}", out);
                 }
             }
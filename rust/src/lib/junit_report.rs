@@ -0,0 +1,229 @@
+//! JUnit XML report of executions (`--junit <file>`)
+//!
+//! Writes a set of executions as JUnit test cases so CI dashboards can show
+//! `$`-wrapped steps like test results. The executions are those matching the
+//! record filters (as for `--list`, including `--tag`/`--label`), optionally
+//! narrowed to the nested executions of one parent (`--parent <id>`). Each
+//! execution becomes a `<testcase>`:
+//! - `name` is the command and `classname` is `start.<backend>`
+//! - `time` is the duration in seconds
+//! - a non-zero exit code becomes a `<failure>` with the exit code as message
+//! - a still executing run is `<skipped>`
+//! - the last [`JUNIT_LOG_TAIL_LINES`] lines of the log are the `<system-out>`
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::execution_stats::record_duration_ms;
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::log_viewer::{read_stored_log, split_log, strip_ansi};
+use crate::record_filter::{record_backend, RecordFilter};
+use crate::status_formatter::{enrich_detached_status, StatusQueryResult};
+
+/// Lines from the end of each log included as `<system-out>`
+pub const JUNIT_LOG_TAIL_LINES: usize = 200;
+
+/// Escape text for XML content and attributes, dropping characters that
+/// XML 1.0 does not allow (such as the escape character of ANSI sequences)
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Records nested (at any depth) under the execution `parent_uuid`
+pub fn descendants_of(records: Vec<ExecutionRecord>, parent_uuid: &str) -> Vec<ExecutionRecord> {
+    let parents: HashMap<String, String> = records
+        .iter()
+        .filter_map(|r| Some((r.uuid.clone(), r.parent_uuid.clone()?)))
+        .collect();
+    records
+        .into_iter()
+        .filter(|record| {
+            let mut current = record.parent_uuid.as_deref();
+            // Bounded walk so a corrupted cycle cannot loop forever
+            for _ in 0..=parents.len() {
+                match current {
+                    Some(uuid) if uuid == parent_uuid => return true,
+                    Some(uuid) => current = parents.get(uuid).map(String::as_str),
+                    None => return false,
+                }
+            }
+            false
+        })
+        .collect()
+}
+
+/// The last `lines` lines of a record's command output, without ANSI escapes
+pub fn log_tail(record: &ExecutionRecord, lines: usize) -> String {
+    if record.log_path.is_empty() {
+        return String::new();
+    }
+    let Ok(content) = read_stored_log(Path::new(&record.log_path)) else {
+        return String::new();
+    };
+    let output = strip_ansi(split_log(&content).output);
+    let all: Vec<&str> = output.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+fn seconds(duration_ms: Option<f64>) -> String {
+    format!("{:.3}", duration_ms.unwrap_or(0.0) / 1000.0)
+}
+
+/// Render `(record, log tail)` pairs as a JUnit XML document with one test
+/// suite named `suite`
+pub fn render_junit(records: &[(ExecutionRecord, String)], suite: &str, timestamp: &str) -> String {
+    let failures = records
+        .iter()
+        .filter(|(r, _)| r.status == ExecutionStatus::Executed && r.exit_code != Some(0))
+        .count();
+    let skipped = records
+        .iter()
+        .filter(|(r, _)| r.status == ExecutionStatus::Executing)
+        .count();
+    let total_ms: f64 = records
+        .iter()
+        .filter_map(|(r, _)| record_duration_ms(r))
+        .sum();
+    let counts = format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\" time=\"{}\"",
+        records.len(),
+        failures,
+        skipped,
+        seconds(Some(total_ms))
+    );
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" {}>\n",
+        xml_escape(suite),
+        counts
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" {} timestamp=\"{}\">\n",
+        xml_escape(suite),
+        counts,
+        xml_escape(timestamp)
+    ));
+    for (record, tail) in records {
+        xml.push_str(&testcase(record, tail));
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn testcase(record: &ExecutionRecord, tail: &str) -> String {
+    let mut xml = format!(
+        "    <testcase name=\"{}\" classname=\"start.{}\" time=\"{}\">\n",
+        xml_escape(&record.command),
+        xml_escape(record_backend(record)),
+        seconds(record_duration_ms(record))
+    );
+    xml.push_str("      <properties>\n");
+    for (name, value) in [
+        ("uuid", record.uuid.as_str()),
+        ("startTime", record.start_time.as_str()),
+        ("logPath", record.log_path.as_str()),
+    ] {
+        xml.push_str(&format!(
+            "        <property name=\"{}\" value=\"{}\"/>\n",
+            name,
+            xml_escape(value)
+        ));
+    }
+    xml.push_str("      </properties>\n");
+    match (record.status, record.exit_code) {
+        (ExecutionStatus::Executing, _) => {
+            xml.push_str("      <skipped message=\"Still executing\"/>\n");
+        }
+        (_, Some(0)) => {}
+        (_, code) => {
+            let message = match code {
+                Some(code) => format!("Exited with code {}", code),
+                None => "Exited without an exit code".to_string(),
+            };
+            xml.push_str(&format!(
+                "      <failure message=\"{}\" type=\"ExitCode\">{}\nLog: {}</failure>\n",
+                xml_escape(&message),
+                xml_escape(&format!("{} failed", record.command)),
+                xml_escape(&record.log_path)
+            ));
+        }
+    }
+    if !tail.is_empty() {
+        xml.push_str(&format!(
+            "      <system-out>{}</system-out>\n",
+            xml_escape(tail)
+        ));
+    }
+    xml.push_str("    </testcase>\n");
+    xml
+}
+
+/// Handle --junit: write the filtered executions (oldest first), or the
+/// executions nested under `parent`, to `output` as JUnit XML
+pub fn query_junit(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    parent: Option<&str>,
+    output: &Path,
+) -> StatusQueryResult {
+    let failure = |error: String| StatusQueryResult {
+        success: false,
+        output: None,
+        error: Some(error),
+    };
+    let Some(store) = store else {
+        return failure("Execution tracking is disabled.".to_string());
+    };
+
+    let mut records: Vec<ExecutionRecord> =
+        store.get_all().iter().map(enrich_detached_status).collect();
+    let mut suite = "start-command".to_string();
+    if let Some(identifier) = parent {
+        let Some(parent) = store.get(identifier) else {
+            return failure(format!(
+                "No execution found with UUID or session name: {}",
+                identifier
+            ));
+        };
+        records = descendants_of(records, &parent.uuid);
+        suite = parent.command;
+    }
+    let mut records = filter.apply(records);
+    records.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+    let cases: Vec<(ExecutionRecord, String)> = records
+        .into_iter()
+        .map(|record| {
+            let tail = log_tail(&record, JUNIT_LOG_TAIL_LINES);
+            (record, tail)
+        })
+        .collect();
+    let xml = render_junit(&cases, &suite, &chrono::Utc::now().to_rfc3339());
+    match fs::write(output, xml) {
+        Ok(()) => StatusQueryResult {
+            success: true,
+            output: Some(format!(
+                "Wrote JUnit report of {} executions to {}",
+                cases.len(),
+                output.display()
+            )),
+            error: None,
+        },
+        Err(e) => failure(format!("Failed to write {}: {}", output.display(), e)),
+    }
+}
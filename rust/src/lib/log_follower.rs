@@ -0,0 +1,257 @@
+//! Following the live log of a tracked execution (`--follow`)
+//!
+//! Streams an execution's log as it grows, like `tail -f`, and stops once the
+//! `Exit Code:` footer is written, returning that exit code. Local logs
+//! (direct runs and detached screen/tmux/docker sessions) are polled; the log
+//! of a detached ssh execution lives on the remote host and is streamed with
+//! `tail -F` over ssh.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::isolation::isolation_log::shell_quote;
+use crate::isolation::ssh_remote_log_path;
+
+/// How often a local log is checked for new output
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How often the store is checked for an execution that ended without footer
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Settings for --follow
+#[derive(Debug, Clone, Default)]
+pub struct FollowOptions {
+    /// Start with only the last N lines of the log (--tail)
+    pub tail: Option<usize>,
+}
+
+/// Where the log of an execution is written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogSource {
+    Local(PathBuf),
+    /// Log of a detached ssh execution on the remote host
+    Remote {
+        endpoint: String,
+        path: String,
+    },
+}
+
+/// Log source of `record`
+pub fn log_source(record: &ExecutionRecord) -> Result<LogSource, String> {
+    let option = |key: &str| record.options.get(key).and_then(|value| value.as_str());
+    if option("isolated") == Some("ssh") && option("isolationMode") == Some("detached") {
+        if let (Some(endpoint), Some(session)) = (option("endpoint"), option("sessionName")) {
+            return Ok(LogSource::Remote {
+                endpoint: endpoint.to_string(),
+                path: ssh_remote_log_path(session),
+            });
+        }
+    }
+    if record.log_path.is_empty() {
+        return Err("Execution record does not have a log path.".to_string());
+    }
+    Ok(LogSource::Local(PathBuf::from(&record.log_path)))
+}
+
+/// Detects the footer (a line of `=`, `Finished:` and `Exit Code:`) that ends
+/// every log, in output that may arrive in arbitrary chunks. A bare
+/// `Exit Code:` line printed by the command itself is not mistaken for it.
+#[derive(Debug, Default)]
+pub struct LogFooterScanner {
+    partial: String,
+    recent: VecDeque<String>,
+}
+
+impl LogFooterScanner {
+    /// Scan the next chunk of output, returning the exit code once the
+    /// footer is complete
+    pub fn feed(&mut self, chunk: &str) -> Option<i32> {
+        self.partial.push_str(chunk);
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            let line = line.trim_end_matches(['\n', '\r']).to_string();
+            let exit_code = line
+                .strip_prefix("Exit Code:")
+                .and_then(|code| code.trim().parse::<i32>().ok());
+            if let (Some(code), [separator, finished]) = (exit_code, self.recent.make_contiguous())
+            {
+                if separator.len() >= 3
+                    && separator.chars().all(|c| c == '=')
+                    && finished.starts_with("Finished:")
+                {
+                    return Some(code);
+                }
+            }
+            if self.recent.len() == 2 {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(line);
+        }
+        None
+    }
+
+    /// The `Finished:` time of the footer the last [`feed`](Self::feed)
+    /// call completed
+    pub fn finished(&self) -> Option<&str> {
+        self.recent.back()?.strip_prefix("Finished:").map(str::trim)
+    }
+}
+
+/// The last `lines` lines of `content`
+pub fn tail_lines(content: &str, lines: usize) -> &str {
+    if lines == 0 {
+        return "";
+    }
+    let trimmed = content.strip_suffix('\n').unwrap_or(content);
+    match trimmed.rmatch_indices('\n').nth(lines - 1) {
+        Some((index, _)) => &content[index + 1..],
+        None => content,
+    }
+}
+
+/// Follow a local log file, writing its output to `out` until the footer
+/// appears. `finished` is asked now and then for the exit code of an
+/// execution that ended without writing a footer (e.g. it was killed).
+pub fn follow_file(
+    path: &Path,
+    options: &FollowOptions,
+    out: &mut dyn Write,
+    mut finished: impl FnMut() -> Option<i32>,
+) -> Result<i32, String> {
+    let mut file =
+        File::open(path).map_err(|_| format!("Log file not found: {}", path.display()))?;
+    let mut scanner = LogFooterScanner::default();
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let text = String::from_utf8_lossy(&content);
+    let footer = scanner.feed(&text);
+    let shown = options
+        .tail
+        .map_or(&*text, |lines| tail_lines(&text, lines));
+    write_output(out, shown.as_bytes())?;
+    if let Some(code) = footer {
+        return Ok(code);
+    }
+
+    let mut last_status_check = Instant::now();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read > 0 {
+            write_output(out, &buffer[..read])?;
+            if let Some(code) = scanner.feed(&String::from_utf8_lossy(&buffer[..read])) {
+                return Ok(code);
+            }
+            continue;
+        }
+        // Truncated (e.g. rotated): start over from the beginning
+        let position = file.stream_position().unwrap_or(0);
+        if file
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() < position)
+        {
+            let _ = file.seek(SeekFrom::Start(0));
+            continue;
+        }
+        if last_status_check.elapsed() >= STATUS_INTERVAL {
+            last_status_check = Instant::now();
+            if let Some(code) = finished() {
+                // Drain what was written just before the execution ended
+                let mut rest = Vec::new();
+                let _ = file.read_to_end(&mut rest);
+                write_output(out, &rest)?;
+                return Ok(code);
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn write_output(out: &mut dyn Write, bytes: &[u8]) -> Result<(), String> {
+    out.write_all(bytes)
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("Failed to write output: {}", e))
+}
+
+/// Follow the log of a detached ssh execution through `ssh <endpoint> tail -F`
+fn follow_remote(
+    endpoint: &str,
+    path: &str,
+    options: &FollowOptions,
+    out: &mut dyn Write,
+) -> Result<i32, String> {
+    let lines = options
+        .tail
+        .map_or("+1".to_string(), |lines| lines.to_string());
+    let mut child = Command::new("ssh")
+        .arg(endpoint)
+        .arg(format!("tail -n {} -F {}", lines, shell_quote(path)))
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "Failed to read ssh output".to_string())?;
+
+    let mut scanner = LogFooterScanner::default();
+    let mut reader = BufReader::new(stdout);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                write_output(out, &line)?;
+                if let Some(code) = scanner.feed(&String::from_utf8_lossy(&line)) {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Ok(code);
+                }
+            }
+        }
+    }
+    let _ = child.wait();
+    Err(format!(
+        "Lost connection to {} before the execution finished",
+        endpoint
+    ))
+}
+
+/// Follow the log of a stored execution by UUID or session name, returning
+/// the exit code written to its footer
+pub fn follow_execution_log(
+    store: Option<&ExecutionStore>,
+    identifier: &str,
+    options: &FollowOptions,
+) -> Result<i32, String> {
+    let store = store.ok_or_else(|| "Execution tracking is disabled.".to_string())?;
+    let record = store.get(identifier).ok_or_else(|| {
+        format!(
+            "No execution found with UUID or session name: {}",
+            identifier
+        )
+    })?;
+
+    let mut stdout = io::stdout();
+    match log_source(&record)? {
+        LogSource::Local(path) => follow_file(&path, options, &mut stdout, || {
+            store
+                .get(&record.uuid)
+                .filter(|current| current.status == ExecutionStatus::Executed)
+                .map(|current| current.exit_code.unwrap_or(1))
+        }),
+        LogSource::Remote { endpoint, path } => {
+            follow_remote(&endpoint, &path, options, &mut stdout)
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Viewing the stored log of a tracked execution (`--log`)
+//!
+//! Prints an execution's log with ANSI escape sequences kept or stripped,
+//! optionally without the header and footer written by start-command, only
+//! its stderr (when a separate stderr log was recorded), a line range or the
+//! last N lines, and optionally through `$PAGER`. Gzip-compressed logs are
+//! decompressed transparently, and truncated logs (no exit code footer, or
+//! invalid UTF-8 cut mid-character) are shown as far as they go.
+
+use std::fs;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use flate2::read::GzDecoder;
+use regex::Regex;
+
+use crate::execution_store::ExecutionStore;
+use crate::isolation::stderr_log_path;
+
+/// Valid values for --ansi
+pub const VALID_ANSI_MODES: [&str; 3] = ["auto", "keep", "strip"];
+
+/// What to do with ANSI escape sequences in the log
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnsiMode {
+    /// Keep them when writing to a terminal, strip them otherwise
+    #[default]
+    Auto,
+    Keep,
+    Strip,
+}
+
+impl AnsiMode {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "auto" => Ok(AnsiMode::Auto),
+            "keep" => Ok(AnsiMode::Keep),
+            "strip" => Ok(AnsiMode::Strip),
+            _ => Err(format!(
+                "Invalid ANSI mode: \"{}\". Valid options are: {}",
+                value,
+                VALID_ANSI_MODES.join(", ")
+            )),
+        }
+    }
+}
+
+/// 1-based inclusive line range (`10:20`, `10:`, `:20` or `15`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+/// Parse a --lines range
+pub fn parse_line_range(value: &str) -> Result<LineRange, String> {
+    let invalid = || {
+        format!(
+            "Invalid line range: \"{}\". Expected <start>:<end>, <start>:, :<end> or <line>",
+            value
+        )
+    };
+    let bound = |text: &str| -> Result<Option<usize>, String> {
+        match text.trim() {
+            "" => Ok(None),
+            number => match number.parse::<usize>() {
+                Ok(0) | Err(_) => Err(invalid()),
+                Ok(line) => Ok(Some(line)),
+            },
+        }
+    };
+    let range = match value.split_once(':') {
+        Some((start, end)) => LineRange {
+            start: bound(start)?,
+            end: bound(end)?,
+        },
+        None => {
+            let line = bound(value)?.ok_or_else(invalid)?;
+            LineRange {
+                start: Some(line),
+                end: Some(line),
+            }
+        }
+    };
+    match (range.start, range.end) {
+        (Some(start), Some(end)) if start > end => Err(invalid()),
+        _ => Ok(range),
+    }
+}
+
+/// Settings for --log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogViewOptions {
+    pub ansi: AnsiMode,
+    /// Drop the header and footer written by start-command
+    pub output_only: bool,
+    /// Show the separately recorded stderr instead of the combined log
+    pub stderr_only: bool,
+    pub lines: Option<LineRange>,
+    /// Only the last N lines (after --lines)
+    pub tail: Option<usize>,
+    /// Page the log through $PAGER
+    pub pager: bool,
+}
+
+/// A log split into the header and footer written by start-command and the
+/// command output between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogParts<'a> {
+    pub header: Option<&'a str>,
+    pub output: &'a str,
+    pub footer: Option<&'a str>,
+}
+
+impl LogParts<'_> {
+    /// Whether the log ends without its exit code footer
+    pub fn is_truncated(&self) -> bool {
+        self.header.is_some() && self.footer.is_none()
+    }
+}
+
+/// Split a stored log into header, command output and footer
+pub fn split_log(content: &str) -> LogParts<'_> {
+    let separator = "=".repeat(50);
+    let mut output = content;
+    let mut header = None;
+    if content.starts_with("=== Start Command Log ===") {
+        let marker = format!("\n{}\n", separator);
+        if let Some(index) = content.find(&marker) {
+            let mut end = index + marker.len();
+            // The header is followed by a blank line
+            if content[end..].starts_with('\n') {
+                end += 1;
+            }
+            header = Some(&content[..end]);
+            output = &content[end..];
+        }
+    }
+
+    let mut footer = None;
+    let marker = format!("\n{}\nFinished:", separator);
+    if let Some(index) = output.rfind(&marker) {
+        if output[index..].contains("\nExit Code:") {
+            footer = Some(&output[index..]);
+            output = &output[..index];
+        }
+    }
+    LogParts {
+        header,
+        output,
+        footer,
+    }
+}
+
+fn ansi_regex() -> &'static Regex {
+    static ANSI: OnceLock<Regex> = OnceLock::new();
+    ANSI.get_or_init(|| {
+        // CSI sequences (colors, cursor movement) and OSC sequences (titles, links)
+        Regex::new(r"\x1b\[[0-?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)|\x1b[@-Z\\-_]")
+            .expect("valid ANSI regex")
+    })
+}
+
+/// `text` without ANSI escape sequences
+pub fn strip_ansi(text: &str) -> String {
+    ansi_regex().replace_all(text, "").to_string()
+}
+
+/// Read a stored log, decompressing gzip logs (`.gz` path, or a `.gz` copy
+/// next to a log that no longer exists)
+pub fn read_stored_log(path: &Path) -> Result<String, String> {
+    let compressed = PathBuf::from(format!("{}.gz", path.display()));
+    let (path, gzip) = if path.exists() {
+        (
+            path.to_path_buf(),
+            path.extension().is_some_and(|e| e == "gz"),
+        )
+    } else if compressed.exists() {
+        (compressed, true)
+    } else {
+        return Err(format!("Log file not found: {}", path.display()));
+    };
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bytes = if gzip {
+        let mut data = Vec::new();
+        // A truncated archive still yields everything before the cut
+        let _ = GzDecoder::new(bytes.as_slice()).read_to_end(&mut data);
+        data
+    } else {
+        bytes
+    };
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Apply the view options (except paging) to the content of a log
+pub fn render_log(content: &str, options: &LogViewOptions, keep_ansi: bool) -> String {
+    let text = if options.output_only {
+        split_log(content).output
+    } else {
+        content
+    };
+    let mut lines: Vec<&str> = text.lines().collect();
+    if let Some(range) = options.lines {
+        let start = range.start.unwrap_or(1).min(lines.len() + 1);
+        let end = range.end.unwrap_or(lines.len()).min(lines.len());
+        lines = lines
+            .get(start - 1..end)
+            .map(<[&str]>::to_vec)
+            .unwrap_or_default();
+    }
+    if let Some(tail) = options.tail {
+        lines.drain(..lines.len().saturating_sub(tail));
+    }
+    let rendered = lines.join("\n");
+    if keep_ansi {
+        rendered
+    } else {
+        strip_ansi(&rendered)
+    }
+}
+
+/// Write `text` through `$PAGER` (default `less -R`)
+fn page(text: &str) -> Result<(), String> {
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| "less -R".to_string());
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&pager)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start pager \"{}\": {}", pager, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager may quit before reading everything
+        let _ = stdin.write_all(text.as_bytes());
+        let _ = stdin.write_all(b"\n");
+    }
+    child
+        .wait()
+        .map(|_| ())
+        .map_err(|e| format!("Pager \"{}\" failed: {}", pager, e))
+}
+
+/// Print the stored log of an execution by UUID or session name
+pub fn view_execution_log(
+    store: Option<&ExecutionStore>,
+    identifier: &str,
+    options: &LogViewOptions,
+) -> Result<(), String> {
+    let store = store.ok_or_else(|| "Execution tracking is disabled.".to_string())?;
+    let record = store.get(identifier).ok_or_else(|| {
+        format!(
+            "No execution found with UUID or session name: {}",
+            identifier
+        )
+    })?;
+    if record.log_path.is_empty() {
+        return Err("Execution record does not have a log path.".to_string());
+    }
+
+    let content = if options.stderr_only {
+        let path = stderr_log_path(Path::new(&record.log_path));
+        if !path.exists() {
+            return Err(
+                "No separate stderr log was recorded for this execution (only direct runs record one)"
+                    .to_string(),
+            );
+        }
+        read_stored_log(&path)?
+    } else {
+        let content = read_stored_log(Path::new(&record.log_path))?;
+        if split_log(&content).is_truncated() {
+            eprintln!(
+                "Note: the log ends without an exit code footer (still running or truncated)"
+            );
+        }
+        content
+    };
+
+    let keep_ansi = match options.ansi {
+        AnsiMode::Keep => true,
+        AnsiMode::Strip => false,
+        AnsiMode::Auto => options.pager || std::io::stdout().is_terminal(),
+    };
+    let rendered = render_log(&content, options, keep_ansi);
+    if options.pager {
+        return page(&rendered);
+    }
+    // A reader like `head` may close the pipe early; that is not an error
+    match writeln!(std::io::stdout().lock(), "{}", rendered) {
+        Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+            Err(format!("Failed to write log: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
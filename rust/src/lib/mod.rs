@@ -3,20 +3,43 @@
 //! Provides command execution with isolation, substitution, and failure handling.
 
 pub mod args_parser;
+pub mod ci_output;
+pub mod command_builder;
+pub mod dashboard;
 pub(crate) mod docker_cleanup;
+pub mod event_stream;
+pub mod execution_annotations;
 pub mod execution_control;
+pub mod execution_diff;
+pub mod execution_export;
+pub mod execution_grep;
+pub mod execution_lineage;
+pub mod execution_provenance;
+pub mod execution_repair;
+pub mod execution_schema;
+pub mod execution_stats;
 pub mod execution_store;
+pub mod execution_sync;
 pub mod failure_handler;
+pub mod html_report;
 pub mod isolation;
 pub mod isolation_metadata;
+pub mod junit_report;
+pub mod log_follower;
 pub mod log_uploader;
+pub mod log_viewer;
 pub mod output_blocks;
+pub mod output_theme;
+pub mod record_filter;
+pub mod record_template;
 pub mod sequence_parser;
 pub mod signal_handler;
 pub mod status_formatter;
+pub mod store_commands;
 pub mod substitution;
 pub mod usage;
 pub mod user_manager;
+pub mod version_info;
 
 mod lino_value_json;
 mod local_hostname;
@@ -24,8 +47,24 @@ mod local_hostname;
 // Re-export commonly used items
 pub use args_parser::{
     generate_session_name, generate_uuid, get_effective_mode, has_isolation, is_valid_uuid,
-    parse_args, validate_options, ParsedArgs, WrapperOptions, VALID_BACKENDS, VALID_OUTPUT_FORMATS,
-    VALID_SHELLS,
+    output_mode, parse_args, validate_options, ParsedArgs, WrapperOptions, CONTAINER_BACKENDS,
+    MAX_ISOLATION_DEPTH, VALID_BACKENDS, VALID_OUTPUT_FORMATS, VALID_SHELLS,
+};
+pub use ci_output::{
+    append_step_summary, ci_duration, ci_enabled, ci_from_env, ci_group, error_annotation,
+    escape_data, escape_property, report_ci_run, step_summary, CiRun, DISABLE_CI_ENV,
+    STEP_SUMMARY_ENV,
+};
+pub use command_builder::{build_next_level_command, level_program};
+pub use dashboard::{
+    attach_command, elapsed_label, log_preview, parse_key, replay_args, run_dashboard,
+    status_label, Dashboard, DashboardKey, DASHBOARD_KEYS,
+};
+pub use event_stream::{
+    emit_event, emit_finished, emit_started, event_line, EventStream, EventTarget, EVENT_TYPES,
+};
+pub use execution_annotations::{
+    annotate_execution, apply_launch_annotations, parse_label, parse_tag, query_annotate,
 };
 pub use execution_control::{
     collect_descendant_pids, collect_descendant_pids_with_runner, collect_process_ids,
@@ -34,22 +73,75 @@ pub use execution_control::{
     CommandRunOutput, CommandRunner, ControlAction, ControlCommand, ExecutionControlResult,
     SystemCommandRunner,
 };
+pub use execution_diff::{
+    compare_records, diff_executions, diff_lines, format_diff_report, normalize_log_line,
+    query_diff, unified_diff, DiffOp, ExecutionDiff, FieldChange,
+};
+pub use execution_export::{
+    export_records, import_records, parse_records, query_export, query_import, serialize_records,
+    ExportOptions, ExportSummary, ImportSummary, VALID_EXPORT_FORMATS,
+};
+pub use execution_grep::{
+    build_grep_regex, format_grep_report, grep_records, grep_text, query_grep, ExecutionMatches,
+    GrepLine, GrepOptions, GrepReport,
+};
+pub use execution_lineage::{
+    build_execution_trees, execution_tree_for, format_tree, format_trees, link_to_parent,
+    link_to_parent_execution, list_execution_trees, query_status_tree, ExecutionTree,
+    ExecutionTreeNode, EXECUTION_ID_ENV, ROOT_EXECUTION_ID_ENV,
+};
+pub use execution_provenance::{
+    capture_git, capture_provenance, parse_git_status, record_provenance, resolve_executable,
+    tool_versions, tools_used_by, ExecutionProvenance, GitProvenance, ToolVersions, PROVENANCE_ENV,
+};
+pub use execution_repair::{
+    format_repair_report, parse_links_records, query_repair, reconcile_stores,
+    recover_from_log_footers, Reconciliation, RecoveredExecution, RepairReport,
+};
+pub use execution_schema::{
+    migrate_record, query_schema, record_schema_version, CURRENT_SCHEMA_VERSION,
+    EXECUTION_RECORD_SCHEMA, LEGACY_SCHEMA_VERSION,
+};
+pub use execution_stats::{
+    compute_stats, format_stats_report, query_stats, GroupStats, StatsGrouping, StatsReport,
+    TrendBucket, VALID_STATS_GROUPS,
+};
 pub use execution_store::{
-    is_clink_installed, CleanupOptions, CleanupResult, ExecutionRecord, ExecutionRecordOptions,
-    ExecutionStats, ExecutionStatus, ExecutionStore, ExecutionStoreOptions,
+    is_clink_installed, shared_store_users, CleanupOptions, CleanupResult, ExecutionNote,
+    ExecutionRecord, ExecutionRecordOptions, ExecutionStats, ExecutionStatus, ExecutionStore,
+    ExecutionStoreOptions, MergeResult, SHARED_STORE_ENV,
+};
+pub use execution_sync::{
+    format_sync_report, query_sync, sync_store, SyncOptions, SyncReport, SYNC_BRANCH,
 };
 pub use failure_handler::{handle_failure, Config as FailureConfig};
+pub use html_report::{
+    ansi_to_html, html_escape, isolation_lines, query_report, render_html_report,
+    MAX_REPORT_LOG_LINES, VALID_REPORT_FORMATS,
+};
 pub use isolation::{
     append_log_file, build_shell_with_args_cmd_args, create_log_footer, create_log_header,
     create_log_path, create_log_path_for_execution, docker_image_exists, docker_pull_image,
     get_default_docker_image, get_timestamp, is_command_available, is_interactive_shell_command,
-    is_shell_invocation_with_args, run_as_isolated_user, run_isolated, write_log_file,
-    IsolationOptions, IsolationResult, LogHeaderParams,
+    is_shell_invocation_with_args, podman_image_reference, run_as_isolated_user, run_isolated,
+    tee_child_output, write_log_file, IsolationOptions, IsolationResult, LogHeaderParams,
 };
 pub use isolation_metadata::{
     build_isolation_options_map, docker_runtime_metadata, docker_runtime_status_lines,
+    isolation_stack_status_lines,
+};
+pub use junit_report::{
+    descendants_of, log_tail, query_junit, render_junit, xml_escape, JUNIT_LOG_TAIL_LINES,
+};
+pub use log_follower::{
+    follow_execution_log, follow_file, log_source, tail_lines, FollowOptions, LogFooterScanner,
+    LogSource,
 };
 pub use log_uploader::upload_execution_log;
+pub use log_viewer::{
+    parse_line_range, read_stored_log, render_log, split_log, strip_ansi, view_execution_log,
+    AnsiMode, LineRange, LogParts, LogViewOptions, VALID_ANSI_MODES,
+};
 #[allow(deprecated)]
 pub use output_blocks::{
     // Timeline format API (formerly "status spine")
@@ -77,21 +169,33 @@ pub use output_blocks::{
     SUCCESS_MARKER,
     TIMELINE_MARKER,
 };
+pub use output_theme::{
+    color_enabled, color_from_env, paint_result_marker, render_block, report_finish,
+    silent_failure_report, wrap_command, wrap_value, BlockLine, FinishReport, OutputMode,
+    OutputStyle, OutputTheme, SILENT_TAIL_LINES, THEME_ENV, VALID_THEMES,
+};
+pub use record_filter::{parse_time_bound, RecordFilter, VALID_OUTCOMES};
+pub use record_template::{
+    field_value, format_records_with_layout, list_executions_with_layout, parse_columns,
+    parse_template, query_status_with_layout, record_fields, render_table, render_template,
+    RecordLayout, TemplatePart,
+};
 pub use signal_handler::{
     clear_current_execution, get_signal_exit_code, set_current_execution, setup_signal_handlers,
     was_signal_received,
 };
 pub use status_formatter::{
-    attach_current_time, enrich_detached_status, format_record, format_record_as_links_notation,
-    format_record_as_links_notation_with_current_time, format_record_as_text,
-    format_record_as_text_with_current_time, format_record_list,
+    attach_current_time, enrich_detached_status, format_cleanup_report, format_record,
+    format_record_as_links_notation, format_record_as_links_notation_with_current_time,
+    format_record_as_text, format_record_as_text_with_current_time, format_record_list,
     format_record_list_as_links_notation, format_record_list_as_text,
-    format_record_with_current_time, is_detached_session_alive, list_executions, query_status,
-    StatusQueryResult,
+    format_record_with_current_time, is_detached_session_alive, list_executions,
+    list_executions_filtered, query_status, status_csv_header, value_to_yaml, StatusQueryResult,
 };
+pub use store_commands::run_store_command;
 pub use substitution::{process_command, ProcessOptions, SubstitutionResult};
 pub use usage::print_usage;
 pub use user_manager::{
     create_isolated_user, delete_user, get_current_user, get_current_user_groups, has_sudo_access,
-    CreateIsolatedUserOptions, DeleteUserOptions, UserOperationResult,
+    prepare_isolated_user, CreateIsolatedUserOptions, DeleteUserOptions, UserOperationResult,
 };
@@ -11,6 +11,8 @@
 
 use regex::Regex;
 
+use crate::output_theme::{paint_result_marker, render_block, BlockLine, OutputStyle};
+
 /// Timeline marker character (formerly called "spine")
 /// Used to prefix metadata lines in the timeline format
 pub const TIMELINE_MARKER: &str = "│";
@@ -61,17 +63,16 @@ pub fn create_virtual_command_block(command: &str) -> String {
 }
 
 /// Create a result marker line for a virtual command
+/// (themed and colored with the installed [`OutputStyle`])
 pub fn create_virtual_command_result(success: bool) -> String {
-    if success {
-        SUCCESS_MARKER.to_string()
-    } else {
-        FAILURE_MARKER.to_string()
-    }
+    let style = OutputStyle::current();
+    paint_result_marker(style.theme, success, style.color)
 }
 
 /// Create a separator line between virtual commands and user commands
+/// (the installed [`OutputStyle`]'s marker)
 pub fn create_timeline_separator() -> String {
-    create_empty_timeline_line()
+    OutputStyle::current().theme.marker().to_string()
 }
 
 /// Get the result marker based on exit code
@@ -134,23 +135,23 @@ pub fn parse_isolation_metadata(extra_lines: &[&str]) -> IsolationMetadata {
     metadata
 }
 
-/// Generate isolation metadata lines for timeline format
-pub fn generate_isolation_lines(
+/// Labeled isolation metadata shown in the start and finish blocks
+fn isolation_fields(
     metadata: &IsolationMetadata,
     container_or_screen_name: Option<&str>,
-) -> Vec<String> {
-    let mut lines = Vec::new();
+) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
 
     if let Some(ref isolation) = metadata.isolation {
-        lines.push(create_timeline_line("isolation", isolation));
+        fields.push(("isolation", isolation.clone()));
     }
 
     if let Some(ref mode) = metadata.mode {
-        lines.push(create_timeline_line("mode", mode));
+        fields.push(("mode", mode.clone()));
     }
 
     if let Some(ref image) = metadata.image {
-        lines.push(create_timeline_line("image", image));
+        fields.push(("image", image.clone()));
     }
 
     // Use provided container/screen name or fall back to metadata.session
@@ -161,12 +162,12 @@ pub fn generate_isolation_lines(
 
         if let Some(name) = name {
             match isolation.as_str() {
-                "docker" => lines.push(create_timeline_line("container", &name)),
-                "screen" => lines.push(create_timeline_line("screen", &name)),
-                "tmux" => lines.push(create_timeline_line("tmux", &name)),
+                "docker" | "podman" => fields.push(("container", name)),
+                "screen" => fields.push(("screen", name)),
+                "tmux" => fields.push(("tmux", name)),
                 "ssh" => {
                     if let Some(ref endpoint) = metadata.endpoint {
-                        lines.push(create_timeline_line("endpoint", endpoint));
+                        fields.push(("endpoint", endpoint.clone()));
                     }
                 }
                 _ => {}
@@ -175,10 +176,48 @@ pub fn generate_isolation_lines(
     }
 
     if let Some(ref user) = metadata.user {
-        lines.push(create_timeline_line("user", user));
+        fields.push(("user", user.clone()));
     }
 
-    lines
+    fields
+}
+
+/// Generate isolation metadata lines for timeline format
+pub fn generate_isolation_lines(
+    metadata: &IsolationMetadata,
+    container_or_screen_name: Option<&str>,
+) -> Vec<String> {
+    isolation_fields(metadata, container_or_screen_name)
+        .iter()
+        .map(|(label, value)| create_timeline_line(label, value))
+        .collect()
+}
+
+/// Blank line and isolation fields of the extra lines, if they describe an
+/// isolation environment. A stacked isolation adds its `chain` after the
+/// outermost `isolation` level.
+fn isolation_block_lines(extra_lines: Option<&Vec<&str>>) -> Vec<BlockLine> {
+    let Some(extra) = extra_lines else {
+        return Vec::new();
+    };
+    let metadata = parse_isolation_metadata(extra);
+    if metadata.isolation.is_none() {
+        return Vec::new();
+    }
+    let mut fields = isolation_fields(&metadata, None);
+    if let Some(chain) = extra
+        .iter()
+        .find_map(|line| line.strip_prefix("[Isolation] Chain: "))
+    {
+        fields.insert(1, ("chain", chain.to_string()));
+    }
+    std::iter::once(BlockLine::Blank)
+        .chain(
+            fields
+                .into_iter()
+                .map(|(label, value)| BlockLine::Field(label, value)),
+        )
+        .collect()
 }
 
 /// Options for creating a start block
@@ -187,8 +226,12 @@ pub struct StartBlockOptions<'a> {
     pub timestamp: &'a str,
     pub command: &'a str,
     pub extra_lines: Option<Vec<&'a str>>,
+    /// Theme name (timeline, ascii, compact, boxed); timeline when unknown
     pub style: Option<&'a str>,
+    /// Wrap long values and commands to this width
     pub width: Option<usize>,
+    /// Color the result markers
+    pub color: bool,
     /// If true, the command line is omitted from the start block
     /// (useful when virtual commands will be shown before the actual command)
     pub defer_command: bool,
@@ -196,31 +239,27 @@ pub struct StartBlockOptions<'a> {
 
 /// Create a start block for command execution using timeline format
 pub fn create_start_block(options: &StartBlockOptions) -> String {
-    let mut lines = Vec::new();
-
     // Header: session and start time
-    lines.push(create_timeline_line("session", options.session_id));
-    lines.push(create_timeline_line("start", options.timestamp));
+    let mut lines = vec![
+        BlockLine::Field("session", options.session_id.to_string()),
+        BlockLine::Field("start", options.timestamp.to_string()),
+    ];
 
     // Parse and add isolation metadata if present
-    if let Some(ref extra) = options.extra_lines {
-        let metadata = parse_isolation_metadata(extra);
-
-        if metadata.isolation.is_some() {
-            lines.push(create_empty_timeline_line());
-            lines.extend(generate_isolation_lines(&metadata, None));
-        }
-    }
+    lines.extend(isolation_block_lines(options.extra_lines.as_ref()));
 
     // Empty timeline line before command (always needed for separation)
-    lines.push(create_empty_timeline_line());
+    lines.push(BlockLine::Blank);
 
     // Command line (unless deferred for virtual command handling)
     if !options.defer_command {
-        lines.push(create_command_line(options.command));
+        lines.push(BlockLine::Command(options.command.to_string()));
     }
 
-    lines.join("\n")
+    render_block(
+        &lines,
+        &OutputStyle::from_options(options.style, options.width, options.color),
+    )
 }
 
 /// Format duration in seconds with appropriate precision
@@ -247,8 +286,12 @@ pub struct FinishBlockOptions<'a> {
     pub duration_ms: Option<f64>,
     pub result_message: Option<&'a str>,
     pub extra_lines: Option<Vec<&'a str>>,
+    /// Theme name (timeline, ascii, compact, boxed); timeline when unknown
     pub style: Option<&'a str>,
+    /// Wrap long values and paths to this width
     pub width: Option<usize>,
+    /// Color the result markers
+    pub color: bool,
 }
 
 /// Create a finish block for command execution using timeline format
@@ -263,40 +306,32 @@ pub struct FinishBlockOptions<'a> {
 /// 7. log path (always second-to-last)
 /// 8. session ID (always last)
 pub fn create_finish_block(options: &FinishBlockOptions) -> String {
-    let mut lines = Vec::new();
-
     // Result marker appears first in footer (after program output)
-    lines.push(get_result_marker(options.exit_code).to_string());
-
-    // Finish metadata
-    lines.push(create_timeline_line("finish", options.timestamp));
+    let mut lines = vec![
+        BlockLine::Result(options.exit_code == 0),
+        BlockLine::Field("finish", options.timestamp.to_string()),
+    ];
 
     if let Some(duration_ms) = options.duration_ms {
-        lines.push(create_timeline_line(
-            "duration",
-            &format_duration(duration_ms),
-        ));
+        lines.push(BlockLine::Field("duration", format_duration(duration_ms)));
     }
 
-    lines.push(create_timeline_line("exit", &options.exit_code.to_string()));
+    lines.push(BlockLine::Field("exit", options.exit_code.to_string()));
 
     // Repeat isolation metadata if present
-    if let Some(ref extra) = options.extra_lines {
-        let metadata = parse_isolation_metadata(extra);
-        if metadata.isolation.is_some() {
-            lines.push(create_empty_timeline_line());
-            lines.extend(generate_isolation_lines(&metadata, None));
-        }
-    }
+    lines.extend(isolation_block_lines(options.extra_lines.as_ref()));
 
     // Empty timeline line before final two entries
-    lines.push(create_empty_timeline_line());
+    lines.push(BlockLine::Blank);
 
     // Log and session are ALWAYS last (in that order)
-    lines.push(create_timeline_line("log", options.log_path));
-    lines.push(create_timeline_line("session", options.session_id));
+    lines.push(BlockLine::Field("log", options.log_path.to_string()));
+    lines.push(BlockLine::Field("session", options.session_id.to_string()));
 
-    lines.join("\n")
+    render_block(
+        &lines,
+        &OutputStyle::from_options(options.style, options.width, options.color),
+    )
 }
 
 /// Escape a value for Links notation
@@ -512,6 +547,7 @@ mod tests {
             extra_lines: Some(extra_lines),
             style: None,
             width: None,
+            color: false,
             defer_command: false,
         });
         // Issue #67: The start block should include the session name for reconnection
@@ -540,6 +576,7 @@ mod tests {
             extra_lines: Some(extra_lines),
             style: None,
             width: None,
+            color: false,
         });
         // Issue #67: The finish block should include the container name for reconnection
         assert!(block.contains("✓"));
@@ -0,0 +1,564 @@
+//! Themes, color and wrapping for the start and finish blocks
+//!
+//! The timeline blocks are built as a list of [`BlockLine`]s and rendered
+//! with an [`OutputStyle`]:
+//! - `timeline` → the default `│` / `✓` / `✗` format
+//! - `ascii` → the same layout with ASCII-only characters, for legacy
+//!   terminals and log collectors that mangle box-drawing characters
+//! - `compact` → each block on a single line
+//! - `boxed` → each block framed in a box
+//!
+//! Result markers are colored green/red when color is enabled (`NO_COLOR`
+//! disables it, `CLICOLOR_FORCE` forces it, otherwise only on a terminal),
+//! and long values and commands are wrapped to the terminal width.
+//!
+//! An [`OutputMode`] decides which parts of a run are printed at all
+//! (--quiet, --silent, --summary-only), and in CI mode the blocks are
+//! printed as collapsed log groups.
+
+use std::env;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::ci_output::{ci_duration, ci_group, report_ci_run, CiRun};
+use crate::event_stream::emit_finished;
+use crate::log_viewer::{read_stored_log, split_log};
+use crate::output_blocks::{create_finish_block, FinishBlockOptions};
+use crate::record_template::terminal_width;
+
+/// Valid themes for --theme and START_THEME
+pub const VALID_THEMES: [&str; 4] = ["timeline", "ascii", "compact", "boxed"];
+
+/// Environment variable selecting the theme when --theme is not given
+pub const THEME_ENV: &str = "START_THEME";
+
+/// Width of boxed blocks when the terminal width is unknown
+const BOXED_DEFAULT_WIDTH: usize = 80;
+
+/// Lines are not wrapped to fewer than this many characters of value
+const MIN_WRAP_WIDTH: usize = 20;
+
+/// Width of the padded label column
+const LABEL_WIDTH: usize = 10;
+
+/// Lines of output --silent prints when the command fails (unless --tail is given)
+pub const SILENT_TAIL_LINES: usize = 20;
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Visual theme of the start and finish blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputTheme {
+    #[default]
+    Timeline,
+    Ascii,
+    Compact,
+    Boxed,
+}
+
+impl OutputTheme {
+    /// Theme by name (case-insensitive)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "timeline" => Some(OutputTheme::Timeline),
+            "ascii" => Some(OutputTheme::Ascii),
+            "compact" => Some(OutputTheme::Compact),
+            "boxed" => Some(OutputTheme::Boxed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputTheme::Timeline => "timeline",
+            OutputTheme::Ascii => "ascii",
+            OutputTheme::Compact => "compact",
+            OutputTheme::Boxed => "boxed",
+        }
+    }
+
+    /// Prefix of metadata lines
+    pub fn marker(self) -> &'static str {
+        match self {
+            OutputTheme::Ascii => "|",
+            _ => "│",
+        }
+    }
+
+    /// Marker of a successful or failed command
+    pub fn result_marker(self, success: bool) -> &'static str {
+        match (self, success) {
+            (OutputTheme::Ascii, true) => "OK",
+            (OutputTheme::Ascii, false) => "FAIL",
+            (_, true) => "✓",
+            (_, false) => "✗",
+        }
+    }
+}
+
+/// Which parts of a launched command's run are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Start block, command output and finish block
+    #[default]
+    Normal,
+    /// Only the command output (--quiet)
+    Quiet,
+    /// Nothing, unless the command fails (--silent)
+    Silent,
+    /// Only the finish block (--summary-only)
+    SummaryOnly,
+}
+
+impl OutputMode {
+    /// Whether the start block and virtual commands (like `docker pull`) are printed
+    pub fn shows_start(self) -> bool {
+        self == OutputMode::Normal
+    }
+
+    /// Whether the command's own output is printed
+    pub fn shows_output(self) -> bool {
+        matches!(self, OutputMode::Normal | OutputMode::Quiet)
+    }
+
+    /// Whether the finish block is printed
+    pub fn shows_finish(self) -> bool {
+        matches!(self, OutputMode::Normal | OutputMode::SummaryOnly)
+    }
+}
+
+/// Theme, color, width and mode used to print a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputStyle {
+    pub theme: OutputTheme,
+    /// Color the result markers
+    pub color: bool,
+    /// Wrap long lines to this many characters
+    pub width: Option<usize>,
+    /// Which parts of the run are printed
+    pub mode: OutputMode,
+    /// Print the blocks as CI log groups (see [`crate::ci_output`])
+    pub ci: bool,
+}
+
+static INSTALLED_STYLE: OnceLock<OutputStyle> = OnceLock::new();
+
+impl OutputStyle {
+    /// Style for printing to stdout: the given theme (from --theme), else
+    /// START_THEME, else `timeline`; color per [`color_enabled`]; and the
+    /// terminal width
+    pub fn detect(theme: Option<&str>, mode: OutputMode, ci: bool) -> Self {
+        let theme = theme
+            .map(str::to_string)
+            .or_else(|| env::var(THEME_ENV).ok())
+            .and_then(|name| OutputTheme::parse(&name))
+            .unwrap_or_default();
+        OutputStyle {
+            theme,
+            color: color_enabled(),
+            width: terminal_width(),
+            mode,
+            ci,
+        }
+    }
+
+    /// Use this style for output printed without block options (virtual
+    /// commands and their markers). Only the first call has an effect.
+    pub fn install(self) {
+        let _ = INSTALLED_STYLE.set(self);
+    }
+
+    /// The installed style, or the plain default timeline style
+    pub fn current() -> Self {
+        INSTALLED_STYLE.get().copied().unwrap_or_default()
+    }
+
+    /// Style from block options: an unknown or missing theme is `timeline`
+    pub fn from_options(style: Option<&str>, width: Option<usize>, color: bool) -> Self {
+        OutputStyle {
+            theme: style.and_then(OutputTheme::parse).unwrap_or_default(),
+            color,
+            width,
+            mode: OutputMode::Normal,
+            ci: false,
+        }
+    }
+
+    /// A start block as printed: in CI, a collapsed group titled with the command
+    pub fn start_section(&self, command: &str, block: &str) -> String {
+        if self.ci {
+            ci_group(&format!("$ {}", command), block)
+        } else {
+            block.to_string()
+        }
+    }
+
+    /// A finish block as printed: in CI, a collapsed group titled with the
+    /// result and duration
+    pub fn finish_section(&self, exit_code: i32, duration_ms: f64, block: &str) -> String {
+        if self.ci {
+            let title = format!(
+                "{} exit {} after {}",
+                self.theme.result_marker(exit_code == 0),
+                exit_code,
+                ci_duration(duration_ms)
+            );
+            ci_group(&title, block)
+        } else {
+            block.to_string()
+        }
+    }
+}
+
+/// Whether to color output given NO_COLOR, CLICOLOR_FORCE and whether
+/// stdout is a terminal. A non-empty NO_COLOR always wins; CLICOLOR_FORCE
+/// other than `0` forces color when output is piped.
+pub fn color_from_env(
+    no_color: Option<&str>,
+    clicolor_force: Option<&str>,
+    is_terminal: bool,
+) -> bool {
+    if no_color.is_some_and(|value| !value.is_empty()) {
+        return false;
+    }
+    if clicolor_force.is_some_and(|value| !value.is_empty() && value != "0") {
+        return true;
+    }
+    is_terminal
+}
+
+/// Whether to color output on stdout (see [`color_from_env`])
+pub fn color_enabled() -> bool {
+    #[cfg(unix)]
+    let is_terminal = unsafe { libc::isatty(libc::STDOUT_FILENO) != 0 }
+        && env::var("TERM").map_or(true, |term| term != "dumb");
+    #[cfg(not(unix))]
+    let is_terminal = false;
+    color_from_env(
+        env::var("NO_COLOR").ok().as_deref(),
+        env::var("CLICOLOR_FORCE").ok().as_deref(),
+        is_terminal,
+    )
+}
+
+/// Result marker of `theme`, green or red when `color` is set
+pub fn paint_result_marker(theme: OutputTheme, success: bool, color: bool) -> String {
+    let marker = theme.result_marker(success);
+    if !color {
+        return marker.to_string();
+    }
+    format!("{}{}{}", if success { GREEN } else { RED }, marker, RESET)
+}
+
+/// What --silent prints for a failed command: the last `lines` lines of
+/// its logged output, then the exit code and where the full log is
+pub fn silent_failure_report(
+    log_path: &Path,
+    lines: usize,
+    exit_code: i32,
+    style: &OutputStyle,
+) -> String {
+    let log = read_stored_log(log_path).unwrap_or_default();
+    let output: Vec<&str> = split_log(&log).output.trim_end().lines().collect();
+    let skipped = output.len().saturating_sub(lines);
+    let mut report = Vec::new();
+    if skipped > 0 {
+        report.push(format!("... {} earlier lines omitted", skipped));
+    }
+    report.extend(output[skipped..].iter().map(|line| line.to_string()));
+    report.push(format!(
+        "{} Command failed with exit code {}. Full log: {}",
+        paint_result_marker(style.theme, false, style.color),
+        exit_code,
+        log_path.display()
+    ));
+    report.join("\n")
+}
+
+/// A finished run as reported by [`report_finish`]
+#[derive(Debug, Clone)]
+pub struct FinishReport<'a> {
+    pub command: &'a str,
+    pub session_id: &'a str,
+    pub timestamp: &'a str,
+    pub exit_code: i32,
+    pub duration_ms: f64,
+    pub log_path: &'a Path,
+    /// Result line of an isolated run
+    pub result_message: Option<&'a str>,
+    /// Isolation details repeated in the finish block
+    pub extra_lines: Option<Vec<&'a str>>,
+    /// The command keeps running in a detached session
+    pub detached: bool,
+    /// Lines of output --silent prints when the command fails
+    pub tail: usize,
+}
+
+/// Report the end of a run: the `finished` event, the finish block (or,
+/// under --silent, the end of a failed command's log) and in CI the
+/// `::error` annotation and step summary
+pub fn report_finish(style: &OutputStyle, report: &FinishReport) {
+    emit_finished(
+        report.exit_code,
+        report.duration_ms,
+        report.log_path,
+        report.detached,
+    );
+    if style.mode.shows_finish() {
+        // --summary-only prints nothing before the finish block
+        if style.mode.shows_output() {
+            println!();
+        }
+        let block = create_finish_block(&FinishBlockOptions {
+            session_id: report.session_id,
+            timestamp: report.timestamp,
+            exit_code: report.exit_code,
+            log_path: &report.log_path.to_string_lossy(),
+            duration_ms: Some(report.duration_ms),
+            result_message: report.result_message,
+            extra_lines: report.extra_lines.clone(),
+            style: Some(style.theme.as_str()),
+            width: style.width,
+            color: style.color,
+        });
+        println!(
+            "{}",
+            style.finish_section(report.exit_code, report.duration_ms, &block)
+        );
+    } else if style.mode == OutputMode::Silent && report.exit_code != 0 && !report.detached {
+        eprintln!(
+            "{}",
+            silent_failure_report(report.log_path, report.tail, report.exit_code, style)
+        );
+    }
+    if style.ci {
+        report_ci_run(&CiRun {
+            command: report.command,
+            session_id: report.session_id,
+            exit_code: report.exit_code,
+            duration_ms: report.duration_ms,
+            log_path: report.log_path,
+            detached: report.detached,
+        });
+    }
+}
+
+/// Split `text` into lines of at most `width` characters, breaking at
+/// spaces or after `/` where possible and anywhere otherwise
+pub fn wrap_value(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut rest: Vec<char> = text.chars().collect();
+    while rest.len() > width {
+        let window = &rest[..=width];
+        let (end, skip) = match window.iter().rposition(|c| *c == ' ') {
+            Some(space) if space > 0 => (space, 1),
+            _ => match window[..width].iter().rposition(|c| *c == '/') {
+                Some(slash) if slash > 0 => (slash + 1, 0),
+                _ => (width, 0),
+            },
+        };
+        lines.push(rest[..end].iter().collect());
+        rest.drain(..end + skip);
+    }
+    lines.push(rest.into_iter().collect());
+    lines
+}
+
+/// `$ command` split into lines of at most `width` characters where
+/// possible. Lines break only at unquoted spaces and end with ` \`, so the
+/// wrapped command can still be pasted into a shell.
+pub fn wrap_command(command: &str, width: usize) -> Vec<String> {
+    let line = format!("$ {}", command);
+    if line.chars().count() <= width || command.contains('\n') {
+        return vec![line];
+    }
+
+    // Unquoted spaces are the only safe places to break
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match (c, quote) {
+            _ if escaped => escaped = false,
+            ('\\', q) if q != Some('\'') => escaped = true,
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            (' ', None) => {
+                if !current.is_empty() {
+                    words.push(std::mem::take(&mut current));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    // Room for the two-space indent of continuations and the trailing " \"
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in words {
+        let indent = if lines.is_empty() { 0 } else { 2 };
+        if !line.is_empty()
+            && line != "$"
+            && indent + line.chars().count() + 1 + word.chars().count() + 2 > width
+        {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&word);
+    }
+    lines.push(line);
+
+    let last = lines.len() - 1;
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let indent = if i == 0 { "" } else { "  " };
+            let continuation = if i == last { "" } else { " \\" };
+            format!("{}{}{}", indent, line, continuation)
+        })
+        .collect()
+}
+
+/// One line of a start or finish block before it is themed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockLine {
+    /// Labeled metadata such as `session` or `exit`
+    Field(&'static str, String),
+    /// Separator between groups of metadata
+    Blank,
+    /// The executed command
+    Command(String),
+    /// Result marker of a finished command
+    Result(bool),
+}
+
+/// Lines of a field with its value wrapped to `width` (when set)
+fn field_lines(label: &str, value: &str, width: Option<usize>) -> Vec<String> {
+    let chunks = match width {
+        Some(width) if width >= LABEL_WIDTH + MIN_WRAP_WIDTH => {
+            wrap_value(value, width - LABEL_WIDTH)
+        }
+        _ => vec![value.to_string()],
+    };
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let label = if i == 0 { label } else { "" };
+            format!("{:width$}{}", label, chunk, width = LABEL_WIDTH)
+        })
+        .collect()
+}
+
+/// Lines of a block without the theme's marker prefix
+fn content_lines(lines: &[BlockLine], style: &OutputStyle, width: Option<usize>) -> Vec<String> {
+    let mut content = Vec::new();
+    for line in lines {
+        match line {
+            BlockLine::Field(label, value) => content.extend(field_lines(label, value, width)),
+            BlockLine::Blank => content.push(String::new()),
+            BlockLine::Command(command) => match width {
+                Some(width) => content.extend(wrap_command(command, width)),
+                None => content.push(format!("$ {}", command)),
+            },
+            BlockLine::Result(success) => {
+                content.push(paint_result_marker(style.theme, *success, style.color))
+            }
+        }
+    }
+    content
+}
+
+fn render_timeline(lines: &[BlockLine], style: &OutputStyle) -> String {
+    let marker = style.theme.marker();
+    // The marker and its space take two columns
+    let field_width = style.width.map(|width| width.saturating_sub(2));
+    let mut output = Vec::new();
+    for line in lines {
+        match line {
+            BlockLine::Field(..) => {
+                for text in content_lines(std::slice::from_ref(line), style, field_width) {
+                    output.push(format!("{} {}", marker, text));
+                }
+            }
+            BlockLine::Blank => output.push(marker.to_string()),
+            _ => output.extend(content_lines(
+                std::slice::from_ref(line),
+                style,
+                style.width,
+            )),
+        }
+    }
+    output.join("\n")
+}
+
+fn render_compact(lines: &[BlockLine], style: &OutputStyle) -> String {
+    // The command leads the start line and the result marker the finish line
+    let mut marker = None;
+    let mut parts = Vec::new();
+    for line in lines {
+        match line {
+            BlockLine::Result(success) => {
+                marker = Some(paint_result_marker(style.theme, *success, style.color))
+            }
+            BlockLine::Command(command) => parts.insert(0, format!("$ {}", command)),
+            BlockLine::Field(label, value) => parts.push(format!("{} {}", label, value)),
+            BlockLine::Blank => {}
+        }
+    }
+    let line = parts.join(" · ");
+    match marker {
+        Some(marker) => format!("{} {}", marker, line),
+        None => line,
+    }
+}
+
+fn render_boxed(lines: &[BlockLine], style: &OutputStyle) -> String {
+    let width = style
+        .width
+        .unwrap_or(BOXED_DEFAULT_WIDTH)
+        .max(LABEL_WIDTH + MIN_WRAP_WIDTH + 4);
+    // "│ " and " │" around the content
+    let inner = width - 4;
+    let plain = OutputStyle {
+        color: false,
+        ..*style
+    };
+    let rule = "─".repeat(width - 2);
+    let mut output = vec![format!("┌{}┐", rule)];
+    for line in lines {
+        for text in content_lines(std::slice::from_ref(line), &plain, Some(inner)) {
+            let padding = inner.saturating_sub(text.chars().count());
+            let text = match line {
+                BlockLine::Result(success) => {
+                    paint_result_marker(style.theme, *success, style.color)
+                }
+                _ => text,
+            };
+            output.push(format!("│ {}{} │", text, " ".repeat(padding)));
+        }
+    }
+    output.push(format!("└{}┘", rule));
+    output.join("\n")
+}
+
+/// Render block lines with a theme
+pub fn render_block(lines: &[BlockLine], style: &OutputStyle) -> String {
+    match style.theme {
+        OutputTheme::Timeline | OutputTheme::Ascii => render_timeline(lines, style),
+        OutputTheme::Compact => render_compact(lines, style),
+        OutputTheme::Boxed => render_boxed(lines, style),
+    }
+}
@@ -0,0 +1,161 @@
+//! Record filters shared by --list, --stats, --export and --grep
+//!
+//! Filters narrow the stored execution history by start time, command text,
+//! isolation backend, outcome, tags and labels, and can cap the result to the
+//! newest N records.
+
+use crate::execution_store::{ExecutionRecord, ExecutionStatus};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use std::collections::BTreeMap;
+
+/// Valid values for --outcome
+pub const VALID_OUTCOMES: [&str; 3] = ["succeeded", "failed", "executing"];
+
+/// Filter applied to stored execution records
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordFilter {
+    /// Only records started at or after this instant
+    pub since: Option<DateTime<Utc>>,
+    /// Only records started before this instant
+    pub until: Option<DateTime<Utc>>,
+    /// Only records whose command contains this text (case-insensitive)
+    pub command_contains: Option<String>,
+    /// Only records run with this isolation backend ("direct" for none)
+    pub backend: Option<String>,
+    /// Only records with this outcome: succeeded, failed, executing
+    pub outcome: Option<String>,
+    /// Only records carrying every one of these tags
+    pub tags: Vec<String>,
+    /// Only records carrying every one of these labels with the same value
+    pub labels: BTreeMap<String, String>,
+    /// Keep at most this many records (newest first)
+    pub limit: Option<usize>,
+}
+
+/// Parse a time bound for --since/--until.
+///
+/// Accepts RFC 3339 timestamps (`2026-10-01T12:00:00Z`), plain dates
+/// (`2026-10-01`, midnight UTC), and relative durations counted back from now
+/// (`30m`, `24h`, `7d`, `2w`).
+pub fn parse_time_bound(value: &str) -> Result<DateTime<Utc>, String> {
+    parse_time_bound_at(value, Utc::now())
+}
+
+/// Same as [`parse_time_bound`] but relative durations are counted back from `now`
+pub fn parse_time_bound_at(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        if let Some(midnight) = date.and_hms_opt(0, 0, 0) {
+            return Ok(midnight.and_utc());
+        }
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid time: \"{}\". Use an RFC 3339 timestamp, a YYYY-MM-DD date, or a relative duration like 30m, 24h, 7d, 2w",
+            value
+        )
+    };
+    let (split, unit) = value.char_indices().last().ok_or_else(invalid)?;
+    let amount: i64 = value[..split].parse().map_err(|_| invalid())?;
+    if amount < 0 {
+        return Err(invalid());
+    }
+    let duration = match unit {
+        's' => TimeDelta::try_seconds(amount),
+        'm' => TimeDelta::try_minutes(amount),
+        'h' => TimeDelta::try_hours(amount),
+        'd' => TimeDelta::try_days(amount),
+        'w' => TimeDelta::try_weeks(amount),
+        _ => None,
+    };
+    duration
+        .and_then(|duration| now.checked_sub_signed(duration))
+        .ok_or_else(invalid)
+}
+
+/// Isolation backend a record ran with ("direct" when not isolated)
+pub fn record_backend(record: &ExecutionRecord) -> &str {
+    record
+        .options
+        .get("isolated")
+        .and_then(|v| v.as_str())
+        .unwrap_or("direct")
+}
+
+impl RecordFilter {
+    /// Whether no filter criteria are set
+    pub fn is_empty(&self) -> bool {
+        *self == RecordFilter::default()
+    }
+
+    /// Check whether a single record passes every criterion (ignores `limit`)
+    pub fn matches(&self, record: &ExecutionRecord) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Ok(start) = DateTime::parse_from_rfc3339(&record.start_time) else {
+                return false;
+            };
+            let start = start.with_timezone(&Utc);
+            if self.since.is_some_and(|since| start < since) {
+                return false;
+            }
+            if self.until.is_some_and(|until| start >= until) {
+                return false;
+            }
+        }
+
+        if let Some(ref needle) = self.command_contains {
+            if !record
+                .command
+                .to_lowercase()
+                .contains(&needle.to_lowercase())
+            {
+                return false;
+            }
+        }
+
+        if let Some(ref backend) = self.backend {
+            if record_backend(record) != backend {
+                return false;
+            }
+        }
+
+        if !self.tags.iter().all(|tag| record.tags.contains(tag)) {
+            return false;
+        }
+
+        if !self
+            .labels
+            .iter()
+            .all(|(key, value)| record.labels.get(key) == Some(value))
+        {
+            return false;
+        }
+
+        match self.outcome.as_deref() {
+            Some("succeeded") => {
+                record.status == ExecutionStatus::Executed && record.exit_code == Some(0)
+            }
+            Some("failed") => {
+                record.status == ExecutionStatus::Executed
+                    && record.exit_code.is_some_and(|code| code != 0)
+            }
+            Some("executing") => record.status == ExecutionStatus::Executing,
+            _ => true,
+        }
+    }
+
+    /// Keep matching records, newest first, truncated to `limit`
+    pub fn apply(&self, records: Vec<ExecutionRecord>) -> Vec<ExecutionRecord> {
+        let mut filtered: Vec<ExecutionRecord> =
+            records.into_iter().filter(|r| self.matches(r)).collect();
+        filtered.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+        if let Some(limit) = self.limit {
+            filtered.truncate(limit);
+        }
+        filtered
+    }
+}
@@ -0,0 +1,317 @@
+//! Column tables and format templates for --status and --list
+//!
+//! `--columns uuid,status,exitCode,duration,command` prints the selected
+//! fields as a table that fits the terminal width, and
+//! `--format '{{uuid}} {{exitCode}} {{command}}'` prints one line per record
+//! from a template. Fields are looked up in the JSON form of a record, so
+//! nested values are reachable with dots (`options.isolated`,
+//! `processIds.wrapperPid`, `tags.0`). `duration` and `durationMs` are
+//! computed, with executing records measured up to now.
+
+use chrono::DateTime;
+use serde_json::Value;
+
+use crate::execution_control::collect_process_ids;
+use crate::execution_stats::record_duration_ms;
+use crate::execution_store::{ExecutionRecord, ExecutionStore};
+use crate::output_blocks::format_duration;
+use crate::record_filter::RecordFilter;
+use crate::status_formatter::{
+    attach_current_time, enrich_detached_status, record_json_with_enrichments, StatusQueryResult,
+};
+
+/// Space between table columns
+const COLUMN_GAP: &str = "  ";
+
+/// A column is never shrunk below this many characters (or its header)
+const MIN_COLUMN_WIDTH: usize = 5;
+
+/// How --status/--list print records instead of an output format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordLayout {
+    /// Table of the given fields (--columns)
+    Columns(Vec<String>),
+    /// One line per record (--format)
+    Template(Vec<TemplatePart>),
+}
+
+/// Literal text or a `{{field}}` placeholder of a --format template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Text(String),
+    Field(String),
+}
+
+/// Parse a --columns list
+pub fn parse_columns(value: &str) -> Result<Vec<String>, String> {
+    let columns: Vec<String> = value.split(',').map(|c| c.trim().to_string()).collect();
+    if columns.iter().any(|column| column.is_empty()) {
+        return Err(format!(
+            "Invalid columns: \"{}\". Expected a comma-separated list of field names",
+            value
+        ));
+    }
+    Ok(columns)
+}
+
+/// Parse a --format template. `\n` and `\t` in the template stand for a
+/// newline and a tab.
+pub fn parse_template(template: &str) -> Result<Vec<TemplatePart>, String> {
+    let unescaped = template.replace("\\n", "\n").replace("\\t", "\t");
+    let mut parts = Vec::new();
+    let mut rest = unescaped.as_str();
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(TemplatePart::Text(rest[..start].to_string()));
+        }
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            format!(
+                "Invalid format template: \"{}\". Unclosed {{{{ placeholder",
+                template
+            )
+        })?;
+        let field = after[..end].trim();
+        if field.is_empty() {
+            return Err(format!(
+                "Invalid format template: \"{}\". Empty {{{{}}}} placeholder",
+                template
+            ));
+        }
+        parts.push(TemplatePart::Field(field.to_string()));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest.to_string()));
+    }
+    Ok(parts)
+}
+
+/// JSON form of a record with the fields available to --columns and
+/// --format: the stored fields, `currentTime`, `processIds`, `duration`
+/// and `durationMs`
+pub fn record_fields(
+    record: &ExecutionRecord,
+    current_time: Option<&str>,
+    process_ids: Option<&Value>,
+) -> Value {
+    let mut fields = record_json_with_enrichments(record, current_time, process_ids);
+    let duration_ms = record_duration_ms(record).or_else(|| {
+        let start = DateTime::parse_from_rfc3339(&record.start_time).ok()?;
+        let now = DateTime::parse_from_rfc3339(current_time?).ok()?;
+        let ms = (now - start).num_milliseconds();
+        (ms >= 0).then_some(ms as f64)
+    });
+    if let (Value::Object(map), Some(ms)) = (&mut fields, duration_ms) {
+        map.insert("durationMs".to_string(), serde_json::json!(ms as u64));
+        map.insert("duration".to_string(), Value::String(format_duration(ms)));
+    }
+    fields
+}
+
+/// Value of a dotted field path (`options.isolated`, `processIds.wrapperPid`,
+/// `tags.0` or `tags[0]`) as text; `None` when absent or null
+pub fn field_value(fields: &Value, path: &str) -> Option<String> {
+    let path = path.replace('[', ".").replace(']', "");
+    let mut value = fields;
+    for key in path.split('.').filter(|key| !key.is_empty()) {
+        value = match value {
+            Value::Object(map) => map.get(key)?,
+            Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    match value {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+/// Fill a parsed template with the fields of one record; absent fields are empty
+pub fn render_template(parts: &[TemplatePart], fields: &Value) -> String {
+    parts
+        .iter()
+        .map(|part| match part {
+            TemplatePart::Text(text) => text.clone(),
+            TemplatePart::Field(path) => field_value(fields, path).unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Table of `columns` for each record's fields. With `max_width`, the widest
+/// columns are shrunk (and their values cut with `…`) until the table fits.
+pub fn render_table(rows: &[Value], columns: &[String], max_width: Option<usize>) -> String {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|fields| {
+            columns
+                .iter()
+                .map(|column| {
+                    field_value(fields, column)
+                        .unwrap_or_default()
+                        .replace(['\n', '\r'], " ")
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    if let Some(max_width) = max_width {
+        let minimums: Vec<usize> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| (*width).min(column.chars().count().max(MIN_COLUMN_WIDTH)))
+            .collect();
+        let gaps = COLUMN_GAP.len() * columns.len().saturating_sub(1);
+        while widths.iter().sum::<usize>() + gaps > max_width {
+            let shrinkable = (0..widths.len())
+                .filter(|&i| widths[i] > minimums[i])
+                .max_by_key(|&i| widths[i]);
+            match shrinkable {
+                Some(i) => widths[i] -= 1,
+                None => break,
+            }
+        }
+    }
+
+    let line = |values: Vec<String>| {
+        let last = values.len().saturating_sub(1);
+        values
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (value, width))| {
+                let value = truncate(value, *width);
+                if i == last {
+                    value
+                } else {
+                    format!("{:<width$}", value, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(COLUMN_GAP)
+    };
+    let mut lines = vec![line(columns.to_vec())];
+    lines.extend(cells.into_iter().map(line));
+    lines.join("\n")
+}
+
+/// Width of the terminal on stdout: `$COLUMNS`, or the terminal size when
+/// stdout is a terminal. `None` when piped, so scripts get full values.
+pub fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|columns| *columns > 0)
+    {
+        return Some(columns);
+    }
+    #[cfg(unix)]
+    {
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        let known = unsafe {
+            libc::isatty(libc::STDOUT_FILENO) != 0
+                && libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0
+        };
+        if known && size.ws_col > 0 {
+            return Some(size.ws_col as usize);
+        }
+    }
+    None
+}
+
+/// Print records with a --columns or --format layout
+pub fn format_records_with_layout(
+    rows: &[Value],
+    layout: &RecordLayout,
+    max_width: Option<usize>,
+) -> String {
+    match layout {
+        RecordLayout::Columns(columns) => render_table(rows, columns, max_width),
+        RecordLayout::Template(parts) => rows
+            .iter()
+            .map(|fields| render_template(parts, fields))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Fields of a record whose detached status was already checked
+fn live_fields(record: &ExecutionRecord) -> Value {
+    let current_time = attach_current_time(record);
+    let process_ids = collect_process_ids(record);
+    record_fields(record, current_time.as_deref(), process_ids.as_ref())
+}
+
+/// Handle a --status query printed with a --columns or --format layout
+pub fn query_status_with_layout(
+    store: Option<&ExecutionStore>,
+    identifier: &str,
+    layout: &RecordLayout,
+) -> StatusQueryResult {
+    let Some(store) = store else {
+        return StatusQueryResult {
+            success: false,
+            output: None,
+            error: Some("Execution tracking is disabled.".to_string()),
+        };
+    };
+    match store.get(identifier) {
+        Some(record) => StatusQueryResult {
+            success: true,
+            output: Some(format_records_with_layout(
+                &[live_fields(&enrich_detached_status(&record))],
+                layout,
+                terminal_width(),
+            )),
+            error: None,
+        },
+        None => StatusQueryResult {
+            success: false,
+            output: None,
+            error: Some(format!(
+                "No execution found with UUID or session name: {}",
+                identifier
+            )),
+        },
+    }
+}
+
+/// Handle a --list query printed with a --columns or --format layout
+pub fn list_executions_with_layout(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    layout: &RecordLayout,
+) -> StatusQueryResult {
+    let Some(store) = store else {
+        return StatusQueryResult {
+            success: false,
+            output: None,
+            error: Some("Execution tracking is disabled.".to_string()),
+        };
+    };
+    let mut records: Vec<ExecutionRecord> =
+        filter.apply(store.get_all().iter().map(enrich_detached_status).collect());
+    records.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+    let rows: Vec<Value> = records.iter().map(live_fields).collect();
+    StatusQueryResult {
+        success: true,
+        output: Some(format_records_with_layout(&rows, layout, terminal_width())),
+        error: None,
+    }
+}
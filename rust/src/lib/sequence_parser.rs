@@ -171,13 +171,13 @@ pub fn format_isolation_chain(stack: &[Option<String>], options: &IsolationChain
                     "ssh".to_string()
                 }
             }
-            Some("docker") => {
+            Some(engine @ ("docker" | "podman")) => {
                 if let Some(image) = get_value_at_level(&options.image_stack, i) {
                     let short_name = image.split(':').next().unwrap_or(image);
                     let short_name = short_name.rsplit('/').next().unwrap_or(short_name);
-                    format!("docker:{}", short_name)
+                    format!("{}:{}", engine, short_name)
                 } else {
-                    "docker".to_string()
+                    engine.to_string()
                 }
             }
             Some(b) => b.to_string(),
@@ -191,7 +191,7 @@ pub fn format_isolation_chain(stack: &[Option<String>], options: &IsolationChain
 pub struct IsolationChainOptions {
     /// Distributed endpoints for SSH levels
     pub endpoint_stack: Vec<Option<String>>,
-    /// Distributed images for Docker levels
+    /// Distributed images for Docker and Podman levels
     pub image_stack: Vec<Option<String>>,
 }
 
@@ -4,16 +4,24 @@
 //! - Links Notation (links-notation): Structured link doublet format with nested options
 //! - JSON: Standard JSON output
 //! - Text: Human-readable text format
+//! - YAML, CSV (stable header, nested values as JSON cells) and NDJSON (one
+//!   record per line)
 
-use crate::docker_cleanup::docker_command;
+use crate::docker_cleanup::ContainerEngine;
 use crate::execution_control::collect_process_ids;
-use crate::execution_store::{ExecutionRecord, ExecutionStatus, ExecutionStore};
+use crate::execution_store::{CleanupResult, ExecutionRecord, ExecutionStatus, ExecutionStore};
 use crate::output_blocks::{escape_for_links_notation, format_value_for_links_notation};
+use crate::record_filter::RecordFilter;
 use serde_json::Value;
 use std::fs;
 use std::process::Command;
 
-/// Live state of a detached docker container by name.
+#[path = "status_formatter_formats.rs"]
+mod formats;
+use self::formats::{records_json_to_csv, records_json_to_ndjson};
+pub use self::formats::{status_csv_header, value_to_yaml};
+
+/// Live state of a detached docker or podman container by name.
 struct DockerState {
     running: bool,
     exit_code: Option<i32>,
@@ -30,9 +38,9 @@ struct DockerState {
 /// keep the session running instead of fabricating a terminal `-1` result.
 ///
 /// Returns None when the container cannot be inspected (not found yet, removed,
-/// or docker error).
-fn inspect_docker_state(session_name: &str) -> Option<DockerState> {
-    let output = Command::new(docker_command())
+/// or engine error). Podman accepts the same inspect template.
+fn inspect_docker_state(engine: ContainerEngine, session_name: &str) -> Option<DockerState> {
+    let output = Command::new(engine.command())
         .args([
             "inspect",
             "-f",
@@ -64,16 +72,19 @@ fn inspect_docker_state(session_name: &str) -> Option<DockerState> {
     })
 }
 
+/// Container engine of a record isolated in docker or podman
+fn record_container_engine(record: &ExecutionRecord) -> Option<ContainerEngine> {
+    ContainerEngine::from_backend(record.options.get("isolated")?.as_str()?)
+}
+
 /// Best-effort terminal exit code reported by the isolation backend itself
-/// (currently docker via `docker inspect .State.ExitCode`). Returns None when
-/// the backend cannot provide a real code, so callers never surface the `-1`
-/// sentinel for a session whose real exit code is simply not available yet.
+/// (currently docker or podman via `inspect .State.ExitCode`). Returns None
+/// when the backend cannot provide a real code, so callers never surface the
+/// `-1` sentinel for a session whose real exit code is not available yet.
 fn read_backend_exit_code(record: &ExecutionRecord) -> Option<i32> {
-    if record.options.get("isolated")?.as_str()? != "docker" {
-        return None;
-    }
+    let engine = record_container_engine(record)?;
     let session_name = record.options.get("sessionName")?.as_str()?;
-    let state = inspect_docker_state(session_name)?;
+    let state = inspect_docker_state(engine, session_name)?;
     if state.running {
         None
     } else {
@@ -82,11 +93,9 @@ fn read_backend_exit_code(record: &ExecutionRecord) -> Option<i32> {
 }
 
 fn read_docker_oom_killed(record: &ExecutionRecord) -> Option<bool> {
-    if record.options.get("isolated")?.as_str()? != "docker" {
-        return None;
-    }
+    let engine = record_container_engine(record)?;
     let session_name = record.options.get("sessionName")?.as_str()?;
-    inspect_docker_state(session_name)?.oom_killed
+    inspect_docker_state(engine, session_name)?.oom_killed
 }
 
 /// Check if a detached isolation session is still running
@@ -113,12 +122,13 @@ pub fn is_detached_session_alive(record: &ExecutionRecord) -> Option<bool> {
                 .ok()?;
             Some(status.status.success())
         }
-        "docker" => {
+        "docker" | "podman" => {
             // A failed inspect means the container is not visible yet (still
             // being created on a slow DinD host) or already removed — not
             // "stopped". Return None (unknown) so the session is not falsely
             // marked finished (issue #136).
-            inspect_docker_state(session_name).map(|state| state.running)
+            let engine = ContainerEngine::from_backend(isolated)?;
+            inspect_docker_state(engine, session_name).map(|state| state.running)
         }
         "ssh" => {
             // For SSH, check if the local wrapper PID is still running
@@ -234,6 +244,47 @@ pub fn attach_current_time(record: &ExecutionRecord) -> Option<String> {
     }
 }
 
+/// Human-readable report of the records cleaned up (or, in a dry run, that
+/// would be cleaned up) by --cleanup
+pub fn format_cleanup_report(result: &CleanupResult, dry_run: bool) -> String {
+    if result.records.is_empty() {
+        return "No stale records found.".to_string();
+    }
+
+    let mut lines = vec![if dry_run {
+        format!(
+            "Found {} stale record(s) that would be cleaned up:\n",
+            result.records.len()
+        )
+    } else {
+        format!("Cleaned up {} stale record(s):\n", result.cleaned)
+    }];
+
+    for record in &result.records {
+        // Parse start time for display
+        let start_time_display = chrono::DateTime::parse_from_rfc3339(&record.start_time)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| record.start_time.clone());
+
+        lines.push(format!("  UUID: {}", record.uuid));
+        lines.push(format!("  Command: {}", record.command));
+        lines.push(format!("  Started: {}", start_time_display));
+        lines.push(format!(
+            "  PID: {}",
+            record
+                .pid
+                .map(|p| p.to_string())
+                .unwrap_or("N/A".to_string())
+        ));
+        lines.push(String::new());
+    }
+
+    if dry_run {
+        lines.push("Run with --cleanup to actually clean up these records.".to_string());
+    }
+    lines.join("\n")
+}
+
 /// Format execution record as Links Notation (indented style)
 /// Uses nested Links notation for object values (like options) instead of JSON
 ///
@@ -323,6 +374,9 @@ fn format_record_as_links_notation_with_enrichments(
 
     if let Value::Object(map) = json {
         for (key, value) in map {
+            if ANNOTATION_KEYS.contains(&key.as_str()) {
+                continue;
+            }
             if !value.is_null() {
                 if key == "options" {
                     // Format options as nested Links notation
@@ -337,6 +391,8 @@ fn format_record_as_links_notation_with_enrichments(
                             }
                         }
                     }
+                } else if key == "provenance" {
+                    append_links_value(&mut lines, &key, &value, 2);
                 } else {
                     let formatted_value = match &value {
                         Value::String(s) => escape_for_links_notation(s),
@@ -368,10 +424,85 @@ fn format_record_as_links_notation_with_enrichments(
             }
         }
     }
+    append_annotations_as_links_notation(&mut lines, record);
 
     lines.join("\n")
 }
 
+/// Record fields rendered by the annotation helpers rather than generically
+const ANNOTATION_KEYS: [&str; 3] = ["tags", "labels", "notes"];
+
+fn append_annotations_as_links_notation(lines: &mut Vec<String>, record: &ExecutionRecord) {
+    if !record.tags.is_empty() {
+        lines.push("  tags".to_string());
+        for tag in &record.tags {
+            lines.push(format!("    {}", escape_for_links_notation(tag)));
+        }
+    }
+    if !record.labels.is_empty() {
+        lines.push("  labels".to_string());
+        for (key, value) in &record.labels {
+            lines.push(format!(
+                "    {} {}",
+                escape_for_links_notation(key),
+                escape_for_links_notation(value)
+            ));
+        }
+    }
+    if !record.notes.is_empty() {
+        lines.push("  notes".to_string());
+        for note in &record.notes {
+            lines.push(format!(
+                "    {} {}",
+                escape_for_links_notation(&note.time),
+                escape_for_links_notation(&note.text)
+            ));
+        }
+    }
+}
+
+fn append_provenance_as_text(lines: &mut Vec<String>, record: &ExecutionRecord) {
+    let Some(provenance) = &record.provenance else {
+        return;
+    };
+    if let Some(git) = &provenance.git {
+        lines.push(format!("Git Repository:    {}", git.root));
+        if let Some(branch) = &git.branch {
+            lines.push(format!("Git Branch:        {}", branch));
+        }
+        let commit = git.commit.as_deref().unwrap_or("N/A");
+        let dirty = if git.dirty { " (dirty)" } else { "" };
+        lines.push(format!("Git Commit:        {}{}", commit, dirty));
+    }
+    if let Some(executable) = &provenance.executable {
+        lines.push(format!("Executable:        {}", executable));
+    }
+    if !provenance.tools.is_empty() {
+        lines.push("Tool Versions:".to_string());
+        for (tool, version) in &provenance.tools {
+            lines.push(format!("  {}: {}", tool, version));
+        }
+    }
+}
+
+fn append_annotations_as_text(lines: &mut Vec<String>, record: &ExecutionRecord) {
+    if !record.tags.is_empty() {
+        lines.push(format!("Tags:              {}", record.tags.join(", ")));
+    }
+    if !record.labels.is_empty() {
+        lines.push("Labels:".to_string());
+        for (key, value) in &record.labels {
+            lines.push(format!("  {}: {}", key, value));
+        }
+    }
+    if !record.notes.is_empty() {
+        lines.push("Notes:".to_string());
+        for note in &record.notes {
+            lines.push(format!("  {}: {}", note.time, note.text));
+        }
+    }
+}
+
 /// Format execution record as human-readable text
 pub fn format_record_as_text(record: &ExecutionRecord) -> String {
     format_record_as_text_with_current_time(record, None)
@@ -448,6 +579,10 @@ fn format_record_as_text_with_enrichments(
     }
     lines.push(format!("End Time:          {}", end_time_str));
     lines.push(format!("Log Path:          {}", record.log_path));
+    if let Some(ref owner) = record.owner {
+        lines.push(format!("Owner:             {}", owner));
+    }
+    append_provenance_as_text(&mut lines, record);
 
     // Format options as nested list instead of JSON
     if !record.options.is_empty() {
@@ -463,11 +598,12 @@ fn format_record_as_text_with_enrichments(
             lines.push(format!("  {}: {}", key, value_str));
         }
     }
+    append_annotations_as_text(&mut lines, record);
 
     lines.join("\n")
 }
 
-fn record_json_with_enrichments(
+pub(crate) fn record_json_with_enrichments(
     record: &ExecutionRecord,
     current_time: Option<&str>,
     process_ids: Option<&Value>,
@@ -523,6 +659,14 @@ fn format_record_with_enrichments(
             current_time,
             process_ids,
         )),
+        "yaml" | "csv" | "ndjson" => {
+            let json = record_json_with_enrichments(record, current_time, process_ids);
+            Ok(match format {
+                "yaml" => value_to_yaml(&json),
+                "csv" => records_json_to_csv(&[json]),
+                _ => records_json_to_ndjson(&[json]),
+            })
+        }
         _ => Err(format!("Unknown output format: {}", format)),
     }
 }
@@ -613,20 +757,27 @@ fn format_record_list_as_text_with_current_times(
     lines.join("\n")
 }
 
-fn record_list_json_with_current_times(
+fn record_list_rows(
     records: &[ExecutionRecord],
     current_times: &[Option<String>],
     process_ids: &[Option<Value>],
-) -> Value {
-    let executions: Vec<Value> = records
+) -> Vec<Value> {
+    records
         .iter()
         .zip(current_times.iter())
         .zip(process_ids.iter())
         .map(|((record, current_time), process_ids)| {
             record_json_with_enrichments(record, current_time.as_deref(), process_ids.as_ref())
         })
-        .collect();
+        .collect()
+}
 
+fn record_list_json_with_current_times(
+    records: &[ExecutionRecord],
+    current_times: &[Option<String>],
+    process_ids: &[Option<Value>],
+) -> Value {
+    let executions = record_list_rows(records, current_times, process_ids);
     serde_json::json!({
         "count": records.len(),
         "executions": executions,
@@ -663,6 +814,21 @@ fn format_record_list_with_current_times(
             current_times,
             process_ids,
         )),
+        "yaml" => Ok(value_to_yaml(&record_list_json_with_current_times(
+            records,
+            current_times,
+            process_ids,
+        ))),
+        "csv" => Ok(records_json_to_csv(&record_list_rows(
+            records,
+            current_times,
+            process_ids,
+        ))),
+        "ndjson" => Ok(records_json_to_ndjson(&record_list_rows(
+            records,
+            current_times,
+            process_ids,
+        ))),
         _ => Err(format!("Unknown output format: {}", format)),
     }
 }
@@ -674,10 +840,36 @@ pub struct StatusQueryResult {
     pub error: Option<String>,
 }
 
+impl From<Result<String, String>> for StatusQueryResult {
+    fn from(result: Result<String, String>) -> Self {
+        match result {
+            Ok(output) => StatusQueryResult {
+                success: true,
+                output: Some(output),
+                error: None,
+            },
+            Err(e) => StatusQueryResult {
+                success: false,
+                output: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
 /// Handle execution list query and return the result
 pub fn list_executions(
     store: Option<&ExecutionStore>,
     output_format: Option<&str>,
+) -> StatusQueryResult {
+    list_executions_filtered(store, &RecordFilter::default(), output_format)
+}
+
+/// Same as [`list_executions`] but only lists records passing `filter`
+pub fn list_executions_filtered(
+    store: Option<&ExecutionStore>,
+    filter: &RecordFilter,
+    output_format: Option<&str>,
 ) -> StatusQueryResult {
     let store = match store {
         Some(s) => s,
@@ -691,7 +883,7 @@ pub fn list_executions(
     };
 
     let mut records: Vec<ExecutionRecord> =
-        store.get_all().iter().map(enrich_detached_status).collect();
+        filter.apply(store.get_all().iter().map(enrich_detached_status).collect());
     sort_records_by_start_time_desc(&mut records);
     let current_times: Vec<Option<String>> = records.iter().map(attach_current_time).collect();
     let process_ids: Vec<Option<Value>> = records.iter().map(collect_process_ids).collect();
@@ -769,189 +961,5 @@ pub fn query_status(
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::execution_store::{ExecutionRecordOptions, ExecutionStoreOptions};
-    use serde_json::json;
-    use std::collections::HashMap;
-    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
-    use std::path::{Path, PathBuf};
-    use tempfile::TempDir;
-
-    fn executing_record() -> ExecutionRecord {
-        ExecutionRecord::with_options(ExecutionRecordOptions {
-            command: "sleep 60".to_string(),
-            uuid: Some("issue-126-rust".to_string()),
-            pid: Some(667105),
-            status: Some(ExecutionStatus::Executing),
-            log_path: Some("/tmp/issue-126.log".to_string()),
-            start_time: Some("2026-04-23T10:00:00Z".to_string()),
-            working_directory: Some("/home/user".to_string()),
-            shell: Some("/bin/bash".to_string()),
-            platform: Some("linux".to_string()),
-            ..Default::default()
-        })
-    }
-
-    fn docker_record() -> ExecutionRecord {
-        let mut options = HashMap::new();
-        options.insert(
-            "sessionName".to_string(),
-            Value::String("issue144-oom".to_string()),
-        );
-        options.insert("isolated".to_string(), Value::String("docker".to_string()));
-        options.insert(
-            "isolationMode".to_string(),
-            Value::String("detached".to_string()),
-        );
-
-        ExecutionRecord::with_options(ExecutionRecordOptions {
-            command: "sh -c 'exit 0'".to_string(),
-            uuid: Some("issue144-rust".to_string()),
-            log_path: Some("/tmp/issue144.log".to_string()),
-            options: Some(options),
-            ..Default::default()
-        })
-    }
-
-    fn write_fake_docker(fake_dir: &Path, state_line: &str) -> PathBuf {
-        #[cfg(windows)]
-        {
-            let script = [
-                "@echo off",
-                "if not \"%1\"==\"inspect\" exit /b 1",
-                "echo %3 | findstr /C:\"State.Pid\" >nul",
-                "if %errorlevel%==0 (",
-                "  echo fake-container-id 4321",
-                "  exit /b 0",
-                ")",
-                &format!("echo {}", state_line),
-                "exit /b 0",
-                "",
-            ]
-            .join("\r\n");
-            let docker_path = fake_dir.join("docker.cmd");
-            std::fs::write(&docker_path, script).unwrap();
-            docker_path
-        }
-
-        #[cfg(not(windows))]
-        {
-            use std::os::unix::fs::PermissionsExt;
-
-            let script = [
-                "#!/bin/sh",
-                "[ \"$1\" = \"inspect\" ] || exit 1",
-                "case \"$3\" in",
-                "  *State.Pid*) echo \"fake-container-id 4321\" ;;",
-                &format!("  *) echo \"{}\" ;;", state_line),
-                "esac",
-                "",
-            ]
-            .join("\n");
-            let docker_path = fake_dir.join("docker");
-            std::fs::write(&docker_path, script).unwrap();
-            let mut permissions = std::fs::metadata(&docker_path).unwrap().permissions();
-            permissions.set_mode(0o755);
-            std::fs::set_permissions(&docker_path, permissions).unwrap();
-            docker_path
-        }
-    }
-
-    fn with_fake_docker_inspect<F: FnOnce()>(state_line: &str, run: F) {
-        let fake_dir = TempDir::new().unwrap();
-        let docker_path = write_fake_docker(fake_dir.path(), state_line);
-        let original_path = std::env::var_os("PATH");
-        let original_docker_bin = std::env::var_os("START_DOCKER_BIN");
-        let mut paths = vec![fake_dir.path().to_path_buf()];
-        if let Some(existing) = original_path.as_ref() {
-            paths.extend(std::env::split_paths(existing));
-        }
-        let joined = std::env::join_paths(paths).unwrap();
-        std::env::set_var("PATH", &joined);
-        std::env::set_var("START_DOCKER_BIN", &docker_path);
-        let result = catch_unwind(AssertUnwindSafe(run));
-        if let Some(path) = original_path {
-            std::env::set_var("PATH", path);
-        } else {
-            std::env::remove_var("PATH");
-        }
-        if let Some(path) = original_docker_bin {
-            std::env::set_var("START_DOCKER_BIN", path);
-        } else {
-            std::env::remove_var("START_DOCKER_BIN");
-        }
-        if let Err(payload) = result {
-            resume_unwind(payload);
-        }
-    }
-
-    #[test]
-    fn links_notation_indents_nested_process_id_arrays() {
-        let process_ids = json!({
-            "wrapperPid": 667105,
-            "screenPid": 667120,
-            "commandPids": [667121, 667122],
-        });
-        let output = format_record_with_enrichments(
-            &executing_record(),
-            "links-notation",
-            Some("2026-04-23T10:10:13.042Z"),
-            Some(&process_ids),
-        )
-        .expect("links-notation should format");
-
-        assert!(
-            output.contains(
-                "      commandPids\n        (\n          667121\n          667122\n        )"
-            ),
-            "processIds should be a nested indented block, output: {}",
-            output
-        );
-        assert!(
-            !output.contains("\n(\n"),
-            "opening parenthesis must not start at column 1: {}",
-            output
-        );
-    }
-
-    #[test]
-    fn docker_oom_killed_is_exposed_in_status_and_list_output() {
-        let temp_dir = TempDir::new().unwrap();
-        let store = ExecutionStore::with_options(ExecutionStoreOptions {
-            app_folder: Some(temp_dir.path().to_path_buf()),
-            use_links: Some(false),
-            verbose: false,
-        });
-        let record = docker_record();
-        store.save(&record).unwrap();
-
-        with_fake_docker_inspect("false 0 true", || {
-            let json_result = query_status(Some(&store), "issue144-rust", Some("json"));
-            assert!(json_result.success);
-            let parsed: Value = serde_json::from_str(&json_result.output.unwrap()).unwrap();
-            assert_eq!(parsed["status"], "executed");
-            assert_eq!(parsed["exitCode"], 0);
-            assert_eq!(parsed["oomKilled"], true);
-
-            let links_result = query_status(Some(&store), "issue144-rust", Some("links-notation"));
-            assert!(links_result.success);
-            assert!(links_result.output.unwrap().contains("  oomKilled true"));
-
-            let text_result = query_status(Some(&store), "issue144-rust", Some("text"));
-            assert!(text_result.success);
-            assert!(text_result
-                .output
-                .unwrap()
-                .contains("OOM Killed:        true"));
-
-            let list_result = list_executions(Some(&store), Some("json"));
-            assert!(list_result.success);
-            let listed: Value = serde_json::from_str(&list_result.output.unwrap()).unwrap();
-            assert_eq!(listed["count"], 1);
-            assert_eq!(listed["executions"][0]["status"], "executed");
-            assert_eq!(listed["executions"][0]["exitCode"], 0);
-            assert_eq!(listed["executions"][0]["oomKilled"], true);
-        });
-    }
-}
+#[path = "status_formatter_cases.rs"]
+mod tests;
@@ -0,0 +1,184 @@
+use super::*;
+use crate::execution_store::{ExecutionRecordOptions, ExecutionStoreOptions};
+use serde_json::json;
+use std::collections::HashMap;
+use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+fn executing_record() -> ExecutionRecord {
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        command: "sleep 60".to_string(),
+        uuid: Some("issue-126-rust".to_string()),
+        pid: Some(667105),
+        status: Some(ExecutionStatus::Executing),
+        log_path: Some("/tmp/issue-126.log".to_string()),
+        start_time: Some("2026-04-23T10:00:00Z".to_string()),
+        working_directory: Some("/home/user".to_string()),
+        shell: Some("/bin/bash".to_string()),
+        platform: Some("linux".to_string()),
+        ..Default::default()
+    })
+}
+
+fn docker_record() -> ExecutionRecord {
+    let mut options = HashMap::new();
+    options.insert(
+        "sessionName".to_string(),
+        Value::String("issue144-oom".to_string()),
+    );
+    options.insert("isolated".to_string(), Value::String("docker".to_string()));
+    options.insert(
+        "isolationMode".to_string(),
+        Value::String("detached".to_string()),
+    );
+
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        command: "sh -c 'exit 0'".to_string(),
+        uuid: Some("issue144-rust".to_string()),
+        log_path: Some("/tmp/issue144.log".to_string()),
+        options: Some(options),
+        ..Default::default()
+    })
+}
+
+fn write_fake_docker(fake_dir: &Path, state_line: &str) -> PathBuf {
+    #[cfg(windows)]
+    {
+        let script = [
+            "@echo off",
+            "if not \"%1\"==\"inspect\" exit /b 1",
+            "echo %3 | findstr /C:\"State.Pid\" >nul",
+            "if %errorlevel%==0 (",
+            "  echo fake-container-id 4321",
+            "  exit /b 0",
+            ")",
+            &format!("echo {}", state_line),
+            "exit /b 0",
+            "",
+        ]
+        .join("\r\n");
+        let docker_path = fake_dir.join("docker.cmd");
+        std::fs::write(&docker_path, script).unwrap();
+        docker_path
+    }
+
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = [
+            "#!/bin/sh",
+            "[ \"$1\" = \"inspect\" ] || exit 1",
+            "case \"$3\" in",
+            "  *State.Pid*) echo \"fake-container-id 4321\" ;;",
+            &format!("  *) echo \"{}\" ;;", state_line),
+            "esac",
+            "",
+        ]
+        .join("\n");
+        let docker_path = fake_dir.join("docker");
+        std::fs::write(&docker_path, script).unwrap();
+        let mut permissions = std::fs::metadata(&docker_path).unwrap().permissions();
+        permissions.set_mode(0o755);
+        std::fs::set_permissions(&docker_path, permissions).unwrap();
+        docker_path
+    }
+}
+
+fn with_fake_docker_inspect<F: FnOnce()>(state_line: &str, run: F) {
+    let fake_dir = TempDir::new().unwrap();
+    let docker_path = write_fake_docker(fake_dir.path(), state_line);
+    let original_path = std::env::var_os("PATH");
+    let original_docker_bin = std::env::var_os("START_DOCKER_BIN");
+    let mut paths = vec![fake_dir.path().to_path_buf()];
+    if let Some(existing) = original_path.as_ref() {
+        paths.extend(std::env::split_paths(existing));
+    }
+    let joined = std::env::join_paths(paths).unwrap();
+    std::env::set_var("PATH", &joined);
+    std::env::set_var("START_DOCKER_BIN", &docker_path);
+    let result = catch_unwind(AssertUnwindSafe(run));
+    if let Some(path) = original_path {
+        std::env::set_var("PATH", path);
+    } else {
+        std::env::remove_var("PATH");
+    }
+    if let Some(path) = original_docker_bin {
+        std::env::set_var("START_DOCKER_BIN", path);
+    } else {
+        std::env::remove_var("START_DOCKER_BIN");
+    }
+    if let Err(payload) = result {
+        resume_unwind(payload);
+    }
+}
+
+#[test]
+fn links_notation_indents_nested_process_id_arrays() {
+    let process_ids = json!({
+        "wrapperPid": 667105,
+        "screenPid": 667120,
+        "commandPids": [667121, 667122],
+    });
+    let output = format_record_with_enrichments(
+        &executing_record(),
+        "links-notation",
+        Some("2026-04-23T10:10:13.042Z"),
+        Some(&process_ids),
+    )
+    .expect("links-notation should format");
+
+    assert!(
+        output.contains(
+            "      commandPids\n        (\n          667121\n          667122\n        )"
+        ),
+        "processIds should be a nested indented block, output: {}",
+        output
+    );
+    assert!(
+        !output.contains("\n(\n"),
+        "opening parenthesis must not start at column 1: {}",
+        output
+    );
+}
+
+#[test]
+fn docker_oom_killed_is_exposed_in_status_and_list_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let record = docker_record();
+    store.save(&record).unwrap();
+
+    with_fake_docker_inspect("false 0 true", || {
+        let json_result = query_status(Some(&store), "issue144-rust", Some("json"));
+        assert!(json_result.success);
+        let parsed: Value = serde_json::from_str(&json_result.output.unwrap()).unwrap();
+        assert_eq!(parsed["status"], "executed");
+        assert_eq!(parsed["exitCode"], 0);
+        assert_eq!(parsed["oomKilled"], true);
+
+        let links_result = query_status(Some(&store), "issue144-rust", Some("links-notation"));
+        assert!(links_result.success);
+        assert!(links_result.output.unwrap().contains("  oomKilled true"));
+
+        let text_result = query_status(Some(&store), "issue144-rust", Some("text"));
+        assert!(text_result.success);
+        assert!(text_result
+            .output
+            .unwrap()
+            .contains("OOM Killed:        true"));
+
+        let list_result = list_executions(Some(&store), Some("json"));
+        assert!(list_result.success);
+        let listed: Value = serde_json::from_str(&list_result.output.unwrap()).unwrap();
+        assert_eq!(listed["count"], 1);
+        assert_eq!(listed["executions"][0]["status"], "executed");
+        assert_eq!(listed["executions"][0]["exitCode"], 0);
+        assert_eq!(listed["executions"][0]["oomKilled"], true);
+    });
+}
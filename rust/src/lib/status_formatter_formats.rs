@@ -0,0 +1,138 @@
+//! YAML, CSV and NDJSON output for --status and --list, extracted from
+//! status_formatter.rs
+//!
+//! All three are rendered from the same JSON form as `--output-format json`,
+//! so every format carries the same fields.
+
+use crate::execution_export::{csv_cell, CSV_COLUMNS};
+use serde_json::Value;
+
+/// Columns appended to the export CSV header for live status fields
+const STATUS_CSV_COLUMNS: [&str; 2] = ["currentTime", "processIds"];
+
+/// Stable CSV header of --status/--list output
+pub fn status_csv_header() -> Vec<&'static str> {
+    CSV_COLUMNS
+        .iter()
+        .chain(STATUS_CSV_COLUMNS.iter())
+        .copied()
+        .collect()
+}
+
+/// CSV with the stable [`status_csv_header`], one row per record. Nested
+/// values (options, tags, labels, notes, provenance, processIds) are JSON cells.
+pub(super) fn records_json_to_csv(rows: &[Value]) -> String {
+    let header = status_csv_header();
+    let mut lines = vec![header.join(",")];
+    for row in rows {
+        let cells: Vec<String> = header
+            .iter()
+            .map(|column| csv_cell(row.get(*column)))
+            .collect();
+        lines.push(cells.join(","));
+    }
+    lines.join("\n")
+}
+
+/// One compact JSON document per line
+pub(super) fn records_json_to_ndjson(rows: &[Value]) -> String {
+    rows.iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether a plain YAML scalar would be read back as something other than
+/// this string (a number, timestamp, boolean, null, or different structure)
+fn needs_yaml_quotes(text: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    const RESERVED: [&str; 11] = [
+        "true", "false", "yes", "no", "on", "off", "null", "~", ".inf", "-.inf", ".nan",
+    ];
+    text.is_empty()
+        || text.starts_with(INDICATORS)
+        || text.starts_with(' ')
+        || text.ends_with(' ')
+        || text.ends_with(':')
+        || text.contains(": ")
+        || text.contains(" #")
+        || text.contains(|c: char| c.is_control())
+        || RESERVED.contains(&text.to_lowercase().as_str())
+        || text.parse::<f64>().is_ok()
+        // Dates, times, versions and YAML 1.1 numbers like 0x1F or 1_000
+        || text.starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '+')
+}
+
+fn yaml_string(text: &str) -> String {
+    if needs_yaml_quotes(text) {
+        // A JSON string is a valid double-quoted YAML scalar
+        Value::String(text.to_string()).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Inline form of scalars and empty collections, `None` for nested ones
+fn yaml_scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some("null".to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::String(text) => Some(yaml_string(text)),
+        Value::Array(items) if items.is_empty() => Some("[]".to_string()),
+        Value::Object(map) if map.is_empty() => Some("{}".to_string()),
+        _ => None,
+    }
+}
+
+fn append_yaml(lines: &mut Vec<String>, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = yaml_string(key);
+                match yaml_scalar(value) {
+                    Some(scalar) => lines.push(format!("{}{}: {}", pad, key, scalar)),
+                    None => {
+                        lines.push(format!("{}{}:", pad, key));
+                        append_yaml(lines, value, indent + 2);
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                match yaml_scalar(item) {
+                    Some(scalar) => lines.push(format!("{}- {}", pad, scalar)),
+                    None => {
+                        // The first line of a nested item shares the dash line
+                        let mut nested = Vec::new();
+                        append_yaml(&mut nested, item, indent + 2);
+                        for (index, line) in nested.into_iter().enumerate() {
+                            if index == 0 {
+                                lines.push(format!("{}- {}", pad, &line[indent + 2..]));
+                            } else {
+                                lines.push(line);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        scalar => lines.push(format!(
+            "{}{}",
+            pad,
+            yaml_scalar(scalar).unwrap_or_default()
+        )),
+    }
+}
+
+/// Render a JSON value as a block-style YAML document
+pub fn value_to_yaml(value: &Value) -> String {
+    let mut lines = Vec::new();
+    append_yaml(&mut lines, value, 0);
+    lines.join("\n")
+}
@@ -0,0 +1,113 @@
+//! Store commands: reports and maintenance over the execution store
+//!
+//! Dispatches the wrapper options that operate on the stored execution history
+//! (`--stats`, `--export`, `--import`, `--annotate`, `--schema`, `--repair`,
+//! `--grep`, `--sync`, `--diff`, `--report`, `--junit`)
+//! rather than on a single command run, so the CLI entry point only needs one
+//! branch for all of them.
+
+use crate::args_parser::{record_filter, WrapperOptions};
+use crate::execution_annotations::query_annotate;
+use crate::execution_diff::query_diff;
+use crate::execution_export::{query_export, query_import, ExportOptions};
+use crate::execution_grep::{query_grep, GrepOptions};
+use crate::execution_repair::query_repair;
+use crate::execution_schema::query_schema;
+use crate::execution_stats::query_stats;
+use crate::execution_store::ExecutionStore;
+use crate::execution_sync::query_sync;
+use crate::html_report::query_report;
+use crate::junit_report::query_junit;
+use crate::status_formatter::StatusQueryResult;
+use std::path::Path;
+
+/// Run the store command selected in `options`.
+///
+/// Callers should check [`crate::args_parser::has_store_command`] first;
+/// when no store command is selected an error result is returned.
+pub fn run_store_command(
+    options: &WrapperOptions,
+    store: Option<&ExecutionStore>,
+) -> StatusQueryResult {
+    if options.stats {
+        return query_stats(
+            store,
+            &record_filter(options),
+            options.group_by.as_deref(),
+            options.output_format.as_deref(),
+        );
+    }
+
+    if let Some(ref path) = options.export {
+        return query_export(
+            store,
+            &record_filter(options),
+            Path::new(path),
+            &ExportOptions {
+                format: options.export_format.clone(),
+                with_logs: options.with_logs,
+            },
+        );
+    }
+
+    if let Some(ref path) = options.import {
+        return query_import(store, Path::new(path));
+    }
+
+    if options.annotate.is_some() {
+        return query_annotate(store, options);
+    }
+
+    if options.schema {
+        return query_schema();
+    }
+
+    if options.repair {
+        return query_repair(store, options);
+    }
+
+    if let Some(ref pattern) = options.grep {
+        return query_grep(
+            store,
+            &record_filter(options),
+            &GrepOptions {
+                pattern: pattern.clone(),
+                ignore_case: options.grep_ignore_case,
+                context: options.grep_context.unwrap_or(0),
+            },
+            options.output_format.as_deref(),
+        );
+    }
+
+    if options.sync.is_some() {
+        return query_sync(store, options);
+    }
+
+    if let Some((ref a, ref b)) = options.diff {
+        return query_diff(store, a, b, options.output_format.as_deref());
+    }
+
+    if let Some(ref format) = options.report {
+        return query_report(
+            store,
+            &record_filter(options),
+            format,
+            options.output.as_deref().map(Path::new),
+        );
+    }
+
+    if let Some(ref output) = options.junit {
+        return query_junit(
+            store,
+            &record_filter(options),
+            options.parent.as_deref(),
+            Path::new(output),
+        );
+    }
+
+    StatusQueryResult {
+        success: false,
+        output: None,
+        error: Some("No store command selected".to_string()),
+    }
+}
@@ -3,25 +3,40 @@ pub fn print_usage() {
     println!(
         r#"Usage: start [options] [--] <command> [args...]
        start <command> [args...]
-       start --status <uuid> [--output-format <format>]
-       start --list [--output-format <format>]
+       start --status <uuid> [--tree] [--output-format <format> | --columns <fields> | --format <template>]
+       start --list [filters] [--tree] [--all-users] [--output-format <format> | --columns <fields> | --format <template>]
        start --upload-log <uuid-or-session-name>
        start --stop <uuid-or-session-name>
        start --terminate <uuid-or-session-name>
+       start --follow <uuid-or-session-name> [--tail <n>]
+       start --dashboard [filters] [--all-users]
+       start --log <uuid-or-session-name> [--ansi <mode>] [--output-only] [--stderr-only] [--lines <range>] [--tail <n>] [--pager]
+       start --stats [filters] [--group-by <group>] [--output-format <format>]
+       start --export <file> [filters] [--export-format <format>] [--with-logs]
+       start --import <file>
+       start --annotate <uuid-or-session-name> [--note <text>] [--tag <tag>] [--label <key=value>]
+       start --schema
+       start --repair [--repair-dry-run] [--output-format <format>]
+       start --grep <pattern> [filters] [--ignore-case] [--context <n>] [--output-format <format>]
+       start --sync <remote> [--sync-logs] [--output-format <format>]
+       start --diff <uuid-or-session-name> <uuid-or-session-name> [--output-format <format>]
+       start --report html [filters] [--output <file>]
+       start --junit <file> [filters] [--parent <uuid-or-session-name>]
 
 Options:
-  --isolated, --isolation, -i <env>  Run in isolated environment (screen, tmux, docker, ssh)
+  --isolated, --isolation, -i <env>  Run in isolated environment (screen, tmux, docker, podman, ssh)
+                        A sequence like "screen ssh docker" nests up to 7 levels
   --attached, -a        Run in attached mode (foreground)
   --detached, -d        Run in detached mode (background)
-  --session, -s <name>  Session name for isolation
+  --session, -s <name>  Session name for isolation (a sequence names each stacked level)
   --session-id <uuid>   Session UUID for tracking (auto-generated if not provided)
   --session-name <uuid> Alias for --session-id
-  --image <image>       Docker image (optional, defaults to OS-matched image)
-  --volume, -v <spec>   Docker bind mount/volume host:container[:mode] (repeatable, docker only)
-  --mount <spec>        Docker --mount spec (repeatable, docker only)
-  --env, -e <KEY=VALUE> Environment variable for docker container (repeatable, docker only)
-  --privileged          Run docker container in privileged mode (docker only)
-  --endpoint <endpoint> SSH endpoint (required for ssh isolation, e.g., user@host)
+  --image <image>       Container image (optional, defaults to OS-matched image; "_ _ img" per level)
+  --volume, -v <spec>   Docker bind mount/volume host:container[:mode] (repeatable, docker/podman only)
+  --mount <spec>        Docker --mount spec (repeatable, docker/podman only)
+  --env, -e <KEY=VALUE> Environment variable for the container (repeatable, docker/podman only)
+  --privileged          Run the container in privileged mode (docker/podman only)
+  --endpoint <endpoint> SSH endpoint (required for ssh isolation, e.g., user@host; "_ host _" per level)
   --isolated-user, -u [name]  Create isolated user with same permissions
   --keep-user           Keep isolated user after command completes
   --keep-alive, -k      Keep isolation environment alive after command exits
@@ -31,13 +46,61 @@ Options:
   --keep-container-on-fail  Remove successful docker containers, keep failed or OOM-killed ones
   --shell <shell>       Shell to use in isolation environments: auto, bash, zsh, sh (default: auto)
   --use-command-stream  Use command-stream library for execution (experimental)
-  --status <id>         Show status of execution by UUID or session name (--output-format: links-notation|json|text)
-  --list                List all tracked executions (--output-format: links-notation|json|text)
+  --theme <theme>       Output theme: timeline, ascii, compact, boxed (default: START_THEME or timeline)
+  --quiet, -q           Print only the command output, without the start and finish blocks
+  --silent              Print nothing unless the command fails, then its last 20 lines and the log path (not with --detached or tmux)
+  --summary-only        Print only the finish block, command output still goes to the log (not with --detached or tmux)
+  --events <path|fd:N>  Write newline-delimited JSON events about the run to a file or file descriptor
+  --ci                  GitHub Actions log groups, ::error annotations and step summary (default: when CI is set)
+  --status <id>         Show status of execution by UUID or session name (--output-format: links-notation|json|text|yaml|csv|ndjson)
+  --list                List all tracked executions (--output-format: links-notation|json|text|yaml|csv|ndjson)
+  --columns <fields>    Show --status/--list as a table of fields fitted to the terminal width
+  --format <template>   Show --status/--list as one line per record, e.g. '{{{{uuid}}}} {{{{options.isolated}}}}'
   --upload-log <id>     Upload the stored log for an execution UUID or session name
   --stop <id>           Ask a detached isolated execution to stop gracefully
   --terminate <id>      Terminate a detached isolated execution immediately
+  --follow <id>         Stream an execution's log until it finishes and exit with its code
+  --tail <n>            Show only the last n lines of the log (--follow, --log, --silent)
+  --dashboard           Live dashboard of executions: log preview, stop, terminate, upload, replay, attach
+  --log <id>            Print the stored log of an execution (compressed logs are read transparently)
+  --ansi <mode>         ANSI colors in --log output: auto, keep, strip (default: auto)
+  --output-only         Show --log without the start-command header and footer
+  --stderr-only         Show only stderr in --log (recorded separately for direct runs)
+  --lines <range>       Show only lines <start>:<end> of --log (either bound may be omitted)
+  --pager               Page --log output through $PAGER (default: less -R)
   --cleanup             Clean up stale "executing" records (crashed/killed processes)
   --cleanup-dry-run     Show stale records that would be cleaned up (without cleaning)
+  --stats               Show run counts, success rate, durations, flakiness and trends
+  --group-by <group>    Group --stats by command, prefix, directory, or backend
+  --export <file>       Export execution history (format from extension; .tar bundles logs)
+  --export-format <fmt> Export format: jsonl, csv, lino (default: jsonl)
+  --with-logs           Bundle referenced log files into a tar archive with --export
+  --import <file>       Import exported history, merging by UUID (later end time wins)
+  --since <time>        Only records started at/after time (RFC 3339, YYYY-MM-DD, or 30m/24h/7d/2w)
+  --until <time>        Only records started before time
+  --command-contains <text>  Only records whose command contains text
+  --backend <name>      Only records run with isolation backend (direct, screen, tmux, docker, podman, ssh)
+  --outcome <outcome>   Only records that succeeded, failed, or are executing
+  --limit <n>           Only the newest n records
+  --tag <tag>           Tag the execution (repeatable); filters --list, --stats, --export, --grep
+  --label <key=value>   Label the execution (repeatable); filters --list, --stats, --export, --grep
+  --annotate <id>       Add a note, tags or labels to a tracked execution after the fact
+  --note <text>         Note text for --annotate
+  --tree                Show --status/--list as parent/child trees of nested executions
+  --schema              Print the JSON Schema for stored execution records
+  --repair              Reconcile lino/links stores, recover finished runs, quarantine bad entries
+  --repair-dry-run      Show what --repair would change without writing
+  --grep <pattern>      Search the logs of tracked executions (regex), grouped by execution
+  --ignore-case         Match the --grep pattern case-insensitively
+  --context <n>         Show n lines before and after each --grep match
+  --all-users           Include every user's executions from the shared store (START_SHARED_STORE)
+  --sync <remote>       Push and pull execution records through a git remote (URL or path)
+  --sync-logs           Also sync gzip-compressed logs of finished executions
+  --diff <id-a> <id-b>  Compare two executions: changed fields and a normalized unified diff of their logs
+  --report html         Render filtered executions as one self-contained HTML file
+  --output <file>       Write the --report to <file> instead of stdout
+  --junit <file>        Write filtered executions to <file> as JUnit XML test cases with log tails
+  --parent <id>         Limit --junit to the executions nested under this UUID or session name
   --version, -v         Show version information
 
 Examples:
@@ -49,7 +112,9 @@ Examples:
   start --isolated docker --image oven/bun:latest -- bun install
   start -i docker -v ~/.config/gh:/root/.config/gh -e TOKEN=abc -- gh repo list
   start -i docker --image konard/hive-mind-dind:latest --privileged -- solve ...
+  start --isolated podman --image alpine:3.20 -- uname -a  # rootless, no daemon
   start --isolated ssh --endpoint user@remote.server -- ls -la
+  start -i "screen ssh docker" --endpoint "_ user@host _" --image "_ _ node:20" -- npm test
   start --isolated-user -- npm test
   start -u myuser -- npm start
   start -i screen --isolated-user -- npm test
@@ -57,17 +122,57 @@ Examples:
   start --status a1b2c3d4 --output-format json
   start --list
   start --list --output-format json
+  start --list --since 24h --output-format ndjson >> executions.ndjson
+  start --list --columns uuid,status,exitCode,duration,command
+  start --list --outcome failed --format '{{{{uuid}}}} {{{{exitCode}}}} {{{{command}}}}'
   start --upload-log my-screen-session
   start --stop my-screen-session
   start --terminate my-screen-session
+  start --follow my-screen-session --tail 20
+  start --dashboard --since 24h
+  start --log my-screen-session --output-only --ansi strip --lines 100:200
   start --cleanup-dry-run
+  start --repair-dry-run --output-format text
   start --cleanup
+  start --stats --group-by prefix --output-format text
+  start --list --since 7d --outcome failed
+  start --export history.csv --backend docker
+  start --export history.tar --with-logs --since 2026-10-01
+  start --import history.tar
+  start --tag release-1.4 --label env=prod -- ./release.sh
+  start --annotate my-screen-session --note "bisect says good" --tag bisect-good
+  start --list --tag release-1.4
+  start --grep 'ECONNRESET|timed out' --ignore-case --context 2 --since 7d
+  start --status a1b2c3d4 --tree --output-format text
+  START_SHARED_STORE=/var/lib/start-command start --list --all-users --outcome executing
+  start --sync git@github.com:me/start-history.git --sync-logs
+  start --diff nightly-2026-10-16 nightly-2026-10-17 --output-format text
+  start --report html --since 7d --outcome failed --output report.html
+  start --junit steps.xml --tag nightly --since 1d
+  NO_COLOR=1 start --theme ascii -- cargo build
+  start --silent --tail 50 -- npm ci
+  start --events fd:3 -- npm test 3>events.ndjson
+  start --ci -- npm test
 
 Features:
   - Logs all output to temporary directory
   - Displays timestamps and exit codes
   - Auto-reports failures for NPM packages (when gh is available)
   - Natural language command aliases (via substitutions.lino)
-  - Process isolation via screen, tmux, or docker"#
+  - Process isolation via screen, tmux, docker, or podman
+  - Stacked isolation such as screen → ssh → docker with per-level options
+  - Nested executions are linked via START_EXECUTION_ID/START_ROOT_EXECUTION_ID
+  - Records git commit/branch/dirty state and tool versions (opt in with START_PROVENANCE=1)
+  - Optional system-wide store shared by all users (START_SHARED_STORE=<dir>)
+  - Conflict-free history sync across machines through any git remote (--sync)
+  - Run-to-run comparison with timestamps, PIDs and temp paths normalized away (--diff)
+  - Script-friendly --status/--list output via --columns tables and --format templates
+  - Themes for the timeline output, including ASCII-only and one-line (--theme, START_THEME)
+  - Colored result markers honoring NO_COLOR and CLICOLOR_FORCE
+  - Quiet, silent and summary-only output for scripted steps (--quiet, --silent, --summary-only)
+  - Machine-readable NDJSON event stream for IDEs and harnesses (--events)
+  - CI mode with log groups, error annotations and a step summary table (--ci, START_DISABLE_CI=1)
+  - Static HTML reports with a sortable table, timelines and colorized logs (--report)
+  - JUnit XML reports so CI dashboards show wrapped steps as test results (--junit)"#
     );
 }
@@ -347,6 +347,59 @@ pub fn create_isolated_user(
     )
 }
 
+/// Create the user for --isolated-user, returning its name and the
+/// `[User Isolation]` lines shown in the start block
+pub fn prepare_isolated_user(
+    custom_username: Option<&str>,
+    keep_user: bool,
+) -> Result<(String, Vec<String>), String> {
+    if !has_sudo_access() {
+        return Err("--isolated-user requires sudo access without password.\nConfigure NOPASSWD in sudoers or run with appropriate permissions.".to_string());
+    }
+
+    let current_groups = get_current_user_groups();
+    let important_groups: Vec<&str> = ["sudo", "docker", "wheel", "admin"]
+        .iter()
+        .copied()
+        .filter(|g| current_groups.iter().any(|cg| cg == *g))
+        .collect();
+
+    let mut lines = vec!["[User Isolation] Creating new user...".to_string()];
+    if !important_groups.is_empty() {
+        lines.push(format!(
+            "[User Isolation] Inheriting groups: {}",
+            important_groups.join(", ")
+        ));
+    }
+
+    let user_result = create_isolated_user(custom_username, &CreateIsolatedUserOptions::default());
+    let username = match user_result.username {
+        Some(username) if user_result.success => username,
+        _ => {
+            return Err(format!(
+                "Failed to create isolated user: {}",
+                user_result.message
+            ))
+        }
+    };
+
+    lines.push(format!("[User Isolation] Created user: {}", username));
+    if let Some(groups) = user_result
+        .groups
+        .as_ref()
+        .filter(|groups| !groups.is_empty())
+    {
+        lines.push(format!(
+            "[User Isolation] User groups: {}",
+            groups.join(", ")
+        ));
+    }
+    if keep_user {
+        lines.push("[User Isolation] User will be kept after completion".to_string());
+    }
+    Ok((username, lines))
+}
+
 /// Options for deleting a user
 #[derive(Debug, Default)]
 pub struct DeleteUserOptions {
@@ -747,7 +747,7 @@ mod status_tests {
         let result = parse_args(&args(&["--status", "uuid-here", "--stop", "my-session"]));
         assert!(result.is_err());
         assert!(result.unwrap_err().contains(
-            "Cannot combine --status, --list, --upload-log, --stop, --terminate, or --cleanup"
+            "Cannot combine --status, --list, --upload-log, --stop, --terminate, --follow, --log, or --cleanup"
         ));
     }
 
@@ -834,6 +834,13 @@ mod valid_output_formats_tests {
     fn should_include_text() {
         assert!(VALID_OUTPUT_FORMATS.contains(&"text"));
     }
+
+    #[test]
+    fn should_include_streaming_and_tabular_formats() {
+        for format in ["yaml", "csv", "ndjson"] {
+            assert!(VALID_OUTPUT_FORMATS.contains(&format));
+        }
+    }
 }
 
 mod cleanup_cases {
@@ -0,0 +1,139 @@
+//! Tests for CI mode (--ci)
+//!
+//! Covers detecting CI, log groups, escaping workflow commands, the ::error
+//! annotation, the step summary table and the --ci argument.
+
+use start_command::{
+    append_step_summary, ci_duration, ci_from_env, ci_group, error_annotation, escape_data,
+    escape_property, parse_args, step_summary, CiRun, OutputStyle,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn run(command: &'static str, exit_code: i32) -> CiRun<'static> {
+    CiRun {
+        command,
+        session_id: "test-uuid",
+        exit_code,
+        duration_ms: 12345.6,
+        log_path: Path::new("/tmp/start-command/logs/direct/test-uuid.log"),
+        detached: false,
+    }
+}
+
+#[test]
+fn test_ci_from_env() {
+    assert!(!ci_from_env(false, None, None, None));
+    assert!(ci_from_env(true, None, None, None));
+    assert!(ci_from_env(false, Some("true"), None, None));
+    assert!(ci_from_env(false, None, Some("true"), None));
+    assert!(ci_from_env(false, Some("1"), None, None));
+    assert!(!ci_from_env(false, Some("false"), None, None));
+    // START_DISABLE_CI turns off detection but not --ci
+    assert!(!ci_from_env(false, Some("true"), Some("true"), Some("1")));
+    assert!(ci_from_env(true, None, None, Some("1")));
+}
+
+#[test]
+fn test_ci_duration_and_group() {
+    assert_eq!(ci_duration(12345.6), "12.346s");
+    assert_eq!(ci_duration(125000.0), "125.000s");
+    assert_eq!(ci_duration(0.2), "0.000s");
+    assert_eq!(
+        ci_group("$ npm test", "│ session   test-uuid\n"),
+        "::group::$ npm test\n│ session   test-uuid\n::endgroup::"
+    );
+}
+
+#[test]
+fn test_escape_workflow_commands() {
+    assert_eq!(escape_data("100%\r\ndone"), "100%25%0D%0Adone");
+    assert_eq!(escape_property("a: b, c"), "a%3A b%2C c");
+}
+
+#[test]
+fn test_error_annotation() {
+    assert_eq!(
+        error_annotation(&run("npm test", 1)),
+        "::error title=Command failed with exit code 1::npm test exited with code 1 after 12.346s%0AExecution: test-uuid%0ALog: /tmp/start-command/logs/direct/test-uuid.log"
+    );
+}
+
+#[test]
+fn test_step_summary() {
+    assert_eq!(
+        step_summary(&run("echo a|b", 0), true),
+        "| Command | Result | Duration | Execution | Log |\n\
+         | --- | --- | --- | --- | --- |\n\
+         | `echo a\\|b` | ✓ success | 12.346s | `test-uuid` | `/tmp/start-command/logs/direct/test-uuid.log` |\n"
+    );
+    let detached = CiRun {
+        detached: true,
+        ..run("make", 0)
+    };
+    assert!(step_summary(&detached, false).contains("| executing (detached) |"));
+    assert!(step_summary(&run("make", 2), false).contains("| ✗ exit code 2 |"));
+}
+
+#[test]
+fn test_step_summary_commands_with_backticks() {
+    // The fence is longer than any run of backticks in the command
+    assert!(step_summary(&run("echo `date`", 0), false).starts_with("| `` echo `date` `` |"));
+    assert!(step_summary(&run("echo ``a``", 0), false).starts_with("| ``` echo ``a`` ``` |"));
+    assert!(step_summary(&run("`pwd`/run", 0), false).starts_with("| `` `pwd`/run `` |"));
+    assert!(step_summary(&run("ls `x` y", 0), false).starts_with("| ``ls `x` y`` |"));
+}
+
+#[test]
+fn test_append_step_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("summary.md");
+    fs::write(&path, "## Build\n").unwrap();
+
+    append_step_summary(&path, &run("npm ci", 0)).unwrap();
+    append_step_summary(&path, &run("npm test", 1)).unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(
+        content.starts_with("## Build\n\n| Command |"),
+        "{}",
+        content
+    );
+    // Consecutive runs share one table
+    assert_eq!(content.matches("| Command |").count(), 1);
+    assert!(content.ends_with("| ✗ exit code 1 | 12.346s | `test-uuid` | `/tmp/start-command/logs/direct/test-uuid.log` |\n"));
+}
+
+#[test]
+fn test_ci_sections() {
+    let style = OutputStyle {
+        ci: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        style.start_section("make", "│ session   test-uuid"),
+        "::group::$ make\n│ session   test-uuid\n::endgroup::"
+    );
+    assert!(style
+        .finish_section(2, 1500.0, "✗\n│ exit      2")
+        .starts_with("::group::✗ exit 2 after 1.500s\n"));
+    // Outside CI the blocks are unchanged
+    let plain = OutputStyle::default();
+    assert_eq!(plain.start_section("make", "block"), "block");
+    assert_eq!(plain.finish_section(0, 1.0, "block"), "block");
+}
+
+#[test]
+fn test_ci_argument() {
+    let options = parse_args(&args(&["--ci", "--", "npm", "test"]))
+        .unwrap()
+        .wrapper_options;
+    assert!(options.ci);
+    let options = parse_args(&args(&["npm", "test"])).unwrap().wrapper_options;
+    assert!(!options.ci);
+}
@@ -0,0 +1,189 @@
+//! Tests for the interactive execution dashboard (--dashboard)
+//!
+//! Covers key bindings, labels, the rendered frame and selection, the attach
+//! and replay commands, log previews and the --dashboard arguments.
+
+use serde_json::json;
+use start_command::{
+    attach_command, elapsed_label, log_preview, parse_args, parse_key, replay_args, status_label,
+    Dashboard, DashboardKey, ExecutionRecord, DASHBOARD_KEYS,
+};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn detached(command: &str, backend: &str, session: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.options = HashMap::from([
+        ("isolated".to_string(), json!(backend)),
+        ("isolationMode".to_string(), json!("detached")),
+        ("sessionName".to_string(), json!(session)),
+    ]);
+    record
+}
+
+#[test]
+fn test_parse_key() {
+    assert_eq!(parse_key(b"\x1b[A"), Some(DashboardKey::Up));
+    assert_eq!(parse_key(b"j"), Some(DashboardKey::Down));
+    assert_eq!(parse_key(b"t"), Some(DashboardKey::Terminate));
+    assert_eq!(parse_key(b"\r"), Some(DashboardKey::Attach));
+    assert_eq!(parse_key(b"q"), Some(DashboardKey::Quit));
+    assert_eq!(parse_key(b"\x03"), Some(DashboardKey::Quit));
+    assert_eq!(parse_key(b"z"), None);
+}
+
+#[test]
+fn test_labels() {
+    let mut record = ExecutionRecord::new("make");
+    record.start_time = "2026-10-18T10:00:00Z".to_string();
+    assert_eq!(status_label(&record), "executing");
+    assert!(elapsed_label(&record).ends_with('s'));
+
+    record.complete(2);
+    record.end_time = Some("2026-10-18T10:00:12.5Z".to_string());
+    assert_eq!(status_label(&record), "failed (2)");
+    assert_eq!(elapsed_label(&record), "12.50s");
+
+    record.exit_code = Some(0);
+    assert_eq!(status_label(&record), "succeeded");
+    record.start_time = "not a time".to_string();
+    assert_eq!(elapsed_label(&record), "-");
+}
+
+#[test]
+fn test_render_and_selection() {
+    let mut dashboard = Dashboard::default();
+    let frame = dashboard.render(&[], 80, 12);
+    assert_eq!(frame.len(), 12);
+    assert!(frame[2].contains("No executions found."));
+    assert_eq!(frame[11], DASHBOARD_KEYS);
+
+    let first = detached("npm run dev", "screen", "dev-server");
+    let mut second = ExecutionRecord::new("cargo build");
+    second.complete(0);
+    dashboard.set_records(vec![first.clone(), second.clone()]);
+    dashboard.move_selection(1);
+    dashboard.move_selection(5);
+    assert_eq!(dashboard.selected().unwrap().uuid, second.uuid);
+    dashboard.move_selection(-3);
+    assert_eq!(dashboard.selected().unwrap().uuid, first.uuid);
+
+    // The selection follows the execution when the list changes order
+    dashboard.set_records(vec![second.clone(), first.clone()]);
+    assert_eq!(dashboard.selected().unwrap().uuid, first.uuid);
+
+    dashboard.message = Some("Sent stop to dev-server".to_string());
+    let frame = dashboard.render(&["ready on :3000".to_string()], 60, 14);
+    assert_eq!(frame.len(), 14);
+    assert!(frame[0].contains("2 executions, 1 executing"));
+    let selected = frame.iter().find(|line| line.starts_with('>')).unwrap();
+    assert!(selected.contains("executing") && selected.contains("dev-server"));
+    assert!(frame.iter().any(|line| line == "ready on :3000"));
+    assert_eq!(frame[12], "Sent stop to dev-server");
+    assert!(frame.iter().all(|line| line.chars().count() <= 60));
+}
+
+#[test]
+fn test_attach_and_replay_commands() {
+    let record = detached("npm run dev", "tmux", "dev-server");
+    assert_eq!(
+        attach_command(&record),
+        Some(("tmux".to_string(), args(&["attach", "-t", "dev-server"])))
+    );
+    let mut finished = record.clone();
+    finished.complete(0);
+    assert_eq!(attach_command(&finished), None);
+    assert_eq!(attach_command(&detached("ls", "ssh", "remote")), None);
+
+    let mut docker = detached("bun test", "docker", "tests");
+    docker
+        .options
+        .insert("image".to_string(), json!("oven/bun:latest"));
+    assert_eq!(
+        replay_args(&docker),
+        args(&[
+            "--isolated",
+            "docker",
+            "--image",
+            "oven/bun:latest",
+            "--",
+            "bun test"
+        ])
+    );
+    assert_eq!(
+        replay_args(&ExecutionRecord::new("echo hi")),
+        args(&["--", "echo hi"])
+    );
+}
+
+#[test]
+fn test_log_preview() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut record = ExecutionRecord::new("make");
+    let log_path = temp_dir.path().join("make.log");
+    fs::write(&log_path, "one\ntwo\nthree\n").unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    assert_eq!(log_preview(&record, 2, 80), args(&["two", "three"]));
+
+    fs::remove_file(&log_path).unwrap();
+    assert!(log_preview(&record, 2, 80)[0].starts_with("(log not found"));
+}
+
+#[test]
+fn test_log_preview_is_plain_and_fits_the_terminal() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut record = ExecutionRecord::new("npm ci");
+    let log_path = temp_dir.path().join("npm.log");
+    fs::write(
+        &log_path,
+        "\x1b[32madded\x1b[0m 12 packages\nprogress 10%\rprogress 100%\na\tb\x07\n",
+    )
+    .unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    assert_eq!(
+        log_preview(&record, 3, 80),
+        args(&["added 12 packages", "progress 100%", "a b"])
+    );
+    assert_eq!(log_preview(&record, 1, 2), args(&["a "]));
+}
+
+#[test]
+fn test_log_preview_of_large_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut record = ExecutionRecord::new("make");
+    let log_path = temp_dir.path().join("make.log");
+    let lines: Vec<String> = (1..=100_000).map(|n| format!("line {}", n)).collect();
+    fs::write(&log_path, lines.join("\n") + "\n").unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    assert_eq!(
+        log_preview(&record, 2, 80),
+        args(&["line 99999", "line 100000"])
+    );
+    // Asking for more lines than the read end of the log holds starts at
+    // a whole line
+    let preview = log_preview(&record, 100_000, 80);
+    assert!(preview.len() < 100_000);
+    assert!(lines.contains(&preview[0]), "{}", preview[0]);
+    assert_eq!(preview.last().unwrap(), "line 100000");
+}
+
+#[test]
+fn test_dashboard_arguments() {
+    let options = parse_args(&args(&["--dashboard", "--since", "24h", "--tag", "ci"]))
+        .unwrap()
+        .wrapper_options;
+    assert!(options.dashboard);
+    assert!(options.filter.since.is_some());
+
+    let err = parse_args(&args(&["--dashboard", "--list"])).unwrap_err();
+    assert!(err.contains("--dashboard cannot be combined"));
+    let err = parse_args(&args(&["--dashboard", "--stats"])).unwrap_err();
+    assert!(err.contains("cannot be combined"));
+    let err = parse_args(&args(&["--dashboard", "--output-format", "json"])).unwrap_err();
+    assert!(err.contains("--output-format option is only valid"));
+}
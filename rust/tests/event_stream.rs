@@ -0,0 +1,121 @@
+//! Tests for the machine-readable event stream (--events)
+//!
+//! Covers parsing targets, building event lines, writing events to a file or
+//! an inherited descriptor and the --events argument.
+
+use start_command::{event_line, parse_args, EventStream, EventTarget, EVENT_TYPES};
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_event_target_parse() {
+    assert_eq!(
+        EventTarget::parse("events.ndjson"),
+        Ok(EventTarget::Path(PathBuf::from("events.ndjson")))
+    );
+    assert_eq!(EventTarget::parse("fd:3"), Ok(EventTarget::Fd(3)));
+    assert_eq!(
+        EventTarget::parse("fd:three").unwrap_err(),
+        "Invalid --events target: \"fd:three\". Expected a path or fd:N"
+    );
+    assert!(EventTarget::parse("fd:-1").is_err());
+    assert!(EventTarget::parse("").is_err());
+}
+
+#[test]
+fn test_event_line() {
+    let line = event_line(
+        "finished",
+        "2026-10-18T09:00:00.000Z",
+        "test-uuid",
+        serde_json::json!({"exitCode": 1, "event": "ignored"}),
+    );
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({
+            "event": "finished",
+            "timestamp": "2026-10-18T09:00:00.000Z",
+            "session": "test-uuid",
+            "exitCode": 1,
+        })
+    );
+    assert!(!line.contains('\n'));
+    assert!(EVENT_TYPES.contains(&"isolation-session-created"));
+}
+
+#[test]
+fn test_event_stream_appends_to_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("events.ndjson");
+    fs::write(&path, "{\"event\":\"earlier\"}\n").unwrap();
+
+    let stream = EventStream::open(&path.to_string_lossy(), "test-uuid").unwrap();
+    stream.emit("started", serde_json::json!({"command": "echo hi"}));
+    stream.emit(
+        "output",
+        serde_json::json!({"stream": "stdout", "line": "hi"}),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    let events: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1]["event"], "started");
+    assert_eq!(events[1]["session"], "test-uuid");
+    assert_eq!(events[2]["line"], "hi");
+    assert!(events[2]["timestamp"].as_str().unwrap().ends_with('Z'));
+
+    let missing = temp_dir.path().join("missing").join("events.ndjson");
+    let err = EventStream::open(&missing.to_string_lossy(), "test-uuid").unwrap_err();
+    assert!(err.starts_with("Could not open --events file"), "{}", err);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_event_stream_file_descriptor() {
+    use std::os::unix::io::AsRawFd;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("fd.ndjson");
+    let file = fs::File::create(&path).unwrap();
+    let spec = format!("fd:{}", file.as_raw_fd());
+    let stream = EventStream::open(&spec, "test-uuid").unwrap();
+    // The stream keeps its own copy of the descriptor
+    drop(file);
+    stream.emit("finished", serde_json::json!({"exitCode": 0}));
+    assert!(fs::read_to_string(&path)
+        .unwrap()
+        .contains("\"event\":\"finished\""));
+
+    let err = EventStream::open("fd:987654", "test-uuid").unwrap_err();
+    assert!(
+        err.starts_with("Could not open --events fd:987654"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_events_argument() {
+    let options = parse_args(&args(&["--events", "fd:3", "--", "npm", "test"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.events.as_deref(), Some("fd:3"));
+    let options = parse_args(&args(&["--events=/tmp/events.ndjson", "make"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.events.as_deref(), Some("/tmp/events.ndjson"));
+
+    let err = parse_args(&args(&["--events", "fd:x", "make"])).unwrap_err();
+    assert!(err.starts_with("Invalid --events target"), "{}", err);
+    let err = parse_args(&args(&["--events"])).unwrap_err();
+    assert!(err.contains("requires a path or fd:N"));
+}
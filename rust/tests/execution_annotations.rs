@@ -0,0 +1,295 @@
+//! Tests for execution tags, labels and notes
+//!
+//! Covers --tag/--label at launch, --annotate with --note, the tag/label
+//! filters for --list, and how --status shows annotations.
+
+use start_command::args_parser::record_filter;
+use start_command::status_formatter::{format_record, list_executions_filtered};
+use start_command::{
+    annotate_execution, apply_launch_annotations, parse_args, parse_label, parse_records,
+    query_annotate, serialize_records, ExecutionRecord, ExecutionStore, ExecutionStoreOptions,
+    WrapperOptions,
+};
+use std::collections::BTreeMap;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn create_store() -> (ExecutionStore, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    (store, temp_dir)
+}
+
+fn labels(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_parse_tags_and_labels_at_launch() {
+    let result = parse_args(&args(&[
+        "--tag",
+        "release-1.4",
+        "--tag=bisect-good",
+        "--label",
+        "env=staging",
+        "--",
+        "npm",
+        "test",
+    ]))
+    .unwrap();
+    assert_eq!(
+        result.wrapper_options.tags,
+        vec!["release-1.4".to_string(), "bisect-good".to_string()]
+    );
+    assert_eq!(result.wrapper_options.labels, labels(&[("env", "staging")]));
+    assert_eq!(result.command, "npm test");
+}
+
+#[test]
+fn test_parse_label_validation() {
+    assert_eq!(
+        parse_label("team = infra").unwrap(),
+        ("team".to_string(), "infra".to_string())
+    );
+    assert!(parse_label("no-equals").is_err());
+    assert!(parse_label("=value").is_err());
+    assert!(parse_label("two words=x").is_err());
+
+    let err = parse_args(&args(&["--label", "oops", "--", "ls"])).unwrap_err();
+    assert!(err.contains("Invalid label"));
+    let err = parse_args(&args(&["--tag=", "--", "ls"])).unwrap_err();
+    assert!(err.contains("--tag requires"));
+}
+
+#[test]
+fn test_annotate_argument_validation() {
+    let result = parse_args(&args(&[
+        "--annotate",
+        "my-session",
+        "--note",
+        "bisect good",
+    ]))
+    .unwrap();
+    assert_eq!(
+        result.wrapper_options.annotate.as_deref(),
+        Some("my-session")
+    );
+    assert_eq!(result.wrapper_options.note.as_deref(), Some("bisect good"));
+
+    let err = parse_args(&args(&["--annotate", "my-session"])).unwrap_err();
+    assert!(err.contains("--annotate requires --note, --tag, or --label"));
+
+    let err = parse_args(&args(&["--note", "text", "--", "ls"])).unwrap_err();
+    assert!(err.contains("--note option is only valid with --annotate"));
+
+    let err = parse_args(&args(&["--annotate", "x", "--note", "y", "--list"])).unwrap_err();
+    assert!(err.contains("--annotate cannot be combined"));
+
+    let err = parse_args(&args(&["--status", "abc", "--tag", "release"])).unwrap_err();
+    assert!(err.contains("--tag and --label are only valid"));
+}
+
+#[test]
+fn test_list_tag_and_label_become_filters() {
+    let result = parse_args(&args(&[
+        "--list",
+        "--tag",
+        "release-1.4",
+        "--label=env=prod",
+    ]))
+    .unwrap();
+    let filter = record_filter(&result.wrapper_options);
+    assert_eq!(filter.tags, vec!["release-1.4".to_string()]);
+    assert_eq!(filter.labels, labels(&[("env", "prod")]));
+}
+
+#[test]
+fn test_apply_launch_annotations() {
+    let options = WrapperOptions {
+        tags: vec!["release-1.4".to_string()],
+        labels: labels(&[("env", "staging")]),
+        ..Default::default()
+    };
+    let mut record = ExecutionRecord::new("make release");
+    apply_launch_annotations(&mut record, &options);
+    apply_launch_annotations(&mut record, &options);
+    assert_eq!(record.tags, vec!["release-1.4".to_string()]);
+    assert_eq!(record.labels, labels(&[("env", "staging")]));
+}
+
+#[test]
+fn test_annotate_execution_adds_note_tags_and_labels() {
+    let (store, _temp) = create_store();
+    let record = ExecutionRecord::new("git bisect run make test");
+    store.save(&record).unwrap();
+
+    let updated = annotate_execution(
+        &store,
+        &record.uuid,
+        Some("bisect says good"),
+        &["bisect-good".to_string()],
+        &labels(&[("commit", "abc123")]),
+    )
+    .unwrap();
+    assert_eq!(updated.notes.len(), 1);
+    assert_eq!(updated.notes[0].text, "bisect says good");
+
+    let stored = store.get(&record.uuid).unwrap();
+    assert_eq!(stored.tags, vec!["bisect-good".to_string()]);
+    assert_eq!(stored.labels, labels(&[("commit", "abc123")]));
+    assert_eq!(stored.notes, updated.notes);
+
+    let missing = annotate_execution(&store, "missing", Some("x"), &[], &BTreeMap::new());
+    assert!(missing.unwrap_err().contains("No execution found"));
+}
+
+#[test]
+fn test_save_keeps_annotations_added_while_running() {
+    let (store, _temp) = create_store();
+    let mut running = ExecutionRecord::new("sleep 60");
+    store.save(&running).unwrap();
+
+    annotate_execution(
+        &store,
+        &running.uuid,
+        Some("slow run"),
+        &[],
+        &BTreeMap::new(),
+    )
+    .unwrap();
+
+    // The running process still holds its original copy when it finishes
+    running.complete(0);
+    store.save(&running).unwrap();
+
+    let stored = store.get(&running.uuid).unwrap();
+    assert_eq!(stored.exit_code, Some(0));
+    assert_eq!(stored.notes.len(), 1);
+    assert_eq!(stored.notes[0].text, "slow run");
+}
+
+#[test]
+fn test_completion_keeps_labels_changed_while_running() {
+    let (store, _temp) = create_store();
+    let mut running = ExecutionRecord::new("make deploy");
+    running.labels = labels(&[("env", "staging")]);
+    store.save(&running).unwrap();
+
+    annotate_execution(
+        &store,
+        &running.uuid,
+        None,
+        &["hotfix".to_string()],
+        &labels(&[("env", "production"), ("owner", "web")]),
+    )
+    .unwrap();
+
+    running.complete(0);
+    store.save(&running).unwrap();
+
+    let stored = store.get(&running.uuid).unwrap();
+    assert_eq!(stored.exit_code, Some(0));
+    assert_eq!(stored.tags, vec!["hotfix".to_string()]);
+    assert_eq!(
+        stored.labels,
+        labels(&[("env", "production"), ("owner", "web")])
+    );
+}
+
+#[test]
+fn test_query_annotate_outputs_record() {
+    let (store, _temp) = create_store();
+    let record = ExecutionRecord::new("echo hi");
+    store.save(&record).unwrap();
+
+    let options = WrapperOptions {
+        annotate: Some(record.uuid.clone()),
+        note: Some("checked".to_string()),
+        output_format: Some("json".to_string()),
+        ..Default::default()
+    };
+    let result = query_annotate(Some(&store), &options);
+    assert!(result.success, "{:?}", result.error);
+    assert!(result.output.unwrap().contains("\"checked\""));
+
+    let disabled = query_annotate(None, &options);
+    assert!(!disabled.success);
+}
+
+#[test]
+fn test_list_filters_by_tag_and_label() {
+    let (store, _temp) = create_store();
+    let mut release = ExecutionRecord::new("make release");
+    release.tags.push("release-1.4".to_string());
+    release.labels.insert("env".to_string(), "prod".to_string());
+    store.save(&release).unwrap();
+    store.save(&ExecutionRecord::new("make test")).unwrap();
+
+    let result = parse_args(&args(&["--list", "--tag", "release-1.4"])).unwrap();
+    let listed = list_executions_filtered(
+        Some(&store),
+        &record_filter(&result.wrapper_options),
+        Some("json"),
+    );
+    let output = listed.output.unwrap();
+    assert!(output.contains("\"count\": 1"));
+    assert!(output.contains("make release"));
+
+    let result = parse_args(&args(&["--list", "--label", "env=staging"])).unwrap();
+    let listed = list_executions_filtered(
+        Some(&store),
+        &record_filter(&result.wrapper_options),
+        Some("json"),
+    );
+    assert!(listed.output.unwrap().contains("\"count\": 0"));
+}
+
+#[test]
+fn test_status_shows_annotations() {
+    let (store, _temp) = create_store();
+    let record = ExecutionRecord::new("make release");
+    store.save(&record).unwrap();
+    let annotated = annotate_execution(
+        &store,
+        &record.uuid,
+        Some("shipped to users"),
+        &["release-1.4".to_string()],
+        &labels(&[("env", "prod")]),
+    )
+    .unwrap();
+
+    let links = format_record(&annotated, "links-notation").unwrap();
+    assert!(links.contains("  tags\n    release-1.4"));
+    assert!(links.contains("  labels\n    env prod"));
+    assert!(links.contains("  notes\n    "));
+    assert!(links.contains("\"shipped to users\""));
+
+    let text = format_record(&annotated, "text").unwrap();
+    assert!(text.contains("Tags:              release-1.4"));
+    assert!(text.contains("Labels:\n  env: prod"));
+    assert!(text.contains(": shipped to users"));
+}
+
+#[test]
+fn test_annotations_survive_export_round_trip() {
+    let mut record = ExecutionRecord::new("make release");
+    record.tags.push("release-1.4".to_string());
+    record.labels.insert("env".to_string(), "prod".to_string());
+
+    for format in ["jsonl", "csv", "lino"] {
+        let content = serialize_records(std::slice::from_ref(&record), format).unwrap();
+        let parsed = parse_records(&content).unwrap();
+        assert_eq!(parsed[0].tags, record.tags, "format {}", format);
+        assert_eq!(parsed[0].labels, record.labels, "format {}", format);
+    }
+}
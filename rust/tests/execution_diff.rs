@@ -0,0 +1,261 @@
+//! Tests for comparing two executions (--diff)
+//!
+//! Covers normalizing volatile values in log lines, the line diff and its
+//! unified format, comparing recorded fields and provenance, the store query
+//! and the --diff arguments.
+
+use start_command::execution_provenance::{ExecutionProvenance, GitProvenance};
+use start_command::{
+    compare_records, create_log_footer, diff_lines, normalize_log_line, parse_args, query_diff,
+    unified_diff, DiffOp, ExecutionRecord, ExecutionStore, ExecutionStoreOptions,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn finished(command: &str, exit_code: i32, start: &str, end: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.start_time = start.to_string();
+    record.complete(exit_code);
+    record.end_time = Some(end.to_string());
+    record
+}
+
+fn write_log(dir: &Path, record: &mut ExecutionRecord, output: &str) {
+    let path = dir.join(format!("{}.log", record.uuid));
+    let log = format!(
+        "=== Start Command Log ===\nTimestamp: {}\nCommand: {}\n{}\n\n{}{}",
+        record.start_time,
+        record.command,
+        "=".repeat(50),
+        output,
+        create_log_footer("2026-10-18 10:00:05.000", record.exit_code.unwrap_or(0))
+    );
+    fs::write(&path, log).unwrap();
+    record.log_path = path.to_string_lossy().to_string();
+}
+
+#[test]
+fn test_normalize_volatile_values() {
+    assert_eq!(
+        normalize_log_line("[2026-10-17T09:15:02.123Z] started pid=4242 in 1.52s"),
+        "[<timestamp>] started pid=<pid> in <duration>"
+    );
+    assert_eq!(
+        normalize_log_line("wrote /tmp/build-8f3a/out.o at 10:01:02"),
+        "wrote <tmp> at <time>"
+    );
+    assert_eq!(
+        normalize_log_line("run 0b7e3c52-1f1a-4c5e-9f5e-2a4d1c9e7b10 took 250 ms"),
+        "run <uuid> took <duration>"
+    );
+    assert_eq!(
+        normalize_log_line("\x1b[31merror\x1b[0m: 3 tests failed  "),
+        "error: 3 tests failed"
+    );
+}
+
+#[test]
+fn test_diff_lines() {
+    let a = ["a", "b", "c", "d"];
+    let b = ["a", "c", "d", "e"];
+    assert_eq!(
+        diff_lines(&a, &b),
+        vec![
+            DiffOp::Equal("a"),
+            DiffOp::Delete("b"),
+            DiffOp::Equal("c"),
+            DiffOp::Equal("d"),
+            DiffOp::Insert("e"),
+        ]
+    );
+    assert!(diff_lines(&a, &a)
+        .iter()
+        .all(|op| matches!(op, DiffOp::Equal(_))));
+    assert_eq!(diff_lines(&[], &["x"]), vec![DiffOp::Insert("x")]);
+}
+
+#[test]
+fn test_diff_lines_large_different_inputs() {
+    // Too different for a minimal diff: reported as replaced, without
+    // spending memory per edit round
+    let a: Vec<String> = (0..5000).map(|i| format!("old line {}", i)).collect();
+    let b: Vec<String> = (0..5000).map(|i| format!("new line {}", i)).collect();
+    let a: Vec<&str> = a.iter().map(String::as_str).collect();
+    let b: Vec<&str> = b.iter().map(String::as_str).collect();
+    let ops = diff_lines(&a, &b);
+    assert_eq!(ops.len(), 10_000);
+    assert!(ops[..5000].iter().all(|op| matches!(op, DiffOp::Delete(_))));
+    assert!(ops[5000..].iter().all(|op| matches!(op, DiffOp::Insert(_))));
+
+    // Scattered changes in large inputs still get a minimal diff
+    let mut changed = a.clone();
+    for index in (0..5000).step_by(50) {
+        changed[index] = "changed";
+    }
+    let ops = diff_lines(&a, &changed);
+    let edits = ops
+        .iter()
+        .filter(|op| !matches!(op, DiffOp::Equal(_)))
+        .count();
+    assert_eq!(edits, 200);
+}
+
+#[test]
+fn test_unified_diff() {
+    let a = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+    let b = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n";
+    let diff = unified_diff(a, b, "a/x", "b/y", 1);
+    assert_eq!(
+        diff,
+        "--- a/x\n+++ b/y\n@@ -2,3 +2,3 @@\n 2\n-3\n+three\n 4\n@@ -12,1 +12,2 @@\n 12\n+13"
+    );
+    // Changes within twice the context share a hunk
+    let merged = unified_diff(a, b, "a/x", "b/y", 5);
+    assert_eq!(merged.matches("@@ -").count(), 1);
+    assert_eq!(unified_diff(a, a, "a/x", "b/y", 3), "");
+    assert!(unified_diff("", "new\n", "a", "b", 3).contains("@@ -0,0 +1,1 @@"));
+}
+
+#[test]
+fn test_compare_records() {
+    let mut a = finished(
+        "cargo test",
+        0,
+        "2026-10-17T09:00:00Z",
+        "2026-10-17T09:00:10Z",
+    );
+    let mut b = finished(
+        "cargo test",
+        101,
+        "2026-10-18T09:00:00Z",
+        "2026-10-18T09:01:40Z",
+    );
+    b.working_directory = a.working_directory.clone();
+    b.shell = a.shell.clone();
+    b.platform = a.platform.clone();
+    a.options
+        .insert("isolated".to_string(), serde_json::json!("docker"));
+    let git = |commit: &str| GitProvenance {
+        root: "/repo".to_string(),
+        branch: Some("main".to_string()),
+        commit: Some(commit.to_string()),
+        dirty: false,
+    };
+    a.provenance = Some(ExecutionProvenance {
+        git: Some(git("abc123")),
+        ..Default::default()
+    });
+    let mut provenance_b = ExecutionProvenance {
+        git: Some(git("def456")),
+        ..Default::default()
+    };
+    provenance_b
+        .tools
+        .insert("cargo".to_string(), "1.90.0".to_string());
+    b.provenance = Some(provenance_b);
+
+    let changes = compare_records(&a, &b);
+    let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "exitCode",
+            "duration",
+            "options.isolated",
+            "git.commit",
+            "tools.cargo"
+        ]
+    );
+    assert_eq!(changes[0].a.as_deref(), Some("0"));
+    assert_eq!(changes[0].b.as_deref(), Some("101"));
+    assert_eq!(changes[2].b, None);
+    assert_eq!(changes[4].a, None);
+
+    // Durations that round to the same display value are not a difference
+    let mut c = a.clone();
+    c.end_time = Some("2026-10-17T09:00:10.004Z".to_string());
+    assert!(compare_records(&a, &c).is_empty());
+}
+
+#[test]
+fn test_query_diff() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut passing = finished(
+        "npm test",
+        0,
+        "2026-10-17T09:00:00Z",
+        "2026-10-17T09:00:03Z",
+    );
+    let mut failing = finished(
+        "npm test",
+        1,
+        "2026-10-18T09:00:00Z",
+        "2026-10-18T09:00:04Z",
+    );
+    failing.working_directory = passing.working_directory.clone();
+    write_log(
+        temp_dir.path(),
+        &mut passing,
+        "server pid 100 ready in 12ms\nok 1 - login\nok 2 - logout\n",
+    );
+    write_log(
+        temp_dir.path(),
+        &mut failing,
+        "server pid 200 ready in 15ms\nok 1 - login\nnot ok 2 - logout\n",
+    );
+    store.save(&passing).unwrap();
+    store.save(&failing).unwrap();
+
+    let result = query_diff(Some(&store), &passing.uuid, &failing.uuid, Some("text"));
+    assert!(result.success, "{:?}", result.error);
+    let output = result.output.unwrap();
+    assert!(output.contains("exitCode"));
+    assert!(output.contains("-ok 2 - logout\n+not ok 2 - logout"));
+    // The volatile first line is normalized away
+    assert!(!output.contains("pid 100"));
+    assert!(!output.contains("-server"));
+
+    let same = query_diff(Some(&store), &passing.uuid, &passing.uuid, None);
+    let output = same.output.unwrap();
+    assert!(output.starts_with("diff\n"));
+    assert!(output.contains("  changes ()"));
+    assert!(output.contains("  logsIdentical true"));
+
+    let json = query_diff(Some(&store), &passing.uuid, &failing.uuid, Some("json"));
+    let value: serde_json::Value = serde_json::from_str(&json.output.unwrap()).unwrap();
+    assert_eq!(value["logsIdentical"], false);
+
+    let missing = query_diff(Some(&store), &passing.uuid, "missing", None);
+    assert!(missing.error.unwrap().contains("missing"));
+    let disabled = query_diff(None, "a", "b", None);
+    assert_eq!(
+        disabled.error.as_deref(),
+        Some("Execution tracking is disabled.")
+    );
+}
+
+#[test]
+fn test_diff_arguments() {
+    let options = parse_args(&args(&["--diff", "a", "b", "--output-format", "json"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.diff, Some(("a".to_string(), "b".to_string())));
+    assert_eq!(options.output_format.as_deref(), Some("json"));
+
+    let err = parse_args(&args(&["--diff", "a"])).unwrap_err();
+    assert!(err.contains("requires two UUID or session name arguments"));
+    let err = parse_args(&args(&["--diff", "a", "--list"])).unwrap_err();
+    assert!(err.contains("requires two"));
+    let err = parse_args(&args(&["--diff", "a", "b", "--list"])).unwrap_err();
+    assert!(err.contains("--diff cannot be combined"));
+}
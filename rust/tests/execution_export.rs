@@ -0,0 +1,399 @@
+//! Tests for execution history export/import (--export, --import)
+//!
+//! Covers the jsonl/csv/lino round-trips, rejecting invalid records, tar
+//! bundles with logs, merging imported records into an existing store,
+//! record filters, and the related argument parsing.
+
+use chrono::{TimeZone, Utc};
+use lino_objects_codec::{decode, encode, LinoValue};
+use serde_json::Value;
+use start_command::execution_export::{format_import_summary, infer_export_format};
+use start_command::record_filter::parse_time_bound_at;
+use start_command::{
+    export_records, import_records, parse_args, parse_records, query_export, serialize_records,
+    ExecutionRecord, ExecutionRecordOptions, ExecutionStatus, ExecutionStore,
+    ExecutionStoreOptions, ExportOptions, RecordFilter,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn create_store(dir: &Path) -> ExecutionStore {
+    ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(dir.to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    })
+}
+
+fn finished(command: &str, start: &str, end: &str, exit_code: i32) -> ExecutionRecord {
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        command: command.to_string(),
+        status: Some(ExecutionStatus::Executed),
+        exit_code: Some(exit_code),
+        start_time: Some(start.to_string()),
+        end_time: Some(end.to_string()),
+        working_directory: Some("/repo".to_string()),
+        ..Default::default()
+    })
+}
+
+fn sample_records() -> Vec<ExecutionRecord> {
+    let mut docker = finished(
+        "cargo build, \"release\"",
+        "2026-10-02T09:00:00Z",
+        "2026-10-02T09:00:30Z",
+        0,
+    );
+    docker
+        .options
+        .insert("isolated".to_string(), Value::String("docker".to_string()));
+
+    let running = ExecutionRecord::with_options(ExecutionRecordOptions {
+        command: "npm run dev".to_string(),
+        start_time: Some("2026-10-03T08:00:00Z".to_string()),
+        ..Default::default()
+    });
+
+    vec![
+        finished(
+            "npm test",
+            "2026-10-01T10:00:00Z",
+            "2026-10-01T10:00:01Z",
+            1,
+        ),
+        docker,
+        running,
+    ]
+}
+
+fn by_uuid(records: Vec<ExecutionRecord>) -> HashMap<String, ExecutionRecord> {
+    records.into_iter().map(|r| (r.uuid.clone(), r)).collect()
+}
+
+fn assert_round_trip(format: &str) {
+    let records = sample_records();
+    let content = serialize_records(&records, format).unwrap();
+    let parsed = by_uuid(parse_records(&content).unwrap());
+
+    assert_eq!(parsed.len(), records.len(), "format {}", format);
+    for original in &records {
+        let restored = &parsed[&original.uuid];
+        assert_eq!(restored.command, original.command, "format {}", format);
+        assert_eq!(restored.status, original.status, "format {}", format);
+        assert_eq!(restored.exit_code, original.exit_code, "format {}", format);
+        assert_eq!(
+            restored.start_time, original.start_time,
+            "format {}",
+            format
+        );
+        assert_eq!(restored.end_time, original.end_time, "format {}", format);
+        assert_eq!(restored.options, original.options, "format {}", format);
+    }
+}
+
+#[test]
+fn test_jsonl_round_trip() {
+    assert_round_trip("jsonl");
+}
+
+#[test]
+fn test_csv_round_trip() {
+    assert_round_trip("csv");
+}
+
+#[test]
+fn test_lino_round_trip() {
+    assert_round_trip("lino");
+}
+
+#[test]
+fn test_invalid_lino_record_is_reported() {
+    let valid = ExecutionRecord::new("ls");
+    let content = encode(&LinoValue::Array(vec![
+        decode(&serialize_records(&[valid], "lino").unwrap())
+            .map(|value| match value {
+                LinoValue::Array(mut values) => values.remove(0),
+                other => other,
+            })
+            .unwrap(),
+        LinoValue::Object(vec![("command".to_string(), LinoValue::Int(42))]),
+    ]));
+
+    let err = parse_records(&content).unwrap_err();
+    assert_eq!(err, "Invalid record at index 1");
+}
+
+#[test]
+fn test_infer_export_format_from_extension() {
+    assert_eq!(infer_export_format(Path::new("out.csv")), "csv");
+    assert_eq!(infer_export_format(Path::new("out.lino")), "lino");
+    assert_eq!(infer_export_format(Path::new("out.jsonl")), "jsonl");
+    assert_eq!(infer_export_format(Path::new("out")), "jsonl");
+}
+
+#[test]
+fn test_unknown_export_format_is_rejected() {
+    assert!(serialize_records(&sample_records(), "xml").is_err());
+}
+
+#[test]
+fn test_tar_bundle_includes_logs_and_imports_them() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    fs::write(&log_path, "hello from the log\n").unwrap();
+
+    let mut records = sample_records();
+    records[0].log_path = log_path.to_string_lossy().to_string();
+
+    let archive = temp_dir.path().join("history.tar");
+    let summary = export_records(
+        &records,
+        &archive,
+        &ExportOptions {
+            format: None,
+            with_logs: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(summary.format, "jsonl+tar");
+    assert_eq!(summary.records, 3);
+    assert_eq!(summary.logs, 1);
+
+    let target = TempDir::new().unwrap();
+    let store = create_store(target.path());
+    let imported = import_records(&store, &archive).unwrap();
+    assert_eq!(imported.added, 3);
+    assert_eq!(imported.logs, 1);
+
+    let restored = store.get(&records[0].uuid).unwrap();
+    assert!(restored
+        .log_path
+        .starts_with(&*target.path().to_string_lossy()));
+    assert_eq!(
+        fs::read_to_string(&restored.log_path).unwrap(),
+        "hello from the log\n"
+    );
+}
+
+#[test]
+fn test_import_skips_records_with_traversal_uuids() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut evil = sample_records().remove(0);
+    evil.uuid = "../../../escaped".to_string();
+    let good = sample_records().remove(1);
+    let jsonl = serialize_records(&[evil, good.clone()], "jsonl").unwrap();
+
+    // Hand-craft the bundle: tar::Builder refuses `..` in entry names
+    let archive = temp_dir.path().join("evil.tar");
+    let mut builder = tar::Builder::new(fs::File::create(&archive).unwrap());
+    for (name, data) in [
+        ("executions.jsonl", jsonl.as_bytes()),
+        ("logs/../../../escaped.log", b"pwned\n".as_slice()),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append(&header, data).unwrap();
+    }
+    builder.finish().unwrap();
+    drop(builder);
+
+    let app_folder = temp_dir.path().join("a").join("b").join("app");
+    let store = create_store(&app_folder);
+    let summary = import_records(&store, &archive).unwrap();
+    assert_eq!(summary.added, 1);
+    assert_eq!(summary.logs, 0);
+    assert_eq!(summary.skipped, vec!["../../../escaped".to_string()]);
+    assert!(store.get(&good.uuid).is_some());
+    assert!(store.get("../../../escaped").is_none());
+    assert!(!temp_dir.path().join("escaped.log").exists());
+    assert!(!temp_dir.path().join("a").join("escaped.log").exists());
+    assert!(format_import_summary(&summary).contains("  skipped"));
+}
+
+#[test]
+fn test_import_merges_by_end_time() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = create_store(temp_dir.path());
+    let records = sample_records();
+    for record in &records {
+        store.save(record).unwrap();
+    }
+
+    let mut incoming = records.clone();
+    // The running record finished on the other machine
+    incoming[2].status = ExecutionStatus::Executed;
+    incoming[2].exit_code = Some(0);
+    incoming[2].end_time = Some("2026-10-03T08:05:00Z".to_string());
+    incoming.push(finished(
+        "make lint",
+        "2026-10-04T12:00:00Z",
+        "2026-10-04T12:00:05Z",
+        0,
+    ));
+
+    let file = temp_dir.path().join("incoming.jsonl");
+    fs::write(&file, serialize_records(&incoming, "jsonl").unwrap()).unwrap();
+
+    let summary = import_records(&store, &file).unwrap();
+    assert_eq!(summary.added, 1);
+    assert_eq!(summary.updated, 1);
+    assert_eq!(summary.unchanged, 2);
+    assert_eq!(store.get_all().len(), 4);
+    assert_eq!(
+        store.get(&records[2].uuid).unwrap().status,
+        ExecutionStatus::Executed
+    );
+}
+
+#[test]
+fn test_query_export_applies_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = create_store(temp_dir.path());
+    for record in sample_records() {
+        store.save(&record).unwrap();
+    }
+
+    let out = temp_dir.path().join("failed.jsonl");
+    let filter = RecordFilter {
+        outcome: Some("failed".to_string()),
+        ..Default::default()
+    };
+    let result = query_export(Some(&store), &filter, &out, &ExportOptions::default());
+    assert!(result.success, "{:?}", result.error);
+    assert!(result.output.unwrap().contains("records 1"));
+
+    let exported = parse_records(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].command, "npm test");
+
+    let disabled = query_export(None, &filter, &out, &ExportOptions::default());
+    assert!(!disabled.success);
+}
+
+#[test]
+fn test_record_filter_criteria() {
+    let records = sample_records();
+    let backend = RecordFilter {
+        backend: Some("docker".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(backend.apply(records.clone()).len(), 1);
+
+    let direct = RecordFilter {
+        backend: Some("direct".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(direct.apply(records.clone()).len(), 2);
+
+    let command = RecordFilter {
+        command_contains: Some("NPM".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(command.apply(records.clone()).len(), 2);
+
+    let window = RecordFilter {
+        since: Some(Utc.with_ymd_and_hms(2026, 10, 2, 0, 0, 0).unwrap()),
+        until: Some(Utc.with_ymd_and_hms(2026, 10, 3, 0, 0, 0).unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(window.apply(records.clone()).len(), 1);
+
+    let limited = RecordFilter {
+        limit: Some(2),
+        ..Default::default()
+    }
+    .apply(records);
+    assert_eq!(limited.len(), 2);
+    assert_eq!(limited[0].command, "npm run dev");
+}
+
+#[test]
+fn test_parse_time_bound_formats() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    assert_eq!(
+        parse_time_bound_at("7d", now).unwrap(),
+        Utc.with_ymd_and_hms(2026, 10, 11, 12, 0, 0).unwrap()
+    );
+    assert_eq!(
+        parse_time_bound_at("2026-10-01", now).unwrap(),
+        Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap()
+    );
+    assert_eq!(
+        parse_time_bound_at("2026-10-01T06:30:00Z", now).unwrap(),
+        Utc.with_ymd_and_hms(2026, 10, 1, 6, 30, 0).unwrap()
+    );
+    assert!(parse_time_bound_at("yesterday", now).is_err());
+    assert!(parse_time_bound_at("", now).is_err());
+    // Multi-byte units, overflowing and negative amounts are errors, not panics
+    assert!(parse_time_bound_at("7é", now).is_err());
+    assert!(parse_time_bound_at("99999999999999d", now).is_err());
+    assert!(parse_time_bound_at("-5d", now).is_err());
+}
+
+#[test]
+fn test_parse_export_and_import_args() {
+    let result = parse_args(&args(&[
+        "--export",
+        "out.tar",
+        "--export-format=csv",
+        "--with-logs",
+        "--outcome",
+        "Failed",
+        "--limit=5",
+    ]))
+    .unwrap();
+    assert_eq!(result.wrapper_options.export.as_deref(), Some("out.tar"));
+    assert_eq!(result.wrapper_options.export_format.as_deref(), Some("csv"));
+    assert!(result.wrapper_options.with_logs);
+    assert_eq!(
+        result.wrapper_options.filter.outcome.as_deref(),
+        Some("failed")
+    );
+    assert_eq!(result.wrapper_options.filter.limit, Some(5));
+
+    let result = parse_args(&args(&["--import=history.jsonl"])).unwrap();
+    assert_eq!(
+        result.wrapper_options.import.as_deref(),
+        Some("history.jsonl")
+    );
+
+    let result = parse_args(&args(&["--list", "--since", "24h", "--backend=docker"])).unwrap();
+    assert!(result.wrapper_options.filter.since.is_some());
+    assert_eq!(
+        result.wrapper_options.filter.backend.as_deref(),
+        Some("docker")
+    );
+}
+
+#[test]
+fn test_export_and_import_argument_validation() {
+    let err = parse_args(&args(&["--export", "a.jsonl", "--import", "b.jsonl"])).unwrap_err();
+    assert!(err.contains("--export cannot be combined"));
+
+    let err = parse_args(&args(&["--import", "b.jsonl", "--list"])).unwrap_err();
+    assert!(err.contains("--import cannot be combined"));
+
+    let err = parse_args(&args(&["--export", "a.x", "--export-format", "xml"])).unwrap_err();
+    assert!(err.contains("Invalid export format"));
+
+    let err = parse_args(&args(&["--with-logs", "--list"])).unwrap_err();
+    assert!(err.contains("--with-logs option is only valid with --export"));
+
+    let err = parse_args(&args(&["--since", "7d", "--", "echo", "hi"])).unwrap_err();
+    assert!(err.contains("Record filters"));
+
+    let err = parse_args(&args(&["--list", "--outcome", "maybe"])).unwrap_err();
+    assert!(err.contains("Invalid outcome"));
+
+    let err = parse_args(&args(&["--list", "--limit", "many"])).unwrap_err();
+    assert!(err.contains("Invalid limit"));
+}
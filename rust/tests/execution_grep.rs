@@ -0,0 +1,198 @@
+//! Tests for full-text search across stored logs (--grep)
+//!
+//! Covers matching with context lines, case-insensitive patterns, record
+//! filters, missing logs, the report formats and the --grep arguments.
+
+use start_command::{
+    build_grep_regex, format_grep_report, grep_records, grep_text, parse_args, run_store_command,
+    ExecutionRecord, ExecutionStore, ExecutionStoreOptions, GrepLine, GrepOptions,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn line(line_number: usize, text: &str, is_match: bool) -> GrepLine {
+    GrepLine {
+        line_number,
+        text: text.to_string(),
+        is_match,
+    }
+}
+
+/// Record whose log holds `content`
+fn logged(dir: &Path, command: &str, start_time: &str, content: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.start_time = start_time.to_string();
+    let log_path = dir.join(format!("{}.log", record.uuid));
+    fs::write(&log_path, content).unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    record
+}
+
+fn search(pattern: &str, ignore_case: bool, context: usize) -> GrepOptions {
+    GrepOptions {
+        pattern: pattern.to_string(),
+        ignore_case,
+        context,
+    }
+}
+
+#[test]
+fn test_grep_text_merges_context() {
+    let content = "a\nerror one\nb\nc\nerror two\nd\ne\nf\nerror three";
+    let regex = build_grep_regex("error", false).unwrap();
+
+    assert_eq!(
+        grep_text(content, &regex, 0),
+        vec![
+            line(2, "error one", true),
+            line(5, "error two", true),
+            line(9, "error three", true),
+        ]
+    );
+
+    let lines = grep_text(content, &regex, 1);
+    let numbers: Vec<usize> = lines.iter().map(|l| l.line_number).collect();
+    assert_eq!(numbers, vec![1, 2, 3, 4, 5, 6, 8, 9]);
+    assert_eq!(lines[3], line(4, "c", false));
+    assert_eq!(lines[4], line(5, "error two", true));
+}
+
+#[test]
+fn test_grep_records_groups_by_execution() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let first = logged(dir, "npm test", "2026-03-01T10:00:00Z", "ok\nERROR: boom\n");
+    let second = logged(dir, "npm run build", "2026-03-02T10:00:00Z", "all good\n");
+    let mut missing = ExecutionRecord::new("gone");
+    missing.log_path = dir.join("gone.log").to_string_lossy().to_string();
+
+    let records = vec![first.clone(), second, missing.clone()];
+    let report = grep_records(&records, &search("error", false, 0)).unwrap();
+    assert_eq!(report.searched, 2);
+    assert_eq!(report.matches, 0);
+    assert_eq!(report.missing_logs, vec![missing.uuid]);
+
+    let report = grep_records(&records, &search("error", true, 0)).unwrap();
+    assert_eq!(report.matches, 1);
+    assert_eq!(report.executions.len(), 1);
+    assert_eq!(report.executions[0].uuid, first.uuid);
+    assert_eq!(report.executions[0].command, "npm test");
+    assert_eq!(
+        report.executions[0].lines,
+        vec![line(2, "ERROR: boom", true)]
+    );
+
+    assert!(grep_records(&records, &search("(unclosed", false, 0)).is_err());
+}
+
+#[test]
+fn test_grep_report_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    let record = logged(
+        temp_dir.path(),
+        "cargo test",
+        "2026-03-01T10:00:00Z",
+        "compiling\ntest failed: x\ndone\nmore\nfailed again",
+    );
+    let report = grep_records(std::slice::from_ref(&record), &search("failed", false, 1)).unwrap();
+
+    let text = format_grep_report(&report, "text").unwrap();
+    assert!(text.starts_with(&format!(
+        "{}  2026-03-01T10:00:00Z  cargo test\n  1- compiling\n  2: test failed: x\n  3- done\n  4- more\n  5: failed again",
+        record.uuid
+    )));
+    assert!(text.ends_with("2 matching lines in 1 of 1 logs searched"));
+
+    let links = format_grep_report(&report, "links-notation").unwrap();
+    assert!(links.starts_with("grep\n  pattern failed\n  searched 1\n  matches 2\n"));
+    assert!(links.contains("    command \"cargo test\"\n"));
+    assert!(links.contains("      match 2 \"test failed: x\"\n"));
+    assert!(links.contains("      context 1 compiling\n"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&format_grep_report(&report, "json").unwrap()).unwrap();
+    assert_eq!(json["executions"][0]["lines"][1]["lineNumber"], 2);
+    assert_eq!(json["executions"][0]["lines"][1]["isMatch"], true);
+
+    assert!(format_grep_report(&report, "yaml").is_err());
+}
+
+#[test]
+fn test_grep_honors_list_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let old = logged(
+        temp_dir.path(),
+        "make",
+        "2020-01-01T00:00:00Z",
+        "segfault\n",
+    );
+    let mut recent = logged(
+        temp_dir.path(),
+        "make",
+        "2099-01-01T00:00:00Z",
+        "segfault\n",
+    );
+    recent.tags.push("nightly".to_string());
+    store.save(&old).unwrap();
+    store.save(&recent).unwrap();
+
+    let mut options = parse_args(&args(&[
+        "--grep",
+        "segfault",
+        "--since",
+        "2026-01-01",
+        "--output-format",
+        "json",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    let result = run_store_command(&options, Some(&store));
+    let json: serde_json::Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+    assert_eq!(json["searched"], 1);
+    assert_eq!(json["executions"][0]["uuid"], recent.uuid.as_str());
+
+    options.filter.since = None;
+    options.tags.push("nightly".to_string());
+    let result = run_store_command(&options, Some(&store));
+    let json: serde_json::Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+    assert_eq!(json["matches"], 1);
+
+    assert!(!run_store_command(&options, None).success);
+}
+
+#[test]
+fn test_grep_arguments() {
+    let options = parse_args(&args(&[
+        "--grep",
+        "timed out",
+        "--ignore-case",
+        "--context=3",
+        "--limit",
+        "5",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    assert_eq!(options.grep.as_deref(), Some("timed out"));
+    assert!(options.grep_ignore_case);
+    assert_eq!(options.grep_context, Some(3));
+    assert_eq!(options.filter.limit, Some(5));
+
+    let err = parse_args(&args(&["--grep", "x", "--list"])).unwrap_err();
+    assert!(err.contains("--grep cannot be combined"));
+    let err = parse_args(&args(&["--grep", "(x"])).unwrap_err();
+    assert!(err.contains("Invalid --grep pattern"));
+    let err = parse_args(&args(&["--context", "2", "--list"])).unwrap_err();
+    assert!(err.contains("only valid with --grep"));
+    let err = parse_args(&args(&["--grep", "x", "--context", "many"])).unwrap_err();
+    assert!(err.contains("Invalid context"));
+}
@@ -0,0 +1,273 @@
+//! Tests for parent/child linkage of nested executions
+//!
+//! Covers reading and exporting START_EXECUTION_ID/START_ROOT_EXECUTION_ID,
+//! building execution trees with aggregate durations and the failing leaf,
+//! the tree output formats, and the --tree argument.
+
+use serde_json::Value;
+use start_command::{
+    build_execution_trees, execution_tree_for, format_tree, link_to_parent,
+    link_to_parent_execution, list_execution_trees, parse_args, parse_records, query_status_tree,
+    serialize_records, ExecutionRecord, ExecutionRecordOptions, ExecutionStatus, ExecutionStore,
+    ExecutionStoreOptions, RecordFilter, EXECUTION_ID_ENV, ROOT_EXECUTION_ID_ENV,
+};
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn run(
+    uuid: &str,
+    parent: Option<&str>,
+    command: &str,
+    start: &str,
+    end: &str,
+    exit_code: i32,
+) -> ExecutionRecord {
+    let mut record = ExecutionRecord::with_options(ExecutionRecordOptions {
+        uuid: Some(uuid.to_string()),
+        command: command.to_string(),
+        status: Some(ExecutionStatus::Executed),
+        exit_code: Some(exit_code),
+        start_time: Some(start.to_string()),
+        end_time: Some(end.to_string()),
+        ..Default::default()
+    });
+    link_to_parent(&mut record, parent, Some("root"));
+    record
+}
+
+/// root ─┬─ build (ok)
+///       └─ test (failed) ── unit (failed, outlives everything)
+fn nested_records() -> Vec<ExecutionRecord> {
+    vec![
+        run(
+            "root",
+            None,
+            "make release",
+            "2026-10-01T10:00:00Z",
+            "2026-10-01T10:00:10Z",
+            1,
+        ),
+        run(
+            "build",
+            Some("root"),
+            "cargo build",
+            "2026-10-01T10:00:01Z",
+            "2026-10-01T10:00:04Z",
+            0,
+        ),
+        run(
+            "test",
+            Some("root"),
+            "cargo test",
+            "2026-10-01T10:00:05Z",
+            "2026-10-01T10:00:09Z",
+            101,
+        ),
+        run(
+            "unit",
+            Some("test"),
+            "cargo test --lib",
+            "2026-10-01T10:00:06Z",
+            "2026-10-01T10:00:12Z",
+            101,
+        ),
+    ]
+}
+
+#[test]
+fn test_link_to_parent() {
+    let mut record = ExecutionRecord::new("echo child");
+    link_to_parent(&mut record, Some("parent-id"), None);
+    assert_eq!(record.parent_uuid.as_deref(), Some("parent-id"));
+    assert_eq!(record.root_uuid.as_deref(), Some("parent-id"));
+
+    link_to_parent(&mut record, Some("other-parent"), Some("root-id"));
+    assert_eq!(record.root_uuid.as_deref(), Some("root-id"));
+
+    let mut top = ExecutionRecord::new("echo top");
+    link_to_parent(&mut top, Some(""), Some("root-id"));
+    let own_uuid = top.uuid.clone();
+    link_to_parent(&mut top, Some(&own_uuid), None);
+    assert!(top.parent_uuid.is_none());
+    assert!(top.root_uuid.is_none());
+}
+
+#[test]
+fn test_link_to_parent_execution_exports_ids() {
+    std::env::remove_var(EXECUTION_ID_ENV);
+    std::env::remove_var(ROOT_EXECUTION_ID_ENV);
+
+    let mut outer = ExecutionRecord::new("make all");
+    link_to_parent_execution(&mut outer);
+    assert!(outer.parent_uuid.is_none());
+    assert_eq!(std::env::var(EXECUTION_ID_ENV).unwrap(), outer.uuid);
+    assert_eq!(std::env::var(ROOT_EXECUTION_ID_ENV).unwrap(), outer.uuid);
+
+    let mut inner = ExecutionRecord::new("make test");
+    link_to_parent_execution(&mut inner);
+    assert_eq!(inner.parent_uuid.as_deref(), Some(outer.uuid.as_str()));
+    assert_eq!(inner.root_uuid.as_deref(), Some(outer.uuid.as_str()));
+    assert_eq!(std::env::var(EXECUTION_ID_ENV).unwrap(), inner.uuid);
+    assert_eq!(std::env::var(ROOT_EXECUTION_ID_ENV).unwrap(), outer.uuid);
+
+    std::env::remove_var(EXECUTION_ID_ENV);
+    std::env::remove_var(ROOT_EXECUTION_ID_ENV);
+}
+
+#[test]
+fn test_build_tree_with_aggregates_and_failing_leaf() {
+    let trees = build_execution_trees(&nested_records());
+    assert_eq!(trees.len(), 1);
+    let tree = &trees[0];
+    assert_eq!(tree.count, 4);
+    assert_eq!(tree.root.uuid, "root");
+    assert_eq!(tree.failing_leaf.as_deref(), Some("unit"));
+
+    let children: Vec<&str> = tree.root.children.iter().map(|c| c.uuid.as_str()).collect();
+    assert_eq!(children, vec!["build", "test"]);
+    assert_eq!(tree.root.duration_ms, Some(10000.0));
+    // The nested unit run ends at 10:00:12, after the root finished
+    assert_eq!(tree.root.total_duration_ms, Some(12000.0));
+    assert_eq!(tree.root.children[1].total_duration_ms, Some(7000.0));
+}
+
+#[test]
+fn test_total_duration_unknown_while_child_executing() {
+    let mut records = nested_records();
+    records[3].status = ExecutionStatus::Executing;
+    records[3].exit_code = None;
+    records[3].end_time = None;
+
+    let tree = &build_execution_trees(&records)[0];
+    assert_eq!(tree.root.total_duration_ms, None);
+    assert_eq!(tree.root.children[0].total_duration_ms, Some(3000.0));
+    assert_eq!(tree.failing_leaf.as_deref(), Some("test"));
+}
+
+#[test]
+fn test_orphans_become_roots_and_tree_for_leaf() {
+    let records: Vec<ExecutionRecord> = nested_records().into_iter().skip(1).collect();
+    let trees = build_execution_trees(&records);
+    let roots: Vec<&str> = trees.iter().map(|t| t.root.uuid.as_str()).collect();
+    assert_eq!(roots, vec!["test", "build"]);
+
+    let tree = execution_tree_for(&nested_records(), "unit").unwrap();
+    assert_eq!(tree.root.uuid, "root");
+    assert_eq!(tree.count, 4);
+    assert!(execution_tree_for(&nested_records(), "missing").is_none());
+}
+
+#[test]
+fn test_parent_cycles_are_broken_at_the_oldest_record() {
+    // A corrupted store: root ↔ test point at each other, unit hangs below
+    let mut records = nested_records();
+    records[0].parent_uuid = Some("test".to_string());
+    let trees = build_execution_trees(&records);
+    let roots: Vec<&str> = trees.iter().map(|t| t.root.uuid.as_str()).collect();
+    assert_eq!(roots, vec!["root"]);
+    assert_eq!(trees[0].count, 4);
+
+    let tree = execution_tree_for(&records, "unit").unwrap();
+    assert_eq!(tree.root.uuid, "root");
+    assert_eq!(tree.count, 4);
+
+    // A record that is its own parent
+    let mut lonely = run(
+        "self",
+        None,
+        "ls",
+        "2026-10-02T10:00:00Z",
+        "2026-10-02T10:00:01Z",
+        0,
+    );
+    lonely.parent_uuid = Some("self".to_string());
+    let trees = build_execution_trees(&[lonely]);
+    assert_eq!(trees.len(), 1);
+    assert_eq!(trees[0].count, 1);
+}
+
+#[test]
+fn test_tree_output_formats() {
+    let tree = execution_tree_for(&nested_records(), "root").unwrap();
+
+    let links = format_tree(&tree, "links-notation").unwrap();
+    assert!(links.starts_with("executionTree\n  root root\n  count 4"));
+    assert!(links.contains("  failingLeaf unit"));
+    assert!(links.contains("      children\n        build"));
+
+    let text = format_tree(&tree, "text").unwrap();
+    assert!(text.contains("├── cargo build [succeeded]"));
+    assert!(text.contains("└── cargo test [failed, exit 101]"));
+    assert!(text.contains("    └── cargo test --lib [failed, exit 101]"));
+    assert!(text.contains("Failing leaf: cargo test --lib (unit)"));
+
+    let json: Value = serde_json::from_str(&format_tree(&tree, "json").unwrap()).unwrap();
+    assert_eq!(json["failingLeaf"], "unit");
+    assert_eq!(json["root"]["children"][1]["children"][0]["uuid"], "unit");
+
+    assert!(format_tree(&tree, "xml").is_err());
+}
+
+#[test]
+fn test_status_and_list_tree_queries() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut records = nested_records();
+    let mut session = HashMap::new();
+    session.insert("sessionName".to_string(), Value::from("unit-session"));
+    records[3].options = session;
+    for record in &records {
+        store.save(record).unwrap();
+    }
+    store.save(&ExecutionRecord::new("echo unrelated")).unwrap();
+
+    let status = query_status_tree(Some(&store), "unit-session", Some("json"));
+    assert!(status.success, "{:?}", status.error);
+    let json: Value = serde_json::from_str(&status.output.unwrap()).unwrap();
+    assert_eq!(json["root"]["uuid"], "root");
+
+    let list = list_execution_trees(Some(&store), &RecordFilter::default(), None);
+    let output = list.output.unwrap();
+    assert!(output.starts_with("executionTrees\n  count 2"));
+
+    let missing = query_status_tree(Some(&store), "missing", None);
+    assert!(missing.error.unwrap().contains("No execution found"));
+    assert!(!query_status_tree(None, "root", None).success);
+}
+
+#[test]
+fn test_tree_argument() {
+    assert!(
+        parse_args(&args(&["--list", "--tree"]))
+            .unwrap()
+            .wrapper_options
+            .tree
+    );
+    assert!(
+        parse_args(&args(&["--status", "abc", "--tree"]))
+            .unwrap()
+            .wrapper_options
+            .tree
+    );
+    let err = parse_args(&args(&["--tree", "--", "ls"])).unwrap_err();
+    assert!(err.contains("--tree option is only valid with --status or --list"));
+}
+
+#[test]
+fn test_lineage_survives_export_round_trip() {
+    for format in ["jsonl", "csv", "lino"] {
+        let content = serialize_records(&nested_records(), format).unwrap();
+        let parsed = parse_records(&content).unwrap();
+        let unit = parsed.iter().find(|r| r.uuid == "unit").unwrap();
+        assert_eq!(unit.parent_uuid.as_deref(), Some("test"), "{}", format);
+        assert_eq!(unit.root_uuid.as_deref(), Some("root"), "{}", format);
+    }
+}
@@ -0,0 +1,191 @@
+//! Tests for git and toolchain provenance capture
+//!
+//! Covers parsing git status, capturing repository state in a real
+//! repository, executable resolution, well-known tool detection (in the
+//! background for launches), and how provenance is stored, exported and
+//! shown by --status.
+
+use start_command::status_formatter::format_record;
+use start_command::{
+    capture_git, capture_provenance, parse_git_status, parse_records, record_provenance,
+    resolve_executable, serialize_records, tools_used_by, ExecutionProvenance, ExecutionRecord,
+    ExecutionStore, ExecutionStoreOptions, GitProvenance,
+};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+fn sample_provenance() -> ExecutionProvenance {
+    let mut provenance = ExecutionProvenance {
+        git: Some(GitProvenance {
+            root: "/work/repo".to_string(),
+            branch: Some("main".to_string()),
+            commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+            dirty: true,
+        }),
+        executable: Some("/usr/bin/cargo".to_string()),
+        ..Default::default()
+    };
+    provenance
+        .tools
+        .insert("cargo".to_string(), "cargo 1.80.0".to_string());
+    provenance
+}
+
+#[test]
+fn test_parse_git_status() {
+    let clean = "# branch.oid 0123abc\n# branch.head main\n# branch.upstream origin/main\n";
+    let git = parse_git_status(clean);
+    assert_eq!(git.commit.as_deref(), Some("0123abc"));
+    assert_eq!(git.branch.as_deref(), Some("main"));
+    assert!(!git.dirty);
+
+    let dirty = "# branch.oid (initial)\n# branch.head (detached)\n1 .M N... 100644 100644 100644 a b src/lib.rs\n";
+    let git = parse_git_status(dirty);
+    assert!(git.commit.is_none());
+    assert!(git.branch.is_none());
+    assert!(git.dirty);
+}
+
+#[test]
+fn test_capture_git_in_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    assert!(capture_git(dir).is_none());
+
+    git(dir, &["init", "-q", "-b", "trunk"]);
+    fs::write(dir.join("file.txt"), "one").unwrap();
+    git(dir, &["add", "file.txt"]);
+    git(dir, &["commit", "-q", "-m", "initial"]);
+
+    let nested = dir.join("nested");
+    fs::create_dir(&nested).unwrap();
+    let git_state = capture_git(&nested).unwrap();
+    assert_eq!(
+        Path::new(&git_state.root).canonicalize().unwrap(),
+        dir.canonicalize().unwrap()
+    );
+    assert_eq!(git_state.branch.as_deref(), Some("trunk"));
+    assert_eq!(git_state.commit.as_ref().map(|c| c.len()), Some(40));
+    assert!(!git_state.dirty);
+
+    // Untracked files do not count, modified tracked files do
+    fs::write(dir.join("untracked.txt"), "x").unwrap();
+    assert!(!capture_git(dir).unwrap().dirty);
+    fs::write(dir.join("file.txt"), "two").unwrap();
+    assert!(capture_git(dir).unwrap().dirty);
+}
+
+#[test]
+fn test_resolve_executable() {
+    let temp_dir = TempDir::new().unwrap();
+    let resolved = resolve_executable("sh", temp_dir.path()).unwrap();
+    assert!(resolved.ends_with("sh"));
+    assert!(resolved.is_absolute());
+    assert!(resolve_executable("definitely-not-a-real-command-xyz", temp_dir.path()).is_none());
+    assert!(resolve_executable("./missing.sh", temp_dir.path()).is_none());
+}
+
+#[test]
+fn test_tools_used_by_command() {
+    assert_eq!(tools_used_by("npm test"), vec!["node"]);
+    assert_eq!(
+        tools_used_by("cargo test && bun run lint"),
+        vec!["bun", "cargo"]
+    );
+    assert_eq!(tools_used_by("/usr/bin/python3 -m pytest"), vec!["python"]);
+    assert!(tools_used_by("make build").is_empty());
+}
+
+#[test]
+fn test_remote_environments_skip_local_tools() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path().to_string_lossy().to_string();
+    let local = capture_provenance("sh -c true", &dir, true);
+    assert!(local.executable.is_some());
+    assert!(local.git.is_none());
+
+    let mut record = ExecutionRecord::new("sh -c true");
+    record.working_directory = dir;
+    record
+        .options
+        .insert("isolated".to_string(), serde_json::Value::from("docker"));
+    assert!(record_provenance(&mut record).is_none());
+    assert!(record.provenance.is_none());
+}
+
+#[test]
+fn test_tool_versions_are_detected_in_the_background() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut record = ExecutionRecord::new("cargo --version");
+    record.working_directory = temp_dir.path().to_string_lossy().to_string();
+    let versions = record_provenance(&mut record).unwrap();
+    // Launching does not wait for the versions
+    assert!(record.provenance.as_ref().unwrap().tools.is_empty());
+    versions.attach(&mut record);
+    assert!(record.provenance.unwrap().tools["cargo"].starts_with("cargo "));
+
+    let mut record = ExecutionRecord::new("make build");
+    assert!(record_provenance(&mut record).is_none());
+}
+
+#[test]
+fn test_provenance_persists_and_exports() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut record = ExecutionRecord::new("cargo test");
+    record.provenance = Some(sample_provenance());
+    store.save(&record).unwrap();
+    assert_eq!(
+        store.get(&record.uuid).unwrap().provenance,
+        record.provenance
+    );
+
+    for format in ["jsonl", "csv", "lino"] {
+        let content = serialize_records(std::slice::from_ref(&record), format).unwrap();
+        let parsed = parse_records(&content).unwrap();
+        assert_eq!(parsed[0].provenance, record.provenance, "format {}", format);
+    }
+
+    let plain = ExecutionRecord::new("echo hi");
+    assert!(plain.to_json().get("provenance").is_none());
+}
+
+#[test]
+fn test_status_shows_provenance() {
+    let mut record = ExecutionRecord::new("cargo test");
+    record.provenance = Some(sample_provenance());
+
+    let links = format_record(&record, "links-notation").unwrap();
+    assert!(links.contains("  provenance\n"));
+    assert!(links.contains("branch main"));
+    assert!(links.contains("dirty true"));
+    assert!(links.contains("executable /usr/bin/cargo"));
+    assert!(links.contains("cargo \"cargo 1.80.0\""));
+
+    let text = format_record(&record, "text").unwrap();
+    assert!(text.contains("Git Repository:    /work/repo"));
+    assert!(text.contains("Git Branch:        main"));
+    assert!(text.contains("Git Commit:        0123456789abcdef0123456789abcdef01234567 (dirty)"));
+    assert!(text.contains("Tool Versions:\n  cargo: cargo 1.80.0"));
+
+    let json = format_record(&record, "json").unwrap();
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["provenance"]["git"]["root"], "/work/repo");
+}
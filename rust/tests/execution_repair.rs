@@ -0,0 +1,256 @@
+//! Tests for store repair and quarantine of undecodable entries
+//!
+//! Covers entry-by-entry decoding of executions.lino, quarantining corrupt
+//! entries on write, reconciling lino and links copies, recovering finished
+//! executions from log footers, and the --repair arguments and report.
+
+use start_command::execution_store::decode_lino_entries;
+use start_command::{
+    create_log_footer, format_repair_report, parse_args, parse_links_records, query_repair,
+    reconcile_stores, recover_from_log_footers, ExecutionRecord, ExecutionRecordOptions,
+    ExecutionStatus, ExecutionStore, ExecutionStoreOptions, RecoveredExecution, WrapperOptions,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn create_store(dir: &Path) -> ExecutionStore {
+    ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(dir.to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    })
+}
+
+fn finished(uuid: &str, end: &str) -> ExecutionRecord {
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        uuid: Some(uuid.to_string()),
+        command: format!("run {}", uuid),
+        status: Some(ExecutionStatus::Executed),
+        exit_code: Some(0),
+        start_time: Some("2026-03-01T10:00:00+00:00".to_string()),
+        end_time: Some(end.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Store holding two good records after a corrupt entry
+fn corrupt_store() -> (ExecutionStore, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let store = create_store(temp_dir.path());
+    store.save(&ExecutionRecord::new("echo first")).unwrap();
+    store.save(&ExecutionRecord::new("echo second")).unwrap();
+    let path = temp_dir.path().join("executions.lino");
+    let content = fs::read_to_string(&path).unwrap();
+    let corrupted = content.replacen("(array ", "(array (bogus 1) ", 1);
+    assert_ne!(content, corrupted);
+    fs::write(&path, corrupted).unwrap();
+    (store, temp_dir)
+}
+
+#[test]
+fn test_decode_lino_entries_keeps_good_entries() {
+    let (values, rejected) = decode_lino_entries("(array (int 1) (bogus 2) (int 3))");
+    assert_eq!(values, vec![serde_json::json!(1), serde_json::json!(3)]);
+    assert_eq!(rejected, vec!["(bogus 2)".to_string()]);
+
+    // A truncated write leaves the last entry unbalanced
+    let (values, rejected) = decode_lino_entries("(array (int 1) (object ((str YQ==) (in");
+    assert_eq!(values.len(), 1);
+    assert_eq!(rejected, vec!["(object ((str YQ==) (in".to_string()]);
+
+    let (values, rejected) = decode_lino_entries("not lino at all");
+    assert!(values.is_empty());
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(decode_lino_entries("  "), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn test_corrupt_entry_no_longer_hides_history() {
+    let (store, temp) = corrupt_store();
+    assert_eq!(store.get_all().len(), 2);
+
+    // The next write quarantines the corrupt entry instead of losing everything
+    store.save(&ExecutionRecord::new("echo third")).unwrap();
+    assert_eq!(store.get_all().len(), 3);
+    let quarantine = fs::read_to_string(temp.path().join("executions.lino.quarantine")).unwrap();
+    assert_eq!(quarantine, "(bogus 1)\n");
+    assert!(!fs::read_to_string(temp.path().join("executions.lino"))
+        .unwrap()
+        .contains("bogus"));
+}
+
+#[test]
+fn test_repair_quarantines_and_dry_run_writes_nothing() {
+    let (store, temp) = corrupt_store();
+    let before = fs::read_to_string(temp.path().join("executions.lino")).unwrap();
+
+    let report = store.repair(true).unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.quarantined, 1);
+    assert!(report.has_changes());
+    assert_eq!(
+        fs::read_to_string(temp.path().join("executions.lino")).unwrap(),
+        before
+    );
+    assert!(!temp.path().join("executions.lino.quarantine").exists());
+
+    let report = store.repair(false).unwrap();
+    assert_eq!(report.quarantined, 1);
+    assert!(temp.path().join("executions.lino.quarantine").exists());
+    assert!(temp.path().join("executions.lino.repair.bak").exists());
+    assert!(!store.repair(false).unwrap().has_changes());
+}
+
+#[test]
+fn test_recover_from_log_footers() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    fs::write(
+        &log_path,
+        "output\n\n==================================================\nFinished: 2026-03-01 10:05:00.250\nExit Code: 3\n",
+    )
+    .unwrap();
+
+    let mut executing = ExecutionRecord::new("make test");
+    executing.log_path = log_path.to_string_lossy().to_string();
+    let mut no_footer = ExecutionRecord::new("sleep 100");
+    no_footer.log_path = temp_dir
+        .path()
+        .join("missing.log")
+        .to_string_lossy()
+        .to_string();
+    let mut records = vec![executing, no_footer];
+
+    let recovered = recover_from_log_footers(&mut records);
+    assert_eq!(
+        recovered,
+        vec![RecoveredExecution {
+            uuid: records[0].uuid.clone(),
+            exit_code: 3
+        }]
+    );
+    assert_eq!(records[0].status, ExecutionStatus::Executed);
+    assert_eq!(records[0].exit_code, Some(3));
+    assert_eq!(
+        records[0].end_time.as_deref(),
+        Some("2026-03-01T10:05:00.250+00:00")
+    );
+    assert_eq!(records[1].status, ExecutionStatus::Executing);
+}
+
+#[test]
+fn test_exit_code_printed_by_command_is_not_a_footer() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    let mut record = ExecutionRecord::new("./deploy.sh");
+    record.log_path = log_path.to_string_lossy().to_string();
+
+    // Still running: the command printed `Exit Code:` but no footer follows
+    fs::write(&log_path, "step 1\nFinished: migrations\nExit Code: 0\n").unwrap();
+    let mut records = vec![record.clone()];
+    assert!(recover_from_log_footers(&mut records).is_empty());
+    assert_eq!(records[0].status, ExecutionStatus::Executing);
+
+    // A footer-shaped block followed by more output did not end the log
+    fs::write(
+        &log_path,
+        "==================================================\nFinished: 2026-03-01 10:05:00\nExit Code: 0\nstep 2\n",
+    )
+    .unwrap();
+    let mut records = vec![record];
+    assert!(recover_from_log_footers(&mut records).is_empty());
+}
+
+#[test]
+fn test_repair_persists_recovered_executions() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = create_store(temp_dir.path());
+    let log_path = temp_dir.path().join("run.log");
+    fs::write(
+        &log_path,
+        format!("npm test\n{}", create_log_footer("2026-03-01 10:05:00", 0)),
+    )
+    .unwrap();
+    let mut record = ExecutionRecord::new("npm test");
+    record.log_path = log_path.to_string_lossy().to_string();
+    store.save(&record).unwrap();
+
+    let report = store.repair(false).unwrap();
+    assert_eq!(report.recovered.len(), 1);
+    let stored = store.get(&record.uuid).unwrap();
+    assert_eq!(stored.status, ExecutionStatus::Executed);
+    assert_eq!(stored.exit_code, Some(0));
+}
+
+#[test]
+fn test_reconcile_stores() {
+    let uuid = |n: u32| format!("00000000-0000-4000-8000-{:012}", n);
+    let mut stale = finished(&uuid(2), "2026-03-01T10:00:05+00:00");
+    stale.status = ExecutionStatus::Executing;
+    stale.end_time = None;
+    stale.tags.push("keep-me".to_string());
+    let lino = vec![
+        finished(&uuid(1), "2026-03-01T10:00:05+00:00"),
+        stale,
+        finished(&uuid(3), "2026-03-01T10:00:09+00:00"),
+    ];
+    let links = vec![
+        finished(&uuid(2), "2026-03-01T10:00:07+00:00"),
+        finished(&uuid(3), "2026-03-01T10:00:04+00:00"),
+        finished(&uuid(4), "2026-03-01T10:00:01+00:00"),
+    ];
+
+    let plan = reconcile_stores(lino, &links);
+    assert_eq!(plan.added, vec![uuid(4)]);
+    assert_eq!(plan.updated, vec![uuid(2)]);
+    assert_eq!(plan.to_links, vec![uuid(1), uuid(3)]);
+    assert_eq!(plan.records.len(), 4);
+    let updated = plan.records.iter().find(|r| r.uuid == uuid(2)).unwrap();
+    assert_eq!(updated.status, ExecutionStatus::Executed);
+    assert_eq!(updated.tags, vec!["keep-me".to_string()]);
+}
+
+#[test]
+fn test_parse_links_records() {
+    let uuid = "0a1b2c3d-0000-4000-8000-000000000001";
+    let output = format!(
+        r#"({u}: ExecutionRecord {u}) ({u}.uuid: uuid "{u}") ({u}.command: command "echo \"hi\"") ({u}.status: status "executed") ({u}.exitCode: exitCode "0") ({u}.pid: pid "null") ({u}.logPath: logPath "") ({u}.startTime: startTime "2026-03-01T10:00:00Z") ({u}.endTime: endTime "null") ({u}.workingDirectory: workingDirectory "/tmp") ({u}.shell: shell "/bin/sh") ({u}.platform: platform "linux") ({u}.options: options "{{\"isolated\":\"screen\"}}") ({u}.owner: owner "1000")"#,
+        u = uuid
+    );
+    let records = parse_links_records(&output);
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].command, "echo \"hi\"");
+    assert_eq!(records[0].exit_code, Some(0));
+    assert_eq!(records[0].end_time, None);
+    assert_eq!(records[0].options["isolated"], "screen");
+    // A numeric-looking owner stays a string
+    assert_eq!(records[0].owner.as_deref(), Some("1000"));
+}
+
+#[test]
+fn test_repair_arguments_and_report() {
+    let options = parse_args(&args(&["--repair-dry-run", "--output-format", "text"]))
+        .unwrap()
+        .wrapper_options;
+    assert!(options.repair && options.repair_dry_run);
+    let err = parse_args(&args(&["--repair", "--list"])).unwrap_err();
+    assert!(err.contains("--repair cannot be combined"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let store = create_store(temp_dir.path());
+    let result = query_repair(Some(&store), &options);
+    let output = result.output.unwrap();
+    assert!(output.starts_with("Store Repair (dry run)"));
+    assert!(output.contains("nothing to repair"));
+
+    let report = store.repair(true).unwrap();
+    let links = format_repair_report(&report, "links-notation").unwrap();
+    assert!(links.starts_with("repair\n  dryRun true\n  changed false"));
+
+    assert!(!query_repair(None, &WrapperOptions::default()).success);
+}
@@ -0,0 +1,206 @@
+//! Tests for store schema versioning and migrations
+//!
+//! Covers migrating records written before versioning (fixtures in
+//! tests/fixtures), backing up the old store file, keeping fields written by
+//! newer versions, and the published JSON Schema behind --schema.
+
+use serde_json::{json, Value};
+use start_command::{
+    migrate_record, parse_args, parse_records, record_schema_version, run_store_command,
+    ExecutionRecord, ExecutionStatus, ExecutionStore, ExecutionStoreOptions,
+    CURRENT_SCHEMA_VERSION, EXECUTION_RECORD_SCHEMA, LEGACY_SCHEMA_VERSION,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const V1_FIXTURE: &str = include_str!("fixtures/executions-v1.lino");
+const V3_FIXTURE: &str = include_str!("fixtures/executions-v3.lino");
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+/// Store whose executions.lino starts out with `content`
+fn store_with(content: &str) -> (ExecutionStore, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("executions.lino"), content).unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    (store, temp_dir)
+}
+
+fn backup_path(dir: &Path, version: u32) -> std::path::PathBuf {
+    dir.join(format!("executions.lino.v{}.bak", version))
+}
+
+#[test]
+fn test_v1_fixture_still_loads() {
+    let (store, temp) = store_with(V1_FIXTURE);
+    let records = store.get_all();
+    assert_eq!(records.len(), 3);
+    assert!(records
+        .iter()
+        .all(|r| r.schema_version == CURRENT_SCHEMA_VERSION));
+
+    let js = store.get("web-dev").unwrap();
+    assert_eq!(js.command, "npm run dev");
+    assert_eq!(js.oom_killed, Some(false));
+    assert_eq!(js.status, ExecutionStatus::Executing);
+
+    // The partial record lacked status, shell, platform and options
+    let partial = store.get("c3e5a7b9-1b2d-4f40-9c6e-8d0f2a4b6c03").unwrap();
+    assert_eq!(partial.status, ExecutionStatus::Executed);
+    assert_eq!(partial.exit_code, Some(2));
+    assert_eq!(partial.shell, "");
+    assert!(partial.options.is_empty());
+
+    // Loading migrates in memory only
+    assert_eq!(
+        fs::read_to_string(temp.path().join("executions.lino")).unwrap(),
+        V1_FIXTURE
+    );
+    assert!(!backup_path(temp.path(), 1).exists());
+
+    // Migrating persists the records and keeps the original as a backup
+    assert_eq!(store.migrate().unwrap(), Some(1));
+    assert_eq!(
+        fs::read_to_string(backup_path(temp.path(), 1)).unwrap(),
+        V1_FIXTURE
+    );
+    assert_ne!(
+        fs::read_to_string(temp.path().join("executions.lino")).unwrap(),
+        V1_FIXTURE
+    );
+    assert_eq!(store.migrate().unwrap(), None);
+}
+
+#[test]
+fn test_repeated_migration_keeps_one_backup() {
+    let (store, temp) = store_with(V1_FIXTURE);
+    store.migrate().unwrap();
+    // An older release writes a store of version 1 records again (here the
+    // first record of the fixture only)
+    let mut depth = 0;
+    let first_end = V1_FIXTURE["(array ".len()..]
+        .char_indices()
+        .find_map(|(index, c)| {
+            depth += match c {
+                '(' => 1,
+                ')' => -1,
+                _ => 0,
+            };
+            (depth == 0).then_some("(array ".len() + index + 1)
+        })
+        .unwrap();
+    let rewritten = format!("{})", &V1_FIXTURE[..first_end]);
+    fs::write(temp.path().join("executions.lino"), &rewritten).unwrap();
+    assert_eq!(store.get_all().len(), 1);
+    store.save(&ExecutionRecord::new("echo new")).unwrap();
+    assert_eq!(store.migrate().unwrap(), None);
+
+    // The backup still holds the store from before the first migration
+    assert_eq!(
+        fs::read_to_string(backup_path(temp.path(), 1)).unwrap(),
+        V1_FIXTURE
+    );
+    let backups = fs::read_dir(temp.path())
+        .unwrap()
+        .filter(|entry| {
+            let name = entry.as_ref().unwrap().file_name();
+            name.to_string_lossy().ends_with(".bak")
+        })
+        .count();
+    assert_eq!(backups, 1);
+    assert_eq!(store.get_all().len(), 2);
+}
+
+#[test]
+fn test_write_to_legacy_store_backs_up_first() {
+    let (store, temp) = store_with(V1_FIXTURE);
+    store.save(&ExecutionRecord::new("echo new")).unwrap();
+    assert!(backup_path(temp.path(), 1).exists());
+    assert_eq!(store.get_all().len(), 4);
+}
+
+#[test]
+fn test_newer_records_keep_unknown_fields() {
+    let (store, temp) = store_with(V3_FIXTURE);
+    let record = store.get("d4f6b8c0-2c3e-4051-8d7f-9e1a3b5c7d04").unwrap();
+    assert_eq!(record.schema_version, 3);
+    assert_eq!(
+        record.extra.get("retryOf"),
+        Some(&Value::from("6f1c7a52-1d1e-4c38-9a7e-2f0f7b6c1a01"))
+    );
+
+    // Rewriting the store (here by adding a record) keeps the unknown field
+    store.save(&ExecutionRecord::new("echo new")).unwrap();
+    let reloaded = store.get(&record.uuid).unwrap();
+    assert_eq!(reloaded.schema_version, 3);
+    assert_eq!(reloaded.extra, record.extra);
+    assert!(!backup_path(temp.path(), 3).exists());
+}
+
+#[test]
+fn test_migrate_record_value() {
+    let mut legacy = json!({"uuid": "abc", "command": "ls", "startTime": "2026-01-01T00:00:00Z"});
+    assert_eq!(record_schema_version(&legacy), LEGACY_SCHEMA_VERSION);
+    assert_eq!(migrate_record(&mut legacy), LEGACY_SCHEMA_VERSION);
+    assert_eq!(legacy["schemaVersion"], CURRENT_SCHEMA_VERSION);
+    assert_eq!(legacy["status"], "executing");
+    assert_eq!(legacy["platform"], "");
+    assert_eq!(legacy["options"], json!({}));
+
+    let mut current = ExecutionRecord::new("ls").to_json();
+    let before = current.clone();
+    assert_eq!(migrate_record(&mut current), CURRENT_SCHEMA_VERSION);
+    assert_eq!(current, before);
+}
+
+#[test]
+fn test_import_migrates_legacy_export() {
+    let line = r#"{"uuid":"abc","status":"executed","exitCode":0,"command":"ls","logPath":"","startTime":"2026-01-01T00:00:00Z","endTime":null,"workingDirectory":"/","options":{}}"#;
+    let records = parse_records(line).unwrap();
+    assert_eq!(records[0].schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(records[0].shell, "");
+}
+
+#[test]
+fn test_schema_describes_records() {
+    let schema: Value = serde_json::from_str(EXECUTION_RECORD_SCHEMA).unwrap();
+    assert_eq!(schema["x-schemaVersion"], CURRENT_SCHEMA_VERSION);
+    let properties = schema["properties"].as_object().unwrap();
+
+    let mut full = ExecutionRecord::new("cargo test");
+    full.oom_killed = Some(false);
+    full.tags.push("release".to_string());
+    full.labels.insert("env".to_string(), "prod".to_string());
+    full.parent_uuid = Some("parent".to_string());
+    full.root_uuid = Some("root".to_string());
+    full.provenance = Some(Default::default());
+    full.owner = Some("alice".to_string());
+    let json = full.to_json();
+    for key in json.as_object().unwrap().keys() {
+        assert!(properties.contains_key(key), "{} missing from schema", key);
+    }
+
+    let minimal = ExecutionRecord::new("ls").to_json();
+    for key in schema["required"].as_array().unwrap() {
+        assert!(minimal.get(key.as_str().unwrap()).is_some(), "{}", key);
+    }
+}
+
+#[test]
+fn test_schema_argument() {
+    let options = parse_args(&args(&["--schema"])).unwrap().wrapper_options;
+    assert!(options.schema);
+    let result = run_store_command(&options, None);
+    assert!(result.success);
+    assert_eq!(result.output.unwrap(), EXECUTION_RECORD_SCHEMA.trim_end());
+
+    let err = parse_args(&args(&["--schema", "--list"])).unwrap_err();
+    assert!(err.contains("--schema cannot be combined"));
+}
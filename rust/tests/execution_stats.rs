@@ -0,0 +1,244 @@
+//! Tests for execution statistics (--stats)
+//!
+//! Covers grouping, duration percentiles, flakiness, trends, the three
+//! output formats, and the --stats/--group-by argument parsing.
+
+use serde_json::Value;
+use start_command::execution_stats::{
+    flakiness, normalize_command_prefix, percentile, record_duration_ms,
+};
+use start_command::{
+    compute_stats, format_stats_report, parse_args, query_stats, ExecutionRecord,
+    ExecutionRecordOptions, ExecutionStatus, ExecutionStore, ExecutionStoreOptions, RecordFilter,
+};
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn finished(command: &str, start: &str, end: &str, exit_code: i32) -> ExecutionRecord {
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        command: command.to_string(),
+        status: Some(ExecutionStatus::Executed),
+        exit_code: Some(exit_code),
+        start_time: Some(start.to_string()),
+        end_time: Some(end.to_string()),
+        working_directory: Some("/repo".to_string()),
+        ..Default::default()
+    })
+}
+
+fn sample_records() -> Vec<ExecutionRecord> {
+    let mut docker_options = HashMap::new();
+    docker_options.insert("isolated".to_string(), Value::String("docker".to_string()));
+    let mut docker_run = finished(
+        "cargo build",
+        "2026-10-02T09:00:00Z",
+        "2026-10-02T09:00:30Z",
+        0,
+    );
+    docker_run.options = docker_options;
+
+    vec![
+        finished(
+            "npm test",
+            "2026-10-01T10:00:00Z",
+            "2026-10-01T10:00:01Z",
+            0,
+        ),
+        finished(
+            "npm test",
+            "2026-10-01T11:00:00Z",
+            "2026-10-01T11:00:02Z",
+            1,
+        ),
+        finished(
+            "npm test",
+            "2026-10-02T10:00:00Z",
+            "2026-10-02T10:00:03Z",
+            0,
+        ),
+        finished(
+            "npm test",
+            "2026-10-02T11:00:00Z",
+            "2026-10-02T11:00:04Z",
+            1,
+        ),
+        docker_run,
+        ExecutionRecord::with_options(ExecutionRecordOptions {
+            command: "npm  test".to_string(),
+            status: Some(ExecutionStatus::Executing),
+            start_time: Some("2026-10-02T12:00:00Z".to_string()),
+            working_directory: Some("/other".to_string()),
+            ..Default::default()
+        }),
+    ]
+}
+
+#[test]
+fn normalizes_command_prefix_skipping_env_assignments() {
+    assert_eq!(
+        normalize_command_prefix("FOO=1 npm  test -- --watch", 2),
+        "npm test"
+    );
+    assert_eq!(normalize_command_prefix("ls", 2), "ls");
+    assert_eq!(normalize_command_prefix("echo a=b", 2), "echo a=b");
+}
+
+#[test]
+fn computes_record_duration_from_timestamps() {
+    let record = finished("x", "2026-10-01T10:00:00Z", "2026-10-01T10:00:01.500Z", 0);
+    assert_eq!(record_duration_ms(&record), Some(1500.0));
+
+    let executing = ExecutionRecord::new("x");
+    assert_eq!(record_duration_ms(&executing), None);
+}
+
+#[test]
+fn percentile_uses_nearest_rank() {
+    let values = [1.0, 2.0, 3.0, 4.0];
+    assert_eq!(percentile(&values, 50.0), Some(2.0));
+    assert_eq!(percentile(&values, 95.0), Some(4.0));
+    assert_eq!(percentile(&values, 0.0), Some(1.0));
+    assert_eq!(percentile(&[], 50.0), None);
+}
+
+#[test]
+fn flakiness_measures_alternation() {
+    assert_eq!(flakiness(&[true, true, true]), 0.0);
+    assert_eq!(flakiness(&[true, false, true, false]), 1.0);
+    assert_eq!(flakiness(&[true, true, false]), 0.5);
+    assert_eq!(flakiness(&[false]), 0.0);
+}
+
+#[test]
+fn groups_by_command_directory_and_backend_by_default() {
+    let report = compute_stats(&sample_records(), None);
+    assert_eq!(report.total, 6);
+    assert_eq!(report.executing, 1);
+    assert_eq!(report.successful, 3);
+    assert_eq!(report.failed, 2);
+
+    let groups: Vec<&str> = report
+        .groupings
+        .iter()
+        .map(|g| g.group_by.as_str())
+        .collect();
+    assert_eq!(groups, vec!["command", "directory", "backend"]);
+
+    let by_command = &report.groupings[0].groups;
+    let npm = by_command.iter().find(|g| g.key == "npm test").unwrap();
+    // Whitespace-normalized command joins the executing "npm  test" run
+    assert_eq!(npm.runs, 5);
+    assert_eq!(npm.executing, 1);
+    assert_eq!(npm.success_rate, Some(0.5));
+    assert_eq!(npm.p50_duration_ms, Some(2000.0));
+    assert_eq!(npm.p95_duration_ms, Some(4000.0));
+    assert_eq!(npm.max_duration_ms, Some(4000.0));
+    assert_eq!(npm.flakiness, 1.0);
+
+    let by_backend = &report.groupings[2].groups;
+    assert_eq!(by_backend[0].key, "direct");
+    assert_eq!(by_backend[1].key, "docker");
+    assert_eq!(by_backend[1].flakiness, 0.0);
+}
+
+#[test]
+fn builds_daily_trends() {
+    let report = compute_stats(&sample_records(), Some("prefix"));
+    assert_eq!(report.groupings.len(), 1);
+    assert_eq!(report.groupings[0].group_by, "prefix");
+
+    let dates: Vec<&str> = report.trends.iter().map(|t| t.date.as_str()).collect();
+    assert_eq!(dates, vec!["2026-10-01", "2026-10-02"]);
+    assert_eq!(report.trends[0].runs, 2);
+    assert_eq!(report.trends[0].success_rate, Some(0.5));
+    assert_eq!(report.trends[1].runs, 4);
+}
+
+#[test]
+fn formats_report_in_all_output_formats() {
+    let report = compute_stats(&sample_records(), None);
+
+    let links = format_stats_report(&report, "links-notation").unwrap();
+    assert!(links.starts_with("stats\n  total 6"));
+    assert!(links.contains("  byCommand\n    \"npm test\"\n      runs 5"));
+    assert!(links.contains("      flakiness 1"));
+    assert!(links.contains("  trends\n    2026-10-01"));
+
+    let json: Value = serde_json::from_str(&format_stats_report(&report, "json").unwrap()).unwrap();
+    assert_eq!(json["total"], 6);
+    assert_eq!(json["groupings"][0]["groupBy"], "command");
+    assert_eq!(json["groupings"][0]["groups"][0]["key"], "npm test");
+    assert_eq!(json["groupings"][0]["groups"][0]["successRate"], 0.5);
+
+    let text = format_stats_report(&report, "text").unwrap();
+    assert!(text.contains("Execution Statistics"));
+    assert!(text.contains("By Backend"));
+    assert!(text.contains("Flakiness: 100.0%"));
+
+    assert!(format_stats_report(&report, "xml").is_err());
+}
+
+#[test]
+fn query_stats_reads_the_store() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    for record in sample_records().iter().take(4) {
+        store.save(record).unwrap();
+    }
+
+    let result = query_stats(
+        Some(&store),
+        &RecordFilter::default(),
+        Some("backend"),
+        Some("json"),
+    );
+    assert!(result.success);
+    let json: Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+    assert_eq!(json["total"], 4);
+    assert_eq!(json["groupings"][0]["groups"][0]["key"], "direct");
+
+    let disabled = query_stats(None, &RecordFilter::default(), None, None);
+    assert!(!disabled.success);
+    assert!(disabled.error.unwrap().contains("tracking is disabled"));
+}
+
+#[test]
+fn parses_stats_and_group_by_options() {
+    let result = parse_args(&args(&[
+        "--stats",
+        "--group-by",
+        "Prefix",
+        "--output-format",
+        "text",
+    ]))
+    .unwrap();
+    assert!(result.wrapper_options.stats);
+    assert_eq!(result.wrapper_options.group_by.as_deref(), Some("prefix"));
+    assert_eq!(
+        result.wrapper_options.output_format.as_deref(),
+        Some("text")
+    );
+
+    let eq = parse_args(&args(&["--stats", "--group-by=backend"])).unwrap();
+    assert_eq!(eq.wrapper_options.group_by.as_deref(), Some("backend"));
+}
+
+#[test]
+fn rejects_invalid_stats_option_combinations() {
+    let invalid_group = parse_args(&args(&["--stats", "--group-by", "user"])).unwrap_err();
+    assert!(invalid_group.contains("Invalid stats grouping"));
+
+    let without_stats = parse_args(&args(&["--group-by", "command", "--", "ls"])).unwrap_err();
+    assert!(without_stats.contains("--group-by option is only valid with --stats"));
+
+    let combined = parse_args(&args(&["--stats", "--list"])).unwrap_err();
+    assert!(combined.contains("--stats cannot be combined"));
+}
@@ -0,0 +1,106 @@
+//! Tests for the execution store's cross-process locks
+//!
+//! Covers exclusive and shared advisory locks, the legacy lock file kept for
+//! the JS implementation, and concurrent writers finishing at the same time.
+
+use start_command::execution_store::LockManager;
+use start_command::{ExecutionRecord, ExecutionStore, ExecutionStoreOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use tempfile::TempDir;
+
+fn lock_path(dir: &Path) -> PathBuf {
+    dir.join("executions.lock")
+}
+
+#[test]
+fn test_exclusive_lock_excludes_everyone() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut writer = LockManager::new(lock_path(temp_dir.path()));
+    assert!(writer.acquire(100));
+    assert!(lock_path(temp_dir.path()).exists());
+
+    assert!(!LockManager::new(lock_path(temp_dir.path())).acquire(50));
+    assert!(!LockManager::new(lock_path(temp_dir.path())).acquire_shared(50));
+
+    writer.release();
+    assert!(!lock_path(temp_dir.path()).exists());
+    assert!(LockManager::new(lock_path(temp_dir.path())).acquire(100));
+}
+
+#[test]
+fn test_shared_locks_admit_readers_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut first = LockManager::new(lock_path(temp_dir.path()));
+    let mut second = LockManager::new(lock_path(temp_dir.path()));
+    assert!(first.acquire_shared(100));
+    assert!(second.acquire_shared(100));
+    // Readers leave the legacy lock file alone
+    assert!(!lock_path(temp_dir.path()).exists());
+
+    assert!(!LockManager::new(lock_path(temp_dir.path())).acquire(50));
+    drop(first);
+    drop(second);
+    assert!(LockManager::new(lock_path(temp_dir.path())).acquire(100));
+}
+
+#[test]
+fn test_writer_waits_for_legacy_lock_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let now = chrono::Utc::now().timestamp_millis();
+    let live = format!(r#"{{"pid":{},"timestamp":{}}}"#, std::process::id(), now);
+    fs::write(lock_path(temp_dir.path()), live).unwrap();
+    assert!(!LockManager::new(lock_path(temp_dir.path())).acquire(150));
+
+    // A lock file left behind by a crashed JS process is stale
+    let stale = format!(
+        r#"{{"pid":{},"timestamp":{}}}"#,
+        std::process::id(),
+        now - 120_000
+    );
+    fs::write(lock_path(temp_dir.path()), stale).unwrap();
+    assert!(LockManager::new(lock_path(temp_dir.path())).acquire(150));
+}
+
+#[test]
+fn test_parallel_writers_lose_no_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let handles: Vec<_> = (0..8)
+        .map(|worker| {
+            let app_folder = temp_dir.path().to_path_buf();
+            thread::spawn(move || {
+                let store = ExecutionStore::with_options(ExecutionStoreOptions {
+                    app_folder: Some(app_folder),
+                    use_links: Some(false),
+                    verbose: false,
+                });
+                for n in 0..5 {
+                    let mut record = ExecutionRecord::new(&format!("echo {}-{}", worker, n));
+                    store.save(&record).unwrap();
+                    assert_eq!(store.get(&record.uuid).unwrap().command, record.command);
+                    record.complete(0);
+                    store.save(&record).unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let records = store.get_all();
+    assert_eq!(records.len(), 40);
+    assert!(records.iter().all(|r| r.exit_code == Some(0)));
+    assert!(!lock_path(temp_dir.path()).exists());
+    assert!(!fs::read_dir(temp_dir.path()).unwrap().any(|entry| entry
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .ends_with(".tmp")));
+}
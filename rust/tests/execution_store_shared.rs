@@ -0,0 +1,139 @@
+//! Tests for the shared multi-user execution store
+//!
+//! Covers the per-user folder layout and permissions, owner stamping,
+//! reading across users with --all-users, and ownership checks on --stop.
+
+use serde_json::json;
+use start_command::{
+    control_execution_with_runner, parse_args, shared_store_users, CommandRunOutput, CommandRunner,
+    ControlAction, ExecutionRecord, ExecutionStore, ExecutionStoreOptions,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+/// Runner that must never be reached
+struct UnreachableRunner;
+
+impl CommandRunner for UnreachableRunner {
+    fn run(&self, command: &str, _args: &[String]) -> CommandRunOutput {
+        panic!(
+            "{} must not run for an execution owned by someone else",
+            command
+        );
+    }
+}
+
+fn detached(command: &str, session: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.options = HashMap::from([
+        ("isolated".to_string(), json!("screen")),
+        ("isolationMode".to_string(), json!("detached")),
+        ("sessionName".to_string(), json!(session)),
+    ]);
+    record
+}
+
+fn shared(root: &Path, user: &str) -> ExecutionStore {
+    ExecutionStore::shared(root, user, false)
+}
+
+#[test]
+fn test_shared_store_layout_and_owner() {
+    let temp_dir = TempDir::new().unwrap();
+    let alice = shared(temp_dir.path(), "alice");
+    assert_eq!(alice.owner(), Some("alice"));
+    assert_eq!(alice.shared_root(), Some(temp_dir.path()));
+
+    let record = ExecutionRecord::new("make deploy");
+    assert!(record.owner.is_none());
+    alice.save(&record).unwrap();
+    assert_eq!(
+        alice.get(&record.uuid).unwrap().owner.as_deref(),
+        Some("alice")
+    );
+    assert!(temp_dir.path().join("users/alice/executions.lino").exists());
+    shared(temp_dir.path(), "bob");
+    assert_eq!(shared_store_users(temp_dir.path()), vec!["alice", "bob"]);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode(&temp_dir.path().join("users/alice")) & 0o777, 0o750);
+        assert_ne!(mode(&temp_dir.path().join("users")) & 0o1000, 0);
+    }
+}
+
+#[test]
+fn test_all_users_reads_every_folder() {
+    let temp_dir = TempDir::new().unwrap();
+    let alice = shared(temp_dir.path(), "alice");
+    let bob = shared(temp_dir.path(), "bob");
+    alice.save(&ExecutionRecord::new("npm test")).unwrap();
+    bob.save(&ExecutionRecord::new("cargo build")).unwrap();
+
+    assert_eq!(bob.get_all().len(), 1);
+    let team = bob.with_all_users(true);
+    let records = team.get_all();
+    assert_eq!(records.len(), 2);
+    let owners: Vec<_> = records.iter().filter_map(|r| r.owner.as_deref()).collect();
+    assert!(owners.contains(&"alice") && owners.contains(&"bob"));
+
+    // Writes still only go to the own folder
+    team.save(&ExecutionRecord::new("ls")).unwrap();
+    assert_eq!(alice.get_all().len(), 1);
+
+    // Personal stores have no other users to read
+    let personal = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(personal.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    })
+    .with_all_users(true);
+    assert!(store.owner().is_none());
+    assert!(store.get_all().is_empty());
+}
+
+#[test]
+fn test_stop_respects_ownership() {
+    let temp_dir = TempDir::new().unwrap();
+    let alice = shared(temp_dir.path(), "alice");
+    alice.save(&detached("sleep 100", "alice-job")).unwrap();
+
+    let bob = shared(temp_dir.path(), "bob").with_all_users(true);
+    let record = bob.get("alice-job").unwrap();
+    assert!(bob.check_ownership(&record).is_err());
+    assert!(alice.check_ownership(&record).is_ok());
+
+    let result = control_execution_with_runner(
+        Some(&bob),
+        "alice-job",
+        ControlAction::Stop,
+        &UnreachableRunner,
+    );
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("is owned by alice"));
+}
+
+#[test]
+fn test_all_users_argument() {
+    let options = parse_args(&args(&["--list", "--all-users"]))
+        .unwrap()
+        .wrapper_options;
+    assert!(options.all_users);
+    assert!(
+        parse_args(&args(&["--grep", "panic", "--all-users"]))
+            .unwrap()
+            .wrapper_options
+            .all_users
+    );
+
+    let err = parse_args(&args(&["--all-users", "--", "echo", "hi"])).unwrap_err();
+    assert!(err.contains("--all-users option is only valid with"));
+}
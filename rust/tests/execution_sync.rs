@@ -0,0 +1,228 @@
+//! Tests for syncing the execution store through a git remote (--sync)
+//!
+//! Covers converging two stores through a bare repository, keeping the more
+//! complete copy of a record, carrying annotations and compressed logs, the
+//! report formats and the --sync arguments.
+
+use start_command::{
+    format_sync_report, parse_args, run_store_command, sync_store, ExecutionRecord, ExecutionStore,
+    ExecutionStoreOptions, SyncOptions, SYNC_BRANCH,
+};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn store_in(dir: &Path) -> ExecutionStore {
+    ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(dir.to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    })
+}
+
+/// Empty bare repository to sync through
+fn bare_remote(dir: &Path) -> String {
+    let remote = dir.join("history.git");
+    let status = Command::new("git")
+        .args(["init", "-q", "--bare"])
+        .arg(&remote)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    remote.to_string_lossy().to_string()
+}
+
+fn uuids(store: &ExecutionStore) -> Vec<String> {
+    let mut uuids: Vec<String> = store.get_all().into_iter().map(|r| r.uuid).collect();
+    uuids.sort();
+    uuids
+}
+
+#[test]
+fn test_two_stores_converge() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = bare_remote(temp_dir.path());
+    let alice = store_in(&temp_dir.path().join("alice"));
+    let bob = store_in(&temp_dir.path().join("bob"));
+    let options = SyncOptions::default();
+
+    let mut shared = ExecutionRecord::new("cargo build");
+    alice.save(&shared).unwrap();
+    alice.save(&ExecutionRecord::new("npm test")).unwrap();
+    bob.save(&ExecutionRecord::new("make lint")).unwrap();
+
+    let report = sync_store(&alice, &remote, &options).unwrap();
+    assert_eq!(report.branch, SYNC_BRANCH);
+    assert!(report.pulled.is_empty());
+    assert_eq!(report.pushed.len(), 2);
+    assert!(report.commit.is_some());
+
+    // Bob finishes the execution Alice still sees as running
+    shared.complete(0);
+    bob.save(&shared).unwrap();
+    let report = sync_store(&bob, &remote, &options).unwrap();
+    assert_eq!(report.pulled.len(), 1);
+    assert_eq!(report.pushed.len(), 2);
+
+    let report = sync_store(&alice, &remote, &options).unwrap();
+    assert_eq!(report.pulled.len(), 2);
+    assert!(report.pushed.is_empty());
+    assert!(report.commit.is_none());
+
+    assert_eq!(uuids(&alice).len(), 3);
+    assert_eq!(uuids(&alice), uuids(&bob));
+    assert_eq!(alice.get(&shared.uuid).unwrap().exit_code, Some(0));
+
+    // Nothing left to exchange
+    let report = sync_store(&bob, &remote, &options).unwrap();
+    assert!(report.pulled.is_empty() && report.pushed.is_empty());
+}
+
+#[test]
+fn test_sync_carries_annotations() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = bare_remote(temp_dir.path());
+    let alice = store_in(&temp_dir.path().join("alice"));
+    let bob = store_in(&temp_dir.path().join("bob"));
+    let options = SyncOptions::default();
+
+    let mut record = ExecutionRecord::new("./release.sh");
+    record.complete(0);
+    alice.save(&record).unwrap();
+    sync_store(&alice, &remote, &options).unwrap();
+    sync_store(&bob, &remote, &options).unwrap();
+
+    let mut tagged = bob.get(&record.uuid).unwrap();
+    tagged.tags.push("release-1.4".to_string());
+    bob.save(&tagged).unwrap();
+    assert_eq!(sync_store(&bob, &remote, &options).unwrap().pushed.len(), 1);
+
+    let report = sync_store(&alice, &remote, &options).unwrap();
+    assert_eq!(report.pulled, vec![record.uuid.clone()]);
+    assert_eq!(alice.get(&record.uuid).unwrap().tags, vec!["release-1.4"]);
+}
+
+#[test]
+fn test_sync_logs() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = bare_remote(temp_dir.path());
+    let alice_dir = temp_dir.path().join("alice");
+    let alice = store_in(&alice_dir);
+    let bob = store_in(&temp_dir.path().join("bob"));
+    let options = SyncOptions { logs: true };
+
+    let mut record = ExecutionRecord::new("cargo test");
+    fs::create_dir_all(&alice_dir).unwrap();
+    let log_path = alice_dir.join("run.log");
+    fs::write(&log_path, "test result: ok\n").unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    record.complete(0);
+    alice.save(&record).unwrap();
+
+    let report = sync_store(&alice, &remote, &options).unwrap();
+    assert_eq!(report.logs_pushed, 1);
+
+    // The log now only exists in the repository, as seen from another machine
+    fs::remove_file(&log_path).unwrap();
+    let report = sync_store(&bob, &remote, &options).unwrap();
+    assert_eq!(report.logs_pulled, 1);
+    let pulled = bob.get(&record.uuid).unwrap();
+    assert_ne!(pulled.log_path, record.log_path);
+    assert_eq!(
+        fs::read_to_string(&pulled.log_path).unwrap(),
+        "test result: ok\n"
+    );
+}
+
+#[test]
+fn test_sync_skips_records_with_invalid_uuids() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = bare_remote(temp_dir.path());
+    let alice = store_in(&temp_dir.path().join("alice"));
+    let bob = store_in(&temp_dir.path().join("bob"));
+    alice.save(&ExecutionRecord::new("ls")).unwrap();
+    sync_store(&alice, &remote, &SyncOptions::default()).unwrap();
+
+    // Someone with push access adds a record whose UUID is a path
+    let work = temp_dir.path().join("work");
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&[
+        "clone",
+        "-q",
+        "-b",
+        SYNC_BRANCH,
+        &remote,
+        &work.to_string_lossy(),
+    ]);
+    let mut evil = ExecutionRecord::new("echo pwned");
+    evil.uuid = "../../escaped".to_string();
+    evil.complete(0);
+    fs::write(
+        work.join("records").join("evil.json"),
+        evil.to_json().to_string(),
+    )
+    .unwrap();
+    let work = work.to_string_lossy().to_string();
+    git(&["-C", &work, "add", "-A"]);
+    git(&["-C", &work, "commit", "-q", "-m", "evil"]);
+    git(&["-C", &work, "push", "-q", "origin", SYNC_BRANCH]);
+
+    let report = sync_store(&bob, &remote, &SyncOptions { logs: true }).unwrap();
+    assert_eq!(report.skipped, vec!["../../escaped".to_string()]);
+    assert_eq!(report.pulled.len(), 1);
+    assert!(bob.get("../../escaped").is_none());
+    let links = format_sync_report(&report, "links-notation").unwrap();
+    assert!(links.contains("  skipped"));
+}
+
+#[test]
+fn test_sync_report_and_arguments() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = bare_remote(temp_dir.path());
+    let store = store_in(&temp_dir.path().join("store"));
+    store.save(&ExecutionRecord::new("ls")).unwrap();
+
+    let options = parse_args(&args(&["--sync", &remote, "--output-format", "json"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.sync.as_deref(), Some(remote.as_str()));
+    let result = run_store_command(&options, Some(&store));
+    let json: serde_json::Value = serde_json::from_str(&result.output.unwrap()).unwrap();
+    assert_eq!(json["pushed"].as_array().unwrap().len(), 1);
+    assert_eq!(json["logsPushed"], 0);
+    assert!(!run_store_command(&options, None).success);
+
+    let report = sync_store(&store, &remote, &SyncOptions::default()).unwrap();
+    let links = format_sync_report(&report, "links-notation").unwrap();
+    assert!(links.starts_with("sync\n  remote "));
+    assert!(links.contains("\n  pulled ()\n  pushed ()\n"));
+    let text = format_sync_report(&report, "text").unwrap();
+    assert!(text.starts_with("Store Sync"));
+    assert!(text.ends_with("Remote already up to date."));
+    assert!(format_sync_report(&report, "yaml").is_err());
+
+    assert!(
+        parse_args(&args(&["--sync=origin", "--sync-logs"]))
+            .unwrap()
+            .wrapper_options
+            .sync_logs
+    );
+    let err = parse_args(&args(&["--sync", "origin", "--list"])).unwrap_err();
+    assert!(err.contains("--sync cannot be combined"));
+    let err = parse_args(&args(&["--sync-logs", "--list"])).unwrap_err();
+    assert!(err.contains("only valid with --sync"));
+    let err = parse_args(&args(&["--sync"])).unwrap_err();
+    assert!(err.contains("requires a git remote"));
+}
@@ -0,0 +1,220 @@
+//! Tests for the self-contained HTML report (--report html)
+//!
+//! Covers escaping, converting ANSI colors to styled spans, the isolation
+//! lines of the timeline blocks, the rendered table and execution pages, the
+//! store query writing a file and the --report arguments.
+
+use start_command::{
+    ansi_to_html, create_log_footer, html_escape, isolation_lines, parse_args, query_report,
+    render_html_report, ExecutionRecord, ExecutionStore, ExecutionStoreOptions, RecordFilter,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn finished(command: &str, exit_code: i32, start: &str, end: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.start_time = start.to_string();
+    record.complete(exit_code);
+    record.end_time = Some(end.to_string());
+    record
+}
+
+#[test]
+fn test_html_escape_and_ansi() {
+    assert_eq!(
+        html_escape("<a href=\"x\">Tom & 'Jerry'</a>"),
+        "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+    );
+    assert_eq!(
+        ansi_to_html("\x1b[31merror\x1b[0m: <3>"),
+        "<span style=\"color:#cd3131\">error</span>: &lt;3&gt;"
+    );
+    assert_eq!(
+        ansi_to_html("\x1b[1;38;2;255;0;128mhot\x1b[m cold"),
+        "<span style=\"color:#ff0080;font-weight:bold\">hot</span> cold"
+    );
+    assert_eq!(
+        ansi_to_html("\x1b[38;5;196mred\x1b[39m"),
+        "<span style=\"color:#ff0000\">red</span>"
+    );
+    // Cursor movement and other escapes are dropped
+    assert_eq!(ansi_to_html("\x1b[2Kdone\x1b[0m"), "done");
+    // A style left open at the end is closed
+    assert_eq!(
+        ansi_to_html("\x1b[4mlink"),
+        "<span style=\"text-decoration:underline\">link</span>"
+    );
+}
+
+#[test]
+fn test_isolation_lines() {
+    let mut record = ExecutionRecord::new("make");
+    assert!(isolation_lines(&record).is_empty());
+    for (key, value) in [
+        ("isolated", "docker"),
+        ("isolationMode", "detached"),
+        ("sessionName", "build-1"),
+        ("image", "rust:1.90"),
+    ] {
+        record
+            .options
+            .insert(key.to_string(), serde_json::json!(value));
+    }
+    assert_eq!(
+        isolation_lines(&record),
+        vec![
+            "[Isolation] Environment: docker, Mode: detached",
+            "[Isolation] Session: build-1",
+            "[Isolation] Image: rust:1.90",
+        ]
+    );
+}
+
+#[test]
+fn test_render_html_report() {
+    let passing = finished(
+        "echo <ok>",
+        0,
+        "2026-10-17T09:00:00Z",
+        "2026-10-17T09:00:02Z",
+    );
+    let mut failing = finished(
+        "cargo test",
+        101,
+        "2026-10-18T09:00:00Z",
+        "2026-10-18T09:01:00Z",
+    );
+    failing
+        .options
+        .insert("isolated".to_string(), serde_json::json!("screen"));
+    let running = ExecutionRecord::new("sleep 100");
+    let html = render_html_report(
+        &[
+            (
+                failing.clone(),
+                Some(serde_json::json!({"wrapperPid": 4242})),
+            ),
+            (passing.clone(), None),
+            (running.clone(), None),
+        ],
+        "2026-10-18T10:00:00Z",
+    );
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(
+        html.contains("3 executions &middot; 1 succeeded &middot; 1 failed &middot; 1 executing")
+    );
+    // Overview rows link to the execution pages and sort by raw values
+    assert!(html.contains(&format!(
+        "<a href=\"#exec-{}\">echo &lt;ok&gt;</a>",
+        passing.uuid
+    )));
+    assert!(html.contains("<td data-sort=\"60000\">60.00s</td>"));
+    assert!(html.contains("<td>screen</td>"));
+    assert!(html.contains(&format!(
+        "<section class=\"execution\" id=\"exec-{}\">",
+        failing.uuid
+    )));
+    // Timeline header with isolation lines, and footer only once finished
+    assert!(html.contains("│ session"));
+    assert!(html.contains("│ isolation screen"));
+    assert!(html.contains("│ exit      101"));
+    assert!(html.contains("<dt>wrapperPid</dt><dd>4242</dd>"));
+    assert_eq!(html.matches("│ exit ").count(), 2);
+    // Nothing is fetched from the network
+    assert!(!html.contains("http://") && !html.contains("https://"));
+}
+
+#[test]
+fn test_query_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut failing = finished(
+        "npm test",
+        1,
+        "2026-10-18T09:00:00Z",
+        "2026-10-18T09:00:04Z",
+    );
+    let log_path = temp_dir.path().join("failing.log");
+    fs::write(
+        &log_path,
+        format!(
+            "=== Start Command Log ===\nCommand: npm test\n{}\n\n\x1b[31mnot ok 2 - logout\x1b[0m\n{}",
+            "=".repeat(50),
+            create_log_footer("2026-10-18 09:00:04.000", 1)
+        ),
+    )
+    .unwrap();
+    failing.log_path = log_path.to_string_lossy().to_string();
+    let passing = finished(
+        "npm run lint",
+        0,
+        "2026-10-17T09:00:00Z",
+        "2026-10-17T09:00:01Z",
+    );
+    store.save(&failing).unwrap();
+    store.save(&passing).unwrap();
+
+    let output = temp_dir.path().join("report.html");
+    let filter = RecordFilter {
+        outcome: Some("failed".to_string()),
+        ..Default::default()
+    };
+    let result = query_report(Some(&store), &filter, "html", Some(&output));
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(
+        result.output.unwrap(),
+        format!("Wrote report of 1 executions to {}", output.display())
+    );
+    let html = fs::read_to_string(&output).unwrap();
+    assert!(html.contains("<span style=\"color:#cd3131\">not ok 2 - logout</span>"));
+    // The log header and footer are replaced by the timeline blocks
+    assert!(!html.contains("=== Start Command Log ==="));
+    assert!(!html.contains("npm run lint"));
+
+    let stdout = query_report(Some(&store), &RecordFilter::default(), "html", None);
+    assert!(stdout.output.unwrap().contains("npm run lint"));
+
+    let disabled = query_report(None, &RecordFilter::default(), "html", None);
+    assert_eq!(
+        disabled.error.as_deref(),
+        Some("Execution tracking is disabled.")
+    );
+}
+
+#[test]
+fn test_report_arguments() {
+    let options = parse_args(&args(&[
+        "--report",
+        "HTML",
+        "--output=report.html",
+        "--since",
+        "7d",
+        "--all-users",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    assert_eq!(options.report.as_deref(), Some("html"));
+    assert_eq!(options.output.as_deref(), Some("report.html"));
+    assert!(options.filter.since.is_some());
+
+    let err = parse_args(&args(&["--report", "pdf"])).unwrap_err();
+    assert_eq!(
+        err,
+        "Invalid report format: \"pdf\". Valid options are: html"
+    );
+    let err = parse_args(&args(&["--list", "--output", "report.html"])).unwrap_err();
+    assert_eq!(err, "--output option is only valid with --report");
+    let err = parse_args(&args(&["--report", "html", "--list"])).unwrap_err();
+    assert!(err.contains("--report cannot be combined"));
+    let err = parse_args(&args(&["--report"])).unwrap_err();
+    assert!(err.contains("requires a format argument"));
+}
@@ -0,0 +1,181 @@
+//! Tests for isolation stacking (--isolated "screen ssh docker").
+//!
+//! Mirrors the stacking cases of js/test/args-parser.js and
+//! js/test/command-builder.js.
+
+use start_command::{
+    build_isolation_options_map, build_next_level_command, isolation_stack_status_lines,
+    parse_args, WrapperOptions, MAX_ISOLATION_DEPTH,
+};
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn stacked(list: &[&str]) -> WrapperOptions {
+    parse_args(&args(list)).unwrap().wrapper_options
+}
+
+#[test]
+fn should_split_stack_and_keep_outermost_level() {
+    let options = stacked(&[
+        "--isolated",
+        "screen ssh docker",
+        "--endpoint",
+        "_ user@host _",
+        "--image",
+        "node:20",
+        "--",
+        "npm",
+        "test",
+    ]);
+    assert_eq!(options.isolated.as_deref(), Some("screen"));
+    assert_eq!(options.isolated_stack, vec!["screen", "ssh", "docker"]);
+    assert_eq!(
+        options.endpoint_stack,
+        vec![None, Some("user@host".to_string()), None]
+    );
+    // A single image only applies to docker levels
+    assert_eq!(
+        options.image_stack,
+        vec![None, None, Some("node:20".to_string())]
+    );
+    assert!(options.image.is_none());
+    assert!(options.endpoint.is_none());
+}
+
+#[test]
+fn should_default_image_and_name_nested_sessions() {
+    let options = stacked(&[
+        "--isolated",
+        "screen docker",
+        "--session",
+        "outer",
+        "--",
+        "ls",
+    ]);
+    assert_eq!(options.session.as_deref(), Some("outer"));
+    assert!(options.image_stack[1].is_some());
+    assert_eq!(options.session_stack[0].as_deref(), Some("outer"));
+    assert!(options.session_stack[1]
+        .as_deref()
+        .is_some_and(|name| name.starts_with("docker-")));
+
+    let named = stacked(&["--isolated", "screen tmux", "--session", "a b", "--", "ls"]);
+    assert_eq!(
+        named.session_stack,
+        vec![Some("a".to_string()), Some("b".to_string())]
+    );
+}
+
+#[test]
+fn should_reject_invalid_stacks() {
+    let error = |list: &[&str]| parse_args(&args(list)).unwrap_err();
+
+    let too_deep = ["screen"; MAX_ISOLATION_DEPTH + 1].join(" ");
+    assert!(error(&["--isolated", &too_deep, "--", "ls"]).contains("Isolation stack too deep"));
+    assert!(error(&["--isolated", "screen lxc", "--", "ls"])
+        .contains("Invalid isolation backend: \"lxc\""));
+    assert!(error(&["--isolated", "screen _", "--", "ls"]).contains("Invalid isolation stack"));
+    assert!(error(&["--isolated", "screen ssh", "--", "ls"])
+        .contains("SSH isolation at level 2 requires --endpoint"));
+    assert!(error(&[
+        "--isolated",
+        "screen ssh docker",
+        "--endpoint",
+        "_ user@host",
+        "--",
+        "ls"
+    ])
+    .contains("--endpoint has 2 value(s) but isolation stack has 3 level(s)"));
+    assert!(error(&[
+        "--isolated",
+        "screen docker",
+        "--image",
+        "ubuntu _",
+        "--",
+        "ls"
+    ])
+    .contains("--image has a value for level 1 (screen)"));
+    assert!(
+        error(&["--isolated", "screen tmux", "--image", "ubuntu", "--", "ls"])
+            .contains("--image option is only valid when the isolation stack includes docker")
+    );
+}
+
+#[test]
+fn should_accept_docker_options_when_a_nested_level_is_docker() {
+    let options = stacked(&[
+        "--isolated",
+        "screen docker",
+        "--volume",
+        "/a:/a",
+        "--keep-container",
+        "--",
+        "ls",
+    ]);
+    let command = build_next_level_command(&options, "ls");
+    assert!(command.contains("--volume '/a:/a'"));
+    assert!(command.contains("--keep-container"));
+}
+
+#[test]
+fn should_build_nested_command_for_remaining_levels() {
+    let options = stacked(&[
+        "--isolated",
+        "docker ssh",
+        "--image",
+        "alpine _",
+        "--endpoint",
+        "_ user@host",
+        "--session",
+        "box remote",
+        "--detached",
+        "--",
+        "echo",
+        "hi",
+    ]);
+    assert_eq!(
+        build_next_level_command(&options, "echo hi | wc -c"),
+        "start --isolated 'ssh' --endpoint 'user@host' --session 'remote' --detached -- 'echo hi | wc -c'"
+    );
+
+    let single = stacked(&["--isolated", "screen", "--", "ls"]);
+    assert_eq!(single.isolated_stack, vec!["screen"]);
+    assert_eq!(build_next_level_command(&single, "ls -la"), "ls -la");
+}
+
+#[test]
+fn should_describe_stack_in_header_and_record() {
+    let options = stacked(&[
+        "--isolated",
+        "screen ssh docker",
+        "--endpoint",
+        "_ user@host _",
+        "--image",
+        "_ _ ubuntu:22.04",
+        "--session",
+        "one two three",
+        "--",
+        "ls",
+    ]);
+    assert_eq!(
+        isolation_stack_status_lines(&options),
+        vec!["[Isolation] Chain: screen → ssh@user@host → docker:ubuntu".to_string()]
+    );
+
+    let map = build_isolation_options_map(Some("screen"), "attached", "one", None, &options, None);
+    assert_eq!(
+        map["isolationStack"],
+        serde_json::json!(["screen", "ssh", "docker"])
+    );
+    assert_eq!(
+        map["sessionStack"],
+        serde_json::json!(["one", "two", "three"])
+    );
+
+    let single = stacked(&["--isolated", "tmux", "--", "ls"]);
+    assert!(isolation_stack_status_lines(&single).is_empty());
+    let map = build_isolation_options_map(Some("tmux"), "attached", "s", None, &single, None);
+    assert!(!map.contains_key("isolationStack"));
+}
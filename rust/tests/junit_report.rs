@@ -0,0 +1,201 @@
+//! Tests for the JUnit XML report (--junit)
+//!
+//! Covers escaping, selecting nested executions, the rendered test cases,
+//! the store query writing a file and the --junit/--parent arguments.
+
+use start_command::{
+    create_log_footer, descendants_of, parse_args, query_junit, render_junit, xml_escape,
+    ExecutionRecord, ExecutionStore, ExecutionStoreOptions, RecordFilter,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn finished(command: &str, exit_code: i32, start: &str, end: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.start_time = start.to_string();
+    record.complete(exit_code);
+    record.end_time = Some(end.to_string());
+    record
+}
+
+fn child_of(parent: &ExecutionRecord, command: &str) -> ExecutionRecord {
+    let mut record = ExecutionRecord::new(command);
+    record.parent_uuid = Some(parent.uuid.clone());
+    record.root_uuid = Some(parent.root_uuid.clone().unwrap_or(parent.uuid.clone()));
+    record
+}
+
+#[test]
+fn test_xml_escape() {
+    assert_eq!(
+        xml_escape("<a b=\"c\">Tom & 'Jerry'</a>"),
+        "&lt;a b=&quot;c&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;"
+    );
+    // Characters not allowed in XML 1.0 are dropped, whitespace is kept
+    assert_eq!(xml_escape("\x1b[31mred\x07\tok\n"), "[31mred\tok\n");
+}
+
+#[test]
+fn test_descendants_of() {
+    let root = ExecutionRecord::new("make all");
+    let child = child_of(&root, "make test");
+    let grandchild = child_of(&child, "cargo test");
+    let other = ExecutionRecord::new("ls");
+    let records = vec![
+        root.clone(),
+        child.clone(),
+        grandchild.clone(),
+        other.clone(),
+    ];
+
+    let uuids = |records: Vec<ExecutionRecord>| -> Vec<String> {
+        records.into_iter().map(|r| r.uuid).collect()
+    };
+    assert_eq!(
+        uuids(descendants_of(records.clone(), &root.uuid)),
+        vec![child.uuid.clone(), grandchild.uuid.clone()]
+    );
+    assert_eq!(
+        uuids(descendants_of(records.clone(), &child.uuid)),
+        vec![grandchild.uuid.clone()]
+    );
+    assert!(descendants_of(records, &other.uuid).is_empty());
+}
+
+#[test]
+fn test_render_junit() {
+    let passing = finished(
+        "npm run lint",
+        0,
+        "2026-10-18T09:00:00Z",
+        "2026-10-18T09:00:01.500Z",
+    );
+    let mut failing = finished(
+        "npm test -- --grep \"login\"",
+        2,
+        "2026-10-18T09:00:02Z",
+        "2026-10-18T09:00:06Z",
+    );
+    failing
+        .options
+        .insert("isolated".to_string(), serde_json::json!("docker"));
+    let running = ExecutionRecord::new("npm run e2e");
+    let xml = render_junit(
+        &[
+            (passing, "lint ok".to_string()),
+            (failing, "not ok 2 - login <timeout>".to_string()),
+            (running, String::new()),
+        ],
+        "start-command",
+        "2026-10-18T10:00:00+00:00",
+    );
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"start-command\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\" time=\"5.500\">"));
+    assert!(
+        xml.contains("<testcase name=\"npm run lint\" classname=\"start.direct\" time=\"1.500\">")
+    );
+    assert!(xml.contains(
+        "<testcase name=\"npm test -- --grep &quot;login&quot;\" classname=\"start.docker\" time=\"4.000\">"
+    ));
+    assert!(xml.contains("<failure message=\"Exited with code 2\" type=\"ExitCode\">"));
+    assert_eq!(xml.matches("<failure ").count(), 1);
+    assert!(xml.contains("<system-out>not ok 2 - login &lt;timeout&gt;</system-out>"));
+    assert!(xml.contains("<skipped message=\"Still executing\"/>"));
+    assert!(xml.trim_end().ends_with("</testsuites>"));
+}
+
+#[test]
+fn test_query_junit() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let parent = finished("make ci", 1, "2026-10-18T09:00:00Z", "2026-10-18T09:10:00Z");
+    let mut step = child_of(&parent, "cargo test");
+    step.start_time = "2026-10-18T09:01:00Z".to_string();
+    step.complete(101);
+    let log_path = temp_dir.path().join("step.log");
+    let output: Vec<String> = (1..=300).map(|n| format!("line {}", n)).collect();
+    fs::write(
+        &log_path,
+        format!(
+            "=== Start Command Log ===\nCommand: cargo test\n{}\n\n\x1b[31m{}\x1b[0m\n{}",
+            "=".repeat(50),
+            output.join("\n"),
+            create_log_footer("2026-10-18 09:02:00.000", 101)
+        ),
+    )
+    .unwrap();
+    step.log_path = log_path.to_string_lossy().to_string();
+    let mut unrelated = ExecutionRecord::new("ls");
+    unrelated.complete(0);
+    store.save(&parent).unwrap();
+    store.save(&step).unwrap();
+    store.save(&unrelated).unwrap();
+
+    let path = temp_dir.path().join("junit.xml");
+    let filter = RecordFilter::default();
+    let result = query_junit(Some(&store), &filter, Some(&parent.uuid), &path);
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(
+        result.output.unwrap(),
+        format!("Wrote JUnit report of 1 executions to {}", path.display())
+    );
+    let xml = fs::read_to_string(&path).unwrap();
+    assert!(xml.contains("<testsuite name=\"make ci\" tests=\"1\" failures=\"1\""));
+    assert!(xml.contains("<failure message=\"Exited with code 101\""));
+    // Only the log tail is kept, without the header, footer or ANSI escapes
+    assert!(xml.contains("<system-out>line 101\n"));
+    assert!(!xml.contains("line 100\n"));
+    assert!(xml.contains("line 300</system-out>"));
+    assert!(!xml.contains("Start Command Log") && !xml.contains("[31m"));
+
+    let result = query_junit(Some(&store), &filter, None, &path);
+    assert!(result.output.unwrap().contains("of 3 executions"));
+
+    let missing = query_junit(Some(&store), &filter, Some("nope"), &path);
+    assert_eq!(
+        missing.error.as_deref(),
+        Some("No execution found with UUID or session name: nope")
+    );
+    let disabled = query_junit(None, &filter, None, &path);
+    assert_eq!(
+        disabled.error.as_deref(),
+        Some("Execution tracking is disabled.")
+    );
+}
+
+#[test]
+fn test_junit_arguments() {
+    let options = parse_args(&args(&[
+        "--junit",
+        "steps.xml",
+        "--tag",
+        "nightly",
+        "--since",
+        "1d",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    assert_eq!(options.junit.as_deref(), Some("steps.xml"));
+    assert_eq!(options.tags, vec!["nightly"]);
+    assert!(options.filter.since.is_some());
+
+    let options = parse_args(&args(&["--junit=out.xml", "--parent", "build-1"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.parent.as_deref(), Some("build-1"));
+
+    let err = parse_args(&args(&["--list", "--parent", "build-1"])).unwrap_err();
+    assert_eq!(err, "--parent option is only valid with --junit");
+    let err = parse_args(&args(&["--junit", "out.xml", "--report", "html"])).unwrap_err();
+    assert!(err.contains("cannot be combined"), "{}", err);
+    let err = parse_args(&args(&["--junit"])).unwrap_err();
+    assert!(err.contains("requires a file path argument"));
+}
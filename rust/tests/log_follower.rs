@@ -0,0 +1,198 @@
+//! Tests for following the live log of an execution (--follow)
+//!
+//! Covers footer detection, --tail, streaming a growing log until its footer,
+//! executions that end without a footer, log sources (including quoting the
+//! remote log path of ssh sessions) and the arguments.
+
+use serde_json::json;
+use start_command::isolation::{ssh_detached_command, ssh_remote_log_path};
+use start_command::{
+    create_log_footer, follow_file, log_source, parse_args, tail_lines, ExecutionRecord,
+    ExecutionStore, ExecutionStoreOptions, FollowOptions, LogFooterScanner, LogSource,
+};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_footer_scanner() {
+    let mut scanner = LogFooterScanner::default();
+    // The command's own output is not mistaken for the footer
+    assert_eq!(scanner.feed("Exit Code: 3\nbuilding\n"), None);
+
+    let footer = create_log_footer("2026-10-18 10:00:00.000", 7);
+    let (first, second) = footer.split_at(footer.len() - 6);
+    assert_eq!(scanner.feed(first), None);
+    assert_eq!(scanner.feed(second), Some(7));
+}
+
+#[test]
+fn test_tail_lines() {
+    assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc\n");
+    assert_eq!(tail_lines("a\nb\nc", 1), "c");
+    assert_eq!(tail_lines("a\nb\n", 5), "a\nb\n");
+    assert_eq!(tail_lines("a\nb\n", 0), "");
+}
+
+#[test]
+fn test_follow_until_footer() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    fs::write(&log_path, "line 1\nline 2\nline 3\n").unwrap();
+
+    let writer_path = log_path.clone();
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(300));
+        let mut file = OpenOptions::new().append(true).open(writer_path).unwrap();
+        file.write_all(b"line 4\n").unwrap();
+        file.write_all(create_log_footer("2026-10-18 10:00:00.000", 2).as_bytes())
+            .unwrap();
+    });
+
+    let mut out = Vec::new();
+    let code = follow_file(
+        &log_path,
+        &FollowOptions { tail: Some(1) },
+        &mut out,
+        || None,
+    )
+    .unwrap();
+    writer.join().unwrap();
+    assert_eq!(code, 2);
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.starts_with("line 3\nline 4\n"));
+    assert!(out.ends_with("Exit Code: 2\n"));
+}
+
+#[test]
+fn test_follow_finished_without_footer() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("killed.log");
+    fs::write(&log_path, "partial output\n").unwrap();
+
+    let mut out = Vec::new();
+    let code = follow_file(&log_path, &FollowOptions::default(), &mut out, || Some(-1)).unwrap();
+    assert_eq!(code, -1);
+    assert_eq!(out, b"partial output\n");
+
+    let missing = temp_dir.path().join("missing.log");
+    let err = follow_file(&missing, &FollowOptions::default(), &mut out, || None).unwrap_err();
+    assert!(err.contains("Log file not found"));
+}
+
+#[test]
+fn test_log_source() {
+    let mut record = ExecutionRecord::new("npm start");
+    record.log_path = "/tmp/start-command/logs/isolation/screen/x.log".to_string();
+    assert_eq!(
+        log_source(&record).unwrap(),
+        LogSource::Local(PathBuf::from(&record.log_path))
+    );
+
+    record.options = HashMap::from([
+        ("isolated".to_string(), json!("ssh")),
+        ("isolationMode".to_string(), json!("detached")),
+        ("sessionName".to_string(), json!("ssh-job")),
+        ("endpoint".to_string(), json!("user@host")),
+    ]);
+    assert_eq!(
+        log_source(&record).unwrap(),
+        LogSource::Remote {
+            endpoint: "user@host".to_string(),
+            path: "/tmp/start-command/logs/isolation/ssh/ssh-job.log".to_string(),
+        }
+    );
+
+    let err = log_source(&ExecutionRecord::new("ls")).unwrap_err();
+    assert!(err.contains("does not have a log path"));
+}
+
+#[test]
+fn test_remote_log_path_is_quoted() {
+    let command = ssh_detached_command("bash -i", "bash", "make", "nightly build; x");
+    assert!(
+        command.ends_with("> '/tmp/start-command/logs/isolation/ssh/nightly build; x.log' 2>&1 &"),
+        "{}",
+        command
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_follow_remote_log_of_session_with_space() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // An `ssh` that runs the remote command locally
+    let temp_dir = TempDir::new().unwrap();
+    let ssh = temp_dir.path().join("ssh");
+    fs::write(&ssh, "#!/bin/sh\nexec sh -c \"$2\"\n").unwrap();
+    fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut record = ExecutionRecord::new("make");
+    let session = format!("nightly build {}", record.uuid);
+    record.options = HashMap::from([
+        ("isolated".to_string(), json!("ssh")),
+        ("isolationMode".to_string(), json!("detached")),
+        ("sessionName".to_string(), json!(session)),
+        ("endpoint".to_string(), json!("user@host")),
+    ]);
+    let app_folder = temp_dir.path().join("app");
+    ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(app_folder.clone()),
+        use_links: Some(false),
+        verbose: false,
+    })
+    .save(&record)
+    .unwrap();
+    let remote_log = PathBuf::from(ssh_remote_log_path(&session));
+    fs::create_dir_all(remote_log.parent().unwrap()).unwrap();
+    fs::write(
+        &remote_log,
+        format!("built\n{}", create_log_footer("2026-10-18 10:00:00.000", 5)),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_start"))
+        .args(["--follow", &record.uuid])
+        .env(
+            "PATH",
+            format!(
+                "{}:{}",
+                temp_dir.path().display(),
+                std::env::var("PATH").unwrap_or_default()
+            ),
+        )
+        .env("START_APP_FOLDER", &app_folder)
+        .output()
+        .unwrap();
+    let _ = fs::remove_file(&remote_log);
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("built\n"));
+}
+
+#[test]
+fn test_follow_arguments() {
+    let options = parse_args(&args(&["--follow", "my-session", "--tail=20"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.follow.as_deref(), Some("my-session"));
+    assert_eq!(options.tail, Some(20));
+
+    let err = parse_args(&args(&["--follow", "a", "--status", "a"])).unwrap_err();
+    assert!(err.contains("Cannot combine"));
+    let err = parse_args(&args(&["--tail", "5", "--list"])).unwrap_err();
+    assert!(err.contains("--tail option is only valid with --follow"));
+    let err = parse_args(&args(&["--follow", "a", "--tail", "lots"])).unwrap_err();
+    assert!(err.contains("Invalid tail"));
+    let err = parse_args(&args(&["--follow"])).unwrap_err();
+    assert!(err.contains("requires a UUID or session name"));
+}
@@ -0,0 +1,208 @@
+//! Tests for viewing stored execution logs (--log)
+//!
+//! Covers splitting logs into header, output and footer, ANSI stripping, line
+//! ranges and tails, compressed and truncated logs, the separate stderr log
+//! and the --log arguments.
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use start_command::{
+    create_log_footer, parse_args, parse_line_range, read_stored_log, render_log, split_log,
+    strip_ansi, view_execution_log, AnsiMode, ExecutionRecord, ExecutionStore,
+    ExecutionStoreOptions, LineRange, LogViewOptions,
+};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+const HEADER: &str = "=== Start Command Log ===\nTimestamp: 2026-10-18 10:00:00.000\nCommand: make\n==================================================\n\n";
+
+fn full_log(output: &str) -> String {
+    format!(
+        "{}{}{}",
+        HEADER,
+        output,
+        create_log_footer("2026-10-18 10:00:05.000", 0)
+    )
+}
+
+#[test]
+fn test_split_log() {
+    let log = full_log("one\ntwo\n");
+    let parts = split_log(&log);
+    assert_eq!(parts.header, Some(HEADER));
+    assert_eq!(parts.output, "one\ntwo\n");
+    assert!(parts.footer.unwrap().contains("Exit Code: 0"));
+    assert!(!parts.is_truncated());
+
+    let truncated = format!("{}partial", HEADER);
+    let parts = split_log(&truncated);
+    assert_eq!(parts.output, "partial");
+    assert!(parts.is_truncated());
+
+    let plain = split_log("no header here\n");
+    assert_eq!(plain.header, None);
+    assert_eq!(plain.output, "no header here\n");
+}
+
+#[test]
+fn test_strip_ansi() {
+    assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: x"), "error: x");
+    assert_eq!(strip_ansi("\x1b]0;title\x07done"), "done");
+    assert_eq!(strip_ansi("plain"), "plain");
+}
+
+#[test]
+fn test_line_ranges() {
+    assert_eq!(
+        parse_line_range("10:20").unwrap(),
+        LineRange {
+            start: Some(10),
+            end: Some(20)
+        }
+    );
+    assert_eq!(parse_line_range(":5").unwrap().start, None);
+    assert_eq!(parse_line_range("7").unwrap().end, Some(7));
+    assert!(parse_line_range("5:2").is_err());
+    assert!(parse_line_range("0:2").is_err());
+    assert!(parse_line_range("a:b").is_err());
+
+    let log = full_log("1\n2\n3\n4\n\x1b[32m5\x1b[0m\n");
+    let options = LogViewOptions {
+        output_only: true,
+        lines: Some(parse_line_range("2:").unwrap()),
+        tail: Some(2),
+        ..Default::default()
+    };
+    assert_eq!(render_log(&log, &options, false), "4\n5");
+    assert_eq!(render_log(&log, &options, true), "4\n\x1b[32m5\x1b[0m");
+
+    let beyond = LogViewOptions {
+        lines: Some(parse_line_range("100:").unwrap()),
+        ..Default::default()
+    };
+    assert_eq!(render_log(&log, &beyond, false), "");
+}
+
+#[test]
+fn test_read_compressed_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"compressed output\n").unwrap();
+    let compressed = encoder.finish().unwrap();
+    fs::write(temp_dir.path().join("run.log.gz"), &compressed).unwrap();
+
+    // The original log is gone but its compressed copy remains
+    assert_eq!(read_stored_log(&log_path).unwrap(), "compressed output\n");
+
+    // A cut-off archive still yields what it holds
+    let cut = temp_dir.path().join("cut.log.gz");
+    fs::write(&cut, &compressed[..compressed.len() - 8]).unwrap();
+    assert_eq!(read_stored_log(&cut).unwrap(), "compressed output\n");
+
+    let err = read_stored_log(Path::new("/nonexistent/x.log")).unwrap_err();
+    assert!(err.contains("Log file not found"));
+}
+
+#[test]
+fn test_view_execution_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut record = ExecutionRecord::new("make");
+    let log_path = temp_dir.path().join("make.log");
+    fs::write(&log_path, full_log("building\n")).unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    store.save(&record).unwrap();
+
+    let plain = LogViewOptions {
+        ansi: AnsiMode::Strip,
+        ..Default::default()
+    };
+    assert!(view_execution_log(Some(&store), &record.uuid, &plain).is_ok());
+
+    let stderr = LogViewOptions {
+        stderr_only: true,
+        ..plain.clone()
+    };
+    let err = view_execution_log(Some(&store), &record.uuid, &stderr).unwrap_err();
+    assert!(err.contains("No separate stderr log"));
+    fs::write(temp_dir.path().join("make.stderr.log"), "warning: x\n").unwrap();
+    assert!(view_execution_log(Some(&store), &record.uuid, &stderr).is_ok());
+
+    assert!(view_execution_log(Some(&store), "missing", &plain).is_err());
+    assert!(view_execution_log(None, &record.uuid, &plain).is_err());
+}
+
+#[test]
+fn test_log_into_closed_pipe_exits_cleanly() {
+    // `start --log <id> | head` closes the pipe before the log is written
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut record = ExecutionRecord::new("seq 1 100000");
+    let log_path = temp_dir.path().join("seq.log");
+    let output: String = (1..=100_000).map(|i| format!("{}\n", i)).collect();
+    fs::write(&log_path, full_log(&output)).unwrap();
+    record.log_path = log_path.to_string_lossy().to_string();
+    store.save(&record).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_start"))
+        .args(["--log", &record.uuid])
+        .env("START_APP_FOLDER", temp_dir.path())
+        .env("START_DISABLE_AUTO_ISSUE", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut first = [0u8; 16];
+    child.stdout.take().unwrap().read_exact(&mut first).unwrap();
+    let result = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(result.status.success(), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+}
+
+#[test]
+fn test_log_arguments() {
+    let options = parse_args(&args(&[
+        "--log",
+        "my-session",
+        "--ansi",
+        "STRIP",
+        "--output-only",
+        "--lines=10:20",
+        "--tail",
+        "5",
+        "--pager",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    assert_eq!(options.log.as_deref(), Some("my-session"));
+    assert_eq!(options.log_view.ansi, AnsiMode::Strip);
+    assert!(options.log_view.output_only && options.log_view.pager);
+    assert_eq!(options.log_view.lines.unwrap().end, Some(20));
+    assert_eq!(options.tail, Some(5));
+
+    let err = parse_args(&args(&["--log", "a", "--ansi", "rainbow"])).unwrap_err();
+    assert!(err.contains("Invalid ANSI mode"));
+    let err = parse_args(&args(&["--log", "a", "--follow", "a"])).unwrap_err();
+    assert!(err.contains("Cannot combine"));
+    let err = parse_args(&args(&["--output-only", "--list"])).unwrap_err();
+    assert!(err.contains("only valid with --log"));
+    let err = parse_args(&args(&["--lines", "9:1", "--log", "a"])).unwrap_err();
+    assert!(err.contains("Invalid line range"));
+}
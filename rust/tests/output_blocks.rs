@@ -46,6 +46,7 @@ fn test_create_start_block() {
         extra_lines: None,
         style: Some("rounded"),
         width: Some(50),
+        color: false,
         defer_command: false,
     });
 
@@ -67,6 +68,7 @@ fn test_create_start_block_with_isolation() {
         extra_lines: Some(extra),
         style: Some("rounded"),
         width: Some(60),
+        color: false,
         defer_command: false,
     });
 
@@ -92,6 +94,7 @@ fn test_create_start_block_with_docker_isolation() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
         defer_command: false,
     });
 
@@ -116,6 +119,7 @@ fn test_create_start_block_with_defer_command() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
         defer_command: true,
     });
 
@@ -140,6 +144,7 @@ fn test_create_finish_block() {
         extra_lines: None,
         style: Some("rounded"),
         width: Some(60),
+        color: false,
     });
 
     assert!(block.contains("✓"));
@@ -162,6 +167,7 @@ fn test_create_finish_block_failure() {
         extra_lines: None,
         style: None,
         width: None,
+        color: false,
     });
 
     assert!(block.contains("✗"));
@@ -185,6 +191,7 @@ fn test_create_finish_block_with_isolation_repeated() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
     });
 
     assert!(block.contains("│ isolation docker"));
@@ -205,6 +212,7 @@ fn test_create_finish_block_without_duration() {
         extra_lines: None,
         style: Some("rounded"),
         width: Some(50),
+        color: false,
     });
 
     assert!(block.contains("│ finish    2025-01-01 00:00:01"));
@@ -227,6 +235,7 @@ fn test_finish_block_log_session_last() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
     });
 
     let lines: Vec<&str> = block.lines().collect();
@@ -292,6 +301,7 @@ fn test_start_block_ends_with_empty_timeline_when_defer_command_true() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
         defer_command: true,
     });
 
@@ -325,6 +335,7 @@ fn test_visual_continuity_start_block_for_docker_isolation() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
         defer_command: true,
     });
 
@@ -422,6 +433,7 @@ fn test_visual_continuity_command_output_pattern() {
         extra_lines: Some(extra),
         style: None,
         width: None,
+        color: false,
         defer_command: true,
     });
 
@@ -0,0 +1,181 @@
+//! Tests for the --quiet, --silent and --summary-only output modes
+//!
+//! Covers what each mode prints, selecting the mode from the arguments, the
+//! argument checks, the failure report printed by --silent and hiding the
+//! output of an attached isolated run.
+
+use start_command::{
+    create_log_footer, output_mode, parse_args, silent_failure_report, OutputMode, OutputStyle,
+    OutputTheme,
+};
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn mode(strs: &[&str]) -> OutputMode {
+    output_mode(&parse_args(&args(strs)).unwrap().wrapper_options)
+}
+
+#[test]
+fn test_output_mode_parts() {
+    assert!(OutputMode::Normal.shows_start());
+    assert!(OutputMode::Normal.shows_output());
+    assert!(OutputMode::Normal.shows_finish());
+
+    assert!(!OutputMode::Quiet.shows_start());
+    assert!(OutputMode::Quiet.shows_output());
+    assert!(!OutputMode::Quiet.shows_finish());
+
+    assert!(!OutputMode::Silent.shows_start());
+    assert!(!OutputMode::Silent.shows_output());
+    assert!(!OutputMode::Silent.shows_finish());
+
+    assert!(!OutputMode::SummaryOnly.shows_start());
+    assert!(!OutputMode::SummaryOnly.shows_output());
+    assert!(OutputMode::SummaryOnly.shows_finish());
+}
+
+#[test]
+fn test_output_mode_arguments() {
+    assert_eq!(mode(&["echo", "hi"]), OutputMode::Normal);
+    assert_eq!(mode(&["--quiet", "echo", "hi"]), OutputMode::Quiet);
+    assert_eq!(mode(&["-q", "--", "echo", "hi"]), OutputMode::Quiet);
+    assert_eq!(mode(&["--silent", "make"]), OutputMode::Silent);
+    assert_eq!(mode(&["--summary-only", "make"]), OutputMode::SummaryOnly);
+    // --quiet works with isolation since it only hides the blocks
+    assert_eq!(
+        mode(&["--quiet", "--isolated", "screen", "--", "make"]),
+        OutputMode::Quiet
+    );
+    // --tail sets how many lines --silent shows on failure
+    let options = parse_args(&args(&["--silent", "--tail", "50", "--", "npm", "ci"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.tail, Some(50));
+
+    let err = parse_args(&args(&["--quiet", "--silent", "make"])).unwrap_err();
+    assert_eq!(
+        err,
+        "--quiet, --silent and --summary-only cannot be combined"
+    );
+    // Attached isolated runs are captured; detached and tmux sessions are not
+    assert_eq!(
+        mode(&["--silent", "--isolated", "docker", "--", "make"]),
+        OutputMode::Silent
+    );
+    assert_eq!(
+        mode(&["--summary-only", "--isolated-user", "--", "make"]),
+        OutputMode::SummaryOnly
+    );
+    for rejected in [
+        &[
+            "--silent",
+            "--isolated",
+            "docker",
+            "--detached",
+            "--",
+            "make",
+        ][..],
+        &["--summary-only", "--isolated", "tmux", "--", "make"][..],
+    ] {
+        assert_eq!(
+            parse_args(&args(rejected)).unwrap_err(),
+            "--silent and --summary-only are not valid with --detached or --isolated tmux"
+        );
+    }
+    let err = parse_args(&args(&["--tail", "5", "make"])).unwrap_err();
+    assert_eq!(
+        err,
+        "--tail option is only valid with --follow, --log, or --silent"
+    );
+}
+
+#[test]
+fn test_silent_failure_report() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("run.log");
+    let output: Vec<String> = (1..=30).map(|n| format!("line {}", n)).collect();
+    fs::write(
+        &log_path,
+        format!(
+            "=== Start Command Log ===\nCommand: npm ci\n{}\n\n{}\n{}",
+            "=".repeat(50),
+            output.join("\n"),
+            create_log_footer("2026-10-18 09:00:04.000", 2)
+        ),
+    )
+    .unwrap();
+    let style = OutputStyle {
+        theme: OutputTheme::Ascii,
+        ..Default::default()
+    };
+
+    let report = silent_failure_report(&log_path, 3, 2, &style);
+    assert_eq!(
+        report,
+        format!(
+            "... 27 earlier lines omitted\nline 28\nline 29\nline 30\nFAIL Command failed with exit code 2. Full log: {}",
+            log_path.display()
+        )
+    );
+
+    // No note when the whole output fits
+    let report = silent_failure_report(&log_path, 100, 2, &style);
+    assert!(report.starts_with("line 1\n"));
+    assert!(!report.contains("Start Command Log"));
+    assert!(!report.contains("Exit Code:"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_silent_isolated_run_reports_log_tail() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // An `ssh` that runs the remote command locally
+    let temp_dir = TempDir::new().unwrap();
+    let ssh = temp_dir.path().join("ssh");
+    fs::write(&ssh, "#!/bin/sh\nshift\nexec \"$@\"\n").unwrap();
+    fs::set_permissions(&ssh, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        temp_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+    let run = |mode: &str, command: &str| {
+        Command::new(env!("CARGO_BIN_EXE_start"))
+            .args([mode, "--isolated", "ssh", "--endpoint", "me@host", "--"])
+            .arg(command)
+            .env("PATH", &path)
+            .env("START_APP_FOLDER", temp_dir.path().join("app"))
+            .env("START_DISABLE_AUTO_ISSUE", "1")
+            .env("START_DISABLE_CI", "1")
+            .env("START_DISABLE_TRACKING", "1")
+            .output()
+            .unwrap()
+    };
+
+    let output = run("--silent", "echo hidden");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+
+    let output = run("--silent", "echo first; echo last; exit 3");
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+    let report = String::from_utf8_lossy(&output.stderr);
+    assert!(report.contains("first\nlast\n"), "{}", report);
+    assert!(
+        report.contains("Command failed with exit code 3"),
+        "{}",
+        report
+    );
+
+    let output = run("--summary-only", "echo hidden");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("hidden"), "{}", stdout);
+    assert!(stdout.contains("exit      0"), "{}", stdout);
+}
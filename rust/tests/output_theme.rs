@@ -0,0 +1,186 @@
+//! Tests for themes, color and wrapping of the start and finish blocks
+//!
+//! Covers theme names, NO_COLOR/CLICOLOR_FORCE handling, wrapping values and
+//! commands, rendering each theme and the --theme argument.
+
+use start_command::{
+    color_from_env, create_finish_block, create_start_block, parse_args, strip_ansi, wrap_command,
+    wrap_value, FinishBlockOptions, OutputTheme, StartBlockOptions, VALID_THEMES,
+};
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn start_block(style: &str, width: Option<usize>) -> String {
+    create_start_block(&StartBlockOptions {
+        session_id: "test-uuid",
+        timestamp: "2025-01-01 00:00:00",
+        command: "echo hello",
+        extra_lines: Some(vec![
+            "[Isolation] Environment: docker, Mode: attached",
+            "[Isolation] Session: docker-container-123",
+        ]),
+        style: Some(style),
+        width,
+        color: false,
+        defer_command: false,
+    })
+}
+
+fn finish_block(style: &str, exit_code: i32, color: bool) -> String {
+    create_finish_block(&FinishBlockOptions {
+        session_id: "test-uuid",
+        timestamp: "2025-01-01 00:00:01",
+        exit_code,
+        log_path: "/tmp/start-command/logs/direct/test-uuid.log",
+        duration_ms: Some(17.0),
+        result_message: None,
+        extra_lines: None,
+        style: Some(style),
+        width: None,
+        color,
+    })
+}
+
+#[test]
+fn test_theme_names() {
+    for name in VALID_THEMES {
+        assert_eq!(OutputTheme::parse(name).unwrap().as_str(), name);
+    }
+    assert_eq!(OutputTheme::parse("ASCII"), Some(OutputTheme::Ascii));
+    assert_eq!(OutputTheme::parse("rounded"), None);
+    assert_eq!(OutputTheme::default(), OutputTheme::Timeline);
+    assert_eq!(OutputTheme::Ascii.result_marker(false), "FAIL");
+}
+
+#[test]
+fn test_color_from_env() {
+    assert!(color_from_env(None, None, true));
+    assert!(!color_from_env(None, None, false));
+    assert!(!color_from_env(Some("1"), None, true));
+    // An empty NO_COLOR does not disable color
+    assert!(color_from_env(Some(""), None, true));
+    assert!(color_from_env(None, Some("1"), false));
+    assert!(!color_from_env(None, Some("0"), false));
+    // NO_COLOR wins over CLICOLOR_FORCE
+    assert!(!color_from_env(Some("1"), Some("1"), true));
+}
+
+#[test]
+fn test_wrap_value() {
+    assert_eq!(wrap_value("short", 20), vec!["short"]);
+    assert_eq!(
+        wrap_value("/tmp/start-command/logs/direct/test-uuid.log", 20),
+        vec!["/tmp/start-command/", "logs/direct/", "test-uuid.log"]
+    );
+    assert_eq!(
+        wrap_value("three words here", 10),
+        vec!["three", "words here"]
+    );
+    assert_eq!(wrap_value("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+}
+
+#[test]
+fn test_wrap_command() {
+    assert_eq!(wrap_command("echo hi", 40), vec!["$ echo hi"]);
+    let lines = wrap_command("cargo test --workspace --all-features -- --nocapture", 30);
+    assert_eq!(
+        lines,
+        vec![
+            "$ cargo test --workspace \\",
+            "  --all-features -- \\",
+            "  --nocapture",
+        ]
+    );
+    // Quoted arguments are never split
+    let lines = wrap_command("echo 'one two three four' done", 16);
+    assert_eq!(
+        lines,
+        vec!["$ echo \\", "  'one two three four' \\", "  done"]
+    );
+}
+
+#[test]
+fn test_render_themes() {
+    // The default theme keeps the existing timeline layout
+    let timeline = start_block("timeline", None);
+    assert!(timeline.contains("│ session   test-uuid"));
+    assert!(timeline.contains("│ container docker-container-123"));
+
+    let ascii = format!(
+        "{}\n{}",
+        start_block("ascii", None),
+        finish_block("ascii", 1, false)
+    );
+    assert!(ascii.is_ascii(), "{}", ascii);
+    assert!(ascii.contains("| isolation docker"));
+    assert!(ascii.lines().any(|line| line == "FAIL"));
+
+    let compact = finish_block("compact", 0, false);
+    assert_eq!(
+        compact,
+        "✓ finish 2025-01-01 00:00:01 · duration 0.017s · exit 0 · log /tmp/start-command/logs/direct/test-uuid.log · session test-uuid"
+    );
+    assert_eq!(
+        start_block("compact", None),
+        "$ echo hello · session test-uuid · start 2025-01-01 00:00:00 · isolation docker · mode attached · container docker-container-123"
+    );
+
+    let boxed = strip_ansi(&finish_block("boxed", 0, true));
+    let widths: Vec<usize> = boxed.lines().map(|line| line.chars().count()).collect();
+    assert!(widths.iter().all(|width| *width == 80), "{}", boxed);
+    assert!(boxed.starts_with('┌') && boxed.ends_with('┘'));
+
+    // Long values wrap under the value column
+    let wrapped = create_finish_block(&FinishBlockOptions {
+        width: Some(40),
+        style: None,
+        ..finish_options()
+    });
+    assert!(
+        wrapped.contains("│ log       /tmp/start-command/logs/\n│           direct/test-uuid.log")
+    );
+}
+
+fn finish_options() -> FinishBlockOptions<'static> {
+    FinishBlockOptions {
+        session_id: "test-uuid",
+        timestamp: "2025-01-01 00:00:01",
+        exit_code: 0,
+        log_path: "/tmp/start-command/logs/direct/test-uuid.log",
+        duration_ms: None,
+        result_message: None,
+        extra_lines: None,
+        style: None,
+        width: None,
+        color: false,
+    }
+}
+
+#[test]
+fn test_result_marker_color() {
+    assert!(finish_block("timeline", 0, true).starts_with("\x1b[32m✓\x1b[0m\n"));
+    assert!(finish_block("timeline", 2, true).starts_with("\x1b[31m✗\x1b[0m\n"));
+    assert!(!finish_block("timeline", 2, false).contains('\x1b'));
+}
+
+#[test]
+fn test_theme_argument() {
+    let options = parse_args(&args(&["--theme", "ASCII", "--", "echo", "hi"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.theme.as_deref(), Some("ascii"));
+    let options = parse_args(&args(&["--theme=boxed", "make"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(options.theme.as_deref(), Some("boxed"));
+
+    let err = parse_args(&args(&["--theme", "fancy", "make"])).unwrap_err();
+    assert_eq!(
+        err,
+        "Invalid theme: \"fancy\". Valid options are: timeline, ascii, compact, boxed"
+    );
+    let err = parse_args(&args(&["--theme"])).unwrap_err();
+    assert!(err.contains("requires a theme argument"));
+}
@@ -0,0 +1,179 @@
+//! Tests for the podman isolation backend (--isolated podman).
+
+use serde_json::json;
+use start_command::{
+    build_next_level_command, collect_process_ids_with_runner, get_control_command,
+    isolation_stack_status_lines, parse_args, podman_image_reference, CommandRunOutput,
+    CommandRunner, ControlAction, ExecutionRecord, ExecutionRecordOptions, ExecutionStatus,
+    CONTAINER_BACKENDS, VALID_BACKENDS,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[derive(Default)]
+struct FakeRunner {
+    calls: RefCell<Vec<(String, Vec<String>)>>,
+    stdout: String,
+}
+
+impl CommandRunner for FakeRunner {
+    fn run(&self, command: &str, args: &[String]) -> CommandRunOutput {
+        self.calls
+            .borrow_mut()
+            .push((command.to_string(), args.to_vec()));
+        CommandRunOutput {
+            success: true,
+            stdout: self.stdout.clone(),
+            stderr: String::new(),
+            status: Some(0),
+            error: None,
+        }
+    }
+}
+
+fn podman_record() -> ExecutionRecord {
+    ExecutionRecord::with_options(ExecutionRecordOptions {
+        uuid: Some("podman-test-uuid".to_string()),
+        command: "sleep 100".to_string(),
+        status: Some(ExecutionStatus::Executing),
+        options: Some(HashMap::from([
+            ("isolated".to_string(), json!("podman")),
+            ("isolationMode".to_string(), json!("detached")),
+            ("sessionName".to_string(), json!("podman-session")),
+        ])),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn should_accept_podman_with_container_options() {
+    assert!(VALID_BACKENDS.contains(&"podman"));
+    assert_eq!(CONTAINER_BACKENDS, ["docker", "podman"]);
+
+    let parsed = parse_args(&args(&[
+        "--isolated",
+        "podman",
+        "--image",
+        "alpine:3.20",
+        "-v",
+        "/a:/a",
+        "-e",
+        "KEY=value",
+        "--privileged",
+        "--keep-container-on-fail",
+        "--",
+        "ls",
+    ]))
+    .unwrap();
+    let options = parsed.wrapper_options;
+    assert_eq!(options.isolated.as_deref(), Some("podman"));
+    assert_eq!(options.image.as_deref(), Some("alpine:3.20"));
+    assert_eq!(options.volumes, vec!["/a:/a"]);
+    assert!(options.privileged);
+    assert!(options.keep_container_on_fail);
+
+    // Without --image the OS-matched default applies, as for docker
+    let defaulted = parse_args(&args(&["--isolated", "podman", "--", "ls"])).unwrap();
+    assert!(defaulted.wrapper_options.image.is_some());
+}
+
+#[test]
+fn should_reject_invalid_podman_options() {
+    let error = |list: &[&str]| parse_args(&args(list)).unwrap_err();
+    assert!(
+        error(&["--isolated", "podman", "--isolated-user", "--", "ls"])
+            .contains("--isolated-user is not supported with Podman isolation")
+    );
+    assert!(error(&["--isolated", "tmux", "--env", "A=1", "--", "ls"])
+        .contains("--env option is only valid with --isolated docker or podman"));
+    assert!(
+        error(&["--isolated", "screen tmux", "--image", "alpine", "--", "ls"]).contains(
+            "--image option is only valid when the isolation stack includes docker or podman"
+        )
+    );
+}
+
+#[test]
+fn should_qualify_short_image_names_for_podman() {
+    assert_eq!(
+        podman_image_reference("ubuntu:22.04"),
+        "docker.io/library/ubuntu:22.04"
+    );
+    assert_eq!(
+        podman_image_reference("oven/bun:latest"),
+        "docker.io/oven/bun:latest"
+    );
+    for kept in [
+        "quay.io/podman/stable",
+        "localhost/my-image:dev",
+        "registry:5000/team/app",
+        "sha256:0123abcd",
+    ] {
+        assert_eq!(podman_image_reference(kept), kept);
+    }
+}
+
+#[test]
+fn should_stop_and_terminate_podman_containers() {
+    let record = podman_record();
+
+    let stop = get_control_command(&record, ControlAction::Stop).unwrap();
+    assert_eq!(stop.command, "podman");
+    assert_eq!(stop.args, vec!["stop", "podman-session"]);
+    assert_eq!(stop.method, "PODMAN_STOP");
+    assert!(stop
+        .message
+        .contains("Requested graceful stop for detached podman container: podman-session"));
+
+    let terminate = get_control_command(&record, ControlAction::Terminate).unwrap();
+    assert_eq!(terminate.command, "podman");
+    assert_eq!(terminate.args, vec!["kill", "podman-session"]);
+    assert_eq!(terminate.method, "SIGKILL");
+}
+
+#[test]
+fn should_collect_podman_container_process_ids() {
+    let runner = FakeRunner {
+        stdout: "f00dcafe 4242\n".to_string(),
+        ..Default::default()
+    };
+    let ids = collect_process_ids_with_runner(&podman_record(), &runner).unwrap();
+    assert_eq!(ids["containerId"], json!("f00dcafe"));
+    assert_eq!(ids["containerPid"], json!(4242));
+    assert_eq!(
+        runner.calls.borrow()[0],
+        (
+            "podman".to_string(),
+            args(&["inspect", "-f", "{{.Id}} {{.State.Pid}}", "podman-session"]),
+        )
+    );
+}
+
+#[test]
+fn should_stack_podman_levels() {
+    let options = parse_args(&args(&[
+        "--isolated",
+        "screen podman",
+        "--image",
+        "node:20",
+        "--env",
+        "CI=1",
+        "--",
+        "npm",
+        "test",
+    ]))
+    .unwrap()
+    .wrapper_options;
+    assert_eq!(options.image_stack, vec![None, Some("node:20".to_string())]);
+    assert_eq!(
+        isolation_stack_status_lines(&options),
+        vec!["[Isolation] Chain: screen → podman:node".to_string()]
+    );
+    let command = build_next_level_command(&options, "npm test");
+    assert!(command.contains("--isolated 'podman' --image 'node:20'"));
+    assert!(command.contains("--env 'CI=1'"));
+}
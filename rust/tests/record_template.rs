@@ -0,0 +1,188 @@
+//! Tests for --columns tables and --format templates of --status and --list
+//!
+//! Covers template parsing, nested field access, computed durations, fitting
+//! tables to a width, the store queries and the argument validation.
+
+use serde_json::json;
+use start_command::{
+    field_value, list_executions_with_layout, parse_args, parse_columns, parse_template,
+    query_status_with_layout, record_fields, render_table, render_template, ExecutionRecord,
+    ExecutionStore, ExecutionStoreOptions, RecordFilter, RecordLayout, TemplatePart,
+};
+use tempfile::TempDir;
+
+fn args(strs: &[&str]) -> Vec<String> {
+    strs.iter().map(|s| s.to_string()).collect()
+}
+
+fn columns(list: &str) -> Vec<String> {
+    parse_columns(list).unwrap()
+}
+
+#[test]
+fn test_parse_template() {
+    assert_eq!(
+        parse_template("{{uuid}}: {{ exitCode }}\\t!").unwrap(),
+        vec![
+            TemplatePart::Field("uuid".to_string()),
+            TemplatePart::Text(": ".to_string()),
+            TemplatePart::Field("exitCode".to_string()),
+            TemplatePart::Text("\t!".to_string()),
+        ]
+    );
+    assert!(parse_template("{{uuid")
+        .unwrap_err()
+        .contains("Unclosed {{ placeholder"));
+    assert!(parse_template("a {{ }} b")
+        .unwrap_err()
+        .contains("Empty {{}} placeholder"));
+    assert!(parse_columns("uuid,,command").is_err());
+    assert_eq!(columns(" uuid , status"), vec!["uuid", "status"]);
+}
+
+#[test]
+fn test_field_access() {
+    let fields = json!({
+        "uuid": "abc",
+        "exitCode": 2,
+        "options": {"isolated": "docker", "keepAlive": false},
+        "processIds": {"wrapperPid": 42},
+        "tags": ["nightly", "flaky"],
+        "endTime": null
+    });
+    assert_eq!(field_value(&fields, "exitCode").as_deref(), Some("2"));
+    assert_eq!(
+        field_value(&fields, "options.isolated").as_deref(),
+        Some("docker")
+    );
+    assert_eq!(
+        field_value(&fields, "options.keepAlive").as_deref(),
+        Some("false")
+    );
+    assert_eq!(
+        field_value(&fields, "processIds.wrapperPid").as_deref(),
+        Some("42")
+    );
+    assert_eq!(field_value(&fields, "tags[1]").as_deref(), Some("flaky"));
+    assert_eq!(field_value(&fields, "tags.0").as_deref(), Some("nightly"));
+    assert_eq!(field_value(&fields, "endTime"), None);
+    assert_eq!(field_value(&fields, "options.missing"), None);
+
+    let template = parse_template("{{uuid}} {{options.isolated}} [{{missing}}]").unwrap();
+    assert_eq!(render_template(&template, &fields), "abc docker []");
+}
+
+#[test]
+fn test_computed_duration() {
+    let mut record = ExecutionRecord::new("make");
+    record.start_time = "2026-10-18T10:00:00Z".to_string();
+    let running = record_fields(&record, Some("2026-10-18T10:00:02.500Z"), None);
+    assert_eq!(running["durationMs"], 2500);
+    assert_eq!(running["currentTime"], "2026-10-18T10:00:02.500Z");
+
+    record.complete(0);
+    record.end_time = Some("2026-10-18T10:01:00Z".to_string());
+    let finished = record_fields(&record, None, Some(&json!({"wrapperPid": 7})));
+    assert_eq!(finished["durationMs"], 60000);
+    assert!(finished["duration"].is_string());
+    assert_eq!(finished["processIds"]["wrapperPid"], 7);
+}
+
+#[test]
+fn test_render_table() {
+    let rows = vec![
+        json!({"uuid": "a1", "exitCode": 0, "command": "echo hello world"}),
+        json!({"uuid": "b2", "exitCode": 127, "command": "missing\ncommand"}),
+    ];
+    let table = render_table(&rows, &columns("uuid,exitCode,command"), None);
+    assert_eq!(
+        table,
+        "uuid  exitCode  command\n\
+         a1    0         echo hello world\n\
+         b2    127       missing command"
+    );
+
+    // The widest column gives way first and is cut with an ellipsis
+    let narrow = render_table(&rows, &columns("uuid,exitCode,command"), Some(27));
+    assert!(narrow.lines().all(|line| line.chars().count() <= 27));
+    assert!(narrow.contains("echo hello…"));
+    assert!(narrow.lines().next().unwrap().starts_with("uuid  exitCode"));
+
+    let empty = render_table(&[], &columns("uuid,status"), Some(80));
+    assert_eq!(empty, "uuid  status");
+}
+
+#[test]
+fn test_layout_queries() {
+    let temp_dir = TempDir::new().unwrap();
+    let store = ExecutionStore::with_options(ExecutionStoreOptions {
+        app_folder: Some(temp_dir.path().to_path_buf()),
+        use_links: Some(false),
+        verbose: false,
+    });
+    let mut older = ExecutionRecord::new("echo one");
+    older.start_time = "2026-10-17T09:00:00Z".to_string();
+    older.complete(0);
+    let mut newer = ExecutionRecord::new("echo two");
+    newer.start_time = "2026-10-18T09:00:00Z".to_string();
+    newer.complete(3);
+    store.save(&older).unwrap();
+    store.save(&newer).unwrap();
+
+    let template = RecordLayout::Template(parse_template("{{exitCode}} {{command}}").unwrap());
+    let list = list_executions_with_layout(Some(&store), &RecordFilter::default(), &template);
+    assert_eq!(list.output.as_deref(), Some("3 echo two\n0 echo one"));
+
+    let table = RecordLayout::Columns(columns("status,exitCode"));
+    let status = query_status_with_layout(Some(&store), &older.uuid, &table);
+    assert_eq!(
+        status.output.as_deref(),
+        Some("status    exitCode\nexecuted  0")
+    );
+
+    let missing = query_status_with_layout(Some(&store), "missing", &table);
+    assert!(!missing.success);
+    let disabled = list_executions_with_layout(None, &RecordFilter::default(), &table);
+    assert_eq!(
+        disabled.error.as_deref(),
+        Some("Execution tracking is disabled.")
+    );
+}
+
+#[test]
+fn test_layout_arguments() {
+    let options = parse_args(&args(&["--list", "--columns", "uuid,command"]))
+        .unwrap()
+        .wrapper_options;
+    assert_eq!(
+        options.layout,
+        Some(RecordLayout::Columns(columns("uuid,command")))
+    );
+    let options = parse_args(&args(&["--status", "x", "--format={{uuid}}"]))
+        .unwrap()
+        .wrapper_options;
+    assert!(matches!(options.layout, Some(RecordLayout::Template(_))));
+
+    let err = parse_args(&args(&[
+        "--list",
+        "--columns",
+        "uuid",
+        "--format",
+        "{{uuid}}",
+    ]))
+    .unwrap_err();
+    assert_eq!(err, "--columns and --format cannot be combined");
+    let err = parse_args(&args(&["--columns", "uuid", "--stats"])).unwrap_err();
+    assert!(err.contains("only valid with --status or --list"));
+    let err = parse_args(&args(&[
+        "--list",
+        "--columns",
+        "uuid",
+        "--output-format",
+        "json",
+    ]))
+    .unwrap_err();
+    assert!(err.contains("cannot be combined with --output-format or --tree"));
+    let err = parse_args(&args(&["--list", "--format", "{{uuid"])).unwrap_err();
+    assert!(err.contains("Invalid format template"));
+}
@@ -6,8 +6,8 @@ use start_command::{
     attach_current_time, format_record, format_record_as_links_notation,
     format_record_as_links_notation_with_current_time, format_record_as_text,
     format_record_as_text_with_current_time, format_record_list, format_record_with_current_time,
-    list_executions, query_status, ExecutionRecord, ExecutionRecordOptions, ExecutionStatus,
-    ExecutionStore, ExecutionStoreOptions,
+    list_executions, query_status, status_csv_header, value_to_yaml, ExecutionRecord,
+    ExecutionRecordOptions, ExecutionStatus, ExecutionStore, ExecutionStoreOptions,
 };
 use tempfile::TempDir;
 
@@ -342,3 +342,82 @@ fn test_format_record_unchanged_when_no_current_time() {
         format_record_as_text(&record)
     );
 }
+
+#[test]
+fn test_value_to_yaml() {
+    let value = serde_json::json!({
+        "command": "echo a: b",
+        "exitCode": 0,
+        "empty": "",
+        "flag": "true",
+        "startTime": "2025-01-01T00:00:00Z",
+        "options": {},
+        "tags": ["nightly", "-x"],
+        "notes": [{"text": "multi\nline", "time": "t"}],
+        "labels": {"env": "ci"}
+    });
+    assert_eq!(
+        value_to_yaml(&value),
+        "command: \"echo a: b\"\n\
+         empty: \"\"\n\
+         exitCode: 0\n\
+         flag: \"true\"\n\
+         labels:\n  env: ci\n\
+         notes:\n  - text: \"multi\\nline\"\n    time: t\n\
+         options: {}\n\
+         startTime: \"2025-01-01T00:00:00Z\"\n\
+         tags:\n  - nightly\n  - \"-x\""
+    );
+}
+
+#[test]
+fn test_format_record_yaml_csv_ndjson() {
+    let record = create_test_record();
+
+    let yaml = format_record(&record, "yaml").unwrap();
+    assert!(yaml.contains("uuid: test-uuid-1234"));
+    assert!(yaml.contains("status: executed"));
+
+    let csv = format_record(&record, "csv").unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], status_csv_header().join(","));
+    assert!(lines[1].starts_with("test-uuid-1234,executed,0,"));
+
+    let ndjson = format_record(&record, "ndjson").unwrap();
+    assert_eq!(ndjson.lines().count(), 1);
+    let parsed: serde_json::Value = serde_json::from_str(&ndjson).unwrap();
+    assert_eq!(parsed["uuid"], "test-uuid-1234");
+}
+
+#[test]
+fn test_format_record_list_yaml_csv_ndjson() {
+    let mut second = create_test_record();
+    second.uuid = "test-uuid-5678".to_string();
+    second.command = "echo \"quoted\", with comma".to_string();
+    let records = vec![create_test_record(), second];
+
+    let ndjson = format_record_list(&records, "ndjson").unwrap();
+    let uuids: Vec<String> = ndjson
+        .lines()
+        .map(|line| {
+            let value: serde_json::Value = serde_json::from_str(line).unwrap();
+            value["uuid"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(uuids, vec!["test-uuid-1234", "test-uuid-5678"]);
+
+    let csv = format_record_list(&records, "csv").unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains("\"echo \"\"quoted\"\", with comma\""));
+
+    let yaml = format_record_list(&records, "yaml").unwrap();
+    assert!(yaml.starts_with("count: 2\nexecutions:\n  - command: echo hello\n"));
+    assert!(yaml.contains("\n  - command: "));
+
+    assert_eq!(format_record_list(&[], "ndjson").unwrap(), "");
+    assert_eq!(
+        format_record_list(&[], "csv").unwrap(),
+        status_csv_header().join(",")
+    );
+}